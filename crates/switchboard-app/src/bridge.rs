@@ -1,4 +1,8 @@
-use switchboard_core::{Intent, ProfileId, SettingValue, TabId, WorkspaceId};
+use switchboard_core::{
+    BookmarkFolderId, BookmarkId, DownloadId, HistoryClearScope, Intent, PermissionDecision,
+    PermissionKind, PermissionRequestId, PermissionSetting, ProfileId, SearchEngineId,
+    SettingValue, TabId, WorkspaceId,
+};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum UiCommand {
@@ -17,6 +21,9 @@ pub enum UiCommand {
     NavigateActive {
         url: String,
     },
+    NavigateInput {
+        text: String,
+    },
     NewWorkspace {
         name: String,
     },
@@ -54,6 +61,134 @@ pub enum UiCommand {
         key: String,
         value: SettingValue,
     },
+    ExportWorkspace {
+        workspace_id: u64,
+        path: String,
+    },
+    ExportProfile {
+        profile_id: u64,
+        path: String,
+    },
+    ImportWorkspaces {
+        profile_id: u64,
+        path: String,
+    },
+    AddBookmark {
+        profile_id: u64,
+        folder_id: Option<u64>,
+        url: String,
+        title: String,
+    },
+    RemoveBookmark {
+        bookmark_id: u64,
+    },
+    MoveBookmark {
+        bookmark_id: u64,
+        folder_id: Option<u64>,
+        index: usize,
+    },
+    RenameBookmark {
+        bookmark_id: u64,
+        title: String,
+    },
+    CreateBookmarkFolder {
+        profile_id: u64,
+        parent_id: Option<u64>,
+        name: String,
+    },
+    RenameBookmarkFolder {
+        folder_id: u64,
+        name: String,
+    },
+    RemoveBookmarkFolder {
+        folder_id: u64,
+    },
+    OpenBookmarkFolderAsWorkspace {
+        folder_id: u64,
+    },
+    ClearHistory {
+        profile_id: u64,
+        scope: HistoryClearScope,
+    },
+    AddSearchEngine {
+        profile_id: u64,
+        name: String,
+        keyword: String,
+        url_template: String,
+    },
+    UpdateSearchEngine {
+        engine_id: u64,
+        name: String,
+        keyword: String,
+        url_template: String,
+    },
+    RemoveSearchEngine {
+        engine_id: u64,
+    },
+    SetDefaultSearchEngine {
+        engine_id: u64,
+    },
+    ContinueInsecure {
+        tab_id: u64,
+    },
+    ReloadTab {
+        tab_id: u64,
+    },
+    SetTabKeepAlive {
+        tab_id: u64,
+        keep_alive: bool,
+    },
+    SetTabMuted {
+        tab_id: u64,
+        muted: bool,
+    },
+    MuteOtherTabs {
+        tab_id: u64,
+    },
+    SetWorkspaceMuted {
+        workspace_id: u64,
+        muted: bool,
+    },
+    AddHttpsException {
+        profile_id: u64,
+        host: String,
+    },
+    RemoveHttpsException {
+        profile_id: u64,
+        host: String,
+    },
+    SetSitePermission {
+        profile_id: u64,
+        origin: String,
+        kind: PermissionKind,
+        setting: PermissionSetting,
+    },
+    ResetSitePermission {
+        profile_id: u64,
+        origin: String,
+        kind: Option<PermissionKind>,
+    },
+    ResolvePermission {
+        request_id: u64,
+        decision: PermissionDecision,
+        remember: bool,
+    },
+    PauseDownload {
+        download_id: u64,
+    },
+    ResumeDownload {
+        download_id: u64,
+    },
+    CancelDownload {
+        download_id: u64,
+    },
+    RemoveDownload {
+        download_id: u64,
+    },
+    OpenDownloadFolder {
+        download_id: u64,
+    },
+    ResetMetrics,
 }
 
 impl UiCommand {
@@ -78,6 +213,11 @@ impl UiCommand {
                     "NavigateActive requires runtime tab resolution before intent dispatch"
                 )
             }
+            Self::NavigateInput { .. } => {
+                unreachable!(
+                    "NavigateInput requires runtime search resolution before intent dispatch"
+                )
+            }
             Self::NewWorkspace { .. } => {
                 unreachable!(
                     "NewWorkspace requires runtime profile resolution before intent dispatch"
@@ -115,7 +255,178 @@ impl UiCommand {
             Self::ToggleDevTools => {
                 unreachable!("ToggleDevTools is handled directly by the runtime host")
             }
+            Self::ResetMetrics => {
+                unreachable!("metrics live in the runtime, not in browser state")
+            }
             Self::SettingSet { key, value } => Intent::SettingSet { key, value },
+            Self::ExportWorkspace { workspace_id, path } => Intent::ExportWorkspace {
+                workspace_id: WorkspaceId(workspace_id),
+                path,
+            },
+            Self::ExportProfile { profile_id, path } => Intent::ExportProfile {
+                profile_id: ProfileId(profile_id),
+                path,
+            },
+            Self::ImportWorkspaces { .. } => {
+                unreachable!("ImportWorkspaces requires the runtime to read the bundle file")
+            }
+            Self::AddBookmark {
+                profile_id,
+                folder_id,
+                url,
+                title,
+            } => Intent::AddBookmark {
+                profile_id: ProfileId(profile_id),
+                folder_id: folder_id.map(BookmarkFolderId),
+                url,
+                title,
+            },
+            Self::RemoveBookmark { bookmark_id } => Intent::RemoveBookmark {
+                bookmark_id: BookmarkId(bookmark_id),
+            },
+            Self::MoveBookmark {
+                bookmark_id,
+                folder_id,
+                index,
+            } => Intent::MoveBookmark {
+                bookmark_id: BookmarkId(bookmark_id),
+                folder_id: folder_id.map(BookmarkFolderId),
+                index,
+            },
+            Self::RenameBookmark { bookmark_id, title } => Intent::RenameBookmark {
+                bookmark_id: BookmarkId(bookmark_id),
+                title,
+            },
+            Self::CreateBookmarkFolder {
+                profile_id,
+                parent_id,
+                name,
+            } => Intent::CreateBookmarkFolder {
+                profile_id: ProfileId(profile_id),
+                parent_id: parent_id.map(BookmarkFolderId),
+                name,
+            },
+            Self::RenameBookmarkFolder { folder_id, name } => Intent::RenameBookmarkFolder {
+                folder_id: BookmarkFolderId(folder_id),
+                name,
+            },
+            Self::RemoveBookmarkFolder { folder_id } => Intent::RemoveBookmarkFolder {
+                folder_id: BookmarkFolderId(folder_id),
+            },
+            Self::OpenBookmarkFolderAsWorkspace { folder_id } => {
+                Intent::OpenBookmarkFolderAsWorkspace {
+                    folder_id: BookmarkFolderId(folder_id),
+                }
+            }
+            Self::ClearHistory { profile_id, scope } => Intent::ClearHistory {
+                profile_id: ProfileId(profile_id),
+                scope,
+            },
+            Self::AddSearchEngine {
+                profile_id,
+                name,
+                keyword,
+                url_template,
+            } => Intent::AddSearchEngine {
+                profile_id: ProfileId(profile_id),
+                name,
+                keyword,
+                url_template,
+                make_default: false,
+            },
+            Self::UpdateSearchEngine {
+                engine_id,
+                name,
+                keyword,
+                url_template,
+            } => Intent::UpdateSearchEngine {
+                engine_id: SearchEngineId(engine_id),
+                name,
+                keyword,
+                url_template,
+            },
+            Self::RemoveSearchEngine { engine_id } => Intent::RemoveSearchEngine {
+                engine_id: SearchEngineId(engine_id),
+            },
+            Self::SetDefaultSearchEngine { engine_id } => Intent::SetDefaultSearchEngine {
+                engine_id: SearchEngineId(engine_id),
+            },
+            Self::ContinueInsecure { tab_id } => Intent::ContinueInsecure {
+                tab_id: TabId(tab_id),
+            },
+            Self::ReloadTab { tab_id } => Intent::ReloadTab {
+                tab_id: TabId(tab_id),
+            },
+            Self::SetTabKeepAlive { tab_id, keep_alive } => Intent::SetTabKeepAlive {
+                tab_id: TabId(tab_id),
+                keep_alive,
+            },
+            Self::SetTabMuted { tab_id, muted } => Intent::SetTabMuted {
+                tab_id: TabId(tab_id),
+                muted,
+            },
+            Self::MuteOtherTabs { tab_id } => Intent::MuteOtherTabs {
+                tab_id: TabId(tab_id),
+            },
+            Self::SetWorkspaceMuted {
+                workspace_id,
+                muted,
+            } => Intent::SetWorkspaceMuted {
+                workspace_id: WorkspaceId(workspace_id),
+                muted,
+            },
+            Self::AddHttpsException { profile_id, host } => Intent::AddHttpsException {
+                profile_id: ProfileId(profile_id),
+                host,
+            },
+            Self::RemoveHttpsException { profile_id, host } => Intent::RemoveHttpsException {
+                profile_id: ProfileId(profile_id),
+                host,
+            },
+            Self::SetSitePermission {
+                profile_id,
+                origin,
+                kind,
+                setting,
+            } => Intent::SetSitePermission {
+                profile_id: ProfileId(profile_id),
+                origin,
+                kind,
+                setting,
+            },
+            Self::ResetSitePermission {
+                profile_id,
+                origin,
+                kind,
+            } => Intent::ResetSitePermission {
+                profile_id: ProfileId(profile_id),
+                origin,
+                kind,
+            },
+            Self::ResolvePermission {
+                request_id,
+                decision,
+                remember,
+            } => Intent::ResolvePermission {
+                request_id: PermissionRequestId(request_id),
+                decision,
+                remember,
+            },
+            Self::PauseDownload { download_id } => Intent::PauseDownload {
+                download_id: DownloadId(download_id),
+            },
+            Self::ResumeDownload { download_id } => Intent::ResumeDownload {
+                download_id: DownloadId(download_id),
+            },
+            Self::CancelDownload { download_id } => Intent::CancelDownload {
+                download_id: DownloadId(download_id),
+            },
+            Self::RemoveDownload { download_id } => Intent::RemoveDownload {
+                download_id: DownloadId(download_id),
+            },
+            Self::OpenDownloadFolder { download_id } => Intent::OpenDownloadFolder {
+                download_id: DownloadId(download_id),
+            },
         }
     }
 }
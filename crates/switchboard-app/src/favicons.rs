@@ -0,0 +1,280 @@
+#![cfg_attr(test, allow(dead_code))]
+
+use std::collections::{BTreeSet, HashMap};
+use std::env;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use switchboard_core::url::is_web_url;
+use switchboard_core::ProfileId;
+
+use crate::persistence::{default_data_file_path, AppPersistenceError};
+
+const ENV_FAVICON_DIR: &str = "SWITCHBOARD_FAVICON_DIR";
+pub const FAVICON_URL_PREFIX: &str = "app://favicon/";
+// Pages can point at arbitrary images; anything larger is not an icon.
+pub const MAX_FAVICON_BYTES: usize = 256 * 1024;
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+// Icons are keyed by profile and icon URL, so profiles never share bytes and
+// every tab showing the same icon shares one file. On disk they live at
+// `<dir>/<profile_id>/<hash>`.
+pub fn favicon_key(profile_id: ProfileId, icon_url: &str) -> String {
+    let hash = icon_url.bytes().fold(FNV_OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(FNV_PRIME)
+    });
+    format!("{}-{hash:016x}", profile_id.0)
+}
+
+fn parse_favicon_key(key: &str) -> Option<(ProfileId, &str)> {
+    let (profile_id, hash) = key.split_once('-')?;
+    if hash.len() != 16
+        || !hash
+            .bytes()
+            .all(|byte| matches!(byte, b'0'..=b'9' | b'a'..=b'f'))
+    {
+        return None;
+    }
+    Some((ProfileId(profile_id.parse().ok()?), hash))
+}
+
+// The host lists candidates in document order; the first one we can fetch
+// wins.
+pub fn preferred_favicon_url(urls: &[String]) -> Option<&str> {
+    urls.iter()
+        .map(|url| url.trim())
+        .find(|url| is_web_url(url) || url.starts_with("data:image/"))
+}
+
+pub fn favicon_mime_type(bytes: &[u8]) -> Option<&'static str> {
+    let text_start = bytes
+        .iter()
+        .position(|byte| !byte.is_ascii_whitespace())
+        .map_or(&[][..], |start| &bytes[start..]);
+    match bytes {
+        [0x89, b'P', b'N', b'G', ..] => Some("image/png"),
+        [0x00, 0x00, 0x01, 0x00, ..] => Some("image/x-icon"),
+        [b'G', b'I', b'F', b'8', ..] => Some("image/gif"),
+        [0xff, 0xd8, 0xff, ..] => Some("image/jpeg"),
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => Some("image/webp"),
+        _ if text_start.starts_with(b"<svg") || text_start.starts_with(b"<?xml") => {
+            Some("image/svg+xml")
+        }
+        _ => None,
+    }
+}
+
+pub struct FaviconCache {
+    // None keeps every icon in memory, as tests do.
+    dir: Option<PathBuf>,
+    // Icons of ephemeral profiles, which must not reach disk.
+    memory: HashMap<String, Vec<u8>>,
+}
+
+impl FaviconCache {
+    pub fn open_default() -> Result<Self, AppPersistenceError> {
+        let dir = env::var_os(ENV_FAVICON_DIR)
+            .map(PathBuf::from)
+            .unwrap_or(default_data_file_path("favicons")?);
+        Ok(Self::open_dir(dir))
+    }
+
+    pub fn open_dir(dir: impl AsRef<Path>) -> Self {
+        Self {
+            dir: Some(dir.as_ref().to_path_buf()),
+            memory: HashMap::new(),
+        }
+    }
+
+    #[cfg(test)]
+    pub fn in_memory() -> Self {
+        Self {
+            dir: None,
+            memory: HashMap::new(),
+        }
+    }
+
+    fn path_for(&self, key: &str) -> Option<PathBuf> {
+        let (profile_id, hash) = parse_favicon_key(key)?;
+        Some(self.dir.as_ref()?.join(profile_id.0.to_string()).join(hash))
+    }
+
+    pub fn contains(&self, key: &str) -> bool {
+        self.memory.contains_key(key) || self.path_for(key).is_some_and(|path| path.is_file())
+    }
+
+    pub fn load(&self, key: &str) -> Option<Vec<u8>> {
+        if let Some(bytes) = self.memory.get(key) {
+            return Some(bytes.clone());
+        }
+        fs::read(self.path_for(key)?).ok()
+    }
+
+    pub fn store(
+        &mut self,
+        key: &str,
+        bytes: &[u8],
+        persist: bool,
+    ) -> Result<(), AppPersistenceError> {
+        if parse_favicon_key(key).is_none() {
+            return Err(AppPersistenceError::InvalidData(format!(
+                "malformed favicon key {key}"
+            )));
+        }
+        match self.path_for(key).filter(|_| persist) {
+            Some(path) => {
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::write(path, bytes)?;
+            }
+            None => {
+                self.memory.insert(key.to_owned(), bytes.to_vec());
+            }
+        }
+        Ok(())
+    }
+
+    pub fn remove_profile(&mut self, profile_id: ProfileId) -> Result<(), AppPersistenceError> {
+        self.memory
+            .retain(|key, _| parse_favicon_key(key).is_none_or(|(owner, _)| owner != profile_id));
+        let Some(dir) = &self.dir else {
+            return Ok(());
+        };
+        match fs::remove_dir_all(dir.join(profile_id.0.to_string())) {
+            Err(error) if error.kind() != ErrorKind::NotFound => Err(error.into()),
+            _ => Ok(()),
+        }
+    }
+
+    // Drops every icon no tab refers to, including those of deleted profiles.
+    pub fn retain(&mut self, keep: &BTreeSet<&str>) -> Result<(), AppPersistenceError> {
+        self.memory.retain(|key, _| keep.contains(key.as_str()));
+        let Some(dir) = &self.dir else {
+            return Ok(());
+        };
+        if !dir.is_dir() {
+            return Ok(());
+        }
+        for profile_entry in fs::read_dir(dir)? {
+            let profile_dir = profile_entry?.path();
+            let Some(profile_id) = profile_dir
+                .file_name()
+                .and_then(|name| name.to_str())
+                .map(str::to_owned)
+            else {
+                continue;
+            };
+            if !profile_dir.is_dir() {
+                continue;
+            }
+            let mut remaining = 0_usize;
+            for icon_entry in fs::read_dir(&profile_dir)? {
+                let icon_path = icon_entry?.path();
+                let key = icon_path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .map(|hash| format!("{profile_id}-{hash}"));
+                if key.is_some_and(|key| keep.contains(key.as_str())) {
+                    remaining += 1;
+                } else {
+                    fs::remove_file(&icon_path)?;
+                }
+            }
+            if remaining == 0 {
+                fs::remove_dir(&profile_dir)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+    use std::fs;
+
+    use switchboard_core::ProfileId;
+
+    use super::{favicon_key, favicon_mime_type, preferred_favicon_url, FaviconCache};
+
+    #[test]
+    fn keys_are_per_profile_and_icons_are_sniffed() {
+        let key = favicon_key(ProfileId(3), "https://news.example/favicon.ico");
+        assert!(key.starts_with("3-"));
+        assert_eq!(key.len(), 2 + 16);
+        assert_eq!(
+            key,
+            favicon_key(ProfileId(3), "https://news.example/favicon.ico")
+        );
+        assert_ne!(
+            key,
+            favicon_key(ProfileId(4), "https://news.example/favicon.ico")
+        );
+
+        let urls = vec![
+            "javascript:alert(1)".to_owned(),
+            " https://news.example/icon.png ".to_owned(),
+            "https://news.example/favicon.ico".to_owned(),
+        ];
+        assert_eq!(
+            preferred_favicon_url(&urls),
+            Some("https://news.example/icon.png")
+        );
+        assert_eq!(preferred_favicon_url(&[]), None);
+
+        assert_eq!(
+            favicon_mime_type(b"\x89PNG\r\n\x1a\n...."),
+            Some("image/png")
+        );
+        assert_eq!(
+            favicon_mime_type(b"\x00\x00\x01\x00\x01\x00"),
+            Some("image/x-icon")
+        );
+        assert_eq!(
+            favicon_mime_type(b"  <svg xmlns=\"http://www.w3.org/2000/svg\"/>"),
+            Some("image/svg+xml")
+        );
+        assert_eq!(favicon_mime_type(b"<html>not an icon</html>"), None);
+    }
+
+    #[test]
+    fn disk_cache_keeps_profiles_apart_and_drops_unreferenced_icons() {
+        let dir =
+            std::env::temp_dir().join(format!("switchboard_favicons_test_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let mut cache = FaviconCache::open_dir(&dir);
+        let kept = favicon_key(ProfileId(1), "https://a.example/favicon.ico");
+        let dropped = favicon_key(ProfileId(1), "https://b.example/favicon.ico");
+        let private = favicon_key(ProfileId(2), "https://a.example/favicon.ico");
+        cache
+            .store(&kept, b"kept", true)
+            .expect("icon should store");
+        cache
+            .store(&dropped, b"dropped", true)
+            .expect("icon should store");
+        cache
+            .store(&private, b"private", false)
+            .expect("icon should store");
+        assert!(cache.store("../escape", b"x", true).is_err());
+        assert!(dir.join("1").is_dir());
+        assert!(!dir.join("2").exists());
+
+        let reopened = FaviconCache::open_dir(&dir);
+        assert_eq!(reopened.load(&kept), Some(b"kept".to_vec()));
+        assert!(!reopened.contains(&private));
+
+        cache
+            .retain(&BTreeSet::from([kept.as_str()]))
+            .expect("retain should succeed");
+        assert!(cache.contains(&kept));
+        assert!(!cache.contains(&dropped) && !cache.contains(&private));
+        cache
+            .remove_profile(ProfileId(1))
+            .expect("profile icons should be removed");
+        assert!(!dir.join("1").exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,88 @@
+#![cfg_attr(test, allow(dead_code))]
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use switchboard_core::FilterEngine;
+
+use crate::persistence::{default_data_file_path, AppPersistenceError};
+
+const ENV_FILTER_LISTS: &str = "SWITCHBOARD_FILTER_LISTS";
+const FILTER_LIST_EXTENSION: &str = "txt";
+
+// Every `*.txt` file in the filter list directory is compiled into one engine.
+// A missing directory means no lists are installed yet.
+pub fn load_default() -> Result<FilterEngine, AppPersistenceError> {
+    let dir = env::var_os(ENV_FILTER_LISTS)
+        .map(PathBuf::from)
+        .unwrap_or(default_data_file_path("filter_lists")?);
+    load_dir(dir)
+}
+
+pub fn load_dir(dir: impl AsRef<Path>) -> Result<FilterEngine, AppPersistenceError> {
+    let dir = dir.as_ref();
+    let mut engine = FilterEngine::default();
+    if !dir.is_dir() {
+        return Ok(engine);
+    }
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .extension()
+                    .is_some_and(|extension| extension == FILTER_LIST_EXTENSION)
+        })
+        .collect();
+    paths.sort();
+    for path in paths {
+        engine.extend(&fs::read_to_string(&path)?);
+    }
+    Ok(engine)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use switchboard_core::{FilterDecision, FilterRequest, RequestFilter, ResourceType};
+
+    use super::load_dir;
+
+    #[test]
+    fn load_dir_compiles_txt_lists_and_tolerates_missing_dir() {
+        let mut dir = std::env::temp_dir();
+        dir.push(format!(
+            "switchboard_filter_lists_test_{}_{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .expect("system time should be after unix epoch")
+                .as_nanos()
+        ));
+
+        let missing = load_dir(&dir).expect("missing directory should load empty");
+        assert!(missing.is_empty());
+
+        fs::create_dir_all(&dir).expect("temp directory should be created");
+        fs::write(dir.join("ads.txt"), "! ads\n||ads.example.net^\n")
+            .expect("list should be written");
+        fs::write(
+            dir.join("privacy.txt"),
+            "||metrics.example.org^$third-party\n",
+        )
+        .expect("list should be written");
+        fs::write(dir.join("notes.md"), "||docs.example^\n").expect("notes should be written");
+
+        let engine = load_dir(&dir).expect("lists should load");
+        let _ = fs::remove_dir_all(&dir);
+        assert_eq!(engine.rule_count(), 2);
+        let request = FilterRequest {
+            url: "https://ads.example.net/a.js",
+            source_url: Some("https://news.example/"),
+            resource_type: ResourceType::Script,
+        };
+        assert_eq!(engine.check(&request), FilterDecision::Block);
+    }
+}
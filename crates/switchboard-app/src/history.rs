@@ -0,0 +1,459 @@
+#![cfg_attr(test, allow(dead_code))]
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use switchboard_core::url::web_host;
+use switchboard_core::{HistoryClearScope, HistoryEntry, ProfileId, VisitTransition};
+
+use crate::persistence::{
+    default_data_file_path, optional_cell, parse_i64, parse_u64, required_cell, sql_text_literal,
+    AppPersistenceError, SqliteStore,
+};
+
+const ENV_HISTORY_DB: &str = "SWITCHBOARD_HISTORY_DB";
+pub const DEFAULT_RETENTION_DAYS: i64 = 90;
+pub const DEFAULT_MAX_VISITS_PER_PROFILE: i64 = 50_000;
+const PRUNE_EVERY_RECORDED_VISITS: u64 = 256;
+const MS_PER_DAY: i64 = 24 * 60 * 60 * 1000;
+
+const HISTORY_MIGRATIONS: &[(i64, &str)] = &[(
+    1,
+    "
+        CREATE TABLE IF NOT EXISTS visits (
+            id INTEGER PRIMARY KEY,
+            profile_id INTEGER NOT NULL,
+            url TEXT NOT NULL,
+            host TEXT NOT NULL,
+            title TEXT NOT NULL,
+            visited_at_ms INTEGER NOT NULL,
+            transition INTEGER NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS visits_by_profile_time ON visits(profile_id, visited_at_ms);
+        CREATE INDEX IF NOT EXISTS visits_by_profile_host ON visits(profile_id, host);
+        CREATE INDEX IF NOT EXISTS visits_by_profile_url ON visits(profile_id, url);
+        CREATE VIRTUAL TABLE IF NOT EXISTS visits_fts USING fts5(
+            title, url, content='visits', content_rowid='id', tokenize='unicode61'
+        );
+        CREATE TRIGGER IF NOT EXISTS visits_fts_insert AFTER INSERT ON visits BEGIN
+            INSERT INTO visits_fts(rowid, title, url) VALUES (new.id, new.title, new.url);
+        END;
+        CREATE TRIGGER IF NOT EXISTS visits_fts_delete AFTER DELETE ON visits BEGIN
+            INSERT INTO visits_fts(visits_fts, rowid, title, url)
+                VALUES ('delete', old.id, old.title, old.url);
+        END;
+        CREATE TRIGGER IF NOT EXISTS visits_fts_update AFTER UPDATE ON visits BEGIN
+            INSERT INTO visits_fts(visits_fts, rowid, title, url)
+                VALUES ('delete', old.id, old.title, old.url);
+            INSERT INTO visits_fts(rowid, title, url) VALUES (new.id, new.title, new.url);
+        END;
+    ",
+)];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetentionPolicy {
+    pub max_age_days: i64,
+    pub max_visits_per_profile: i64,
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        Self {
+            max_age_days: DEFAULT_RETENTION_DAYS,
+            max_visits_per_profile: DEFAULT_MAX_VISITS_PER_PROFILE,
+        }
+    }
+}
+
+pub struct HistoryStore {
+    store: SqliteStore,
+    retention: RetentionPolicy,
+    visits_since_prune: u64,
+}
+
+impl HistoryStore {
+    pub fn open_default() -> Result<Self, AppPersistenceError> {
+        let path = env::var_os(ENV_HISTORY_DB)
+            .map(PathBuf::from)
+            .unwrap_or(default_data_file_path("history.sqlite3")?);
+        Self::open_path(path)
+    }
+
+    pub fn open_path(path: impl AsRef<Path>) -> Result<Self, AppPersistenceError> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut store = SqliteStore::open(path)?;
+        store.apply_migrations(HISTORY_MIGRATIONS)?;
+        Ok(Self {
+            store,
+            retention: RetentionPolicy::default(),
+            visits_since_prune: 0,
+        })
+    }
+
+    #[cfg(test)]
+    pub fn open_in_memory() -> Result<Self, AppPersistenceError> {
+        let mut store = SqliteStore::open_memory()?;
+        store.apply_migrations(HISTORY_MIGRATIONS)?;
+        Ok(Self {
+            store,
+            retention: RetentionPolicy::default(),
+            visits_since_prune: 0,
+        })
+    }
+
+    pub fn set_retention(&mut self, retention: RetentionPolicy) {
+        self.retention = retention;
+    }
+
+    pub fn record_visit(
+        &mut self,
+        profile_id: ProfileId,
+        url: &str,
+        title: &str,
+        visited_at_ms: i64,
+        transition: VisitTransition,
+    ) -> Result<(), AppPersistenceError> {
+        self.store.exec_batch(&format!(
+            "INSERT INTO visits(profile_id, url, host, title, visited_at_ms, transition)
+             VALUES({}, {}, {}, {}, {}, {});",
+            profile_id.0,
+            sql_text_literal(url),
+            sql_text_literal(&web_host(url).unwrap_or_default()),
+            sql_text_literal(title),
+            visited_at_ms,
+            transition.as_i64()
+        ))?;
+        self.visits_since_prune += 1;
+        if self.visits_since_prune >= PRUNE_EVERY_RECORDED_VISITS {
+            self.prune(visited_at_ms)?;
+        }
+        Ok(())
+    }
+
+    // Titles usually arrive after the URL commit, so patch the newest visit.
+    pub fn update_latest_title(
+        &mut self,
+        profile_id: ProfileId,
+        url: &str,
+        title: &str,
+    ) -> Result<(), AppPersistenceError> {
+        self.store.exec_batch(&format!(
+            "UPDATE visits SET title = {title}
+             WHERE id = (
+                SELECT id FROM visits WHERE profile_id = {profile} AND url = {url}
+                ORDER BY visited_at_ms DESC, id DESC LIMIT 1
+             ) AND title <> {title};",
+            title = sql_text_literal(title),
+            profile = profile_id.0,
+            url = sql_text_literal(url),
+        ))
+    }
+
+    pub fn search(
+        &mut self,
+        profile_id: ProfileId,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<HistoryEntry>, AppPersistenceError> {
+        let Some(match_expression) = fts_match_expression(query) else {
+            return Ok(Vec::new());
+        };
+        let matching = format!(
+            "SELECT v.* FROM visits_fts JOIN visits v ON v.id = visits_fts.rowid
+             WHERE visits_fts MATCH {} AND v.profile_id = {}",
+            sql_text_literal(&match_expression),
+            profile_id.0
+        );
+        self.grouped_entries(&matching, limit)
+    }
+
+    pub fn recent(
+        &mut self,
+        profile_id: ProfileId,
+        limit: usize,
+    ) -> Result<Vec<HistoryEntry>, AppPersistenceError> {
+        let matching = format!("SELECT * FROM visits WHERE profile_id = {}", profile_id.0);
+        self.grouped_entries(&matching, limit)
+    }
+
+    // Collapses visits to one entry per URL, titled by its newest visit.
+    fn grouped_entries(
+        &mut self,
+        matching_visits: &str,
+        limit: usize,
+    ) -> Result<Vec<HistoryEntry>, AppPersistenceError> {
+        let rows = self.store.query_rows(&format!(
+            "SELECT g.url, latest.title, g.visit_count, g.typed_count, g.last_visited_at_ms
+             FROM (
+                SELECT url, COUNT(*) AS visit_count,
+                    SUM(transition = {typed}) AS typed_count,
+                    MAX(visited_at_ms) AS last_visited_at_ms, MAX(id) AS latest_id
+                FROM ({matching_visits})
+                GROUP BY url
+             ) g
+             JOIN visits latest ON latest.id = g.latest_id
+             ORDER BY g.last_visited_at_ms DESC, g.latest_id DESC
+             LIMIT {limit};",
+            typed = VisitTransition::Typed.as_i64(),
+        ))?;
+        rows.iter().map(|row| history_entry_from_row(row)).collect()
+    }
+
+    #[cfg(test)]
+    pub fn visit_count(&mut self, profile_id: ProfileId) -> Result<u64, AppPersistenceError> {
+        let rows = self.store.query_rows(&format!(
+            "SELECT COUNT(*) FROM visits WHERE profile_id = {};",
+            profile_id.0
+        ))?;
+        rows.first()
+            .and_then(|row| row.first())
+            .and_then(|cell| cell.as_deref())
+            .map(|value| parse_u64(value, "visits.count"))
+            .transpose()
+            .map(|count| count.unwrap_or(0))
+    }
+
+    pub fn clear(
+        &mut self,
+        profile_id: ProfileId,
+        scope: &HistoryClearScope,
+    ) -> Result<(), AppPersistenceError> {
+        let filter = match scope {
+            HistoryClearScope::All => String::new(),
+            HistoryClearScope::TimeRange { start_ms, end_ms } => {
+                format!(" AND visited_at_ms >= {start_ms} AND visited_at_ms < {end_ms}")
+            }
+            HistoryClearScope::Domain(domain) => {
+                let domain = domain.trim().trim_start_matches('.').to_ascii_lowercase();
+                format!(
+                    " AND (host = {} OR host LIKE {} ESCAPE '\\')",
+                    sql_text_literal(&domain),
+                    sql_text_literal(&format!("%.{}", escape_like(&domain)))
+                )
+            }
+        };
+        self.store.exec_batch(&format!(
+            "DELETE FROM visits WHERE profile_id = {}{filter};",
+            profile_id.0
+        ))
+    }
+
+    pub fn prune(&mut self, now_ms: i64) -> Result<(), AppPersistenceError> {
+        self.visits_since_prune = 0;
+        let cutoff_ms = now_ms.saturating_sub(self.retention.max_age_days.max(0) * MS_PER_DAY);
+        let max_visits = self.retention.max_visits_per_profile.max(0);
+        self.store.exec_batch(&format!(
+            "BEGIN IMMEDIATE;
+             DELETE FROM visits WHERE visited_at_ms < {cutoff_ms};
+             DELETE FROM visits WHERE id IN (
+                SELECT id FROM (
+                    SELECT id, ROW_NUMBER() OVER (
+                        PARTITION BY profile_id ORDER BY visited_at_ms DESC, id DESC
+                    ) AS rank
+                    FROM visits
+                ) WHERE rank > {max_visits}
+             );
+             COMMIT;"
+        ))
+    }
+}
+
+fn history_entry_from_row(row: &[Option<String>]) -> Result<HistoryEntry, AppPersistenceError> {
+    Ok(HistoryEntry {
+        url: required_cell(row, 0, "visits.url")?.to_owned(),
+        title: optional_cell(row, 1).unwrap_or_default().to_owned(),
+        visit_count: parse_u64(required_cell(row, 2, "visits.count")?, "visits.count")?,
+        typed_count: optional_cell(row, 3)
+            .map(|value| parse_u64(value, "visits.typed_count"))
+            .transpose()?
+            .unwrap_or(0),
+        last_visited_at_ms: parse_i64(
+            required_cell(row, 4, "visits.visited_at_ms")?,
+            "visits.visited_at_ms",
+        )?,
+    })
+}
+
+// Each whitespace-separated term becomes a quoted prefix query so user input
+// can never inject FTS5 operators.
+fn fts_match_expression(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .map(|term| format!("\"{}\"*", term.replace('"', "\"\"")))
+        .collect();
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
+    }
+}
+
+fn escape_like(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+pub fn unix_time_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use switchboard_core::{HistoryClearScope, ProfileId, VisitTransition};
+
+    use super::{HistoryStore, RetentionPolicy, MS_PER_DAY};
+
+    fn seeded_store() -> HistoryStore {
+        let mut store = HistoryStore::open_in_memory().expect("open in-memory history");
+        let profile = ProfileId(1);
+        let visits = [
+            (
+                "https://docs.rs/tokio",
+                "tokio - Rust",
+                1_000,
+                VisitTransition::Typed,
+            ),
+            (
+                "https://docs.rs/tokio",
+                "tokio - Rust",
+                2_000,
+                VisitTransition::Link,
+            ),
+            (
+                "https://blog.rust-lang.org/",
+                "Rust Blog",
+                3_000,
+                VisitTransition::Link,
+            ),
+            (
+                "https://news.example.com/a",
+                "Tokio release notes",
+                4_000,
+                VisitTransition::Link,
+            ),
+        ];
+        for (url, title, at, transition) in visits {
+            store
+                .record_visit(profile, url, title, at, transition)
+                .expect("visit should be recorded");
+        }
+        store
+            .record_visit(
+                ProfileId(2),
+                "https://docs.rs/tokio",
+                "tokio - Rust",
+                5_000,
+                VisitTransition::Typed,
+            )
+            .expect("visit should be recorded");
+        store
+    }
+
+    #[test]
+    fn search_matches_title_and_url_prefixes_per_profile() {
+        let mut store = seeded_store();
+        let results = store
+            .search(ProfileId(1), "toki", 10)
+            .expect("search should succeed");
+        let urls: Vec<_> = results.iter().map(|entry| entry.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            vec!["https://news.example.com/a", "https://docs.rs/tokio"]
+        );
+        assert_eq!(results[1].visit_count, 2);
+        assert_eq!(results[1].typed_count, 1);
+        assert_eq!(results[1].last_visited_at_ms, 2_000);
+
+        let injection = store
+            .search(ProfileId(1), "rust\" OR title:*", 10)
+            .expect("operators should be treated as text");
+        assert!(injection.is_empty());
+        assert_eq!(
+            store
+                .search(ProfileId(3), "tokio", 10)
+                .expect("search should succeed"),
+            Vec::new()
+        );
+    }
+
+    #[test]
+    fn title_updates_are_searchable() {
+        let mut store = seeded_store();
+        store
+            .update_latest_title(
+                ProfileId(1),
+                "https://blog.rust-lang.org/",
+                "Announcing Rust",
+            )
+            .expect("title update should succeed");
+        let results = store
+            .search(ProfileId(1), "announcing", 10)
+            .expect("search should succeed");
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].title, "Announcing Rust");
+    }
+
+    #[test]
+    fn clear_by_domain_and_time_range() {
+        let mut store = seeded_store();
+        store
+            .clear(
+                ProfileId(1),
+                &HistoryClearScope::Domain("rust-lang.org".to_owned()),
+            )
+            .expect("domain clear should succeed");
+        assert_eq!(store.visit_count(ProfileId(1)).expect("count"), 3);
+
+        store
+            .clear(
+                ProfileId(1),
+                &HistoryClearScope::TimeRange {
+                    start_ms: 1_500,
+                    end_ms: 4_000,
+                },
+            )
+            .expect("range clear should succeed");
+        assert_eq!(store.visit_count(ProfileId(1)).expect("count"), 2);
+
+        store
+            .clear(ProfileId(1), &HistoryClearScope::All)
+            .expect("full clear should succeed");
+        assert_eq!(store.visit_count(ProfileId(1)).expect("count"), 0);
+        assert_eq!(store.visit_count(ProfileId(2)).expect("count"), 1);
+        assert!(store
+            .search(ProfileId(1), "tokio", 10)
+            .expect("search should succeed")
+            .is_empty());
+    }
+
+    #[test]
+    fn prune_enforces_age_and_count_limits() {
+        let mut store = seeded_store();
+        store.set_retention(RetentionPolicy {
+            max_age_days: 1,
+            max_visits_per_profile: 2,
+        });
+        store
+            .prune(MS_PER_DAY + 2_500)
+            .expect("prune should succeed");
+        assert_eq!(store.visit_count(ProfileId(1)).expect("count"), 2);
+        assert_eq!(store.visit_count(ProfileId(2)).expect("count"), 1);
+        let remaining: Vec<_> = store
+            .recent(ProfileId(1), 10)
+            .expect("recent should succeed")
+            .into_iter()
+            .map(|entry| entry.last_visited_at_ms)
+            .collect();
+        assert_eq!(remaining, vec![4_000, 3_000]);
+    }
+}
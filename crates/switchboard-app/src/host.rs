@@ -1,14 +1,39 @@
 use std::cell::{Cell, RefCell};
+#[cfg(target_os = "macos")]
+use std::collections::hash_map::Entry;
 use std::collections::{HashMap, VecDeque};
 use std::error::Error;
 use std::fmt::{Display, Formatter, Result as FmtResult};
 #[cfg(target_os = "macos")]
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+#[cfg(target_os = "macos")]
+use std::sync::{Mutex, OnceLock, PoisonError};
+#[cfg(target_os = "macos")]
+use switchboard_core::content_filter::{
+    CONTENT_FILTER_ALLOWLIST_PROFILE_PREFIX, CONTENT_FILTER_ENABLED_PROFILE_PREFIX,
+};
+#[cfg(target_os = "macos")]
+use switchboard_core::downloads::DOWNLOAD_DIRECTORY_PROFILE_PREFIX;
+#[cfg(target_os = "macos")]
+use switchboard_core::lifecycle::is_lifecycle_setting;
+#[cfg(target_os = "macos")]
+use switchboard_core::navigation::{
+    ALLOWED_DOMAINS_PROFILE_PREFIX, BLOCKED_DOMAINS_PROFILE_PREFIX, HTTPS_ONLY_PROFILE_PREFIX,
+};
+use switchboard_core::url::is_ui_url;
 #[cfg(target_os = "macos")]
-use std::sync::OnceLock;
-use switchboard_core::{SettingValue, TabId};
+use switchboard_core::url::{is_web_url, parse_web_url, url_scheme, UI_URL_SCHEME};
+use switchboard_core::{
+    DownloadCommand, DownloadId, DownloadState, FilterDecision, FilterRequest, MemoryPressureLevel,
+    PermissionKind, PermissionRequestId, RequestFilter, SettingValue, TabId, WindowDisposition,
+};
+#[cfg(target_os = "macos")]
+use switchboard_core::{HistoryClearScope, PermissionDecision, PermissionSetting, ResourceType};
 
 use crate::bridge::UiCommand;
+#[cfg(target_os = "macos")]
+use crate::favicons::FAVICON_URL_PREFIX;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct WindowId(pub u64);
@@ -61,6 +86,44 @@ pub enum HostEvent {
     ContentViewDestroyed {
         view_id: ContentViewId,
     },
+    PermissionRequestCompleted {
+        request_id: PermissionRequestId,
+        granted: bool,
+    },
+    DownloadBegun {
+        download_id: DownloadId,
+        target_path: String,
+    },
+    DownloadControlled {
+        download_id: DownloadId,
+        command: DownloadCommand,
+    },
+    DownloadFolderOpened {
+        target_path: String,
+    },
+    CrashReloadScheduled {
+        tab_id: TabId,
+        delay_ms: u64,
+    },
+    LifecycleTickScheduled {
+        delay_ms: u64,
+    },
+    StartupRestoreScheduled {
+        tab_id: TabId,
+        delay_ms: u64,
+    },
+    MemoryEstimateRequested {
+        view_id: ContentViewId,
+        tab_id: TabId,
+    },
+    AudioMuted {
+        view_id: ContentViewId,
+        muted: bool,
+    },
+    FaviconRequested {
+        tab_id: TabId,
+        icon_url: String,
+    },
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -122,19 +185,170 @@ pub trait CefHost {
 
     fn destroy_content_view(&mut self, view_id: ContentViewId) -> Result<(), Self::Error>;
 
+    fn set_audio_muted(&mut self, view_id: ContentViewId, muted: bool) -> Result<(), Self::Error>;
+
+    // Subresource and navigation requests from the view are checked against
+    // `filter`; None lets everything through.
+    fn set_request_filter(
+        &mut self,
+        view_id: ContentViewId,
+        filter: Option<Arc<dyn RequestFilter>>,
+    ) -> Result<(), Self::Error>;
+
+    // Answers a permission request the host reported through
+    // `ContentEvent::PermissionRequested`.
+    fn complete_permission_request(
+        &mut self,
+        request_id: PermissionRequestId,
+        granted: bool,
+    ) -> Result<(), Self::Error>;
+
+    // Accepts a download reported through `ContentEvent::DownloadStarted`,
+    // saving it to `target_path` and reporting progress under `download_id`.
+    // Downloads that are never begun are dropped by the host.
+    fn begin_download(
+        &mut self,
+        host_download_id: u64,
+        download_id: DownloadId,
+        target_path: &str,
+    ) -> Result<(), Self::Error>;
+
+    fn control_download(
+        &mut self,
+        download_id: DownloadId,
+        command: DownloadCommand,
+    ) -> Result<(), Self::Error>;
+
+    fn open_download_folder(&mut self, target_path: &str) -> Result<(), Self::Error>;
+
+    // Reports `ContentEvent::CrashReloadDue` for the tab once `delay_ms` has
+    // passed.
+    fn schedule_crash_reload(&mut self, tab_id: TabId, delay_ms: u64) -> Result<(), Self::Error>;
+
+    // Reports `ContentEvent::LifecycleTickDue` once `delay_ms` has passed.
+    fn schedule_lifecycle_tick(&mut self, delay_ms: u64) -> Result<(), Self::Error>;
+
+    // Reports `ContentEvent::StartupRestoreDue` for the tab once `delay_ms`
+    // has passed.
+    fn schedule_startup_restore(&mut self, tab_id: TabId, delay_ms: u64)
+        -> Result<(), Self::Error>;
+
+    // Measures the view's renderer process and reports
+    // `ContentEvent::MemoryEstimated` for the tab.
+    fn request_memory_estimate(
+        &mut self,
+        view_id: ContentViewId,
+        tab_id: TabId,
+    ) -> Result<(), Self::Error>;
+
+    // Fetches the icon through the tab's browser so it uses the page's
+    // network context, then reports `ContentEvent::FaviconDownloaded`.
+    fn download_favicon(&mut self, tab_id: TabId, icon_url: &str) -> Result<(), Self::Error>;
+
     fn run_event_loop(&mut self) -> Result<(), Self::Error>;
 }
 
 pub type UiCommandHandler = Box<dyn FnMut(UiCommand) + 'static>;
 pub type UiStateProvider = Box<dyn FnMut() -> String + 'static>;
+pub type UiQueryProvider = Box<dyn FnMut(UiQuery) -> String + 'static>;
 pub type ContentEventHandler = Box<dyn FnMut(ContentEvent) + 'static>;
 pub type WindowEventHandler = Box<dyn FnMut(WindowEvent) + 'static>;
+// Maps an `app://favicon/<key>` key to the icon's MIME type and bytes.
+pub type FaviconProvider = Box<dyn FnMut(&str) -> Option<(&'static str, Vec<u8>)> + 'static>;
+
+// Read-only requests from the UI shell that are answered with JSON.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UiQuery {
+    History(String),
+    Suggestions(String),
+    // The runtime's in-memory metrics, as an object.
+    Metrics,
+}
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ContentEvent {
-    UrlChanged { tab_id: TabId, url: String },
-    TitleChanged { tab_id: TabId, title: String },
-    LoadingChanged { tab_id: TabId, is_loading: bool },
+    UrlChanged {
+        tab_id: TabId,
+        url: String,
+    },
+    TitleChanged {
+        tab_id: TabId,
+        title: String,
+    },
+    LoadingChanged {
+        tab_id: TabId,
+        is_loading: bool,
+    },
+    LoadFailed {
+        tab_id: TabId,
+        url: String,
+        error_code: i32,
+    },
+    RendererCrashed {
+        tab_id: TabId,
+    },
+    AudioStateChanged {
+        tab_id: TabId,
+        audible: bool,
+    },
+    // Icon candidates declared by the page, in document order; empty when
+    // the page has none.
+    FaviconChanged {
+        tab_id: TabId,
+        urls: Vec<String>,
+    },
+    // Answers `download_favicon`; None when the icon could not be fetched.
+    FaviconDownloaded {
+        tab_id: TabId,
+        icon_url: String,
+        data: Option<Vec<u8>>,
+    },
+    // A delay requested through `schedule_crash_reload` has elapsed.
+    CrashReloadDue {
+        tab_id: TabId,
+    },
+    // A delay requested through `schedule_lifecycle_tick` has elapsed.
+    LifecycleTickDue,
+    // A delay requested through `schedule_startup_restore` has elapsed.
+    StartupRestoreDue {
+        tab_id: TabId,
+    },
+    // Answers `request_memory_estimate`.
+    MemoryEstimated {
+        tab_id: TabId,
+        bytes: u64,
+    },
+    // The system's memory pressure changed; reported without being asked.
+    MemoryPressureChanged {
+        level: MemoryPressureLevel,
+    },
+    PermissionRequested {
+        tab_id: TabId,
+        origin: String,
+        kind: PermissionKind,
+        request_id: PermissionRequestId,
+    },
+    NewWindowRequested {
+        opener_tab_id: TabId,
+        url: String,
+        disposition: WindowDisposition,
+        user_gesture: bool,
+    },
+    // `host_download_id` only identifies the download until the runtime
+    // begins it; later events use the assigned `DownloadId`.
+    DownloadStarted {
+        tab_id: TabId,
+        host_download_id: u64,
+        url: String,
+        suggested_name: Option<String>,
+        total_bytes: Option<u64>,
+    },
+    DownloadProgress {
+        download_id: DownloadId,
+        received_bytes: u64,
+        total_bytes: Option<u64>,
+        state: DownloadState,
+    },
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -145,10 +359,12 @@ pub enum WindowEvent {
 thread_local! {
     static UI_COMMAND_HANDLER: RefCell<Option<UiCommandHandler>> = RefCell::new(None);
     static UI_STATE_PROVIDER: RefCell<Option<UiStateProvider>> = RefCell::new(None);
+    static UI_QUERY_PROVIDER: RefCell<Option<UiQueryProvider>> = RefCell::new(None);
     static CONTENT_EVENT_HANDLER: RefCell<Option<ContentEventHandler>> = RefCell::new(None);
     static CONTENT_EVENT_QUEUE: RefCell<VecDeque<ContentEvent>> = RefCell::new(VecDeque::new());
     static CONTENT_EVENT_DISPATCHING: Cell<bool> = const { Cell::new(false) };
     static WINDOW_EVENT_HANDLER: RefCell<Option<WindowEventHandler>> = RefCell::new(None);
+    static FAVICON_PROVIDER: RefCell<Option<FaviconProvider>> = RefCell::new(None);
     static ACTIVE_CONTENT_URI: RefCell<Option<String>> = const { RefCell::new(None) };
     static ACTIVE_CONTENT_TAB: RefCell<Option<TabId>> = const { RefCell::new(None) };
     #[cfg(target_os = "macos")]
@@ -159,6 +375,33 @@ thread_local! {
     static ACTIVE_CONTENT_BROWSER: RefCell<*mut cef_browser_t> = const { RefCell::new(std::ptr::null_mut()) };
     #[cfg(target_os = "macos")]
     static CONTENT_BROWSERS_BY_TAB: RefCell<HashMap<TabId, *mut cef_browser_t>> = RefCell::new(HashMap::new());
+    #[cfg(target_os = "macos")]
+    static CONTENT_TABS_BY_VIEW: RefCell<HashMap<ContentViewId, TabId>> = RefCell::new(HashMap::new());
+    #[cfg(target_os = "macos")]
+    static PENDING_PERMISSIONS: RefCell<Vec<PendingPermission>> = const { RefCell::new(Vec::new()) };
+    #[cfg(target_os = "macos")]
+    static NEXT_PERMISSION_REQUEST_ID: Cell<u64> = const { Cell::new(0) };
+    #[cfg(target_os = "macos")]
+    static PENDING_DOWNLOADS: RefCell<HashMap<u64, *mut cef_before_download_callback_t>> =
+        RefCell::new(HashMap::new());
+    #[cfg(target_os = "macos")]
+    static ACTIVE_DOWNLOADS: RefCell<HashMap<u64, ActiveDownload>> = RefCell::new(HashMap::new());
+    #[cfg(target_os = "macos")]
+    static CEF_STRING_LIST_API: Cell<Option<CefStringListApi>> = const { Cell::new(None) };
+    // Tabs waiting on each icon URL; one download answers all of them.
+    #[cfg(target_os = "macos")]
+    static PENDING_FAVICONS: RefCell<HashMap<String, Vec<TabId>>> = RefCell::new(HashMap::new());
+    #[cfg(target_os = "macos")]
+    static FAVICON_DOWNLOAD_CALLBACK: Cell<*mut cef_download_image_callback_t> =
+        const { Cell::new(std::ptr::null_mut()) };
+    // Heap usage queries waiting on a DevTools reply, by message id.
+    #[cfg(target_os = "macos")]
+    static PENDING_MEMORY_ESTIMATES: RefCell<HashMap<c_int, TabId>> = RefCell::new(HashMap::new());
+    #[cfg(target_os = "macos")]
+    static NEXT_DEV_TOOLS_MESSAGE_ID: Cell<c_int> = const { Cell::new(0) };
+    #[cfg(target_os = "macos")]
+    static MEMORY_ESTIMATE_OBSERVER: Cell<*mut cef_dev_tools_message_observer_t> =
+        const { Cell::new(std::ptr::null_mut()) };
 }
 
 pub fn install_ui_command_handler(handler: Option<UiCommandHandler>) {
@@ -173,6 +416,12 @@ pub fn install_ui_state_provider(provider: Option<UiStateProvider>) {
     });
 }
 
+pub fn install_ui_query_provider(provider: Option<UiQueryProvider>) {
+    UI_QUERY_PROVIDER.with(|slot| {
+        *slot.borrow_mut() = provider;
+    });
+}
+
 pub fn install_content_event_handler(handler: Option<ContentEventHandler>) {
     CONTENT_EVENT_HANDLER.with(|slot| {
         *slot.borrow_mut() = handler;
@@ -185,6 +434,12 @@ pub fn install_window_event_handler(handler: Option<WindowEventHandler>) {
     });
 }
 
+pub fn install_favicon_provider(provider: Option<FaviconProvider>) {
+    FAVICON_PROVIDER.with(|slot| {
+        *slot.borrow_mut() = provider;
+    });
+}
+
 fn emit_ui_command(command: UiCommand) {
     UI_COMMAND_HANDLER.with(|slot| {
         if let Some(handler) = slot.borrow_mut().as_mut() {
@@ -204,6 +459,28 @@ fn query_ui_shell_state() -> String {
     })
 }
 
+fn query_ui(query: UiQuery) -> String {
+    UI_QUERY_PROVIDER.with(|slot| {
+        let mut slot_ref = slot.borrow_mut();
+        if let Some(provider) = slot_ref.as_mut() {
+            return provider(query);
+        }
+        "[]".to_owned()
+    })
+}
+
+// Resolves `app://favicon/<key>` requests from the UI view's scheme handler.
+#[cfg(target_os = "macos")]
+fn favicon_resource(url: &str) -> Option<(&'static str, Vec<u8>)> {
+    let key = url.strip_prefix(FAVICON_URL_PREFIX)?;
+    let key = key.split(['?', '#']).next().unwrap_or(key);
+    FAVICON_PROVIDER.with(|slot| {
+        slot.borrow_mut()
+            .as_mut()
+            .and_then(|provider| provider(key))
+    })
+}
+
 fn emit_content_event(event: ContentEvent) {
     CONTENT_EVENT_QUEUE.with(|queue| {
         queue.borrow_mut().push_back(event);
@@ -315,6 +592,25 @@ fn forget_browser_for_tab(tab_id: TabId) {
     });
 }
 
+#[cfg(target_os = "macos")]
+fn set_content_view_tab(view_id: ContentViewId, tab_id: TabId) {
+    CONTENT_TABS_BY_VIEW.with(|slot| {
+        slot.borrow_mut().insert(view_id, tab_id);
+    });
+}
+
+#[cfg(target_os = "macos")]
+fn content_view_tab(view_id: ContentViewId) -> Option<TabId> {
+    CONTENT_TABS_BY_VIEW.with(|slot| slot.borrow().get(&view_id).copied())
+}
+
+#[cfg(target_os = "macos")]
+fn forget_content_view_tab(view_id: ContentViewId) {
+    CONTENT_TABS_BY_VIEW.with(|slot| {
+        slot.borrow_mut().remove(&view_id);
+    });
+}
+
 #[cfg(target_os = "macos")]
 fn set_ui_view_handles(root_view: ObjcId, ui_view: ObjcId) {
     UI_ROOT_VIEW.with(|slot| {
@@ -351,6 +647,19 @@ pub struct MockCefHost {
     next_ui_view_id: u64,
     next_content_view_id: u64,
     events: Vec<HostEvent>,
+    request_filters: HashMap<ContentViewId, Arc<dyn RequestFilter>>,
+    next_host_download_id: u64,
+    pending_downloads: HashMap<u64, MockDownload>,
+    downloads: HashMap<DownloadId, MockDownload>,
+}
+
+#[cfg(any(test, not(target_os = "macos")))]
+#[derive(Debug, Clone, Copy, Default)]
+struct MockDownload {
+    received_bytes: u64,
+    total_bytes: Option<u64>,
+    paused: bool,
+    finished: bool,
 }
 
 #[cfg(any(test, not(target_os = "macos")))]
@@ -358,6 +667,89 @@ impl MockCefHost {
     pub fn events(&self) -> &[HostEvent] {
         &self.events
     }
+
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub fn filter_request(
+        &self,
+        view_id: ContentViewId,
+        request: &FilterRequest<'_>,
+    ) -> FilterDecision {
+        self.request_filters
+            .get(&view_id)
+            .map_or(FilterDecision::Allow, |filter| filter.check(request))
+    }
+
+    // Simulates a page starting a download; feed the returned event to the
+    // runtime, which answers through `begin_download`.
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub fn start_download(
+        &mut self,
+        tab_id: TabId,
+        url: &str,
+        suggested_name: Option<&str>,
+        total_bytes: Option<u64>,
+    ) -> ContentEvent {
+        self.next_host_download_id += 1;
+        self.pending_downloads.insert(
+            self.next_host_download_id,
+            MockDownload {
+                total_bytes,
+                ..MockDownload::default()
+            },
+        );
+        ContentEvent::DownloadStarted {
+            tab_id,
+            host_download_id: self.next_host_download_id,
+            url: url.to_owned(),
+            suggested_name: suggested_name.map(str::to_owned),
+            total_bytes,
+        }
+    }
+
+    // Receives the next `bytes` of a begun download. Returns None once the
+    // transfer is paused, cancelled or finished, so tests can loop until the
+    // sequence stops.
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub fn advance_download(
+        &mut self,
+        download_id: DownloadId,
+        bytes: u64,
+    ) -> Option<ContentEvent> {
+        let download = self.downloads.get_mut(&download_id)?;
+        if download.paused || download.finished {
+            return None;
+        }
+        download.received_bytes = download.received_bytes.saturating_add(bytes);
+        if let Some(total_bytes) = download.total_bytes {
+            download.received_bytes = download.received_bytes.min(total_bytes);
+            download.finished = download.received_bytes == total_bytes;
+        }
+        Some(ContentEvent::DownloadProgress {
+            download_id,
+            received_bytes: download.received_bytes,
+            total_bytes: download.total_bytes,
+            state: if download.finished {
+                DownloadState::Complete
+            } else {
+                DownloadState::InProgress
+            },
+        })
+    }
+
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub fn fail_download(&mut self, download_id: DownloadId) -> Option<ContentEvent> {
+        let download = self.downloads.get_mut(&download_id)?;
+        if download.finished {
+            return None;
+        }
+        download.finished = true;
+        Some(ContentEvent::DownloadProgress {
+            download_id,
+            received_bytes: download.received_bytes,
+            total_bytes: download.total_bytes,
+            state: DownloadState::Failed,
+        })
+    }
 }
 
 #[cfg(any(test, not(target_os = "macos")))]
@@ -376,7 +768,7 @@ impl CefHost for MockCefHost {
     }
 
     fn create_ui_view(&mut self, window_id: WindowId, url: &str) -> Result<UiViewId, Self::Error> {
-        if !url.starts_with("app://ui") {
+        if !is_ui_url(url) {
             return Err(HostError::InvalidUiUrl(url.to_owned()));
         }
 
@@ -438,11 +830,125 @@ impl CefHost for MockCefHost {
     }
 
     fn destroy_content_view(&mut self, view_id: ContentViewId) -> Result<(), Self::Error> {
+        self.request_filters.remove(&view_id);
         self.events
             .push(HostEvent::ContentViewDestroyed { view_id });
         Ok(())
     }
 
+    fn set_request_filter(
+        &mut self,
+        view_id: ContentViewId,
+        filter: Option<Arc<dyn RequestFilter>>,
+    ) -> Result<(), Self::Error> {
+        match filter {
+            Some(filter) => self.request_filters.insert(view_id, filter),
+            None => self.request_filters.remove(&view_id),
+        };
+        Ok(())
+    }
+
+    fn complete_permission_request(
+        &mut self,
+        request_id: PermissionRequestId,
+        granted: bool,
+    ) -> Result<(), Self::Error> {
+        self.events.push(HostEvent::PermissionRequestCompleted {
+            request_id,
+            granted,
+        });
+        Ok(())
+    }
+
+    fn begin_download(
+        &mut self,
+        host_download_id: u64,
+        download_id: DownloadId,
+        target_path: &str,
+    ) -> Result<(), Self::Error> {
+        let download = self
+            .pending_downloads
+            .remove(&host_download_id)
+            .unwrap_or_default();
+        self.downloads.insert(download_id, download);
+        self.events.push(HostEvent::DownloadBegun {
+            download_id,
+            target_path: target_path.to_owned(),
+        });
+        Ok(())
+    }
+
+    fn control_download(
+        &mut self,
+        download_id: DownloadId,
+        command: DownloadCommand,
+    ) -> Result<(), Self::Error> {
+        if let Some(download) = self.downloads.get_mut(&download_id) {
+            match command {
+                DownloadCommand::Pause => download.paused = true,
+                DownloadCommand::Resume => download.paused = false,
+                DownloadCommand::Cancel => download.finished = true,
+            }
+        }
+        self.events.push(HostEvent::DownloadControlled {
+            download_id,
+            command,
+        });
+        Ok(())
+    }
+
+    fn open_download_folder(&mut self, target_path: &str) -> Result<(), Self::Error> {
+        self.events.push(HostEvent::DownloadFolderOpened {
+            target_path: target_path.to_owned(),
+        });
+        Ok(())
+    }
+
+    fn schedule_crash_reload(&mut self, tab_id: TabId, delay_ms: u64) -> Result<(), Self::Error> {
+        self.events
+            .push(HostEvent::CrashReloadScheduled { tab_id, delay_ms });
+        Ok(())
+    }
+
+    fn schedule_lifecycle_tick(&mut self, delay_ms: u64) -> Result<(), Self::Error> {
+        self.events
+            .push(HostEvent::LifecycleTickScheduled { delay_ms });
+        Ok(())
+    }
+
+    fn schedule_startup_restore(
+        &mut self,
+        tab_id: TabId,
+        delay_ms: u64,
+    ) -> Result<(), Self::Error> {
+        self.events
+            .push(HostEvent::StartupRestoreScheduled { tab_id, delay_ms });
+        Ok(())
+    }
+
+    fn request_memory_estimate(
+        &mut self,
+        view_id: ContentViewId,
+        tab_id: TabId,
+    ) -> Result<(), Self::Error> {
+        self.events
+            .push(HostEvent::MemoryEstimateRequested { view_id, tab_id });
+        Ok(())
+    }
+
+    fn set_audio_muted(&mut self, view_id: ContentViewId, muted: bool) -> Result<(), Self::Error> {
+        self.events.push(HostEvent::AudioMuted { view_id, muted });
+        Ok(())
+    }
+
+    fn download_favicon(&mut self, tab_id: TabId, icon_url: &str) -> Result<(), Self::Error> {
+        self.events.push(HostEvent::FaviconRequested {
+            tab_id,
+            icon_url: icon_url.to_owned(),
+        });
+        Ok(())
+    }
+
     fn run_event_loop(&mut self) -> Result<(), Self::Error> {
         Ok(())
     }
@@ -466,14 +972,29 @@ use std::slice;
 use switchboard_cef_sys::loader::CefLibrary;
 #[cfg(target_os = "macos")]
 use switchboard_cef_sys::raw::{
-    cef_app_t, cef_base_ref_counted_t, cef_browser_host_create_browser_fn, cef_browser_settings_t,
-    cef_browser_t, cef_callback_t, cef_client_t, cef_display_handler_t, cef_frame_t,
-    cef_jsdialog_callback_t, cef_jsdialog_handler_t, cef_main_args_t, cef_rect_t, cef_request_t,
-    cef_resource_handler_t, cef_response_t, cef_scheme_handler_factory_t, cef_scheme_registrar_t,
-    cef_settings_t, cef_string_t, cef_string_utf16_t, cef_window_info_t, CEF_RUNTIME_STYLE_ALLOY,
-    CEF_SCHEME_OPTION_CORS_ENABLED, CEF_SCHEME_OPTION_DISPLAY_ISOLATED,
-    CEF_SCHEME_OPTION_FETCH_ENABLED, CEF_SCHEME_OPTION_SECURE, CEF_SCHEME_OPTION_STANDARD,
-    JSDIALOGTYPE_PROMPT,
+    cef_app_t, cef_audio_handler_t, cef_audio_parameters_t, cef_base_ref_counted_t,
+    cef_before_download_callback_t, cef_browser_host_create_browser_fn, cef_browser_settings_t,
+    cef_browser_t, cef_callback_t, cef_client_t, cef_dev_tools_message_observer_t,
+    cef_dictionary_value_t, cef_display_handler_t, cef_download_handler_t,
+    cef_download_image_callback_t, cef_download_item_callback_t, cef_download_item_t,
+    cef_errorcode_t, cef_frame_t, cef_image_t, cef_jsdialog_callback_t, cef_jsdialog_handler_t,
+    cef_life_span_handler_t, cef_load_handler_t, cef_main_args_t, cef_media_access_callback_t,
+    cef_permission_handler_t, cef_permission_prompt_callback_t, cef_permission_request_result_t,
+    cef_popup_features_t, cef_rect_t, cef_registration_t, cef_request_handler_t, cef_request_t,
+    cef_resource_handler_t, cef_resource_request_handler_t, cef_resource_type_t, cef_response_t,
+    cef_return_value_t, cef_scheme_handler_factory_t, cef_scheme_registrar_t, cef_settings_t,
+    cef_string_list_size_fn, cef_string_list_t, cef_string_list_value_fn, cef_string_t,
+    cef_string_userfree_t, cef_string_userfree_utf16_free_fn, cef_string_utf16_clear_fn,
+    cef_string_utf16_t, cef_termination_status_t, cef_window_info_t, cef_window_open_disposition_t,
+    CEF_MEDIA_PERMISSION_DEVICE_AUDIO_CAPTURE, CEF_MEDIA_PERMISSION_DEVICE_VIDEO_CAPTURE,
+    CEF_PERMISSION_RESULT_ACCEPT, CEF_PERMISSION_RESULT_DENY, CEF_PERMISSION_TYPE_CAMERA_STREAM,
+    CEF_PERMISSION_TYPE_CLIPBOARD, CEF_PERMISSION_TYPE_GEOLOCATION, CEF_PERMISSION_TYPE_MIC_STREAM,
+    CEF_PERMISSION_TYPE_NOTIFICATIONS, CEF_RUNTIME_STYLE_ALLOY, CEF_SCHEME_OPTION_CORS_ENABLED,
+    CEF_SCHEME_OPTION_DISPLAY_ISOLATED, CEF_SCHEME_OPTION_FETCH_ENABLED, CEF_SCHEME_OPTION_SECURE,
+    CEF_SCHEME_OPTION_STANDARD, CEF_WOD_NEW_BACKGROUND_TAB, CEF_WOD_NEW_POPUP, CEF_WOD_NEW_WINDOW,
+    JSDIALOGTYPE_PROMPT, RT_CSP_REPORT, RT_FAVICON, RT_FONT_RESOURCE, RT_IMAGE, RT_MAIN_FRAME,
+    RT_MEDIA, RT_OBJECT, RT_PING, RT_PLUGIN_RESOURCE, RT_SCRIPT, RT_SERVICE_WORKER,
+    RT_SHARED_WORKER, RT_STYLESHEET, RT_SUB_FRAME, RT_WORKER, RT_XHR, RV_CANCEL, RV_CONTINUE,
 };
 
 #[cfg(target_os = "macos")]
@@ -622,10 +1143,76 @@ extern "C" {
 #[link(name = "WebKit", kind = "framework")]
 extern "C" {}
 
+#[cfg(target_os = "macos")]
+#[repr(C)]
+struct DispatchQueue {
+    _private: [u8; 0],
+}
+
+#[cfg(target_os = "macos")]
+#[repr(C)]
+struct DispatchSource {
+    _private: [u8; 0],
+}
+
+#[cfg(target_os = "macos")]
+#[repr(C)]
+struct DispatchSourceType {
+    _private: [u8; 0],
+}
+
+#[cfg(target_os = "macos")]
+#[link(name = "System")]
+extern "C" {
+    static _dispatch_main_q: DispatchQueue;
+    static _dispatch_source_type_memorypressure: DispatchSourceType;
+    fn dispatch_time(when: u64, delta: i64) -> u64;
+    fn dispatch_async_f(
+        queue: *const DispatchQueue,
+        context: *mut c_void,
+        work: unsafe extern "C" fn(*mut c_void),
+    );
+    fn dispatch_after_f(
+        when: u64,
+        queue: *const DispatchQueue,
+        context: *mut c_void,
+        work: unsafe extern "C" fn(*mut c_void),
+    );
+    fn dispatch_source_create(
+        source_type: *const DispatchSourceType,
+        handle: usize,
+        mask: usize,
+        queue: *const DispatchQueue,
+    ) -> *mut DispatchSource;
+    fn dispatch_source_get_data(source: *mut DispatchSource) -> usize;
+    fn dispatch_source_set_event_handler_f(
+        source: *mut DispatchSource,
+        handler: unsafe extern "C" fn(*mut c_void),
+    );
+    fn dispatch_set_context(object: *mut DispatchSource, context: *mut c_void);
+    fn dispatch_resume(object: *mut DispatchSource);
+}
+
+#[cfg(target_os = "macos")]
+const DISPATCH_TIME_NOW: u64 = 0;
+#[cfg(target_os = "macos")]
+const DISPATCH_MEMORYPRESSURE_NORMAL: usize = 0x01;
+#[cfg(target_os = "macos")]
+const DISPATCH_MEMORYPRESSURE_WARN: usize = 0x02;
+#[cfg(target_os = "macos")]
+const DISPATCH_MEMORYPRESSURE_CRITICAL: usize = 0x04;
+
 #[cfg(target_os = "macos")]
 static NSAPP_HANDLING_SEND_EVENT: AtomicBool = AtomicBool::new(false);
 #[cfg(target_os = "macos")]
 static CEF_QUIT_MESSAGE_LOOP_FN: AtomicUsize = AtomicUsize::new(0);
+#[cfg(target_os = "macos")]
+static CEF_STRING_USERFREE_FREE_FN: AtomicUsize = AtomicUsize::new(0);
+// Consulted from CEF's IO thread, so unlike the rest of the callback state
+// this cannot be thread-local.
+#[cfg(target_os = "macos")]
+static REQUEST_FILTERS: OnceLock<Mutex<HashMap<ContentViewId, Arc<dyn RequestFilter>>>> =
+    OnceLock::new();
 
 #[cfg(target_os = "macos")]
 #[derive(Debug, Clone, Copy)]
@@ -677,21 +1264,53 @@ struct SwitchboardUiSchemeFactory {
 #[repr(C)]
 struct SwitchboardUiResourceHandler {
     handler: cef_resource_handler_t,
+    resource: UiResource,
     offset: usize,
 }
 
 #[cfg(target_os = "macos")]
-#[repr(C)]
-struct SwitchboardUiJsDialogHandler {
-    handler: cef_jsdialog_handler_t,
+enum UiResource {
+    Shell,
+    Favicon {
+        mime_type: &'static str,
+        bytes: Vec<u8>,
+    },
+    NotFound,
 }
 
 #[cfg(target_os = "macos")]
-#[repr(C)]
-struct SwitchboardUiClient {
-    client: cef_client_t,
-    jsdialog_handler: *mut cef_jsdialog_handler_t,
-}
+impl UiResource {
+    fn for_url(url: &str) -> Self {
+        if !url.starts_with(FAVICON_URL_PREFIX) {
+            return Self::Shell;
+        }
+        match favicon_resource(url) {
+            Some((mime_type, bytes)) => Self::Favicon { mime_type, bytes },
+            None => Self::NotFound,
+        }
+    }
+
+    fn body(&self) -> &[u8] {
+        match self {
+            Self::Shell => ui_shell_body(),
+            Self::Favicon { bytes, .. } => bytes,
+            Self::NotFound => &[],
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+#[repr(C)]
+struct SwitchboardUiJsDialogHandler {
+    handler: cef_jsdialog_handler_t,
+}
+
+#[cfg(target_os = "macos")]
+#[repr(C)]
+struct SwitchboardUiClient {
+    client: cef_client_t,
+    jsdialog_handler: *mut cef_jsdialog_handler_t,
+}
 
 #[cfg(target_os = "macos")]
 #[repr(C)]
@@ -699,11 +1318,104 @@ struct SwitchboardContentDisplayHandler {
     handler: cef_display_handler_t,
 }
 
+#[cfg(target_os = "macos")]
+#[repr(C)]
+struct SwitchboardContentLoadHandler {
+    handler: cef_load_handler_t,
+    view_id: ContentViewId,
+}
+
+#[cfg(target_os = "macos")]
+#[repr(C)]
+struct SwitchboardContentRequestHandler {
+    handler: cef_request_handler_t,
+    resource_request_handler: *mut cef_resource_request_handler_t,
+    view_id: ContentViewId,
+}
+
+#[cfg(target_os = "macos")]
+#[repr(C)]
+struct SwitchboardContentResourceRequestHandler {
+    handler: cef_resource_request_handler_t,
+    view_id: ContentViewId,
+}
+
+#[cfg(target_os = "macos")]
+#[repr(C)]
+struct SwitchboardContentPermissionHandler {
+    handler: cef_permission_handler_t,
+    view_id: ContentViewId,
+}
+
+#[cfg(target_os = "macos")]
+#[repr(C)]
+struct SwitchboardContentDownloadHandler {
+    handler: cef_download_handler_t,
+    view_id: ContentViewId,
+}
+
+#[cfg(target_os = "macos")]
+#[repr(C)]
+struct SwitchboardContentLifeSpanHandler {
+    handler: cef_life_span_handler_t,
+    view_id: ContentViewId,
+}
+
+#[cfg(target_os = "macos")]
+#[repr(C)]
+struct SwitchboardContentAudioHandler {
+    handler: cef_audio_handler_t,
+    view_id: ContentViewId,
+}
+
 #[cfg(target_os = "macos")]
 #[repr(C)]
 struct SwitchboardContentClient {
     client: cef_client_t,
     display_handler: *mut cef_display_handler_t,
+    load_handler: *mut cef_load_handler_t,
+    request_handler: *mut cef_request_handler_t,
+    permission_handler: *mut cef_permission_handler_t,
+    download_handler: *mut cef_download_handler_t,
+    life_span_handler: *mut cef_life_span_handler_t,
+    audio_handler: *mut cef_audio_handler_t,
+}
+
+#[cfg(target_os = "macos")]
+#[derive(Clone, Copy)]
+struct CefStringListApi {
+    size: cef_string_list_size_fn,
+    value: cef_string_list_value_fn,
+    clear: cef_string_utf16_clear_fn,
+}
+
+// A download the runtime has begun, keyed by CEF's download id. CEF does not
+// report pauses on the item, so the host remembers them for progress events.
+#[cfg(target_os = "macos")]
+struct ActiveDownload {
+    download_id: DownloadId,
+    callback: *mut cef_download_item_callback_t,
+    paused: bool,
+}
+
+#[cfg(target_os = "macos")]
+#[derive(Debug, Clone, Copy)]
+enum PermissionCallback {
+    Prompt(*mut cef_permission_prompt_callback_t),
+    MediaAccess(*mut cef_media_access_callback_t),
+}
+
+// A CEF permission callback held until the runtime decides. Each permission
+// it asks for is reported as its own request, and CEF is answered once all
+// of them are settled.
+#[cfg(target_os = "macos")]
+struct PendingPermission {
+    callback: PermissionCallback,
+    prompt_id: Option<u64>,
+    // Undecided requests with the CEF permission bits each stands for.
+    parts: Vec<(PermissionRequestId, u32)>,
+    granted_bits: u32,
+    all_granted: bool,
 }
 
 #[cfg(target_os = "macos")]
@@ -870,6 +1582,45 @@ unsafe fn cef_string_to_owned(value: *const cef_string_t) -> String {
     String::from_utf16_lossy(units)
 }
 
+// Takes ownership of a string CEF allocated for us.
+#[cfg(target_os = "macos")]
+unsafe fn cef_userfree_to_owned(value: cef_string_userfree_t) -> String {
+    if value.is_null() {
+        return String::new();
+    }
+    let owned = cef_string_to_owned(value);
+    let raw = CEF_STRING_USERFREE_FREE_FN.load(Ordering::Acquire);
+    if raw != 0 {
+        let free: cef_string_userfree_utf16_free_fn = std::mem::transmute(raw);
+        free(value);
+    }
+    owned
+}
+
+// Keeps a CEF object alive past the callback that handed it to us.
+#[cfg(target_os = "macos")]
+unsafe fn add_ref_counted<T>(value: *mut T) {
+    if value.is_null() {
+        return;
+    }
+    let base = value as *mut cef_base_ref_counted_t;
+    if let Some(add_ref) = (*base).add_ref {
+        add_ref(base);
+    }
+}
+
+// Drops the reference CEF added to an object it handed back to us.
+#[cfg(target_os = "macos")]
+unsafe fn release_ref_counted<T>(value: *mut T) {
+    if value.is_null() {
+        return;
+    }
+    let base = value as *mut cef_base_ref_counted_t;
+    if let Some(release) = (*base).release {
+        release(base);
+    }
+}
+
 #[cfg(target_os = "macos")]
 fn with_stack_cef_string(value: &str, callback: impl FnOnce(*const cef_string_t)) {
     let utf16: Vec<u16> = value.encode_utf16().collect();
@@ -940,6 +1691,29 @@ fn install_nsapplication_event_shim() -> Result<(), HostError> {
     Ok(())
 }
 
+#[cfg(target_os = "macos")]
+unsafe extern "C" fn switchboard_emit_delayed_content_event(context: *mut c_void) {
+    let event = Box::from_raw(context as *mut ContentEvent);
+    emit_content_event(*event);
+}
+
+// Reports `event` from the main queue once `delay_ms` has passed. Both CEF's
+// message loop and the plain NSApp loop drain that queue, so this works with
+// either content backend.
+#[cfg(target_os = "macos")]
+fn emit_content_event_after(event: ContentEvent, delay_ms: u64) {
+    let delay_ns = i64::try_from(delay_ms.saturating_mul(1_000_000)).unwrap_or(i64::MAX);
+    let context = Box::into_raw(Box::new(event)) as *mut c_void;
+    unsafe {
+        dispatch_after_f(
+            dispatch_time(DISPATCH_TIME_NOW, delay_ns),
+            &_dispatch_main_q,
+            context,
+            switchboard_emit_delayed_content_event,
+        );
+    }
+}
+
 #[cfg(target_os = "macos")]
 fn install_cef_quit_message_loop_hook(quit: unsafe extern "C" fn()) {
     CEF_QUIT_MESSAGE_LOOP_FN.store(quit as usize, Ordering::Release);
@@ -950,6 +1724,53 @@ fn clear_cef_quit_message_loop_hook() {
     CEF_QUIT_MESSAGE_LOOP_FN.store(0, Ordering::Release);
 }
 
+#[cfg(target_os = "macos")]
+fn install_cef_string_userfree_free(free: cef_string_userfree_utf16_free_fn) {
+    CEF_STRING_USERFREE_FREE_FN.store(free as usize, Ordering::Release);
+}
+
+#[cfg(target_os = "macos")]
+fn clear_cef_string_userfree_free() {
+    CEF_STRING_USERFREE_FREE_FN.store(0, Ordering::Release);
+}
+
+#[cfg(target_os = "macos")]
+fn install_cef_string_list_api(library: &CefLibrary) {
+    CEF_STRING_LIST_API.with(|slot| {
+        slot.set(Some(CefStringListApi {
+            size: library.api.cef_string_list_size,
+            value: library.api.cef_string_list_value,
+            clear: library.api.cef_string_utf16_clear,
+        }));
+    });
+}
+
+#[cfg(target_os = "macos")]
+fn clear_cef_string_list_api() {
+    CEF_STRING_LIST_API.with(|slot| slot.set(None));
+}
+
+#[cfg(target_os = "macos")]
+unsafe fn cef_string_list_to_owned(list: cef_string_list_t) -> Vec<String> {
+    let Some(api) = CEF_STRING_LIST_API.with(Cell::get) else {
+        return Vec::new();
+    };
+    if list.is_null() {
+        return Vec::new();
+    }
+    (0..(api.size)(list))
+        .filter_map(|index| {
+            let mut value: cef_string_t = zeroed();
+            if (api.value)(list, index, &mut value) == 0 {
+                return None;
+            }
+            let owned = cef_string_to_owned(&value);
+            (api.clear)(&mut value);
+            Some(owned)
+        })
+        .collect()
+}
+
 #[cfg(target_os = "macos")]
 fn quit_cef_message_loop_if_available() {
     let raw = CEF_QUIT_MESSAGE_LOOP_FN.load(Ordering::Acquire);
@@ -1142,6 +1963,7 @@ enum UiPromptAction {
     Intent(UiCommand),
     QueryActiveUri,
     QueryShellState,
+    Query(UiQuery),
     UiOverlay { visible: bool },
     UiReady,
 }
@@ -1154,6 +1976,7 @@ fn is_allowed_setting_key(key: &str) -> bool {
             | "homepage"
             | "new_tab_behavior"
             | "new_tab_custom_url"
+            | "startup.restore_mode"
             | "keybinding_close_tab"
             | "keybinding_command_palette"
             | "keybinding_focus_navigation"
@@ -1162,12 +1985,22 @@ fn is_allowed_setting_key(key: &str) -> bool {
             | "password_manager.default_autofill"
             | "password_manager.default_save_prompt"
             | "password_manager.default_fallback"
+            | "history.retention_days"
+            | "history.max_visits"
     );
     fixed_key_allowed
         || is_profile_scoped_setting_key(key, "password_manager.provider.profile.")
         || is_profile_scoped_setting_key(key, "password_manager.autofill.profile.")
         || is_profile_scoped_setting_key(key, "password_manager.save_prompt.profile.")
         || is_profile_scoped_setting_key(key, "password_manager.fallback.profile.")
+        || is_profile_scoped_setting_key(key, "ephemeral.profile.")
+        || is_profile_scoped_setting_key(key, BLOCKED_DOMAINS_PROFILE_PREFIX)
+        || is_profile_scoped_setting_key(key, ALLOWED_DOMAINS_PROFILE_PREFIX)
+        || is_profile_scoped_setting_key(key, HTTPS_ONLY_PROFILE_PREFIX)
+        || is_profile_scoped_setting_key(key, CONTENT_FILTER_ENABLED_PROFILE_PREFIX)
+        || is_profile_scoped_setting_key(key, CONTENT_FILTER_ALLOWLIST_PROFILE_PREFIX)
+        || is_profile_scoped_setting_key(key, DOWNLOAD_DIRECTORY_PROFILE_PREFIX)
+        || is_lifecycle_setting(key)
 }
 
 #[cfg(target_os = "macos")]
@@ -1178,6 +2011,44 @@ fn is_profile_scoped_setting_key(key: &str, prefix: &str) -> bool {
     !suffix.is_empty() && suffix.chars().all(|ch| ch.is_ascii_digit())
 }
 
+// The path goes last so it may contain spaces.
+#[cfg(target_os = "macos")]
+fn parse_id_and_path(rest: &str) -> Result<(u64, String), &'static str> {
+    let mut parts = rest.trim().splitn(2, ' ');
+    let id = parts
+        .next()
+        .unwrap_or_default()
+        .parse::<u64>()
+        .map_err(|_| "file commands require a numeric id")?;
+    let path = parts.next().unwrap_or_default().trim();
+    let path = match path.strip_prefix("~/") {
+        Some(relative) => {
+            let home = std::env::var("HOME").map_err(|_| "HOME is not set")?;
+            format!("{home}/{relative}")
+        }
+        None => path.to_owned(),
+    };
+    if !path.starts_with('/') {
+        return Err("file commands require an absolute path");
+    }
+    Ok((id, path))
+}
+
+// "root" stands for the profile's top level.
+#[cfg(target_os = "macos")]
+fn parse_bookmark_folder_id(raw: &str) -> Result<Option<u64>, &'static str> {
+    if raw == "root" {
+        return Ok(None);
+    }
+    raw.parse::<u64>()
+        .map(Some)
+        .map_err(|_| "bookmark folder must be a numeric id or root")
+}
+
+// Prefix, usage message and constructor for commands that take one id.
+#[cfg(target_os = "macos")]
+type IdPromptCommand = (&'static str, &'static str, fn(u64) -> UiCommand);
+
 #[cfg(target_os = "macos")]
 fn parse_ui_prompt_payload(payload: &str) -> Result<UiPromptAction, &'static str> {
     let trimmed = payload.trim();
@@ -1187,6 +2058,54 @@ fn parse_ui_prompt_payload(payload: &str) -> Result<UiPromptAction, &'static str
     if trimmed == "query_shell_state" {
         return Ok(UiPromptAction::QueryShellState);
     }
+    if trimmed == "query_history" {
+        return Ok(UiPromptAction::Query(UiQuery::History(String::new())));
+    }
+    if let Some(text) = trimmed.strip_prefix("query_history ") {
+        return Ok(UiPromptAction::Query(UiQuery::History(
+            text.trim().to_owned(),
+        )));
+    }
+    if trimmed == "query_metrics" {
+        return Ok(UiPromptAction::Query(UiQuery::Metrics));
+    }
+    if trimmed == "reset_metrics" {
+        return Ok(UiPromptAction::Intent(UiCommand::ResetMetrics));
+    }
+    if let Some(text) = trimmed.strip_prefix("query_suggestions ") {
+        return Ok(UiPromptAction::Query(UiQuery::Suggestions(
+            text.trim().to_owned(),
+        )));
+    }
+    if let Some(rest) = trimmed.strip_prefix("clear_history ") {
+        let mut parts = rest.split_whitespace();
+        let profile_id = parts
+            .next()
+            .ok_or("clear_history requires profile id")?
+            .parse::<u64>()
+            .map_err(|_| "clear_history requires a numeric profile id")?;
+        let scope = match (parts.next(), parts.next(), parts.next()) {
+            (None | Some("all"), None, None) => HistoryClearScope::All,
+            (Some("range"), Some(start), Some(end)) => HistoryClearScope::TimeRange {
+                start_ms: start
+                    .parse::<i64>()
+                    .map_err(|_| "clear_history range requires numeric bounds")?,
+                end_ms: end
+                    .parse::<i64>()
+                    .map_err(|_| "clear_history range requires numeric bounds")?,
+            },
+            (Some("domain"), Some(domain), None) => HistoryClearScope::Domain(domain.to_owned()),
+            _ => {
+                return Err(
+                    "clear_history scope must be all, range <start> <end>, or domain <host>",
+                )
+            }
+        };
+        return Ok(UiPromptAction::Intent(UiCommand::ClearHistory {
+            profile_id,
+            scope,
+        }));
+    }
     if let Some(raw_name) = trimmed.strip_prefix("new_profile ") {
         let name = raw_name.trim();
         if name.is_empty() {
@@ -1314,13 +2233,214 @@ fn parse_ui_prompt_payload(payload: &str) -> Result<UiPromptAction, &'static str
         }));
     }
     if let Some(url) = trimmed.strip_prefix("navigate ") {
-        let normalized = url.trim();
-        if normalized.starts_with("https://") || normalized.starts_with("http://") {
-            return Ok(UiPromptAction::Intent(UiCommand::NavigateActive {
-                url: normalized.to_owned(),
+        let url = parse_web_url(url).map_err(|_| "navigate intents only allow http/https URLs")?;
+        return Ok(UiPromptAction::Intent(UiCommand::NavigateActive {
+            url: url.to_string(),
+        }));
+    }
+    if let Some(text) = trimmed.strip_prefix("navigate_input ") {
+        let text = text.trim();
+        if text.is_empty() {
+            return Err("navigate_input requires text");
+        }
+        return Ok(UiPromptAction::Intent(UiCommand::NavigateInput {
+            text: text.to_owned(),
+        }));
+    }
+    if let Some(rest) = trimmed.strip_prefix("add_search_engine ") {
+        let (profile_id, keyword, url_template, name) = parse_search_engine_fields(
+            rest,
+            "add_search_engine requires <profile_id> <keyword> <url_template> <name>",
+        )?;
+        return Ok(UiPromptAction::Intent(UiCommand::AddSearchEngine {
+            profile_id,
+            name,
+            keyword,
+            url_template,
+        }));
+    }
+    if let Some(rest) = trimmed.strip_prefix("update_search_engine ") {
+        let (engine_id, keyword, url_template, name) = parse_search_engine_fields(
+            rest,
+            "update_search_engine requires <engine_id> <keyword> <url_template> <name>",
+        )?;
+        return Ok(UiPromptAction::Intent(UiCommand::UpdateSearchEngine {
+            engine_id,
+            name,
+            keyword,
+            url_template,
+        }));
+    }
+    if let Some(value) = trimmed.strip_prefix("remove_search_engine ") {
+        let engine_id = value
+            .trim()
+            .parse::<u64>()
+            .map_err(|_| "remove_search_engine requires a numeric engine id")?;
+        return Ok(UiPromptAction::Intent(UiCommand::RemoveSearchEngine {
+            engine_id,
+        }));
+    }
+    if let Some(value) = trimmed.strip_prefix("set_default_search_engine ") {
+        let engine_id = value
+            .trim()
+            .parse::<u64>()
+            .map_err(|_| "set_default_search_engine requires a numeric engine id")?;
+        return Ok(UiPromptAction::Intent(UiCommand::SetDefaultSearchEngine {
+            engine_id,
+        }));
+    }
+    for (prefix, keep_alive) in [("keep_alive_tab ", true), ("allow_discard_tab ", false)] {
+        if let Some(value) = trimmed.strip_prefix(prefix) {
+            let tab_id = value
+                .trim()
+                .parse::<u64>()
+                .map_err(|_| "keep_alive_tab/allow_discard_tab require a numeric tab id")?;
+            return Ok(UiPromptAction::Intent(UiCommand::SetTabKeepAlive {
+                tab_id,
+                keep_alive,
+            }));
+        }
+    }
+    for (prefix, muted) in [("mute_tab ", true), ("unmute_tab ", false)] {
+        if let Some(value) = trimmed.strip_prefix(prefix) {
+            let tab_id = value
+                .trim()
+                .parse::<u64>()
+                .map_err(|_| "mute_tab/unmute_tab require a numeric tab id")?;
+            return Ok(UiPromptAction::Intent(UiCommand::SetTabMuted {
+                tab_id,
+                muted,
             }));
         }
-        return Err("navigate intents only allow http/https URLs");
+    }
+    if let Some(value) = trimmed.strip_prefix("mute_other_tabs ") {
+        let tab_id = value
+            .trim()
+            .parse::<u64>()
+            .map_err(|_| "mute_other_tabs requires a numeric tab id")?;
+        return Ok(UiPromptAction::Intent(UiCommand::MuteOtherTabs { tab_id }));
+    }
+    for (prefix, muted) in [("mute_workspace ", true), ("unmute_workspace ", false)] {
+        if let Some(value) = trimmed.strip_prefix(prefix) {
+            let workspace_id = value
+                .trim()
+                .parse::<u64>()
+                .map_err(|_| "mute_workspace/unmute_workspace require a numeric workspace id")?;
+            return Ok(UiPromptAction::Intent(UiCommand::SetWorkspaceMuted {
+                workspace_id,
+                muted,
+            }));
+        }
+    }
+    if let Some(value) = trimmed.strip_prefix("reload_tab ") {
+        let tab_id = value
+            .trim()
+            .parse::<u64>()
+            .map_err(|_| "reload_tab requires a numeric tab id")?;
+        return Ok(UiPromptAction::Intent(UiCommand::ReloadTab { tab_id }));
+    }
+    if let Some(value) = trimmed.strip_prefix("continue_insecure ") {
+        let tab_id = value
+            .trim()
+            .parse::<u64>()
+            .map_err(|_| "continue_insecure requires a numeric tab id")?;
+        return Ok(UiPromptAction::Intent(UiCommand::ContinueInsecure {
+            tab_id,
+        }));
+    }
+    if let Some(rest) = trimmed.strip_prefix("add_https_exception ") {
+        let (profile_id, host) =
+            parse_profile_host(rest, "add_https_exception requires <profile_id> <host>")?;
+        return Ok(UiPromptAction::Intent(UiCommand::AddHttpsException {
+            profile_id,
+            host,
+        }));
+    }
+    if let Some(rest) = trimmed.strip_prefix("remove_https_exception ") {
+        let (profile_id, host) =
+            parse_profile_host(rest, "remove_https_exception requires <profile_id> <host>")?;
+        return Ok(UiPromptAction::Intent(UiCommand::RemoveHttpsException {
+            profile_id,
+            host,
+        }));
+    }
+    if let Some(rest) = trimmed.strip_prefix("set_site_permission ") {
+        const USAGE: &str = "set_site_permission requires <profile_id> <origin> <kind> <setting>";
+        let parts: Vec<&str> = rest.split_whitespace().collect();
+        let [profile_id, origin, kind, setting] = parts.as_slice() else {
+            return Err(USAGE);
+        };
+        return Ok(UiPromptAction::Intent(UiCommand::SetSitePermission {
+            profile_id: profile_id.parse::<u64>().map_err(|_| USAGE)?,
+            origin: (*origin).to_owned(),
+            kind: PermissionKind::parse(kind).ok_or(USAGE)?,
+            setting: PermissionSetting::parse(setting).ok_or(USAGE)?,
+        }));
+    }
+    let download_commands: [IdPromptCommand; 5] = [
+        (
+            "pause_download ",
+            "pause_download requires a numeric download id",
+            |download_id| UiCommand::PauseDownload { download_id },
+        ),
+        (
+            "resume_download ",
+            "resume_download requires a numeric download id",
+            |download_id| UiCommand::ResumeDownload { download_id },
+        ),
+        (
+            "cancel_download ",
+            "cancel_download requires a numeric download id",
+            |download_id| UiCommand::CancelDownload { download_id },
+        ),
+        (
+            "remove_download ",
+            "remove_download requires a numeric download id",
+            |download_id| UiCommand::RemoveDownload { download_id },
+        ),
+        (
+            "open_download_folder ",
+            "open_download_folder requires a numeric download id",
+            |download_id| UiCommand::OpenDownloadFolder { download_id },
+        ),
+    ];
+    for (prefix, usage, command) in download_commands {
+        if let Some(value) = trimmed.strip_prefix(prefix) {
+            let download_id = value.trim().parse::<u64>().map_err(|_| usage)?;
+            return Ok(UiPromptAction::Intent(command(download_id)));
+        }
+    }
+    if let Some(rest) = trimmed.strip_prefix("resolve_permission ") {
+        const USAGE: &str = "resolve_permission requires <request_id> <allow|deny> [remember]";
+        let parts: Vec<&str> = rest.split_whitespace().collect();
+        let (request_id, decision, remember) = match parts.as_slice() {
+            [request_id, decision] => (request_id, decision, false),
+            [request_id, decision, "remember"] => (request_id, decision, true),
+            _ => return Err(USAGE),
+        };
+        return Ok(UiPromptAction::Intent(UiCommand::ResolvePermission {
+            request_id: request_id.parse::<u64>().map_err(|_| USAGE)?,
+            decision: PermissionDecision::parse(decision).ok_or(USAGE)?,
+            remember,
+        }));
+    }
+    if let Some(rest) = trimmed.strip_prefix("reset_site_permission ") {
+        const USAGE: &str = "reset_site_permission requires <profile_id> <origin> [kind]";
+        let parts: Vec<&str> = rest.split_whitespace().collect();
+        let (profile_id, origin, kind) = match parts.as_slice() {
+            [profile_id, origin] => (profile_id, origin, None),
+            [profile_id, origin, kind] => (
+                profile_id,
+                origin,
+                Some(PermissionKind::parse(kind).ok_or(USAGE)?),
+            ),
+            _ => return Err(USAGE),
+        };
+        return Ok(UiPromptAction::Intent(UiCommand::ResetSitePermission {
+            profile_id: profile_id.parse::<u64>().map_err(|_| USAGE)?,
+            origin: (*origin).to_owned(),
+            kind,
+        }));
     }
     if trimmed == "devtools_toggle" {
         return Ok(UiPromptAction::Intent(UiCommand::ToggleDevTools));
@@ -1384,6 +2504,140 @@ fn parse_ui_prompt_payload(payload: &str) -> Result<UiPromptAction, &'static str
             value: SettingValue::Bool(value),
         }));
     }
+    if let Some(rest) = trimmed.strip_prefix("export_workspace ") {
+        let (workspace_id, path) = parse_id_and_path(rest)?;
+        return Ok(UiPromptAction::Intent(UiCommand::ExportWorkspace {
+            workspace_id,
+            path,
+        }));
+    }
+    if let Some(rest) = trimmed.strip_prefix("export_profile ") {
+        let (profile_id, path) = parse_id_and_path(rest)?;
+        return Ok(UiPromptAction::Intent(UiCommand::ExportProfile {
+            profile_id,
+            path,
+        }));
+    }
+    if let Some(rest) = trimmed.strip_prefix("import_workspaces ") {
+        let (profile_id, path) = parse_id_and_path(rest)?;
+        return Ok(UiPromptAction::Intent(UiCommand::ImportWorkspaces {
+            profile_id,
+            path,
+        }));
+    }
+    if let Some(rest) = trimmed.strip_prefix("add_bookmark ") {
+        let mut parts = rest.trim().splitn(4, ' ');
+        let profile_id = parts
+            .next()
+            .unwrap_or_default()
+            .parse::<u64>()
+            .map_err(|_| "add_bookmark requires a numeric profile id")?;
+        let folder_id = parse_bookmark_folder_id(parts.next().unwrap_or_default())?;
+        let url = parts.next().unwrap_or_default();
+        if !url.starts_with("https://") && !url.starts_with("http://") {
+            return Err("add_bookmark only allows http/https URLs");
+        }
+        let title = parts.next().unwrap_or_default().trim();
+        return Ok(UiPromptAction::Intent(UiCommand::AddBookmark {
+            profile_id,
+            folder_id,
+            url: url.to_owned(),
+            title: title.to_owned(),
+        }));
+    }
+    if let Some(value) = trimmed.strip_prefix("remove_bookmark ") {
+        let bookmark_id = value
+            .trim()
+            .parse::<u64>()
+            .map_err(|_| "remove_bookmark requires a numeric bookmark id")?;
+        return Ok(UiPromptAction::Intent(UiCommand::RemoveBookmark {
+            bookmark_id,
+        }));
+    }
+    if let Some(rest) = trimmed.strip_prefix("rename_bookmark ") {
+        let mut parts = rest.trim().splitn(2, ' ');
+        let bookmark_id = parts
+            .next()
+            .unwrap_or_default()
+            .parse::<u64>()
+            .map_err(|_| "rename_bookmark requires a numeric bookmark id")?;
+        let title = parts.next().unwrap_or_default().trim();
+        return Ok(UiPromptAction::Intent(UiCommand::RenameBookmark {
+            bookmark_id,
+            title: title.to_owned(),
+        }));
+    }
+    if let Some(rest) = trimmed.strip_prefix("move_bookmark ") {
+        let mut parts = rest.trim().split(' ');
+        let bookmark_id = parts
+            .next()
+            .unwrap_or_default()
+            .parse::<u64>()
+            .map_err(|_| "move_bookmark requires a numeric bookmark id")?;
+        let folder_id = parse_bookmark_folder_id(parts.next().unwrap_or_default())?;
+        let index = parts
+            .next()
+            .unwrap_or_default()
+            .parse::<usize>()
+            .map_err(|_| "move_bookmark requires a numeric index")?;
+        return Ok(UiPromptAction::Intent(UiCommand::MoveBookmark {
+            bookmark_id,
+            folder_id,
+            index,
+        }));
+    }
+    if let Some(rest) = trimmed.strip_prefix("create_bookmark_folder ") {
+        let mut parts = rest.trim().splitn(3, ' ');
+        let profile_id = parts
+            .next()
+            .unwrap_or_default()
+            .parse::<u64>()
+            .map_err(|_| "create_bookmark_folder requires a numeric profile id")?;
+        let parent_id = parse_bookmark_folder_id(parts.next().unwrap_or_default())?;
+        let name = parts.next().unwrap_or_default().trim();
+        if name.is_empty() {
+            return Err("bookmark folder name cannot be empty");
+        }
+        return Ok(UiPromptAction::Intent(UiCommand::CreateBookmarkFolder {
+            profile_id,
+            parent_id,
+            name: name.to_owned(),
+        }));
+    }
+    if let Some(rest) = trimmed.strip_prefix("rename_bookmark_folder ") {
+        let mut parts = rest.trim().splitn(2, ' ');
+        let folder_id = parts
+            .next()
+            .unwrap_or_default()
+            .parse::<u64>()
+            .map_err(|_| "rename_bookmark_folder requires a numeric folder id")?;
+        let name = parts.next().unwrap_or_default().trim();
+        if name.is_empty() {
+            return Err("bookmark folder name cannot be empty");
+        }
+        return Ok(UiPromptAction::Intent(UiCommand::RenameBookmarkFolder {
+            folder_id,
+            name: name.to_owned(),
+        }));
+    }
+    if let Some(value) = trimmed.strip_prefix("remove_bookmark_folder ") {
+        let folder_id = value
+            .trim()
+            .parse::<u64>()
+            .map_err(|_| "remove_bookmark_folder requires a numeric folder id")?;
+        return Ok(UiPromptAction::Intent(UiCommand::RemoveBookmarkFolder {
+            folder_id,
+        }));
+    }
+    if let Some(value) = trimmed.strip_prefix("open_bookmark_folder ") {
+        let folder_id = value
+            .trim()
+            .parse::<u64>()
+            .map_err(|_| "open_bookmark_folder requires a numeric folder id")?;
+        return Ok(UiPromptAction::Intent(
+            UiCommand::OpenBookmarkFolderAsWorkspace { folder_id },
+        ));
+    }
     if trimmed == "ui_overlay on" {
         return Ok(UiPromptAction::UiOverlay { visible: true });
     }
@@ -1397,10 +2651,46 @@ fn parse_ui_prompt_payload(payload: &str) -> Result<UiPromptAction, &'static str
 }
 
 #[cfg(target_os = "macos")]
-unsafe extern "C" fn switchboard_ui_on_jsdialog(
-    _self_: *mut cef_jsdialog_handler_t,
-    _browser: *mut cef_browser_t,
-    _origin_url: *const cef_string_t,
+fn parse_profile_host(rest: &str, usage: &'static str) -> Result<(u64, String), &'static str> {
+    let (profile_id, host) = rest.trim().split_once(' ').ok_or(usage)?;
+    let profile_id = profile_id.parse::<u64>().map_err(|_| usage)?;
+    let host = host.trim();
+    if host.is_empty() {
+        return Err(usage);
+    }
+    Ok((profile_id, host.to_owned()))
+}
+
+// `<id> <keyword> <url_template> <name...>`; the name is last so it may
+// contain spaces.
+#[cfg(target_os = "macos")]
+fn parse_search_engine_fields(
+    rest: &str,
+    usage: &'static str,
+) -> Result<(u64, String, String, String), &'static str> {
+    let mut parts = rest.trim().splitn(4, ' ').map(str::trim);
+    let id = parts
+        .next()
+        .and_then(|value| value.parse::<u64>().ok())
+        .ok_or(usage)?;
+    let mut next_field = || {
+        parts
+            .next()
+            .filter(|value| !value.is_empty())
+            .map(str::to_owned)
+            .ok_or(usage)
+    };
+    let keyword = next_field()?;
+    let url_template = next_field()?;
+    let name = next_field()?;
+    Ok((id, keyword, url_template, name))
+}
+
+#[cfg(target_os = "macos")]
+unsafe extern "C" fn switchboard_ui_on_jsdialog(
+    _self_: *mut cef_jsdialog_handler_t,
+    _browser: *mut cef_browser_t,
+    _origin_url: *const cef_string_t,
     dialog_type: switchboard_cef_sys::raw::cef_jsdialog_type_t,
     message_text: *const cef_string_t,
     default_prompt_text: *const cef_string_t,
@@ -1460,6 +2750,25 @@ unsafe extern "C" fn switchboard_ui_on_jsdialog(
             });
             1
         }
+        Ok(UiPromptAction::Query(query)) => {
+            if callback.is_null() {
+                if !suppress_message.is_null() {
+                    *suppress_message = 1;
+                }
+                return 0;
+            }
+            let Some(cont) = (*callback).cont else {
+                if !suppress_message.is_null() {
+                    *suppress_message = 1;
+                }
+                return 0;
+            };
+            let json = query_ui(query);
+            with_stack_cef_string(&json, |value| unsafe {
+                cont(callback, 1, value);
+            });
+            1
+        }
         Ok(UiPromptAction::QueryActiveUri) => {
             if callback.is_null() {
                 if !suppress_message.is_null() {
@@ -1576,31 +2885,40 @@ unsafe extern "C" fn switchboard_resource_handler_process_request(
 
 #[cfg(target_os = "macos")]
 unsafe extern "C" fn switchboard_resource_handler_get_response_headers(
-    _self_: *mut cef_resource_handler_t,
+    self_: *mut cef_resource_handler_t,
     response: *mut cef_response_t,
     response_length: *mut i64,
     _redirect_url: *mut cef_string_t,
 ) {
+    if self_.is_null() {
+        return;
+    }
+    let resource = &(*(self_ as *mut SwitchboardUiResourceHandler)).resource;
     if !response_length.is_null() {
-        *response_length = ui_shell_body().len() as i64;
+        *response_length = resource.body().len() as i64;
     }
     if response.is_null() {
         return;
     }
+    let (status, status_text, mime_type) = match resource {
+        UiResource::Shell => (200, "OK", "text/html"),
+        UiResource::Favicon { mime_type, .. } => (200, "OK", *mime_type),
+        UiResource::NotFound => (404, "Not Found", "text/plain"),
+    };
     if let Some(set_status) = (*response).set_status {
-        set_status(response, 200);
+        set_status(response, status);
     }
     if let Some(set_status_text) = (*response).set_status_text {
-        with_stack_cef_string("OK", |value| unsafe {
+        with_stack_cef_string(status_text, |value| unsafe {
             set_status_text(response, value);
         });
     }
     if let Some(set_mime_type) = (*response).set_mime_type {
-        with_stack_cef_string("text/html", |value| unsafe {
+        with_stack_cef_string(mime_type, |value| unsafe {
             set_mime_type(response, value);
         });
     }
-    if let Some(set_charset) = (*response).set_charset {
+    if let (UiResource::Shell, Some(set_charset)) = (resource, (*response).set_charset) {
         with_stack_cef_string("utf-8", |value| unsafe {
             set_charset(response, value);
         });
@@ -1644,7 +2962,7 @@ unsafe extern "C" fn switchboard_resource_handler_skip(
         }
         return 1;
     }
-    let total = ui_shell_body().len();
+    let total = (*this).resource.body().len();
     let remaining = total.saturating_sub((*this).offset);
     let to_skip = (bytes_to_skip as usize).min(remaining);
     (*this).offset += to_skip;
@@ -1669,7 +2987,7 @@ unsafe extern "C" fn switchboard_resource_handler_read(
         return 0;
     }
     let this = self_ as *mut SwitchboardUiResourceHandler;
-    let body = ui_shell_body();
+    let body = (*this).resource.body();
     let remaining = body.len().saturating_sub((*this).offset);
     if remaining == 0 {
         if !bytes_read.is_null() {
@@ -1717,8 +3035,16 @@ unsafe extern "C" fn switchboard_scheme_factory_create(
     _browser: *mut cef_browser_t,
     _frame: *mut cef_frame_t,
     _scheme_name: *const cef_string_t,
-    _request: *mut cef_request_t,
+    request: *mut cef_request_t,
 ) -> *mut cef_resource_handler_t {
+    let url = if request.is_null() {
+        String::new()
+    } else {
+        (*request)
+            .get_url
+            .map(|get_url| cef_userfree_to_owned(get_url(request)))
+            .unwrap_or_default()
+    };
     let handler = Box::new(SwitchboardUiResourceHandler {
         handler: cef_resource_handler_t {
             base: ref_counted_base::<cef_resource_handler_t>(),
@@ -1730,158 +3056,1140 @@ unsafe extern "C" fn switchboard_scheme_factory_create(
             read_response: Some(switchboard_resource_handler_read_response),
             cancel: Some(switchboard_resource_handler_cancel),
         },
-        offset: 0,
+        resource: UiResource::for_url(&url),
+        offset: 0,
+    });
+    let ptr = Box::into_raw(handler);
+    &mut (*ptr).handler as *mut cef_resource_handler_t
+}
+
+#[cfg(target_os = "macos")]
+fn allocate_ui_scheme_factory() -> *mut cef_scheme_handler_factory_t {
+    let factory = Box::new(SwitchboardUiSchemeFactory {
+        factory: cef_scheme_handler_factory_t {
+            base: ref_counted_base::<cef_scheme_handler_factory_t>(),
+            create: Some(switchboard_scheme_factory_create),
+        },
+    });
+    let ptr = Box::into_raw(factory);
+    unsafe { &mut (*ptr).factory as *mut cef_scheme_handler_factory_t }
+}
+
+#[cfg(target_os = "macos")]
+unsafe extern "C" fn switchboard_content_on_address_change(
+    _self_: *mut cef_display_handler_t,
+    browser: *mut cef_browser_t,
+    frame: *mut cef_frame_t,
+    url: *const cef_string_t,
+) {
+    if !frame.is_null() {
+        if let Some(is_main) = (*frame).is_main {
+            if is_main(frame) == 0 {
+                return;
+            }
+        }
+    }
+    let next = cef_string_to_owned(url);
+    if next.is_empty() {
+        return;
+    }
+    if !is_web_url(&next) {
+        return;
+    }
+    if !browser.is_null() {
+        remember_browser_for_active_tab(browser);
+    }
+    set_active_content_uri(next.clone());
+    if let Some(tab_id) = active_content_tab() {
+        emit_content_event(ContentEvent::UrlChanged {
+            tab_id,
+            url: next.clone(),
+        });
+    }
+    if env_flag(ENV_CEF_VERBOSE_ERRORS) {
+        eprintln!("switchboard-app: observed content URL change -> {next}");
+    }
+}
+
+#[cfg(target_os = "macos")]
+unsafe extern "C" fn switchboard_content_on_title_change(
+    _self_: *mut cef_display_handler_t,
+    browser: *mut cef_browser_t,
+    title: *const cef_string_t,
+) {
+    if !browser.is_null() {
+        remember_browser_for_active_tab(browser);
+    }
+    let next = cef_string_to_owned(title);
+    if let Some(tab_id) = active_content_tab() {
+        emit_content_event(ContentEvent::TitleChanged {
+            tab_id,
+            title: next,
+        });
+    }
+}
+
+#[cfg(target_os = "macos")]
+unsafe extern "C" fn switchboard_content_on_favicon_urlchange(
+    _self_: *mut cef_display_handler_t,
+    browser: *mut cef_browser_t,
+    icon_urls: cef_string_list_t,
+) {
+    if !browser.is_null() {
+        remember_browser_for_active_tab(browser);
+    }
+    let urls = cef_string_list_to_owned(icon_urls);
+    if let Some(tab_id) = active_content_tab() {
+        emit_content_event(ContentEvent::FaviconChanged { tab_id, urls });
+    }
+}
+
+#[cfg(target_os = "macos")]
+unsafe extern "C" fn switchboard_content_on_loading_progress_change(
+    _self_: *mut cef_display_handler_t,
+    browser: *mut cef_browser_t,
+    progress: f64,
+) {
+    let mut is_loading = progress < 0.999_999;
+    if !browser.is_null() {
+        remember_browser_for_active_tab(browser);
+        if let Some(is_loading_fn) = (*browser).is_loading {
+            is_loading = is_loading_fn(browser) != 0;
+        }
+    }
+    if let Some(tab_id) = active_content_tab() {
+        emit_content_event(ContentEvent::LoadingChanged { tab_id, is_loading });
+    }
+}
+
+#[cfg(target_os = "macos")]
+unsafe extern "C" fn switchboard_content_client_get_display_handler(
+    self_: *mut cef_client_t,
+) -> *mut cef_display_handler_t {
+    if self_.is_null() {
+        return std::ptr::null_mut();
+    }
+    let client = self_ as *mut SwitchboardContentClient;
+    (*client).display_handler
+}
+
+#[cfg(target_os = "macos")]
+fn allocate_content_display_handler() -> *mut cef_display_handler_t {
+    let handler = Box::new(SwitchboardContentDisplayHandler {
+        handler: cef_display_handler_t {
+            base: ref_counted_base::<cef_display_handler_t>(),
+            on_address_change: Some(switchboard_content_on_address_change),
+            on_title_change: Some(switchboard_content_on_title_change),
+            on_favicon_urlchange: Some(switchboard_content_on_favicon_urlchange),
+            on_fullscreen_mode_change: None,
+            on_tooltip: None,
+            on_status_message: None,
+            on_console_message: None,
+            on_auto_resize: None,
+            on_loading_progress_change: Some(switchboard_content_on_loading_progress_change),
+            on_cursor_change: None,
+            on_media_access_change: None,
+            on_contents_bounds_change: None,
+            get_root_window_screen_rect: None,
+        },
+    });
+    let ptr = Box::into_raw(handler);
+    unsafe { &mut (*ptr).handler as *mut cef_display_handler_t }
+}
+
+// Downloaded icons are scaled to fit this many pixels on a side.
+#[cfg(target_os = "macos")]
+const FAVICON_DOWNLOAD_PIXELS: u32 = 64;
+
+// CEF decodes the icon it downloads, so it is encoded back to PNG for the
+// cache.
+#[cfg(target_os = "macos")]
+unsafe fn favicon_png(image: *mut cef_image_t) -> Option<Vec<u8>> {
+    let get_as_png = (*image).get_as_png?;
+    let mut pixel_width = 0;
+    let mut pixel_height = 0;
+    let png = get_as_png(image, 1.0, 1, &mut pixel_width, &mut pixel_height);
+    if png.is_null() {
+        return None;
+    }
+    let bytes = match ((*png).get_size, (*png).get_data) {
+        (Some(get_size), Some(get_data)) => {
+            let mut bytes = vec![0u8; get_size(png)];
+            let copied = get_data(png, bytes.as_mut_ptr() as *mut c_void, bytes.len(), 0);
+            bytes.truncate(copied);
+            Some(bytes).filter(|bytes| !bytes.is_empty())
+        }
+        _ => None,
+    };
+    release_ref_counted(png);
+    bytes
+}
+
+#[cfg(target_os = "macos")]
+unsafe extern "C" fn switchboard_on_favicon_download_finished(
+    _self_: *mut cef_download_image_callback_t,
+    image_url: *const cef_string_t,
+    _http_status_code: c_int,
+    image: *mut cef_image_t,
+) {
+    let icon_url = cef_string_to_owned(image_url);
+    let data = if image.is_null() {
+        None
+    } else {
+        favicon_png(image)
+    };
+    let tab_ids = PENDING_FAVICONS
+        .with(|pending| pending.borrow_mut().remove(&icon_url))
+        .unwrap_or_default();
+    for tab_id in tab_ids {
+        emit_content_event(ContentEvent::FaviconDownloaded {
+            tab_id,
+            icon_url: icon_url.clone(),
+            data: data.clone(),
+        });
+    }
+}
+
+// Every favicon download shares one callback; pending tabs are looked up by
+// the icon URL CEF reports back.
+#[cfg(target_os = "macos")]
+fn favicon_download_callback() -> *mut cef_download_image_callback_t {
+    FAVICON_DOWNLOAD_CALLBACK.with(|slot| {
+        if slot.get().is_null() {
+            slot.set(Box::into_raw(Box::new(cef_download_image_callback_t {
+                base: ref_counted_base::<cef_download_image_callback_t>(),
+                on_download_image_finished: Some(switchboard_on_favicon_download_finished),
+            })));
+        }
+        slot.get()
+    })
+}
+
+// Heap usage is the one memory figure DevTools reports without enabling a
+// domain first, so it stands in for the renderer's footprint.
+#[cfg(target_os = "macos")]
+const MEMORY_ESTIMATE_METHOD: &str = "Runtime.getHeapUsage";
+
+// Reads a top-level number from a DevTools reply; V8 reports sizes as plain
+// decimal numbers, so this avoids pulling in a JSON parser.
+#[cfg(target_os = "macos")]
+fn json_number_field(json: &str, field: &str) -> Option<u64> {
+    let key = format!("\"{field}\"");
+    let start = json.find(&key)? + key.len();
+    let rest = json[start..].trim_start().strip_prefix(':')?.trim_start();
+    let end = rest
+        .find(|c: char| !(c.is_ascii_digit() || matches!(c, '.' | 'e' | 'E' | '+' | '-')))
+        .unwrap_or(rest.len());
+    let value: f64 = rest[..end].parse().ok()?;
+    (value.is_finite() && value >= 0.0).then_some(value as u64)
+}
+
+#[cfg(target_os = "macos")]
+unsafe extern "C" fn switchboard_on_dev_tools_method_result(
+    _self_: *mut cef_dev_tools_message_observer_t,
+    _browser: *mut cef_browser_t,
+    message_id: c_int,
+    success: c_int,
+    result: *const c_void,
+    result_size: usize,
+) {
+    let Some(tab_id) =
+        PENDING_MEMORY_ESTIMATES.with(|pending| pending.borrow_mut().remove(&message_id))
+    else {
+        return;
+    };
+    if success == 0 || result.is_null() {
+        return;
+    }
+    let result = slice::from_raw_parts(result as *const u8, result_size);
+    if let Some(bytes) = json_number_field(&String::from_utf8_lossy(result), "totalSize") {
+        emit_content_event(ContentEvent::MemoryEstimated { tab_id, bytes });
+    }
+}
+
+// One observer serves every browser; replies are matched to tabs by the
+// message id, which is unique across browsers.
+#[cfg(target_os = "macos")]
+fn memory_estimate_observer() -> *mut cef_dev_tools_message_observer_t {
+    MEMORY_ESTIMATE_OBSERVER.with(|slot| {
+        if slot.get().is_null() {
+            slot.set(Box::into_raw(Box::new(cef_dev_tools_message_observer_t {
+                base: ref_counted_base::<cef_dev_tools_message_observer_t>(),
+                on_dev_tools_message: None,
+                on_dev_tools_method_result: Some(switchboard_on_dev_tools_method_result),
+                on_dev_tools_event: None,
+                on_dev_tools_agent_attached: None,
+                on_dev_tools_agent_detached: None,
+            })));
+        }
+        slot.get()
+    })
+}
+
+#[cfg(target_os = "macos")]
+unsafe extern "C" fn switchboard_on_memory_pressure(context: *mut c_void) {
+    let pressure = dispatch_source_get_data(context as *mut DispatchSource);
+    let level = if pressure & DISPATCH_MEMORYPRESSURE_CRITICAL != 0 {
+        MemoryPressureLevel::Critical
+    } else if pressure & DISPATCH_MEMORYPRESSURE_WARN != 0 {
+        MemoryPressureLevel::Moderate
+    } else {
+        MemoryPressureLevel::Normal
+    };
+    emit_content_event(ContentEvent::MemoryPressureChanged { level });
+}
+
+// The source lives as long as the app and reports every change on the main
+// queue, including the drop back to normal.
+#[cfg(target_os = "macos")]
+unsafe fn install_memory_pressure_source() -> Result<(), HostError> {
+    let source = dispatch_source_create(
+        &_dispatch_source_type_memorypressure,
+        0,
+        DISPATCH_MEMORYPRESSURE_NORMAL
+            | DISPATCH_MEMORYPRESSURE_WARN
+            | DISPATCH_MEMORYPRESSURE_CRITICAL,
+        &_dispatch_main_q,
+    );
+    if source.is_null() {
+        return Err(HostError::Native(
+            "failed to create memory pressure source".to_owned(),
+        ));
+    }
+    dispatch_set_context(source, source as *mut c_void);
+    dispatch_source_set_event_handler_f(source, switchboard_on_memory_pressure);
+    dispatch_resume(source);
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+unsafe extern "C" fn switchboard_content_on_load_error(
+    self_: *mut cef_load_handler_t,
+    _browser: *mut cef_browser_t,
+    frame: *mut cef_frame_t,
+    error_code: cef_errorcode_t,
+    _error_text: *const cef_string_t,
+    failed_url: *const cef_string_t,
+) {
+    if self_.is_null() {
+        return;
+    }
+    if !frame.is_null() {
+        if let Some(is_main) = (*frame).is_main {
+            if is_main(frame) == 0 {
+                return;
+            }
+        }
+    }
+    let handler = self_ as *mut SwitchboardContentLoadHandler;
+    let Some(tab_id) = content_view_tab((*handler).view_id) else {
+        return;
+    };
+    emit_content_event(ContentEvent::LoadFailed {
+        tab_id,
+        url: cef_string_to_owned(failed_url),
+        error_code,
+    });
+}
+
+#[cfg(target_os = "macos")]
+unsafe extern "C" fn switchboard_content_client_get_load_handler(
+    self_: *mut cef_client_t,
+) -> *mut cef_load_handler_t {
+    if self_.is_null() {
+        return std::ptr::null_mut();
+    }
+    let client = self_ as *mut SwitchboardContentClient;
+    (*client).load_handler
+}
+
+#[cfg(target_os = "macos")]
+fn allocate_content_load_handler(view_id: ContentViewId) -> *mut cef_load_handler_t {
+    let handler = Box::new(SwitchboardContentLoadHandler {
+        handler: cef_load_handler_t {
+            base: ref_counted_base::<cef_load_handler_t>(),
+            on_loading_state_change: None,
+            on_load_start: None,
+            on_load_end: None,
+            on_load_error: Some(switchboard_content_on_load_error),
+        },
+        view_id,
+    });
+    let ptr = Box::into_raw(handler);
+    unsafe { &mut (*ptr).handler as *mut cef_load_handler_t }
+}
+
+#[cfg(target_os = "macos")]
+fn request_filters() -> &'static Mutex<HashMap<ContentViewId, Arc<dyn RequestFilter>>> {
+    REQUEST_FILTERS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+#[cfg(target_os = "macos")]
+fn request_filter_for_view(view_id: ContentViewId) -> Option<Arc<dyn RequestFilter>> {
+    request_filters()
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(&view_id)
+        .cloned()
+}
+
+#[cfg(target_os = "macos")]
+fn filter_resource_type(resource_type: cef_resource_type_t) -> ResourceType {
+    match resource_type {
+        RT_MAIN_FRAME => ResourceType::Document,
+        RT_SUB_FRAME => ResourceType::Subdocument,
+        RT_STYLESHEET => ResourceType::Stylesheet,
+        RT_SCRIPT | RT_WORKER | RT_SHARED_WORKER | RT_SERVICE_WORKER => ResourceType::Script,
+        RT_IMAGE | RT_FAVICON => ResourceType::Image,
+        RT_FONT_RESOURCE => ResourceType::Font,
+        RT_OBJECT | RT_PLUGIN_RESOURCE => ResourceType::Object,
+        RT_MEDIA => ResourceType::Media,
+        RT_XHR => ResourceType::Xhr,
+        RT_PING | RT_CSP_REPORT => ResourceType::Ping,
+        _ => ResourceType::Other,
+    }
+}
+
+#[cfg(target_os = "macos")]
+unsafe fn main_frame_url(browser: *mut cef_browser_t) -> Option<String> {
+    if browser.is_null() {
+        return None;
+    }
+    let frame = (*browser).get_main_frame.map(|get| get(browser))?;
+    if frame.is_null() {
+        return None;
+    }
+    let url = (*frame)
+        .get_url
+        .map(|get_url| cef_userfree_to_owned(get_url(frame)));
+    release_ref_counted(frame);
+    url.filter(|url| !url.is_empty())
+}
+
+// Runs on CEF's IO thread for every request the view makes, navigations
+// included.
+#[cfg(target_os = "macos")]
+unsafe extern "C" fn switchboard_content_on_before_resource_load(
+    self_: *mut cef_resource_request_handler_t,
+    browser: *mut cef_browser_t,
+    _frame: *mut cef_frame_t,
+    request: *mut cef_request_t,
+    _callback: *mut cef_callback_t,
+) -> cef_return_value_t {
+    if self_.is_null() || request.is_null() {
+        return RV_CONTINUE;
+    }
+    let handler = self_ as *mut SwitchboardContentResourceRequestHandler;
+    let Some(filter) = request_filter_for_view((*handler).view_id) else {
+        return RV_CONTINUE;
+    };
+    let Some(get_url) = (*request).get_url else {
+        return RV_CONTINUE;
+    };
+    let url = cef_userfree_to_owned(get_url(request));
+    let resource_type = (*request)
+        .get_resource_type
+        .map_or(ResourceType::Other, |get_resource_type| {
+            filter_resource_type(get_resource_type(request))
+        });
+    let source_url = if resource_type == ResourceType::Document {
+        None
+    } else {
+        main_frame_url(browser)
+    };
+    let decision = filter.check(&FilterRequest {
+        url: &url,
+        source_url: source_url.as_deref(),
+        resource_type,
+    });
+    match decision {
+        FilterDecision::Allow => RV_CONTINUE,
+        FilterDecision::Block => {
+            if env_flag(ENV_CEF_VERBOSE_ERRORS) {
+                eprintln!("switchboard-app: content filter blocked {url}");
+            }
+            RV_CANCEL
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+#[allow(clippy::too_many_arguments)]
+unsafe extern "C" fn switchboard_content_get_resource_request_handler(
+    self_: *mut cef_request_handler_t,
+    _browser: *mut cef_browser_t,
+    _frame: *mut cef_frame_t,
+    _request: *mut cef_request_t,
+    _is_navigation: c_int,
+    _is_download: c_int,
+    _request_initiator: *const cef_string_t,
+    _disable_default_handling: *mut c_int,
+) -> *mut cef_resource_request_handler_t {
+    if self_.is_null() {
+        return std::ptr::null_mut();
+    }
+    let handler = self_ as *mut SwitchboardContentRequestHandler;
+    (*handler).resource_request_handler
+}
+
+#[cfg(target_os = "macos")]
+unsafe extern "C" fn switchboard_content_client_get_request_handler(
+    self_: *mut cef_client_t,
+) -> *mut cef_request_handler_t {
+    if self_.is_null() {
+        return std::ptr::null_mut();
+    }
+    let client = self_ as *mut SwitchboardContentClient;
+    (*client).request_handler
+}
+
+#[cfg(target_os = "macos")]
+unsafe extern "C" fn switchboard_content_on_render_process_terminated(
+    self_: *mut cef_request_handler_t,
+    _browser: *mut cef_browser_t,
+    _status: cef_termination_status_t,
+    _error_code: c_int,
+    _error_string: *const cef_string_t,
+) {
+    if self_.is_null() {
+        return;
+    }
+    let handler = self_ as *mut SwitchboardContentRequestHandler;
+    if let Some(tab_id) = content_view_tab((*handler).view_id) {
+        emit_content_event(ContentEvent::RendererCrashed { tab_id });
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn allocate_content_request_handler(view_id: ContentViewId) -> *mut cef_request_handler_t {
+    let resource_request_handler = Box::new(SwitchboardContentResourceRequestHandler {
+        handler: cef_resource_request_handler_t {
+            base: ref_counted_base::<cef_resource_request_handler_t>(),
+            get_cookie_access_filter: None,
+            on_before_resource_load: Some(switchboard_content_on_before_resource_load),
+        },
+        view_id,
+    });
+    let resource_request_handler = Box::into_raw(resource_request_handler);
+    let handler = Box::new(SwitchboardContentRequestHandler {
+        handler: cef_request_handler_t {
+            base: ref_counted_base::<cef_request_handler_t>(),
+            on_before_browse: None,
+            on_open_urlfrom_tab: None,
+            get_resource_request_handler: Some(switchboard_content_get_resource_request_handler),
+            get_auth_credentials: None,
+            on_certificate_error: None,
+            on_select_client_certificate: None,
+            on_render_view_ready: None,
+            on_render_process_unresponsive: None,
+            on_render_process_responsive: None,
+            on_render_process_terminated: Some(switchboard_content_on_render_process_terminated),
+            on_document_available_in_main_frame: None,
+        },
+        resource_request_handler: unsafe {
+            &mut (*resource_request_handler).handler as *mut cef_resource_request_handler_t
+        },
+        view_id,
+    });
+    let ptr = Box::into_raw(handler);
+    unsafe { &mut (*ptr).handler as *mut cef_request_handler_t }
+}
+
+#[cfg(target_os = "macos")]
+const PROMPT_PERMISSION_KINDS: [(u32, PermissionKind); 5] = [
+    (CEF_PERMISSION_TYPE_CAMERA_STREAM, PermissionKind::Camera),
+    (CEF_PERMISSION_TYPE_MIC_STREAM, PermissionKind::Microphone),
+    (CEF_PERMISSION_TYPE_GEOLOCATION, PermissionKind::Geolocation),
+    (
+        CEF_PERMISSION_TYPE_NOTIFICATIONS,
+        PermissionKind::Notifications,
+    ),
+    (CEF_PERMISSION_TYPE_CLIPBOARD, PermissionKind::Clipboard),
+];
+
+#[cfg(target_os = "macos")]
+const MEDIA_PERMISSION_KINDS: [(u32, PermissionKind); 2] = [
+    (
+        CEF_MEDIA_PERMISSION_DEVICE_AUDIO_CAPTURE,
+        PermissionKind::Microphone,
+    ),
+    (
+        CEF_MEDIA_PERMISSION_DEVICE_VIDEO_CAPTURE,
+        PermissionKind::Camera,
+    ),
+];
+
+#[cfg(target_os = "macos")]
+impl PermissionCallback {
+    fn as_ref_counted(self) -> *mut cef_base_ref_counted_t {
+        match self {
+            Self::Prompt(callback) => callback as *mut cef_base_ref_counted_t,
+            Self::MediaAccess(callback) => callback as *mut cef_base_ref_counted_t,
+        }
+    }
+}
+
+// Splits `requested` into the kinds the site settings know about; None when
+// any bit has no kind, so CEF's default handling (deny) applies.
+#[cfg(target_os = "macos")]
+fn permission_kinds(
+    requested: u32,
+    known: &[(u32, PermissionKind)],
+) -> Option<Vec<(PermissionKind, u32)>> {
+    let kinds: Vec<(PermissionKind, u32)> = known
+        .iter()
+        .filter(|(bit, _)| requested & bit != 0)
+        .map(|(bit, kind)| (*kind, *bit))
+        .collect();
+    let covered = kinds.iter().fold(0, |mask, (_, bit)| mask | bit);
+    (!kinds.is_empty() && covered == requested).then_some(kinds)
+}
+
+// Parks the callback and reports each part to the runtime, which may settle
+// them before this returns.
+#[cfg(target_os = "macos")]
+unsafe fn request_permissions(
+    view_id: ContentViewId,
+    origin: String,
+    callback: PermissionCallback,
+    prompt_id: Option<u64>,
+    kinds: Vec<(PermissionKind, u32)>,
+) -> bool {
+    let Some(tab_id) = content_view_tab(view_id) else {
+        return false;
+    };
+    add_ref_counted(callback.as_ref_counted());
+    let requests: Vec<(PermissionRequestId, PermissionKind, u32)> = kinds
+        .into_iter()
+        .map(|(kind, bits)| {
+            let request_id = NEXT_PERMISSION_REQUEST_ID.with(|next| {
+                next.set(next.get() + 1);
+                PermissionRequestId(next.get())
+            });
+            (request_id, kind, bits)
+        })
+        .collect();
+    PENDING_PERMISSIONS.with(|pending| {
+        pending.borrow_mut().push(PendingPermission {
+            callback,
+            prompt_id,
+            parts: requests
+                .iter()
+                .map(|(request_id, _, bits)| (*request_id, *bits))
+                .collect(),
+            granted_bits: 0,
+            all_granted: true,
+        });
+    });
+    for (request_id, kind, _) in requests {
+        emit_content_event(ContentEvent::PermissionRequested {
+            tab_id,
+            origin: origin.clone(),
+            kind,
+            request_id,
+        });
+    }
+    true
+}
+
+#[cfg(target_os = "macos")]
+fn settle_permission_request(request_id: PermissionRequestId, granted: bool) {
+    let settled = PENDING_PERMISSIONS.with(|pending| {
+        let mut pending = pending.borrow_mut();
+        let index = pending
+            .iter()
+            .position(|entry| entry.parts.iter().any(|(id, _)| *id == request_id))?;
+        let entry = &mut pending[index];
+        let part = entry.parts.iter().position(|(id, _)| *id == request_id)?;
+        let (_, bits) = entry.parts.remove(part);
+        if granted {
+            entry.granted_bits |= bits;
+        } else {
+            entry.all_granted = false;
+        }
+        entry.parts.is_empty().then(|| pending.remove(index))
+    });
+    let Some(entry) = settled else {
+        return;
+    };
+    unsafe {
+        match entry.callback {
+            PermissionCallback::Prompt(callback) => {
+                if let Some(cont) = (*callback).cont {
+                    cont(
+                        callback,
+                        if entry.all_granted {
+                            CEF_PERMISSION_RESULT_ACCEPT
+                        } else {
+                            CEF_PERMISSION_RESULT_DENY
+                        },
+                    );
+                }
+            }
+            PermissionCallback::MediaAccess(callback) => {
+                if entry.granted_bits == 0 {
+                    if let Some(cancel) = (*callback).cancel {
+                        cancel(callback);
+                    }
+                } else if let Some(cont) = (*callback).cont {
+                    cont(callback, entry.granted_bits);
+                }
+            }
+        }
+        release_ref_counted(entry.callback.as_ref_counted());
+    }
+}
+
+#[cfg(target_os = "macos")]
+unsafe extern "C" fn switchboard_content_on_request_media_access_permission(
+    self_: *mut cef_permission_handler_t,
+    _browser: *mut cef_browser_t,
+    _frame: *mut cef_frame_t,
+    requesting_origin: *const cef_string_t,
+    requested_permissions: u32,
+    callback: *mut cef_media_access_callback_t,
+) -> c_int {
+    if self_.is_null() || callback.is_null() {
+        return 0;
+    }
+    let Some(kinds) = permission_kinds(requested_permissions, &MEDIA_PERMISSION_KINDS) else {
+        return 0;
+    };
+    let handler = self_ as *mut SwitchboardContentPermissionHandler;
+    c_int::from(request_permissions(
+        (*handler).view_id,
+        cef_string_to_owned(requesting_origin),
+        PermissionCallback::MediaAccess(callback),
+        None,
+        kinds,
+    ))
+}
+
+#[cfg(target_os = "macos")]
+unsafe extern "C" fn switchboard_content_on_show_permission_prompt(
+    self_: *mut cef_permission_handler_t,
+    _browser: *mut cef_browser_t,
+    prompt_id: u64,
+    requesting_origin: *const cef_string_t,
+    requested_permissions: u32,
+    callback: *mut cef_permission_prompt_callback_t,
+) -> c_int {
+    if self_.is_null() || callback.is_null() {
+        return 0;
+    }
+    let Some(kinds) = permission_kinds(requested_permissions, &PROMPT_PERMISSION_KINDS) else {
+        return 0;
+    };
+    let handler = self_ as *mut SwitchboardContentPermissionHandler;
+    c_int::from(request_permissions(
+        (*handler).view_id,
+        cef_string_to_owned(requesting_origin),
+        PermissionCallback::Prompt(callback),
+        Some(prompt_id),
+        kinds,
+    ))
+}
+
+// CEF gave up on the prompt, e.g. because the page navigated away; a late
+// decision from the runtime then has nothing to answer.
+#[cfg(target_os = "macos")]
+unsafe extern "C" fn switchboard_content_on_dismiss_permission_prompt(
+    _self_: *mut cef_permission_handler_t,
+    _browser: *mut cef_browser_t,
+    prompt_id: u64,
+    _result: cef_permission_request_result_t,
+) {
+    let dismissed: Vec<PendingPermission> = PENDING_PERMISSIONS.with(|pending| {
+        let mut pending = pending.borrow_mut();
+        let (dismissed, kept) = pending
+            .drain(..)
+            .partition(|entry| entry.prompt_id == Some(prompt_id));
+        *pending = kept;
+        dismissed
+    });
+    for entry in dismissed {
+        release_ref_counted(entry.callback.as_ref_counted());
+    }
+}
+
+#[cfg(target_os = "macos")]
+unsafe extern "C" fn switchboard_content_client_get_permission_handler(
+    self_: *mut cef_client_t,
+) -> *mut cef_permission_handler_t {
+    if self_.is_null() {
+        return std::ptr::null_mut();
+    }
+    let client = self_ as *mut SwitchboardContentClient;
+    (*client).permission_handler
+}
+
+#[cfg(target_os = "macos")]
+fn allocate_content_permission_handler(view_id: ContentViewId) -> *mut cef_permission_handler_t {
+    let handler = Box::new(SwitchboardContentPermissionHandler {
+        handler: cef_permission_handler_t {
+            base: ref_counted_base::<cef_permission_handler_t>(),
+            on_request_media_access_permission: Some(
+                switchboard_content_on_request_media_access_permission,
+            ),
+            on_show_permission_prompt: Some(switchboard_content_on_show_permission_prompt),
+            on_dismiss_permission_prompt: Some(switchboard_content_on_dismiss_permission_prompt),
+        },
+        view_id,
+    });
+    let ptr = Box::into_raw(handler);
+    unsafe { &mut (*ptr).handler as *mut cef_permission_handler_t }
+}
+
+// Asks the runtime where to save the file; CEF waits for `begin_download` to
+// continue the callback.
+#[cfg(target_os = "macos")]
+unsafe extern "C" fn switchboard_content_on_before_download(
+    self_: *mut cef_download_handler_t,
+    _browser: *mut cef_browser_t,
+    download_item: *mut cef_download_item_t,
+    suggested_name: *const cef_string_t,
+    callback: *mut cef_before_download_callback_t,
+) -> c_int {
+    if self_.is_null() || download_item.is_null() || callback.is_null() {
+        return 0;
+    }
+    let handler = self_ as *mut SwitchboardContentDownloadHandler;
+    let Some(tab_id) = content_view_tab((*handler).view_id) else {
+        return 0;
+    };
+    let (Some(get_id), Some(get_url), Some(get_total_bytes)) = (
+        (*download_item).get_id,
+        (*download_item).get_url,
+        (*download_item).get_total_bytes,
+    ) else {
+        return 0;
+    };
+    let host_download_id = u64::from(get_id(download_item));
+    let url = cef_userfree_to_owned(get_url(download_item));
+    let suggested_name = cef_string_to_owned(suggested_name);
+    let total_bytes = u64::try_from(get_total_bytes(download_item))
+        .ok()
+        .filter(|bytes| *bytes > 0);
+
+    add_ref_counted(callback);
+    PENDING_DOWNLOADS.with(|pending| {
+        pending.borrow_mut().insert(host_download_id, callback);
+    });
+    emit_content_event(ContentEvent::DownloadStarted {
+        tab_id,
+        host_download_id,
+        url,
+        suggested_name: (!suggested_name.is_empty()).then_some(suggested_name),
+        total_bytes,
+    });
+    1
+}
+
+#[cfg(target_os = "macos")]
+unsafe extern "C" fn switchboard_content_on_download_updated(
+    _self_: *mut cef_download_handler_t,
+    _browser: *mut cef_browser_t,
+    download_item: *mut cef_download_item_t,
+    callback: *mut cef_download_item_callback_t,
+) {
+    if download_item.is_null() {
+        return;
+    }
+    let item = &*download_item;
+    let (
+        Some(get_id),
+        Some(is_complete),
+        Some(is_canceled),
+        Some(is_interrupted),
+        Some(get_received_bytes),
+        Some(get_total_bytes),
+    ) = (
+        item.get_id,
+        item.is_complete,
+        item.is_canceled,
+        item.is_interrupted,
+        item.get_received_bytes,
+        item.get_total_bytes,
+    )
+    else {
+        return;
+    };
+    let host_download_id = u64::from(get_id(download_item));
+    let Some((download_id, paused)) = ACTIVE_DOWNLOADS.with(|active| {
+        let mut active = active.borrow_mut();
+        let download = active.get_mut(&host_download_id)?;
+        if !callback.is_null() && callback != download.callback {
+            add_ref_counted(callback);
+            release_ref_counted(download.callback);
+            download.callback = callback;
+        }
+        Some((download.download_id, download.paused))
+    }) else {
+        return;
+    };
+
+    let state = if is_complete(download_item) != 0 {
+        DownloadState::Complete
+    } else if is_canceled(download_item) != 0 {
+        DownloadState::Cancelled
+    } else if is_interrupted(download_item) != 0 {
+        DownloadState::Failed
+    } else if paused {
+        DownloadState::Paused
+    } else {
+        DownloadState::InProgress
+    };
+    if !matches!(state, DownloadState::InProgress | DownloadState::Paused) {
+        forget_active_download(host_download_id);
+    }
+    emit_content_event(ContentEvent::DownloadProgress {
+        download_id,
+        received_bytes: u64::try_from(get_received_bytes(download_item)).unwrap_or(0),
+        total_bytes: u64::try_from(get_total_bytes(download_item))
+            .ok()
+            .filter(|bytes| *bytes > 0),
+        state,
+    });
+}
+
+#[cfg(target_os = "macos")]
+fn forget_active_download(host_download_id: u64) {
+    let removed = ACTIVE_DOWNLOADS.with(|active| active.borrow_mut().remove(&host_download_id));
+    if let Some(download) = removed {
+        unsafe { release_ref_counted(download.callback) };
+    }
+}
+
+#[cfg(target_os = "macos")]
+unsafe extern "C" fn switchboard_content_client_get_download_handler(
+    self_: *mut cef_client_t,
+) -> *mut cef_download_handler_t {
+    if self_.is_null() {
+        return std::ptr::null_mut();
+    }
+    let client = self_ as *mut SwitchboardContentClient;
+    (*client).download_handler
+}
+
+#[cfg(target_os = "macos")]
+fn allocate_content_download_handler(view_id: ContentViewId) -> *mut cef_download_handler_t {
+    let handler = Box::new(SwitchboardContentDownloadHandler {
+        handler: cef_download_handler_t {
+            base: ref_counted_base::<cef_download_handler_t>(),
+            can_download: None,
+            on_before_download: Some(switchboard_content_on_before_download),
+            on_download_updated: Some(switchboard_content_on_download_updated),
+        },
+        view_id,
+    });
+    let ptr = Box::into_raw(handler);
+    unsafe { &mut (*ptr).handler as *mut cef_download_handler_t }
+}
+
+#[cfg(target_os = "macos")]
+fn window_disposition(target_disposition: cef_window_open_disposition_t) -> WindowDisposition {
+    match target_disposition {
+        CEF_WOD_NEW_BACKGROUND_TAB => WindowDisposition::BackgroundTab,
+        CEF_WOD_NEW_POPUP => WindowDisposition::Popup,
+        CEF_WOD_NEW_WINDOW => WindowDisposition::Window,
+        _ => WindowDisposition::ForegroundTab,
+    }
+}
+
+// CEF never opens a window of its own; the runtime decides whether the
+// request becomes a tab.
+#[cfg(target_os = "macos")]
+#[allow(clippy::too_many_arguments)]
+unsafe extern "C" fn switchboard_content_on_before_popup(
+    self_: *mut cef_life_span_handler_t,
+    _browser: *mut cef_browser_t,
+    _frame: *mut cef_frame_t,
+    _popup_id: c_int,
+    target_url: *const cef_string_t,
+    _target_frame_name: *const cef_string_t,
+    target_disposition: cef_window_open_disposition_t,
+    user_gesture: c_int,
+    _popup_features: *const cef_popup_features_t,
+    _window_info: *mut cef_window_info_t,
+    _client: *mut *mut cef_client_t,
+    _settings: *mut cef_browser_settings_t,
+    _extra_info: *mut *mut cef_dictionary_value_t,
+    _no_javascript_access: *mut c_int,
+) -> c_int {
+    if self_.is_null() {
+        return 1;
+    }
+    let handler = self_ as *mut SwitchboardContentLifeSpanHandler;
+    let url = cef_string_to_owned(target_url);
+    if url.is_empty() {
+        return 1;
+    }
+    if let Some(opener_tab_id) = content_view_tab((*handler).view_id) {
+        emit_content_event(ContentEvent::NewWindowRequested {
+            opener_tab_id,
+            url,
+            disposition: window_disposition(target_disposition),
+            user_gesture: user_gesture != 0,
+        });
+    }
+    1
+}
+
+#[cfg(target_os = "macos")]
+unsafe extern "C" fn switchboard_content_client_get_life_span_handler(
+    self_: *mut cef_client_t,
+) -> *mut cef_life_span_handler_t {
+    if self_.is_null() {
+        return std::ptr::null_mut();
+    }
+    let client = self_ as *mut SwitchboardContentClient;
+    (*client).life_span_handler
+}
+
+#[cfg(target_os = "macos")]
+fn allocate_content_life_span_handler(view_id: ContentViewId) -> *mut cef_life_span_handler_t {
+    let handler = Box::new(SwitchboardContentLifeSpanHandler {
+        handler: cef_life_span_handler_t {
+            base: ref_counted_base::<cef_life_span_handler_t>(),
+            on_before_popup: Some(switchboard_content_on_before_popup),
+            on_before_popup_aborted: None,
+            on_before_dev_tools_popup: None,
+            on_after_created: None,
+            do_close: None,
+            on_before_close: None,
+        },
+        view_id,
     });
     let ptr = Box::into_raw(handler);
-    &mut (*ptr).handler as *mut cef_resource_handler_t
+    unsafe { &mut (*ptr).handler as *mut cef_life_span_handler_t }
 }
 
+// Accepting CEF's default parameters is what makes it report the stream
+// starting and stopping.
 #[cfg(target_os = "macos")]
-fn allocate_ui_scheme_factory() -> *mut cef_scheme_handler_factory_t {
-    let factory = Box::new(SwitchboardUiSchemeFactory {
-        factory: cef_scheme_handler_factory_t {
-            base: ref_counted_base::<cef_scheme_handler_factory_t>(),
-            create: Some(switchboard_scheme_factory_create),
-        },
-    });
-    let ptr = Box::into_raw(factory);
-    unsafe { &mut (*ptr).factory as *mut cef_scheme_handler_factory_t }
+unsafe extern "C" fn switchboard_content_get_audio_parameters(
+    _self_: *mut cef_audio_handler_t,
+    _browser: *mut cef_browser_t,
+    _params: *mut cef_audio_parameters_t,
+) -> c_int {
+    1
 }
 
 #[cfg(target_os = "macos")]
-unsafe extern "C" fn switchboard_content_on_address_change(
-    _self_: *mut cef_display_handler_t,
-    browser: *mut cef_browser_t,
-    frame: *mut cef_frame_t,
-    url: *const cef_string_t,
-) {
-    if !frame.is_null() {
-        if let Some(is_main) = (*frame).is_main {
-            if is_main(frame) == 0 {
-                return;
-            }
-        }
-    }
-    let next = cef_string_to_owned(url);
-    if next.is_empty() {
-        return;
+unsafe extern "C" fn switchboard_emit_audio_state(context: *mut c_void) {
+    let (view_id, audible) = *Box::from_raw(context as *mut (ContentViewId, bool));
+    if let Some(tab_id) = content_view_tab(view_id) {
+        emit_content_event(ContentEvent::AudioStateChanged { tab_id, audible });
     }
-    if !(next.starts_with("https://") || next.starts_with("http://")) {
+}
+
+// Streams start on CEF's audio capture thread, so every change is reported
+// from the main queue to keep them in order.
+#[cfg(target_os = "macos")]
+unsafe fn emit_audio_state(self_: *mut cef_audio_handler_t, audible: bool) {
+    if self_.is_null() {
         return;
     }
-    if !browser.is_null() {
-        remember_browser_for_active_tab(browser);
-    }
-    set_active_content_uri(next.clone());
-    if let Some(tab_id) = active_content_tab() {
-        emit_content_event(ContentEvent::UrlChanged {
-            tab_id,
-            url: next.clone(),
-        });
-    }
-    if env_flag(ENV_CEF_VERBOSE_ERRORS) {
-        eprintln!("switchboard-app: observed content URL change -> {next}");
-    }
+    let handler = self_ as *mut SwitchboardContentAudioHandler;
+    let context = Box::into_raw(Box::new(((*handler).view_id, audible))) as *mut c_void;
+    dispatch_async_f(&_dispatch_main_q, context, switchboard_emit_audio_state);
 }
 
 #[cfg(target_os = "macos")]
-unsafe extern "C" fn switchboard_content_on_title_change(
-    _self_: *mut cef_display_handler_t,
-    browser: *mut cef_browser_t,
-    title: *const cef_string_t,
+unsafe extern "C" fn switchboard_content_on_audio_stream_started(
+    self_: *mut cef_audio_handler_t,
+    _browser: *mut cef_browser_t,
+    _params: *const cef_audio_parameters_t,
+    _channels: c_int,
 ) {
-    if !browser.is_null() {
-        remember_browser_for_active_tab(browser);
-    }
-    let next = cef_string_to_owned(title);
-    if let Some(tab_id) = active_content_tab() {
-        emit_content_event(ContentEvent::TitleChanged {
-            tab_id,
-            title: next,
-        });
-    }
+    emit_audio_state(self_, true);
 }
 
 #[cfg(target_os = "macos")]
-unsafe extern "C" fn switchboard_content_on_loading_progress_change(
-    _self_: *mut cef_display_handler_t,
-    browser: *mut cef_browser_t,
-    progress: f64,
+unsafe extern "C" fn switchboard_content_on_audio_stream_stopped(
+    self_: *mut cef_audio_handler_t,
+    _browser: *mut cef_browser_t,
 ) {
-    let mut is_loading = progress < 0.999_999;
-    if !browser.is_null() {
-        remember_browser_for_active_tab(browser);
-        if let Some(is_loading_fn) = (*browser).is_loading {
-            is_loading = is_loading_fn(browser) != 0;
-        }
-    }
-    if let Some(tab_id) = active_content_tab() {
-        emit_content_event(ContentEvent::LoadingChanged { tab_id, is_loading });
-    }
+    emit_audio_state(self_, false);
 }
 
+// CEF stops the stream right after reporting an error.
 #[cfg(target_os = "macos")]
-unsafe extern "C" fn switchboard_content_client_get_display_handler(
+unsafe extern "C" fn switchboard_content_on_audio_stream_error(
+    self_: *mut cef_audio_handler_t,
+    _browser: *mut cef_browser_t,
+    _message: *const cef_string_t,
+) {
+    emit_audio_state(self_, false);
+}
+
+#[cfg(target_os = "macos")]
+unsafe extern "C" fn switchboard_content_client_get_audio_handler(
     self_: *mut cef_client_t,
-) -> *mut cef_display_handler_t {
+) -> *mut cef_audio_handler_t {
     if self_.is_null() {
         return std::ptr::null_mut();
     }
     let client = self_ as *mut SwitchboardContentClient;
-    (*client).display_handler
+    (*client).audio_handler
 }
 
 #[cfg(target_os = "macos")]
-fn allocate_content_display_handler() -> *mut cef_display_handler_t {
-    let handler = Box::new(SwitchboardContentDisplayHandler {
-        handler: cef_display_handler_t {
-            base: ref_counted_base::<cef_display_handler_t>(),
-            on_address_change: Some(switchboard_content_on_address_change),
-            on_title_change: Some(switchboard_content_on_title_change),
-            on_favicon_urlchange: None,
-            on_fullscreen_mode_change: None,
-            on_tooltip: None,
-            on_status_message: None,
-            on_console_message: None,
-            on_auto_resize: None,
-            on_loading_progress_change: Some(switchboard_content_on_loading_progress_change),
-            on_cursor_change: None,
-            on_media_access_change: None,
-            on_contents_bounds_change: None,
-            get_root_window_screen_rect: None,
+fn allocate_content_audio_handler(view_id: ContentViewId) -> *mut cef_audio_handler_t {
+    let handler = Box::new(SwitchboardContentAudioHandler {
+        handler: cef_audio_handler_t {
+            base: ref_counted_base::<cef_audio_handler_t>(),
+            get_audio_parameters: Some(switchboard_content_get_audio_parameters),
+            on_audio_stream_started: Some(switchboard_content_on_audio_stream_started),
+            on_audio_stream_packet: None,
+            on_audio_stream_stopped: Some(switchboard_content_on_audio_stream_stopped),
+            on_audio_stream_error: Some(switchboard_content_on_audio_stream_error),
         },
+        view_id,
     });
     let ptr = Box::into_raw(handler);
-    unsafe { &mut (*ptr).handler as *mut cef_display_handler_t }
+    unsafe { &mut (*ptr).handler as *mut cef_audio_handler_t }
 }
 
 #[cfg(target_os = "macos")]
-fn allocate_content_cef_client() -> *mut cef_client_t {
+fn allocate_content_cef_client(view_id: ContentViewId) -> *mut cef_client_t {
     let display_handler = allocate_content_display_handler();
+    let load_handler = allocate_content_load_handler(view_id);
+    let request_handler = allocate_content_request_handler(view_id);
+    let permission_handler = allocate_content_permission_handler(view_id);
+    let download_handler = allocate_content_download_handler(view_id);
+    let life_span_handler = allocate_content_life_span_handler(view_id);
+    let audio_handler = allocate_content_audio_handler(view_id);
     let client = Box::new(SwitchboardContentClient {
         client: cef_client_t {
             base: ref_counted_base::<cef_client_t>(),
-            get_audio_handler: None,
+            get_audio_handler: Some(switchboard_content_client_get_audio_handler),
             get_command_handler: None,
             get_context_menu_handler: None,
             get_dialog_handler: None,
             get_display_handler: Some(switchboard_content_client_get_display_handler),
-            get_download_handler: None,
+            get_download_handler: Some(switchboard_content_client_get_download_handler),
             get_drag_handler: None,
             get_find_handler: None,
             get_focus_handler: None,
             get_frame_handler: None,
-            get_permission_handler: None,
+            get_permission_handler: Some(switchboard_content_client_get_permission_handler),
             get_jsdialog_handler: None,
             get_keyboard_handler: None,
-            get_life_span_handler: None,
-            get_load_handler: None,
+            get_life_span_handler: Some(switchboard_content_client_get_life_span_handler),
+            get_load_handler: Some(switchboard_content_client_get_load_handler),
             get_print_handler: None,
             get_render_handler: None,
-            get_request_handler: None,
+            get_request_handler: Some(switchboard_content_client_get_request_handler),
             on_process_message_received: None,
         },
         display_handler,
+        load_handler,
+        request_handler,
+        permission_handler,
+        download_handler,
+        life_span_handler,
+        audio_handler,
     });
     let client_ptr = Box::into_raw(client);
     if env_flag(ENV_CEF_VERBOSE_ERRORS) {
@@ -1907,6 +4215,47 @@ unsafe fn free_content_cef_client(client: *mut cef_client_t) {
             display_handler as *mut SwitchboardContentDisplayHandler,
         ));
     }
+    let load_handler = (*content_client).load_handler;
+    if !load_handler.is_null() {
+        drop(Box::from_raw(
+            load_handler as *mut SwitchboardContentLoadHandler,
+        ));
+    }
+    let request_handler =
+        (*content_client).request_handler as *mut SwitchboardContentRequestHandler;
+    if !request_handler.is_null() {
+        let resource_request_handler = (*request_handler).resource_request_handler;
+        if !resource_request_handler.is_null() {
+            drop(Box::from_raw(
+                resource_request_handler as *mut SwitchboardContentResourceRequestHandler,
+            ));
+        }
+        drop(Box::from_raw(request_handler));
+    }
+    let permission_handler = (*content_client).permission_handler;
+    if !permission_handler.is_null() {
+        drop(Box::from_raw(
+            permission_handler as *mut SwitchboardContentPermissionHandler,
+        ));
+    }
+    let download_handler = (*content_client).download_handler;
+    if !download_handler.is_null() {
+        drop(Box::from_raw(
+            download_handler as *mut SwitchboardContentDownloadHandler,
+        ));
+    }
+    let life_span_handler = (*content_client).life_span_handler;
+    if !life_span_handler.is_null() {
+        drop(Box::from_raw(
+            life_span_handler as *mut SwitchboardContentLifeSpanHandler,
+        ));
+    }
+    let audio_handler = (*content_client).audio_handler;
+    if !audio_handler.is_null() {
+        drop(Box::from_raw(
+            audio_handler as *mut SwitchboardContentAudioHandler,
+        ));
+    }
     drop(Box::from_raw(content_client));
 }
 
@@ -1938,6 +4287,8 @@ impl CefRuntime {
                 ))
             })?;
             install_cef_quit_message_loop_hook(library.api.cef_quit_message_loop);
+            install_cef_string_userfree_free(library.api.cef_string_userfree_utf16_free);
+            install_cef_string_list_api(&library);
             let app = allocate_cef_app();
             let requested_api_version =
                 env_i32(ENV_CEF_API_VERSION).unwrap_or(DEFAULT_CEF_API_VERSION);
@@ -2181,6 +4532,8 @@ impl CefRuntime {
             (self.library.api.cef_shutdown)();
         }
         clear_cef_quit_message_loop_hook();
+        clear_cef_string_userfree_free();
+        clear_cef_string_list_api();
     }
 }
 
@@ -2399,6 +4752,7 @@ pub struct NativeMacHost {
     content_view_windows: HashMap<ContentViewId, WindowId>,
     cef_clients: HashMap<ContentViewId, *mut cef_client_t>,
     retired_cef_clients: Vec<*mut cef_client_t>,
+    dev_tools_registrations: HashMap<ContentViewId, *mut cef_registration_t>,
 }
 
 #[cfg(target_os = "macos")]
@@ -2420,6 +4774,7 @@ impl NativeMacHost {
             }
             install_nsapplication_event_shim()?;
             install_nsapplication_suspend_shim()?;
+            install_memory_pressure_source()?;
 
             let app_class = objc_class("NSApplication")?;
             let app = msg_send_id(app_class, selector("sharedApplication")?);
@@ -2450,6 +4805,7 @@ impl NativeMacHost {
                 content_view_windows: HashMap::new(),
                 cef_clients: HashMap::new(),
                 retired_cef_clients: Vec::new(),
+                dev_tools_registrations: HashMap::new(),
             })
         }
     }
@@ -2516,7 +4872,7 @@ impl CefHost for NativeMacHost {
     }
 
     fn create_ui_view(&mut self, window_id: WindowId, url: &str) -> Result<UiViewId, Self::Error> {
-        if !url.starts_with("app://ui") {
+        if !is_ui_url(url) {
             return Err(HostError::InvalidUiUrl(url.to_owned()));
         }
         let cef = self.cef.as_ref().ok_or_else(|| {
@@ -2571,7 +4927,7 @@ impl CefHost for NativeMacHost {
         tab_id: TabId,
         url: &str,
     ) -> Result<ContentViewId, Self::Error> {
-        if url.starts_with("app://") {
+        if url_scheme(url).as_deref() == Some(UI_URL_SCHEME) {
             return Err(HostError::InvalidContentUrl(url.to_owned()));
         }
 
@@ -2591,10 +4947,11 @@ impl CefHost for NativeMacHost {
             );
             msg_send_void_id(root_view, selector("addSubview:")?, content_view);
 
+            let view_id = ContentViewId(self.next_content_view_id + 1);
             let mut cef_client: Option<*mut cef_client_t> = None;
             let backend = if let Some(cef) = self.cef.as_ref() {
                 set_active_content_browser(std::ptr::null_mut());
-                let client = allocate_content_cef_client();
+                let client = allocate_content_cef_client(view_id);
                 if let Err(error) = cef.create_browser_in_view(
                     content_view,
                     url,
@@ -2620,9 +4977,9 @@ impl CefHost for NativeMacHost {
             msg_send_void_id(window, selector("setTitle:")?, title_value);
 
             self.next_content_view_id += 1;
-            let view_id = ContentViewId(self.next_content_view_id);
             self.content_views.insert(view_id, backend);
             self.content_view_tabs.insert(view_id, tab_id);
+            set_content_view_tab(view_id, tab_id);
             self.content_view_windows.insert(view_id, window_id);
             if let Some(client) = cef_client {
                 self.cef_clients.insert(view_id, client);
@@ -2637,7 +4994,7 @@ impl CefHost for NativeMacHost {
         tab_id: TabId,
         url: &str,
     ) -> Result<(), Self::Error> {
-        if url.starts_with("app://") {
+        if url_scheme(url).as_deref() == Some(UI_URL_SCHEME) {
             return Err(HostError::InvalidContentUrl(url.to_owned()));
         }
 
@@ -2681,6 +5038,7 @@ impl CefHost for NativeMacHost {
             msg_send_void_id(window, selector("setTitle:")?, title_value);
         }
         self.content_view_tabs.insert(view_id, tab_id);
+        set_content_view_tab(view_id, tab_id);
         Ok(())
     }
 
@@ -2810,9 +5168,17 @@ impl CefHost for NativeMacHost {
             .remove(&view_id)
             .ok_or_else(|| HostError::Native(format!("content view not found: {}", view_id.0)))?;
         self.content_view_windows.remove(&view_id);
+        request_filters()
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&view_id);
         if let Some(tab_id) = self.content_view_tabs.remove(&view_id) {
             forget_browser_for_tab(tab_id);
         }
+        forget_content_view_tab(view_id);
+        if let Some(registration) = self.dev_tools_registrations.remove(&view_id) {
+            unsafe { release_ref_counted(registration) };
+        }
 
         unsafe {
             match content_backend {
@@ -2835,6 +5201,268 @@ impl CefHost for NativeMacHost {
         Ok(())
     }
 
+    fn set_audio_muted(&mut self, view_id: ContentViewId, muted: bool) -> Result<(), Self::Error> {
+        // WebKit views have no per-view mute; only CEF browsers honour it.
+        let Some(tab_id) = self.content_view_tabs.get(&view_id).copied() else {
+            return Ok(());
+        };
+        let browser = browser_for_tab(tab_id);
+        if browser.is_null() {
+            return Ok(());
+        }
+        unsafe {
+            let get_host = (*browser)
+                .get_host
+                .ok_or_else(|| HostError::Native("CEF browser get_host unavailable".to_owned()))?;
+            let browser_host = get_host(browser);
+            if browser_host.is_null() {
+                return Err(HostError::Native(
+                    "CEF browser host unavailable for muting".to_owned(),
+                ));
+            }
+            let set_audio_muted = (*browser_host).set_audio_muted.ok_or_else(|| {
+                HostError::Native("CEF set_audio_muted callback unavailable".to_owned())
+            })?;
+            set_audio_muted(browser_host, c_int::from(muted));
+        }
+        Ok(())
+    }
+
+    fn set_request_filter(
+        &mut self,
+        view_id: ContentViewId,
+        filter: Option<Arc<dyn RequestFilter>>,
+    ) -> Result<(), Self::Error> {
+        // WebKit views have no request hook, so only CEF views are filtered.
+        let mut filters = request_filters()
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        match filter {
+            Some(filter) => filters.insert(view_id, filter),
+            None => filters.remove(&view_id),
+        };
+        Ok(())
+    }
+
+    fn complete_permission_request(
+        &mut self,
+        request_id: PermissionRequestId,
+        granted: bool,
+    ) -> Result<(), Self::Error> {
+        settle_permission_request(request_id, granted);
+        Ok(())
+    }
+
+    fn begin_download(
+        &mut self,
+        host_download_id: u64,
+        download_id: DownloadId,
+        target_path: &str,
+    ) -> Result<(), Self::Error> {
+        let Some(callback) =
+            PENDING_DOWNLOADS.with(|pending| pending.borrow_mut().remove(&host_download_id))
+        else {
+            return Ok(());
+        };
+        let path = self
+            .cef
+            .as_ref()
+            .ok_or_else(|| HostError::Native("CEF runtime unavailable".to_owned()))
+            .and_then(|cef| CefString::new(&cef.library, target_path));
+        unsafe {
+            if let (Ok(path), Some(cont)) = (&path, (*callback).cont) {
+                ACTIVE_DOWNLOADS.with(|active| {
+                    active.borrow_mut().insert(
+                        host_download_id,
+                        ActiveDownload {
+                            download_id,
+                            callback: std::ptr::null_mut(),
+                            paused: false,
+                        },
+                    );
+                });
+                cont(callback, path.as_ptr(), 0);
+            }
+            // Releasing the callback without continuing it cancels the
+            // download.
+            release_ref_counted(callback);
+        }
+        path.map(|_| ())
+    }
+
+    fn control_download(
+        &mut self,
+        download_id: DownloadId,
+        command: DownloadCommand,
+    ) -> Result<(), Self::Error> {
+        let callback = ACTIVE_DOWNLOADS.with(|active| {
+            let mut active = active.borrow_mut();
+            let download = active
+                .values_mut()
+                .find(|download| download.download_id == download_id)?;
+            match command {
+                DownloadCommand::Pause => download.paused = true,
+                DownloadCommand::Resume => download.paused = false,
+                DownloadCommand::Cancel => {}
+            }
+            (!download.callback.is_null()).then_some(download.callback)
+        });
+        let Some(callback) = callback else {
+            return Ok(());
+        };
+        // CEF answers each command with an `on_download_updated` call, which
+        // reports the new state back to the runtime.
+        unsafe {
+            let control = match command {
+                DownloadCommand::Pause => (*callback).pause,
+                DownloadCommand::Resume => (*callback).resume,
+                DownloadCommand::Cancel => (*callback).cancel,
+            };
+            if let Some(control) = control {
+                control(callback);
+            }
+        }
+        Ok(())
+    }
+
+    fn open_download_folder(&mut self, target_path: &str) -> Result<(), Self::Error> {
+        // Finder opens the containing folder with the file selected.
+        unsafe {
+            let path = nsstring(target_path)?;
+            let url = msg_send_id_id(objc_class("NSURL")?, selector("fileURLWithPath:")?, path);
+            if url == NIL {
+                return Err(HostError::Native(format!(
+                    "invalid download path: {target_path}"
+                )));
+            }
+            let urls = msg_send_id_id(objc_class("NSArray")?, selector("arrayWithObject:")?, url);
+            let workspace = msg_send_id(objc_class("NSWorkspace")?, selector("sharedWorkspace")?);
+            msg_send_void_id(
+                workspace,
+                selector("activateFileViewerSelectingURLs:")?,
+                urls,
+            );
+        }
+        Ok(())
+    }
+
+    fn schedule_crash_reload(&mut self, tab_id: TabId, delay_ms: u64) -> Result<(), Self::Error> {
+        emit_content_event_after(ContentEvent::CrashReloadDue { tab_id }, delay_ms);
+        Ok(())
+    }
+
+    fn schedule_lifecycle_tick(&mut self, delay_ms: u64) -> Result<(), Self::Error> {
+        emit_content_event_after(ContentEvent::LifecycleTickDue, delay_ms);
+        Ok(())
+    }
+
+    fn schedule_startup_restore(
+        &mut self,
+        tab_id: TabId,
+        delay_ms: u64,
+    ) -> Result<(), Self::Error> {
+        emit_content_event_after(ContentEvent::StartupRestoreDue { tab_id }, delay_ms);
+        Ok(())
+    }
+
+    // A tab that can't be measured keeps its last estimate; failing here
+    // would stop the lifecycle tick that asked.
+    fn request_memory_estimate(
+        &mut self,
+        view_id: ContentViewId,
+        tab_id: TabId,
+    ) -> Result<(), Self::Error> {
+        let browser = browser_for_tab(tab_id);
+        if browser.is_null() {
+            return Ok(());
+        }
+        unsafe {
+            let Some(get_host) = (*browser).get_host else {
+                return Ok(());
+            };
+            let browser_host = get_host(browser);
+            if browser_host.is_null() {
+                return Ok(());
+            }
+            if let Entry::Vacant(entry) = self.dev_tools_registrations.entry(view_id) {
+                let Some(add_observer) = (*browser_host).add_dev_tools_message_observer else {
+                    return Ok(());
+                };
+                let registration = add_observer(browser_host, memory_estimate_observer());
+                if registration.is_null() {
+                    return Ok(());
+                }
+                entry.insert(registration);
+            }
+            let Some(execute_method) = (*browser_host).execute_dev_tools_method else {
+                return Ok(());
+            };
+            let message_id = NEXT_DEV_TOOLS_MESSAGE_ID.with(|next| {
+                let id = next.get() + 1;
+                next.set(id);
+                id
+            });
+            PENDING_MEMORY_ESTIMATES.with(|pending| {
+                pending.borrow_mut().insert(message_id, tab_id);
+            });
+            with_stack_cef_string(MEMORY_ESTIMATE_METHOD, |method| {
+                if execute_method(browser_host, message_id, method, std::ptr::null_mut()) == 0 {
+                    PENDING_MEMORY_ESTIMATES.with(|pending| {
+                        pending.borrow_mut().remove(&message_id);
+                    });
+                }
+            });
+        }
+        Ok(())
+    }
+
+    fn download_favicon(&mut self, tab_id: TabId, icon_url: &str) -> Result<(), Self::Error> {
+        let Some(cef) = self.cef.as_ref() else {
+            return Ok(());
+        };
+        let browser = browser_for_tab(tab_id);
+        if browser.is_null() {
+            return Ok(());
+        }
+        let already_downloading = PENDING_FAVICONS.with(|pending| {
+            let mut pending = pending.borrow_mut();
+            let tab_ids = pending.entry(icon_url.to_owned()).or_default();
+            let already_downloading = !tab_ids.is_empty();
+            if !tab_ids.contains(&tab_id) {
+                tab_ids.push(tab_id);
+            }
+            already_downloading
+        });
+        if already_downloading {
+            return Ok(());
+        }
+
+        let url_value = CefString::new(&cef.library, icon_url)?;
+        unsafe {
+            let get_host = (*browser)
+                .get_host
+                .ok_or_else(|| HostError::Native("CEF browser get_host unavailable".to_owned()))?;
+            let browser_host = get_host(browser);
+            if browser_host.is_null() {
+                return Err(HostError::Native(
+                    "CEF browser host unavailable for favicon download".to_owned(),
+                ));
+            }
+            let download_image = (*browser_host).download_image.ok_or_else(|| {
+                HostError::Native("CEF download_image callback unavailable".to_owned())
+            })?;
+            download_image(
+                browser_host,
+                url_value.as_ptr(),
+                1,
+                FAVICON_DOWNLOAD_PIXELS,
+                0,
+                favicon_download_callback(),
+            );
+        }
+        Ok(())
+    }
+
     fn run_event_loop(&mut self) -> Result<(), Self::Error> {
         unsafe {
             msg_send_void_bool(self.app, selector("activateIgnoringOtherApps:")?, YES);
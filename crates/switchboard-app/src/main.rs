@@ -1,5 +1,9 @@
 mod bridge;
+mod favicons;
+mod filter_lists;
+mod history;
 mod host;
+mod metrics;
 mod persistence;
 mod runtime;
 
@@ -0,0 +1,192 @@
+use std::cell::Cell;
+use std::time::{Duration, Instant};
+
+use switchboard_core::{BrowserState, Persistence, TabRuntimeState};
+
+// Upper bounds, in microseconds, of every latency bucket but the last, which
+// takes everything slower.
+const LATENCY_BUCKETS_US: [u64; 10] = [
+    100, 250, 500, 1_000, 2_500, 5_000, 10_000, 25_000, 50_000, 100_000,
+];
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LatencyHistogram {
+    buckets: [u64; LATENCY_BUCKETS_US.len() + 1],
+    count: u64,
+    total_us: u64,
+    max_us: u64,
+}
+
+impl LatencyHistogram {
+    pub fn record(&mut self, elapsed: Duration) {
+        let micros = u64::try_from(elapsed.as_micros()).unwrap_or(u64::MAX);
+        let bucket = LATENCY_BUCKETS_US
+            .iter()
+            .position(|bound| micros <= *bound)
+            .unwrap_or(LATENCY_BUCKETS_US.len());
+        self.buckets[bucket] += 1;
+        self.count += 1;
+        self.total_us = self.total_us.saturating_add(micros);
+        self.max_us = self.max_us.max(micros);
+    }
+
+    #[cfg(test)]
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    fn push_json(&self, json: &mut String) {
+        json.push_str(&format!(
+            "{{\"count\":{},\"total_us\":{},\"max_us\":{},\"buckets\":[",
+            self.count, self.total_us, self.max_us
+        ));
+        for (index, count) in self.buckets.iter().enumerate() {
+            if index > 0 {
+                json.push(',');
+            }
+            match LATENCY_BUCKETS_US.get(index) {
+                Some(bound) => json.push_str(&format!("{{\"le_us\":{bound},\"count\":{count}}}")),
+                None => json.push_str(&format!("{{\"le_us\":null,\"count\":{count}}}")),
+            }
+        }
+        json.push_str("]}");
+    }
+}
+
+// Counters for tuning the lifecycle policy. They live in memory for the
+// session and are only ever read through the bridge.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RuntimeMetrics {
+    // From intent to patch, persistence commit included.
+    pub dispatch_latency: LatencyHistogram,
+    pub commit_latency: LatencyHistogram,
+    pub patches: u64,
+    pub patch_ops: u64,
+    pub largest_patch: u64,
+    // Tabs that became the active tab, by the state they were in before.
+    pub restores_from_warm: u64,
+    pub restores_from_discarded: u64,
+    pub restores_from_restoring: u64,
+    pub views_created: u64,
+    pub views_destroyed: u64,
+    pub thumbnail_captures: u64,
+}
+
+impl RuntimeMetrics {
+    pub fn record_patch(&mut self, ops: usize) {
+        let ops = ops as u64;
+        self.patches += 1;
+        self.patch_ops += ops;
+        self.largest_patch = self.largest_patch.max(ops);
+    }
+
+    pub fn record_restore(&mut self, prior_state: TabRuntimeState) {
+        match prior_state {
+            TabRuntimeState::Warm => self.restores_from_warm += 1,
+            TabRuntimeState::Discarded => self.restores_from_discarded += 1,
+            TabRuntimeState::Restoring => self.restores_from_restoring += 1,
+            TabRuntimeState::Active => {}
+        }
+    }
+
+    // The share of restores that found a live view; None until there is one.
+    pub fn warm_hit_rate(&self) -> Option<f64> {
+        let restores =
+            self.restores_from_warm + self.restores_from_discarded + self.restores_from_restoring;
+        (restores > 0).then(|| self.restores_from_warm as f64 / restores as f64)
+    }
+
+    pub fn to_json(&self) -> String {
+        let mut json = String::from("{\"dispatch_latency\":");
+        self.dispatch_latency.push_json(&mut json);
+        json.push_str(",\"commit_latency\":");
+        self.commit_latency.push_json(&mut json);
+        json.push_str(&format!(
+            ",\"patches\":{},\"patch_ops\":{},\"largest_patch\":{}",
+            self.patches, self.patch_ops, self.largest_patch
+        ));
+        json.push_str(&format!(
+            ",\"restores\":{{\"warm\":{},\"discarded\":{},\"restoring\":{}}}",
+            self.restores_from_warm, self.restores_from_discarded, self.restores_from_restoring
+        ));
+        json.push_str(",\"warm_hit_rate\":");
+        match self.warm_hit_rate() {
+            Some(rate) => json.push_str(&format!("{rate:.4}")),
+            None => json.push_str("null"),
+        }
+        json.push_str(&format!(
+            ",\"views_created\":{},\"views_destroyed\":{},\"thumbnail_captures\":{}}}",
+            self.views_created, self.views_destroyed, self.thumbnail_captures
+        ));
+        json
+    }
+}
+
+// Times every commit of the wrapped store; the runtime collects the last
+// one after each dispatch.
+pub struct TimedPersistence<P> {
+    inner: P,
+    last_commit: Cell<Option<Duration>>,
+}
+
+impl<P> TimedPersistence<P> {
+    pub fn new(inner: P) -> Self {
+        Self {
+            inner,
+            last_commit: Cell::new(None),
+        }
+    }
+
+    pub fn take_last_commit(&self) -> Option<Duration> {
+        self.last_commit.take()
+    }
+}
+
+impl<P: Persistence> Persistence for TimedPersistence<P> {
+    type Error = P::Error;
+
+    fn commit(&mut self, state: &BrowserState) -> Result<(), Self::Error> {
+        let started = Instant::now();
+        let result = self.inner.commit(state);
+        self.last_commit.set(Some(started.elapsed()));
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use switchboard_core::TabRuntimeState;
+
+    use super::{LatencyHistogram, RuntimeMetrics};
+
+    #[test]
+    fn histograms_bucket_latencies_and_metrics_render_as_json() {
+        let mut histogram = LatencyHistogram::default();
+        histogram.record(Duration::from_micros(80));
+        histogram.record(Duration::from_micros(100));
+        histogram.record(Duration::from_millis(3));
+        histogram.record(Duration::from_secs(2));
+        assert_eq!(histogram.count(), 4);
+        assert_eq!(histogram.buckets[0], 2);
+        assert_eq!(histogram.buckets[5], 1);
+        assert_eq!(histogram.buckets[10], 1);
+        assert_eq!(histogram.max_us, 2_000_000);
+
+        let mut metrics = RuntimeMetrics::default();
+        assert_eq!(metrics.warm_hit_rate(), None);
+        metrics.record_restore(TabRuntimeState::Warm);
+        metrics.record_restore(TabRuntimeState::Warm);
+        metrics.record_restore(TabRuntimeState::Warm);
+        metrics.record_restore(TabRuntimeState::Discarded);
+        metrics.record_patch(3);
+        metrics.record_patch(7);
+        assert_eq!(metrics.warm_hit_rate(), Some(0.75));
+        let json = metrics.to_json();
+        assert!(json.contains("\"restores\":{\"warm\":3,\"discarded\":1,\"restoring\":0}"));
+        assert!(json.contains("\"warm_hit_rate\":0.7500"));
+        assert!(json.contains("\"patches\":2,\"patch_ops\":10,\"largest_patch\":7"));
+        assert!(json.contains("{\"le_us\":null,\"count\":0}"));
+    }
+}
@@ -1,5 +1,6 @@
 #![cfg_attr(test, allow(dead_code))]
 
+use std::collections::{BTreeMap, BTreeSet};
 use std::env;
 use std::error::Error;
 use std::ffi::{c_char, c_int, c_void, CStr, CString};
@@ -9,15 +10,154 @@ use std::fs;
 use std::os::unix::ffi::OsStrExt;
 use std::path::{Path, PathBuf};
 
+use switchboard_core::history::is_ephemeral_profile;
+use switchboard_core::search::ensure_search_engines;
 use switchboard_core::{
-    BrowserState, Persistence, Profile, ProfileId, SettingValue, Tab, TabId, TabRuntimeState,
-    Workspace, WorkspaceId,
+    Bookmark, BookmarkFolder, BookmarkFolderId, BookmarkId, BrowserState, Download, DownloadId,
+    DownloadState, PermissionKind, PermissionSetting, Persistence, Profile, ProfileId,
+    SearchEngine, SearchEngineId, SettingValue, Tab, TabId, TabRuntimeState, TabStatus, Workspace,
+    WorkspaceId,
 };
 
 const ENV_STATE_DB: &str = "SWITCHBOARD_STATE_DB";
 const META_SCHEMA_VERSION: &str = "schema_version";
 const META_ACTIVE_PROFILE_ID: &str = "active_profile_id";
-const SCHEMA_VERSION: i64 = 1;
+const SCHEMA_VERSION: i64 = 8;
+
+// Applied in order to databases whose `meta.schema_version` is lower than the
+// entry's version. Shipped entries must never be edited; append a new one.
+const MIGRATIONS: &[(i64, &str)] = &[
+    (
+        1,
+        "
+            CREATE TABLE IF NOT EXISTS profiles (
+                id INTEGER PRIMARY KEY,
+                name TEXT NOT NULL,
+                active_workspace_id INTEGER
+            );
+            CREATE TABLE IF NOT EXISTS profile_workspace_order (
+                profile_id INTEGER NOT NULL,
+                position INTEGER NOT NULL,
+                workspace_id INTEGER NOT NULL,
+                PRIMARY KEY (profile_id, position)
+            );
+            CREATE TABLE IF NOT EXISTS workspaces (
+                id INTEGER PRIMARY KEY,
+                profile_id INTEGER NOT NULL,
+                name TEXT NOT NULL,
+                active_tab_id INTEGER
+            );
+            CREATE TABLE IF NOT EXISTS workspace_tab_order (
+                workspace_id INTEGER NOT NULL,
+                position INTEGER NOT NULL,
+                tab_id INTEGER NOT NULL,
+                PRIMARY KEY (workspace_id, position)
+            );
+            CREATE TABLE IF NOT EXISTS tabs (
+                id INTEGER PRIMARY KEY,
+                profile_id INTEGER NOT NULL,
+                workspace_id INTEGER NOT NULL,
+                url TEXT NOT NULL,
+                title TEXT NOT NULL,
+                loading INTEGER NOT NULL,
+                thumbnail_data_url TEXT,
+                pinned INTEGER NOT NULL,
+                muted INTEGER NOT NULL,
+                runtime_state INTEGER NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS settings (
+                key TEXT PRIMARY KEY,
+                kind TEXT NOT NULL,
+                bool_value INTEGER,
+                int_value INTEGER,
+                text_value TEXT
+            );
+        ",
+    ),
+    (
+        2,
+        "
+            CREATE TABLE IF NOT EXISTS bookmark_folders (
+                id INTEGER PRIMARY KEY,
+                profile_id INTEGER NOT NULL,
+                parent_id INTEGER,
+                name TEXT NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS bookmarks (
+                id INTEGER PRIMARY KEY,
+                profile_id INTEGER NOT NULL,
+                folder_id INTEGER,
+                position INTEGER NOT NULL,
+                url TEXT NOT NULL,
+                title TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS bookmarks_by_folder
+                ON bookmarks(profile_id, folder_id, position);
+        ",
+    ),
+    (
+        3,
+        "
+            CREATE TABLE IF NOT EXISTS search_engines (
+                id INTEGER PRIMARY KEY,
+                profile_id INTEGER NOT NULL,
+                name TEXT NOT NULL,
+                keyword TEXT NOT NULL,
+                url_template TEXT NOT NULL,
+                is_default INTEGER NOT NULL,
+                builtin INTEGER NOT NULL
+            );
+        ",
+    ),
+    (
+        4,
+        "
+            CREATE TABLE IF NOT EXISTS https_exceptions (
+                profile_id INTEGER NOT NULL,
+                host TEXT NOT NULL,
+                PRIMARY KEY (profile_id, host)
+            );
+        ",
+    ),
+    (
+        5,
+        "
+            CREATE TABLE IF NOT EXISTS site_permissions (
+                profile_id INTEGER NOT NULL,
+                origin TEXT NOT NULL,
+                kind TEXT NOT NULL,
+                setting TEXT NOT NULL,
+                PRIMARY KEY (profile_id, origin, kind)
+            );
+        ",
+    ),
+    (
+        6,
+        "
+            CREATE TABLE IF NOT EXISTS downloads (
+                id INTEGER PRIMARY KEY,
+                profile_id INTEGER NOT NULL,
+                url TEXT NOT NULL,
+                target_path TEXT NOT NULL,
+                received_bytes INTEGER NOT NULL,
+                total_bytes INTEGER,
+                state TEXT NOT NULL
+            );
+        ",
+    ),
+    (
+        7,
+        "
+            ALTER TABLE tabs ADD COLUMN favicon_key TEXT;
+        ",
+    ),
+    (
+        8,
+        "
+            ALTER TABLE tabs ADD COLUMN keep_alive INTEGER NOT NULL DEFAULT 0;
+        ",
+    ),
+];
 
 const SQLITE_OK: c_int = 0;
 const SQLITE_OPEN_READWRITE: c_int = 0x0000_0002;
@@ -59,7 +199,7 @@ pub struct AppPersistence {
     store: SqliteStore,
 }
 
-struct SqliteStore {
+pub(crate) struct SqliteStore {
     db: *mut sqlite3,
 }
 
@@ -127,7 +267,7 @@ impl Persistence for AppPersistence {
 }
 
 impl SqliteStore {
-    fn open(path: &Path) -> Result<Self, AppPersistenceError> {
+    pub(crate) fn open(path: &Path) -> Result<Self, AppPersistenceError> {
         let c_path = path_to_cstring(path)?;
         let mut db = std::ptr::null_mut();
         let rc = unsafe {
@@ -155,7 +295,7 @@ impl SqliteStore {
     }
 
     #[cfg(test)]
-    fn open_memory() -> Result<Self, AppPersistenceError> {
+    pub(crate) fn open_memory() -> Result<Self, AppPersistenceError> {
         let c_memory = CString::new(":memory:").map_err(|_| {
             AppPersistenceError::InvalidData("invalid sqlite memory uri".to_owned())
         })?;
@@ -185,6 +325,14 @@ impl SqliteStore {
     }
 
     fn migrate(&mut self) -> Result<(), AppPersistenceError> {
+        self.apply_migrations(MIGRATIONS)
+    }
+
+    pub(crate) fn apply_migrations(
+        &mut self,
+        migrations: &[(i64, &str)],
+    ) -> Result<(), AppPersistenceError> {
+        let latest_version = migrations.last().map(|(version, _)| *version).unwrap_or(0);
         self.exec_batch(
             "
             PRAGMA foreign_keys = ON;
@@ -192,58 +340,44 @@ impl SqliteStore {
                 key TEXT PRIMARY KEY,
                 value TEXT NOT NULL
             );
-            CREATE TABLE IF NOT EXISTS profiles (
-                id INTEGER PRIMARY KEY,
-                name TEXT NOT NULL,
-                active_workspace_id INTEGER
-            );
-            CREATE TABLE IF NOT EXISTS profile_workspace_order (
-                profile_id INTEGER NOT NULL,
-                position INTEGER NOT NULL,
-                workspace_id INTEGER NOT NULL,
-                PRIMARY KEY (profile_id, position)
-            );
-            CREATE TABLE IF NOT EXISTS workspaces (
-                id INTEGER PRIMARY KEY,
-                profile_id INTEGER NOT NULL,
-                name TEXT NOT NULL,
-                active_tab_id INTEGER
-            );
-            CREATE TABLE IF NOT EXISTS workspace_tab_order (
-                workspace_id INTEGER NOT NULL,
-                position INTEGER NOT NULL,
-                tab_id INTEGER NOT NULL,
-                PRIMARY KEY (workspace_id, position)
-            );
-            CREATE TABLE IF NOT EXISTS tabs (
-                id INTEGER PRIMARY KEY,
-                profile_id INTEGER NOT NULL,
-                workspace_id INTEGER NOT NULL,
-                url TEXT NOT NULL,
-                title TEXT NOT NULL,
-                loading INTEGER NOT NULL,
-                thumbnail_data_url TEXT,
-                pinned INTEGER NOT NULL,
-                muted INTEGER NOT NULL,
-                runtime_state INTEGER NOT NULL
-            );
-            CREATE TABLE IF NOT EXISTS settings (
-                key TEXT PRIMARY KEY,
-                kind TEXT NOT NULL,
-                bool_value INTEGER,
-                int_value INTEGER,
-                text_value TEXT
-            );
             ",
         )?;
-        self.exec_batch(&format!(
-            "INSERT OR REPLACE INTO meta(key, value) VALUES({}, {});",
-            sql_text_literal(META_SCHEMA_VERSION),
-            sql_text_literal(&SCHEMA_VERSION.to_string()),
-        ))?;
+        let current_version = self.schema_version()?;
+        if current_version > latest_version {
+            return Err(AppPersistenceError::InvalidData(format!(
+                "database schema version {current_version} is newer than supported version {latest_version}"
+            )));
+        }
+        for (version, sql) in migrations {
+            if *version <= current_version {
+                continue;
+            }
+            let batch = format!(
+                "BEGIN IMMEDIATE;\n{sql}\nINSERT OR REPLACE INTO meta(key, value) VALUES({}, {});\nCOMMIT;",
+                sql_text_literal(META_SCHEMA_VERSION),
+                sql_text_literal(&version.to_string()),
+            );
+            if let Err(error) = self.exec_batch(&batch) {
+                let _ = self.exec_batch("ROLLBACK;");
+                return Err(error);
+            }
+        }
         Ok(())
     }
 
+    fn schema_version(&mut self) -> Result<i64, AppPersistenceError> {
+        let rows = self.query_rows(&format!(
+            "SELECT value FROM meta WHERE key = {};",
+            sql_text_literal(META_SCHEMA_VERSION)
+        ))?;
+        rows.first()
+            .and_then(|row| row.first())
+            .and_then(|cell| cell.as_deref())
+            .map(|value| parse_i64(value.trim(), "meta.schema_version"))
+            .transpose()
+            .map(|version| version.unwrap_or(0))
+    }
+
     fn save_state(&mut self, state: &BrowserState) -> Result<(), AppPersistenceError> {
         let mut sql = String::with_capacity(64 * 1024);
         sql.push_str("BEGIN IMMEDIATE;\n");
@@ -255,6 +389,12 @@ impl SqliteStore {
             DELETE FROM workspaces;
             DELETE FROM profiles;
             DELETE FROM settings;
+            DELETE FROM bookmarks;
+            DELETE FROM bookmark_folders;
+            DELETE FROM search_engines;
+            DELETE FROM https_exceptions;
+            DELETE FROM site_permissions;
+            DELETE FROM downloads;
             ",
         );
 
@@ -297,8 +437,8 @@ impl SqliteStore {
             sql.push_str(&format!(
                 "INSERT INTO tabs(
                     id, profile_id, workspace_id, url, title, loading, thumbnail_data_url,
-                    pinned, muted, runtime_state
-                 ) VALUES({}, {}, {}, {}, {}, {}, {}, {}, {}, {});\n",
+                    pinned, muted, runtime_state, favicon_key, keep_alive
+                 ) VALUES({}, {}, {}, {}, {}, {}, {}, {}, {}, {}, {}, {});\n",
                 tab.id.0,
                 tab.profile_id.0,
                 tab.workspace_id.0,
@@ -308,7 +448,9 @@ impl SqliteStore {
                 sql_opt_text(tab.thumbnail_data_url.as_deref()),
                 sql_bool(tab.pinned),
                 sql_bool(tab.muted),
-                runtime_state_to_i64(tab.runtime_state)
+                runtime_state_to_i64(tab.runtime_state),
+                sql_opt_text(tab.favicon_key.as_deref()),
+                sql_bool(tab.keep_alive)
             ));
         }
 
@@ -330,6 +472,78 @@ impl SqliteStore {
             ));
         }
 
+        for folder in state.bookmark_folders.values() {
+            sql.push_str(&format!(
+                "INSERT INTO bookmark_folders(id, profile_id, parent_id, name) VALUES({}, {}, {}, {});\n",
+                folder.id.0,
+                folder.profile_id.0,
+                sql_opt_u64(folder.parent_id.map(|id| id.0)),
+                sql_text_literal(&folder.name)
+            ));
+        }
+
+        for bookmark in state.bookmarks.values() {
+            sql.push_str(&format!(
+                "INSERT INTO bookmarks(id, profile_id, folder_id, position, url, title) VALUES({}, {}, {}, {}, {}, {});\n",
+                bookmark.id.0,
+                bookmark.profile_id.0,
+                sql_opt_u64(bookmark.folder_id.map(|id| id.0)),
+                bookmark.position,
+                sql_text_literal(&bookmark.url),
+                sql_text_literal(&bookmark.title)
+            ));
+        }
+
+        for engine in state.search_engines.values() {
+            sql.push_str(&format!(
+                "INSERT INTO search_engines(id, profile_id, name, keyword, url_template, is_default, builtin) VALUES({}, {}, {}, {}, {}, {}, {});\n",
+                engine.id.0,
+                engine.profile_id.0,
+                sql_text_literal(&engine.name),
+                sql_text_literal(&engine.keyword),
+                sql_text_literal(&engine.url_template),
+                sql_bool(engine.is_default),
+                sql_bool(engine.builtin)
+            ));
+        }
+
+        for (profile_id, hosts) in &state.https_exceptions {
+            for host in hosts {
+                sql.push_str(&format!(
+                    "INSERT INTO https_exceptions(profile_id, host) VALUES({}, {});\n",
+                    profile_id.0,
+                    sql_text_literal(host)
+                ));
+            }
+        }
+
+        for (profile_id, entries) in &state.site_permissions {
+            for ((origin, kind), setting) in entries {
+                sql.push_str(&format!(
+                    "INSERT INTO site_permissions(profile_id, origin, kind, setting) VALUES({}, {}, {}, {});\n",
+                    profile_id.0,
+                    sql_text_literal(origin),
+                    sql_text_literal(kind.as_str()),
+                    sql_text_literal(setting.as_str())
+                ));
+            }
+        }
+
+        for download in state.downloads.values().filter(|download| {
+            download.state.is_finished() && !is_ephemeral_profile(state, download.profile_id)
+        }) {
+            sql.push_str(&format!(
+                "INSERT INTO downloads(id, profile_id, url, target_path, received_bytes, total_bytes, state) VALUES({}, {}, {}, {}, {}, {}, {});\n",
+                download.id.0,
+                download.profile_id.0,
+                sql_text_literal(&download.url),
+                sql_text_literal(&download.target_path),
+                download.received_bytes,
+                sql_opt_u64(download.total_bytes),
+                sql_text_literal(download.state.as_str())
+            ));
+        }
+
         let active_profile_value = state
             .active_profile_id
             .map(|id| id.0.to_string())
@@ -449,7 +663,7 @@ impl SqliteStore {
         for row in self.query_rows(
             "SELECT
                 id, profile_id, workspace_id, url, title, loading, thumbnail_data_url,
-                pinned, muted, runtime_state
+                pinned, muted, runtime_state, favicon_key, keep_alive
              FROM tabs
              ORDER BY id;",
         )? {
@@ -484,9 +698,21 @@ impl SqliteStore {
                     loading: parse_i64(required_cell(&row, 5, "tabs.loading")?, "tabs.loading")?
                         != 0,
                     thumbnail_data_url: optional_cell(&row, 6).map(ToOwned::to_owned),
+                    favicon_key: optional_cell(&row, 10).map(ToOwned::to_owned),
                     pinned: parse_i64(required_cell(&row, 7, "tabs.pinned")?, "tabs.pinned")? != 0,
+                    keep_alive: parse_i64(
+                        required_cell(&row, 11, "tabs.keep_alive")?,
+                        "tabs.keep_alive",
+                    )? != 0,
                     muted: parse_i64(required_cell(&row, 8, "tabs.muted")?, "tabs.muted")? != 0,
+                    audible: false,
                     runtime_state,
+                    blocked_navigation: None,
+                    https_upgraded_from: None,
+                    pending_permissions: Vec::new(),
+                    opener_tab_id: None,
+                    status: TabStatus::Ok,
+                    crash_count: 0,
                 },
             );
         }
@@ -516,6 +742,171 @@ impl SqliteStore {
             state.settings.insert(key, value);
         }
 
+        for row in self.query_rows(
+            "SELECT id, profile_id, parent_id, name FROM bookmark_folders ORDER BY id;",
+        )? {
+            let id = BookmarkFolderId(parse_u64(
+                required_cell(&row, 0, "bookmark_folders.id")?,
+                "bookmark_folders.id",
+            )?);
+            let profile_id = ProfileId(parse_u64(
+                required_cell(&row, 1, "bookmark_folders.profile_id")?,
+                "bookmark_folders.profile_id",
+            )?);
+            let parent_id = optional_cell(&row, 2)
+                .map(|value| parse_u64(value, "bookmark_folders.parent_id"))
+                .transpose()?
+                .map(BookmarkFolderId);
+            state.bookmark_folders.insert(
+                id,
+                BookmarkFolder {
+                    id,
+                    profile_id,
+                    parent_id,
+                    name: required_cell(&row, 3, "bookmark_folders.name")?.to_owned(),
+                },
+            );
+        }
+
+        for row in self.query_rows(
+            "SELECT id, profile_id, folder_id, position, url, title FROM bookmarks ORDER BY id;",
+        )? {
+            let id = BookmarkId(parse_u64(
+                required_cell(&row, 0, "bookmarks.id")?,
+                "bookmarks.id",
+            )?);
+            let profile_id = ProfileId(parse_u64(
+                required_cell(&row, 1, "bookmarks.profile_id")?,
+                "bookmarks.profile_id",
+            )?);
+            let folder_id = optional_cell(&row, 2)
+                .map(|value| parse_u64(value, "bookmarks.folder_id"))
+                .transpose()?
+                .map(BookmarkFolderId);
+            let position = parse_u64(
+                required_cell(&row, 3, "bookmarks.position")?,
+                "bookmarks.position",
+            )? as usize;
+            state.bookmarks.insert(
+                id,
+                Bookmark {
+                    id,
+                    profile_id,
+                    folder_id,
+                    url: required_cell(&row, 4, "bookmarks.url")?.to_owned(),
+                    title: required_cell(&row, 5, "bookmarks.title")?.to_owned(),
+                    position,
+                },
+            );
+        }
+
+        for row in self.query_rows(
+            "SELECT id, profile_id, name, keyword, url_template, is_default, builtin
+             FROM search_engines ORDER BY id;",
+        )? {
+            let id = SearchEngineId(parse_u64(
+                required_cell(&row, 0, "search_engines.id")?,
+                "search_engines.id",
+            )?);
+            let profile_id = ProfileId(parse_u64(
+                required_cell(&row, 1, "search_engines.profile_id")?,
+                "search_engines.profile_id",
+            )?);
+            state.search_engines.insert(
+                id,
+                SearchEngine {
+                    id,
+                    profile_id,
+                    name: required_cell(&row, 2, "search_engines.name")?.to_owned(),
+                    keyword: required_cell(&row, 3, "search_engines.keyword")?.to_owned(),
+                    url_template: required_cell(&row, 4, "search_engines.url_template")?.to_owned(),
+                    is_default: parse_i64(
+                        required_cell(&row, 5, "search_engines.is_default")?,
+                        "search_engines.is_default",
+                    )? != 0,
+                    builtin: parse_i64(
+                        required_cell(&row, 6, "search_engines.builtin")?,
+                        "search_engines.builtin",
+                    )? != 0,
+                },
+            );
+        }
+
+        for row in self.query_rows("SELECT profile_id, host FROM https_exceptions;")? {
+            let profile_id = ProfileId(parse_u64(
+                required_cell(&row, 0, "https_exceptions.profile_id")?,
+                "https_exceptions.profile_id",
+            )?);
+            state
+                .https_exceptions
+                .entry(profile_id)
+                .or_default()
+                .insert(required_cell(&row, 1, "https_exceptions.host")?.to_owned());
+        }
+
+        for row in
+            self.query_rows("SELECT profile_id, origin, kind, setting FROM site_permissions;")?
+        {
+            let profile_id = ProfileId(parse_u64(
+                required_cell(&row, 0, "site_permissions.profile_id")?,
+                "site_permissions.profile_id",
+            )?);
+            let origin = required_cell(&row, 1, "site_permissions.origin")?.to_owned();
+            let kind_value = required_cell(&row, 2, "site_permissions.kind")?;
+            let kind = PermissionKind::parse(kind_value).ok_or_else(|| {
+                AppPersistenceError::InvalidData(format!(
+                    "unsupported site_permissions.kind: {kind_value}"
+                ))
+            })?;
+            let setting_value = required_cell(&row, 3, "site_permissions.setting")?;
+            let setting = PermissionSetting::parse(setting_value).ok_or_else(|| {
+                AppPersistenceError::InvalidData(format!(
+                    "unsupported site_permissions.setting: {setting_value}"
+                ))
+            })?;
+            state
+                .site_permissions
+                .entry(profile_id)
+                .or_default()
+                .insert((origin, kind), setting);
+        }
+
+        for row in self.query_rows(
+            "SELECT id, profile_id, url, target_path, received_bytes, total_bytes, state FROM downloads;",
+        )? {
+            let id = DownloadId(parse_u64(
+                required_cell(&row, 0, "downloads.id")?,
+                "downloads.id",
+            )?);
+            let state_value = required_cell(&row, 6, "downloads.state")?;
+            let download_state = DownloadState::parse(state_value).ok_or_else(|| {
+                AppPersistenceError::InvalidData(format!(
+                    "unsupported downloads.state: {state_value}"
+                ))
+            })?;
+            state.downloads.insert(
+                id,
+                Download {
+                    id,
+                    profile_id: ProfileId(parse_u64(
+                        required_cell(&row, 1, "downloads.profile_id")?,
+                        "downloads.profile_id",
+                    )?),
+                    tab_id: None,
+                    url: required_cell(&row, 2, "downloads.url")?.to_owned(),
+                    target_path: required_cell(&row, 3, "downloads.target_path")?.to_owned(),
+                    received_bytes: parse_u64(
+                        required_cell(&row, 4, "downloads.received_bytes")?,
+                        "downloads.received_bytes",
+                    )?,
+                    total_bytes: optional_cell(&row, 5)
+                        .map(|value| parse_u64(value, "downloads.total_bytes"))
+                        .transpose()?,
+                    state: download_state,
+                },
+            );
+        }
+
         let meta_rows = self.query_rows(&format!(
             "SELECT value FROM meta WHERE key = {};",
             sql_text_literal(META_ACTIVE_PROFILE_ID)
@@ -539,7 +930,7 @@ impl SqliteStore {
         Ok(Some(state))
     }
 
-    fn exec_batch(&mut self, sql: &str) -> Result<(), AppPersistenceError> {
+    pub(crate) fn exec_batch(&mut self, sql: &str) -> Result<(), AppPersistenceError> {
         let c_sql = CString::new(sql).map_err(|_| {
             AppPersistenceError::InvalidData("sql batch contained interior NUL byte".to_owned())
         })?;
@@ -563,7 +954,10 @@ impl SqliteStore {
         Ok(())
     }
 
-    fn query_rows(&mut self, sql: &str) -> Result<Vec<Vec<Option<String>>>, AppPersistenceError> {
+    pub(crate) fn query_rows(
+        &mut self,
+        sql: &str,
+    ) -> Result<Vec<Vec<Option<String>>>, AppPersistenceError> {
         let c_sql = CString::new(sql).map_err(|_| {
             AppPersistenceError::InvalidData("sql query contained interior NUL byte".to_owned())
         })?;
@@ -648,6 +1042,15 @@ fn normalize_loaded_state(state: &mut BrowserState) {
     state
         .workspaces
         .retain(|_, workspace| state.profiles.contains_key(&workspace.profile_id));
+    state
+        .https_exceptions
+        .retain(|profile_id, hosts| state.profiles.contains_key(profile_id) && !hosts.is_empty());
+    state.site_permissions.retain(|profile_id, entries| {
+        state.profiles.contains_key(profile_id) && !entries.is_empty()
+    });
+    state.downloads.retain(|_, download| {
+        state.profiles.contains_key(&download.profile_id) && download.state.is_finished()
+    });
     state.tabs.retain(|_, tab| {
         let Some(workspace) = state.workspaces.get(&tab.workspace_id) else {
             return false;
@@ -731,6 +1134,9 @@ fn normalize_loaded_state(state: &mut BrowserState) {
         }
     }
 
+    normalize_loaded_bookmarks(state);
+    normalize_loaded_search_engines(state);
+
     if state
         .active_profile_id
         .map(|profile_id| !state.profiles.contains_key(&profile_id))
@@ -740,9 +1146,75 @@ fn normalize_loaded_state(state: &mut BrowserState) {
     }
 
     state.recompute_next_ids();
+    // Databases from before the registry get the built-ins on first load.
+    ensure_search_engines(state);
+}
+
+fn normalize_loaded_search_engines(state: &mut BrowserState) {
+    state
+        .search_engines
+        .retain(|_, engine| state.profiles.contains_key(&engine.profile_id));
+    let mut profiles_with_default = BTreeSet::new();
+    for engine in state.search_engines.values_mut() {
+        if engine.is_default && !profiles_with_default.insert(engine.profile_id) {
+            engine.is_default = false;
+        }
+    }
 }
 
-fn required_cell<'a>(
+fn normalize_loaded_bookmarks(state: &mut BrowserState) {
+    state
+        .bookmark_folders
+        .retain(|_, folder| state.profiles.contains_key(&folder.profile_id));
+    let folder_profiles: BTreeMap<BookmarkFolderId, ProfileId> = state
+        .bookmark_folders
+        .values()
+        .map(|folder| (folder.id, folder.profile_id))
+        .collect();
+    for folder in state.bookmark_folders.values_mut() {
+        let parent_valid = folder
+            .parent_id
+            .map(|parent_id| {
+                parent_id != folder.id
+                    && folder_profiles.get(&parent_id) == Some(&folder.profile_id)
+            })
+            .unwrap_or(true);
+        if !parent_valid {
+            folder.parent_id = None;
+        }
+    }
+
+    state
+        .bookmarks
+        .retain(|_, bookmark| state.profiles.contains_key(&bookmark.profile_id));
+    for bookmark in state.bookmarks.values_mut() {
+        let folder_valid = bookmark
+            .folder_id
+            .map(|folder_id| folder_profiles.get(&folder_id) == Some(&bookmark.profile_id))
+            .unwrap_or(true);
+        if !folder_valid {
+            bookmark.folder_id = None;
+        }
+    }
+
+    let mut siblings: BTreeMap<_, Vec<(usize, BookmarkId)>> = BTreeMap::new();
+    for bookmark in state.bookmarks.values() {
+        siblings
+            .entry((bookmark.profile_id, bookmark.folder_id))
+            .or_default()
+            .push((bookmark.position, bookmark.id));
+    }
+    for mut entries in siblings.into_values() {
+        entries.sort();
+        for (position, (_, bookmark_id)) in entries.into_iter().enumerate() {
+            if let Some(bookmark) = state.bookmarks.get_mut(&bookmark_id) {
+                bookmark.position = position;
+            }
+        }
+    }
+}
+
+pub(crate) fn required_cell<'a>(
     row: &'a [Option<String>],
     index: usize,
     field: &str,
@@ -752,11 +1224,11 @@ fn required_cell<'a>(
         .ok_or_else(|| AppPersistenceError::InvalidData(format!("missing required {field}")))
 }
 
-fn optional_cell(row: &[Option<String>], index: usize) -> Option<&str> {
+pub(crate) fn optional_cell(row: &[Option<String>], index: usize) -> Option<&str> {
     row.get(index).and_then(|value| value.as_deref())
 }
 
-fn parse_u64(value: &str, field: &str) -> Result<u64, AppPersistenceError> {
+pub(crate) fn parse_u64(value: &str, field: &str) -> Result<u64, AppPersistenceError> {
     let parsed = value.parse::<i128>().map_err(|_| {
         AppPersistenceError::InvalidData(format!("{field} is not a valid integer: {value}"))
     })?;
@@ -768,7 +1240,7 @@ fn parse_u64(value: &str, field: &str) -> Result<u64, AppPersistenceError> {
     Ok(parsed as u64)
 }
 
-fn parse_i64(value: &str, field: &str) -> Result<i64, AppPersistenceError> {
+pub(crate) fn parse_i64(value: &str, field: &str) -> Result<i64, AppPersistenceError> {
     value.parse::<i64>().map_err(|_| {
         AppPersistenceError::InvalidData(format!("{field} is not a valid i64: {value}"))
     })
@@ -793,7 +1265,7 @@ fn runtime_state_from_i64(value: i64) -> Option<TabRuntimeState> {
     }
 }
 
-fn sql_bool(value: bool) -> &'static str {
+pub(crate) fn sql_bool(value: bool) -> &'static str {
     if value {
         "1"
     } else {
@@ -819,7 +1291,7 @@ fn sql_opt_text(value: Option<&str>) -> String {
         .unwrap_or_else(|| "NULL".to_owned())
 }
 
-fn sql_text_literal(value: &str) -> String {
+pub(crate) fn sql_text_literal(value: &str) -> String {
     let sanitized = value.replace('\0', " ");
     let escaped = sanitized.replace('\'', "''");
     format!("'{escaped}'")
@@ -848,6 +1320,10 @@ fn path_to_cstring(path: &Path) -> Result<CString, AppPersistenceError> {
 }
 
 fn default_state_db_path() -> Result<PathBuf, AppPersistenceError> {
+    default_data_file_path("state.sqlite3")
+}
+
+pub(crate) fn default_data_file_path(file_name: &str) -> Result<PathBuf, AppPersistenceError> {
     #[cfg(target_os = "macos")]
     {
         if let Some(home) = env::var_os("HOME") {
@@ -855,12 +1331,12 @@ fn default_state_db_path() -> Result<PathBuf, AppPersistenceError> {
                 .join("Library")
                 .join("Application Support")
                 .join("Switchboard")
-                .join("state.sqlite3"));
+                .join(file_name));
         }
     }
 
     let cwd = env::current_dir()?;
-    Ok(cwd.join("target").join("switchboard-state.sqlite3"))
+    Ok(cwd.join("target").join(format!("switchboard-{file_name}")))
 }
 
 #[cfg(test)]
@@ -886,9 +1362,18 @@ mod tests {
                 title: "Example".to_owned(),
                 loading: false,
                 thumbnail_data_url: Some("data:image/svg+xml;utf8,test".to_owned()),
+                favicon_key: Some("1-0123456789abcdef".to_owned()),
                 pinned: true,
+                keep_alive: true,
                 muted: false,
+                audible: false,
                 runtime_state: TabRuntimeState::Active,
+                blocked_navigation: None,
+                https_upgraded_from: None,
+                pending_permissions: Vec::new(),
+                opener_tab_id: None,
+                status: TabStatus::Ok,
+                crash_count: 0,
             },
         );
         state
@@ -1008,4 +1493,240 @@ mod tests {
 
         let _ = std::fs::remove_file(path);
     }
+
+    #[test]
+    fn sqlite_persistence_roundtrips_bookmarks() {
+        let mut persistence = AppPersistence::open_in_memory().expect("open in-memory sqlite");
+        let mut state = sample_state();
+        let profile_id = ProfileId(1);
+        let folder_id = BookmarkFolderId(1);
+        state.bookmark_folders.insert(
+            folder_id,
+            BookmarkFolder {
+                id: folder_id,
+                profile_id,
+                parent_id: None,
+                name: "Reading 'list'".to_owned(),
+            },
+        );
+        for (id, folder, position) in [(1, Some(folder_id), 0), (2, None, 0), (3, None, 1)] {
+            state.bookmarks.insert(
+                BookmarkId(id),
+                Bookmark {
+                    id: BookmarkId(id),
+                    profile_id,
+                    folder_id: folder,
+                    url: format!("https://bookmark{id}.example"),
+                    title: format!("Bookmark {id}"),
+                    position,
+                },
+            );
+        }
+
+        persistence.commit(&state).expect("commit should succeed");
+        let loaded = persistence
+            .load_state()
+            .expect("load should succeed")
+            .expect("state should exist");
+        assert_eq!(loaded.bookmark_folders, state.bookmark_folders);
+        assert_eq!(loaded.bookmarks, state.bookmarks);
+    }
+
+    #[test]
+    fn sqlite_persistence_roundtrips_search_engines() {
+        let mut persistence = AppPersistence::open_in_memory().expect("open in-memory sqlite");
+        let mut state = sample_state();
+        ensure_search_engines(&mut state);
+        let engine_id = SearchEngineId(100);
+        state.search_engines.insert(
+            engine_id,
+            SearchEngine {
+                id: engine_id,
+                profile_id: ProfileId(1),
+                name: "Rust 'docs'".to_owned(),
+                keyword: "rs".to_owned(),
+                url_template: "https://docs.rs/releases/search?query={searchTerms}".to_owned(),
+                is_default: false,
+                builtin: false,
+            },
+        );
+
+        persistence.commit(&state).expect("commit should succeed");
+        let loaded = persistence
+            .load_state()
+            .expect("load should succeed")
+            .expect("state should exist");
+        assert_eq!(loaded.search_engines, state.search_engines);
+    }
+
+    #[test]
+    fn sqlite_persistence_roundtrips_https_exceptions() {
+        let mut persistence = AppPersistence::open_in_memory().expect("open in-memory sqlite");
+        let mut state = sample_state();
+        state.https_exceptions.insert(
+            ProfileId(1),
+            ["intranet.example".to_owned(), "legacy.example".to_owned()]
+                .into_iter()
+                .collect(),
+        );
+        state.https_exceptions.insert(
+            ProfileId(99),
+            ["orphan.example".to_owned()].into_iter().collect(),
+        );
+
+        persistence.commit(&state).expect("commit should succeed");
+        let loaded = persistence
+            .load_state()
+            .expect("load should succeed")
+            .expect("state should exist");
+        state.https_exceptions.remove(&ProfileId(99));
+        assert_eq!(loaded.https_exceptions, state.https_exceptions);
+    }
+
+    #[test]
+    fn sqlite_persistence_roundtrips_site_permissions() {
+        let mut persistence = AppPersistence::open_in_memory().expect("open in-memory sqlite");
+        let mut state = sample_state();
+        state.site_permissions.insert(
+            ProfileId(1),
+            [
+                (
+                    ("https://meet.example".to_owned(), PermissionKind::Camera),
+                    PermissionSetting::Allow,
+                ),
+                (
+                    (
+                        "https://meet.example".to_owned(),
+                        PermissionKind::Notifications,
+                    ),
+                    PermissionSetting::Block,
+                ),
+                (
+                    (
+                        "http://localhost:3000".to_owned(),
+                        PermissionKind::JavaScript,
+                    ),
+                    PermissionSetting::Ask,
+                ),
+            ]
+            .into_iter()
+            .collect(),
+        );
+        state.site_permissions.insert(
+            ProfileId(99),
+            [(
+                ("https://orphan.example".to_owned(), PermissionKind::Popups),
+                PermissionSetting::Allow,
+            )]
+            .into_iter()
+            .collect(),
+        );
+
+        persistence.commit(&state).expect("commit should succeed");
+        let loaded = persistence
+            .load_state()
+            .expect("load should succeed")
+            .expect("state should exist");
+        state.site_permissions.remove(&ProfileId(99));
+        assert_eq!(loaded.site_permissions, state.site_permissions);
+    }
+
+    #[test]
+    fn sqlite_persistence_keeps_only_finished_downloads() {
+        let mut persistence = AppPersistence::open_in_memory().expect("open in-memory sqlite");
+        let mut state = sample_state();
+        let download = |id: u64, profile_id: u64, total_bytes, state| Download {
+            id: DownloadId(id),
+            profile_id: ProfileId(profile_id),
+            tab_id: None,
+            url: format!("https://files.example/{id}.zip"),
+            target_path: format!("/tmp/downloads/{id}.zip"),
+            received_bytes: 512,
+            total_bytes,
+            state,
+        };
+        for download in [
+            download(1, 1, Some(512), DownloadState::Complete),
+            download(2, 1, None, DownloadState::Failed),
+            download(3, 1, Some(4096), DownloadState::InProgress),
+            download(4, 1, Some(4096), DownloadState::Paused),
+            download(5, 99, Some(512), DownloadState::Complete),
+        ] {
+            state.downloads.insert(download.id, download);
+        }
+
+        persistence.commit(&state).expect("commit should succeed");
+        let loaded = persistence
+            .load_state()
+            .expect("load should succeed")
+            .expect("state should exist");
+        assert_eq!(
+            loaded.downloads.values().cloned().collect::<Vec<_>>(),
+            vec![
+                download(1, 1, Some(512), DownloadState::Complete),
+                download(2, 1, None, DownloadState::Failed),
+            ]
+        );
+    }
+
+    #[test]
+    fn migrations_upgrade_version_one_databases_in_place() {
+        let mut store = SqliteStore::open_memory().expect("open in-memory sqlite");
+        store
+            .exec_batch(&format!(
+                "CREATE TABLE meta (key TEXT PRIMARY KEY, value TEXT NOT NULL);
+                 {}
+                 INSERT INTO meta(key, value) VALUES('schema_version', '1');
+                 INSERT INTO profiles(id, name, active_workspace_id) VALUES(1, 'Legacy', 1);
+                 INSERT INTO workspaces(id, profile_id, name, active_tab_id) VALUES(1, 1, 'Main', NULL);
+                 INSERT INTO profile_workspace_order(profile_id, position, workspace_id) VALUES(1, 0, 1);",
+                MIGRATIONS[0].1
+            ))
+            .expect("legacy schema should be created");
+
+        store.migrate().expect("migration should succeed");
+        assert_eq!(
+            store.schema_version().expect("version should be readable"),
+            SCHEMA_VERSION
+        );
+        store
+            .migrate()
+            .expect("re-running migrations should be a no-op");
+
+        let loaded = store
+            .load_state()
+            .expect("load should succeed")
+            .expect("legacy state should exist");
+        assert_eq!(
+            loaded
+                .profiles
+                .get(&ProfileId(1))
+                .expect("legacy profile should survive")
+                .name,
+            "Legacy"
+        );
+        assert!(loaded.bookmarks.is_empty());
+        assert!(loaded.https_exceptions.is_empty());
+        assert!(loaded.site_permissions.is_empty());
+        assert!(loaded.downloads.is_empty());
+        assert!(loaded
+            .search_engines
+            .values()
+            .any(|engine| engine.profile_id == ProfileId(1) && engine.is_default));
+    }
+
+    #[test]
+    fn migrate_rejects_newer_schema_versions() {
+        let mut store = SqliteStore::open_memory().expect("open in-memory sqlite");
+        store
+            .exec_batch(
+                "CREATE TABLE meta (key TEXT PRIMARY KEY, value TEXT NOT NULL);
+                 INSERT INTO meta(key, value) VALUES('schema_version', '999');",
+            )
+            .expect("meta should be created");
+        assert!(matches!(
+            store.migrate(),
+            Err(AppPersistenceError::InvalidData(_))
+        ));
+    }
 }
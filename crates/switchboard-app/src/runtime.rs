@@ -2,27 +2,60 @@ use std::collections::BTreeMap;
 use std::collections::BTreeSet;
 use std::error::Error;
 use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Instant;
 
 #[cfg(test)]
 use std::convert::Infallible;
+use switchboard_core::content_filter::{
+    CONTENT_FILTER_ALLOWLIST_PROFILE_PREFIX, CONTENT_FILTER_ENABLED_PROFILE_PREFIX,
+};
+use switchboard_core::downloads::{download_directory, download_file_name, unique_file_name};
+use switchboard_core::history::is_ephemeral_profile;
+use switchboard_core::interchange::{
+    looks_like_netscape_bookmarks, parse_netscape_bookmarks, parse_url_list, to_markdown,
+    to_netscape_html,
+};
+use switchboard_core::lifecycle::{startup_restore_mode, startup_restore_tabs};
+use switchboard_core::omnibox::{resolve_navigation_input, suggest};
+use switchboard_core::search::ensure_search_engines;
+use switchboard_core::state::crash_reload_delay_ms;
+use switchboard_core::url::{is_web_url, normalize_navigation_url, BLANK_PAGE_URL};
 #[cfg(test)]
 use switchboard_core::NoopPersistence;
 use switchboard_core::{
-    BrowserState, Engine, EngineError, Intent, Patch, ProfileId, SettingValue, TabId,
-    TabRuntimeState, WorkspaceId,
+    BrowserState, Clock, DownloadState, Engine, EngineError, FilterEngine, HistoryEntry, Intent,
+    NavigationBlocked, Patch, PatchOp, PermissionKind, PermissionRequestId, PortableBundle,
+    PortableError, PrewarmPolicy, ProfileContentFilter, ProfileId, ReduceError, RequestFilter,
+    SettingValue, StartupRestoreMode, Suggestion, SuggestionKind, TabId, TabRuntimeState,
+    TabStatus, VisitTransition, WorkspaceId,
 };
 
 use crate::bridge::UiCommand;
+use crate::favicons::{
+    favicon_key, favicon_mime_type, preferred_favicon_url, FaviconCache, FAVICON_URL_PREFIX,
+    MAX_FAVICON_BYTES,
+};
+use crate::history::{unix_time_ms, HistoryStore, RetentionPolicy};
 use crate::host::{
-    install_content_event_handler, install_ui_command_handler, install_ui_state_provider,
-    install_window_event_handler, CefHost, ContentEvent, ContentViewId, UiViewId, WindowEvent,
-    WindowId, WindowSize,
+    install_content_event_handler, install_favicon_provider, install_ui_command_handler,
+    install_ui_query_provider, install_ui_state_provider, install_window_event_handler, CefHost,
+    ContentEvent, ContentViewId, UiQuery, UiViewId, WindowEvent, WindowId, WindowSize,
 };
+use crate::metrics::{RuntimeMetrics, TimedPersistence};
 #[cfg(not(test))]
 use crate::persistence::{AppPersistence, AppPersistenceError};
 
 const UI_SHELL_URL_BASE: &str = "app://ui";
 const THUMBNAIL_MAX_ENTRIES: usize = 120;
+// How often the lifecycle policy re-runs on its own, so idle warm tabs are
+// discarded while the user is not doing anything.
+const LIFECYCLE_TICK_INTERVAL_MS: u64 = 60_000;
+// Gap between background restores after launch, so the host is not asked
+// for every view at once.
+const STARTUP_RESTORE_INTERVAL_MS: u64 = 400;
 const WINDOW_WIDTH_SETTING_KEY: &str = "window.width";
 const WINDOW_HEIGHT_SETTING_KEY: &str = "window.height";
 const SEARCH_ENGINE_SETTING_KEY: &str = "search_engine";
@@ -38,13 +71,19 @@ const PASSWORD_MANAGER_DEFAULT_AUTOFILL_SETTING_KEY: &str = "password_manager.de
 const PASSWORD_MANAGER_DEFAULT_SAVE_PROMPT_SETTING_KEY: &str =
     "password_manager.default_save_prompt";
 const PASSWORD_MANAGER_DEFAULT_FALLBACK_SETTING_KEY: &str = "password_manager.default_fallback";
+const IMPORTED_LINKS_WORKSPACE_NAME: &str = "Imported links";
+const HISTORY_RETENTION_DAYS_SETTING_KEY: &str = "history.retention_days";
+const HISTORY_MAX_VISITS_SETTING_KEY: &str = "history.max_visits";
+const HISTORY_QUERY_LIMIT: usize = 50;
+const SUGGESTION_LIMIT: usize = 8;
+const SUGGESTION_HISTORY_CANDIDATES: usize = 50;
 const WINDOW_MIN_WIDTH: u32 = 640;
 const WINDOW_MIN_HEIGHT: u32 = 480;
 
 #[cfg(test)]
-type RuntimePersistence = NoopPersistence;
+type RuntimePersistence = TimedPersistence<NoopPersistence>;
 #[cfg(not(test))]
-type RuntimePersistence = AppPersistence;
+type RuntimePersistence = TimedPersistence<AppPersistence>;
 
 #[cfg(test)]
 type RuntimePersistenceError = Infallible;
@@ -59,7 +98,11 @@ pub enum RuntimeError<HError> {
     Engine(EngineError<RuntimePersistenceError>),
     NoActiveWorkspace,
     NoActiveProfile,
-    BlockedContentNavigation(String),
+    BlockedContentNavigation(NavigationBlocked),
+    Portable(PortableError),
+    PortableFile(String),
+    History(String),
+    Favicon(String),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -68,20 +111,36 @@ struct ContentBinding {
     profile_id: ProfileId,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HistoryUpdate {
+    None,
+    Visit,
+    Title,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct LiveTabBinding {
     content: ContentBinding,
     last_url: String,
+    muted: bool,
 }
 
 pub struct AppRuntime<H: CefHost> {
-    engine: Engine<RuntimePersistence>,
+    engine: Engine<RuntimePersistence, PrewarmPolicy>,
     host: H,
     window_id: WindowId,
     ui_view_id: UiViewId,
     default_workspace_id: WorkspaceId,
     tab_bindings: BTreeMap<TabId, LiveTabBinding>,
     thumbnail_lru: Vec<TabId>,
+    history: HistoryStore,
+    // Tabs whose next committed URL came from the UI rather than a page link.
+    typed_navigations: BTreeSet<TabId>,
+    content_filter: Arc<FilterEngine>,
+    favicons: FaviconCache,
+    metrics: RuntimeMetrics,
+    // Icon URL each tab is waiting on from `download_favicon`.
+    pending_favicons: BTreeMap<TabId, String>,
 }
 
 impl<H: CefHost + 'static> AppRuntime<H> {
@@ -100,9 +159,47 @@ impl<H: CefHost + 'static> AppRuntime<H> {
             (persistence, state)
         };
 
+        #[cfg(test)]
+        let history = HistoryStore::open_in_memory();
+        #[cfg(not(test))]
+        let history = HistoryStore::open_default();
+        let mut history =
+            history.map_err(|error| RuntimeError::PersistenceInit(error.to_string()))?;
+
+        #[cfg(test)]
+        let mut favicons = FaviconCache::in_memory();
+        #[cfg(not(test))]
+        let mut favicons = FaviconCache::open_default()
+            .map_err(|error| RuntimeError::PersistenceInit(error.to_string()))?;
+
         let workspace_id = ensure_bootstrap_state(&mut state);
+        let favicon_keys: BTreeSet<&str> = state
+            .tabs
+            .values()
+            .filter_map(|tab| tab.favicon_key.as_deref())
+            .collect();
+        favicons
+            .retain(&favicon_keys)
+            .map_err(|error| RuntimeError::PersistenceInit(error.to_string()))?;
+        history.set_retention(history_retention(&state));
+        history
+            .prune(unix_time_ms())
+            .map_err(|error| RuntimeError::PersistenceInit(error.to_string()))?;
         let initial_window_size = restored_window_size(&state);
-        let mut engine = Engine::with_state(persistence, state, 0);
+        let mut engine = Engine::with_policy(
+            TimedPersistence::new(persistence),
+            state,
+            0,
+            PrewarmPolicy::default(),
+        );
+
+        #[cfg(test)]
+        let content_filter = FilterEngine::default();
+        #[cfg(not(test))]
+        let content_filter = crate::filter_lists::load_default().unwrap_or_else(|error| {
+            eprintln!("switchboard-app: filter lists not loaded: {error}");
+            FilterEngine::default()
+        });
 
         let window_id = host
             .create_window("Switchboard", initial_window_size)
@@ -118,8 +215,10 @@ impl<H: CefHost + 'static> AppRuntime<H> {
         engine
             .dispatch(ui_ready.into_intent())
             .map_err(RuntimeError::Engine)?;
+        host.schedule_lifecycle_tick(LIFECYCLE_TICK_INTERVAL_MS)
+            .map_err(RuntimeError::Host)?;
 
-        Ok(Self {
+        let mut runtime = Self {
             engine,
             host,
             window_id,
@@ -127,7 +226,72 @@ impl<H: CefHost + 'static> AppRuntime<H> {
             default_workspace_id: workspace_id,
             tab_bindings: BTreeMap::new(),
             thumbnail_lru: Vec::new(),
-        })
+            history,
+            typed_navigations: BTreeSet::new(),
+            content_filter: Arc::new(content_filter),
+            favicons,
+            metrics: RuntimeMetrics::default(),
+            pending_favicons: BTreeMap::new(),
+        };
+        runtime.apply_startup_restore_mode()?;
+        Ok(runtime)
+    }
+
+    // Runs once the active tab is known: either moves to an empty workspace
+    // or queues the tabs `startup.restore_mode` brings back, one per
+    // interval.
+    fn apply_startup_restore_mode(&mut self) -> Result<(), RuntimeError<H::Error>> {
+        if startup_restore_mode(self.engine.state()) == StartupRestoreMode::Fresh {
+            return self.open_fresh_workspace();
+        }
+        let restores = startup_restore_tabs(self.engine.state());
+        for (idx, tab_id) in restores.into_iter().enumerate() {
+            self.host
+                .schedule_startup_restore(tab_id, STARTUP_RESTORE_INTERVAL_MS * (idx as u64 + 1))
+                .map_err(RuntimeError::Host)?;
+        }
+        Ok(())
+    }
+
+    // Reuses an empty workspace of the active profile, left from an earlier
+    // fresh start, before adding another.
+    fn open_fresh_workspace(&mut self) -> Result<(), RuntimeError<H::Error>> {
+        let state = self.engine.state();
+        let Some(profile) = state
+            .active_profile_id
+            .and_then(|profile_id| state.profiles.get(&profile_id))
+        else {
+            return Ok(());
+        };
+        let profile_id = profile.id;
+        let empty_workspace_id = profile
+            .workspace_order
+            .iter()
+            .copied()
+            .find(|workspace_id| {
+                state
+                    .workspaces
+                    .get(workspace_id)
+                    .is_some_and(|workspace| workspace.tab_order.is_empty())
+            });
+        let workspace_id = match empty_workspace_id {
+            Some(workspace_id) => workspace_id,
+            None => {
+                let name = format!("Workspace {}", profile.workspace_order.len() + 1);
+                self.engine
+                    .dispatch(Intent::NewWorkspace { profile_id, name })
+                    .map_err(RuntimeError::Engine)?;
+                *self.engine.state().profiles[&profile_id]
+                    .workspace_order
+                    .last()
+                    .expect("workspace was just added")
+            }
+        };
+        self.engine
+            .dispatch(Intent::SwitchWorkspace { workspace_id })
+            .map_err(RuntimeError::Engine)?;
+        self.default_workspace_id = workspace_id;
+        Ok(())
     }
 
     pub fn default_workspace_id(&self) -> WorkspaceId {
@@ -142,7 +306,7 @@ impl<H: CefHost + 'static> AppRuntime<H> {
         self.engine.revision()
     }
 
-    pub fn engine(&self) -> &Engine<RuntimePersistence> {
+    pub fn engine(&self) -> &Engine<RuntimePersistence, PrewarmPolicy> {
         &self.engine
     }
 
@@ -155,6 +319,17 @@ impl<H: CefHost + 'static> AppRuntime<H> {
         &self.host
     }
 
+    #[cfg(test)]
+    pub fn host_mut(&mut self) -> &mut H {
+        &mut self.host
+    }
+
+    #[cfg(test)]
+    fn set_content_filter(&mut self, engine: FilterEngine) -> Result<(), RuntimeError<H::Error>> {
+        self.content_filter = Arc::new(engine);
+        self.refresh_request_filters()
+    }
+
     pub fn run(mut self) -> Result<(), RuntimeError<H::Error>>
     where
         H::Error: Display,
@@ -170,6 +345,9 @@ impl<H: CefHost + 'static> AppRuntime<H> {
         install_ui_state_provider(Some(Box::new(move || unsafe {
             (*runtime_ptr).ui_shell_state_json()
         })));
+        install_ui_query_provider(Some(Box::new(move |query| unsafe {
+            (*runtime_ptr).handle_ui_query(query)
+        })));
         install_content_event_handler(Some(Box::new(move |event| unsafe {
             if let Err(error) = (*runtime_ptr).handle_content_event(event) {
                 eprintln!("switchboard-app: content event failed: {error}");
@@ -180,12 +358,17 @@ impl<H: CefHost + 'static> AppRuntime<H> {
                 eprintln!("switchboard-app: window event failed: {error}");
             }
         })));
+        install_favicon_provider(Some(Box::new(move |key| unsafe {
+            (*runtime_ptr).favicon_resource(key)
+        })));
 
         let result = self.host.run_event_loop().map_err(RuntimeError::Host);
         install_ui_command_handler(None);
         install_ui_state_provider(None);
+        install_ui_query_provider(None);
         install_content_event_handler(None);
         install_window_event_handler(None);
+        install_favicon_provider(None);
         result
     }
 
@@ -207,6 +390,15 @@ impl<H: CefHost + 'static> AppRuntime<H> {
                     make_active: true,
                 })
             }
+            UiCommand::NavigateInput { text } => {
+                let profile_id = self
+                    .resolve_active_profile_id()
+                    .ok_or(RuntimeError::NoActiveProfile)?;
+                match resolve_navigation_input(self.engine.state(), profile_id, &text) {
+                    Some(url) => self.handle_ui_command(UiCommand::NavigateActive { url }),
+                    None => Ok(self.unchanged_patch()),
+                }
+            }
             UiCommand::NewWorkspace { name } => {
                 let profile_id = self
                     .resolve_active_profile_id()
@@ -225,38 +417,529 @@ impl<H: CefHost + 'static> AppRuntime<H> {
                     to_revision: revision,
                 })
             }
+            UiCommand::ResetMetrics => {
+                self.metrics = RuntimeMetrics::default();
+                Ok(self.unchanged_patch())
+            }
+            UiCommand::ImportWorkspaces { profile_id, path } => {
+                self.import_workspaces_from_file(ProfileId(profile_id), path)
+            }
             other => self.handle_intent(other.into_intent()),
         }
     }
 
-    pub fn handle_intent(&mut self, intent: Intent) -> Result<Patch, RuntimeError<H::Error>> {
-        if let Intent::Navigate { url, .. } = &intent {
-            if url.starts_with("app://") {
-                return Err(RuntimeError::BlockedContentNavigation(url.clone()));
+    pub fn import_workspaces_json(
+        &mut self,
+        profile_id: ProfileId,
+        json: &str,
+    ) -> Result<Patch, RuntimeError<H::Error>> {
+        let bundle = PortableBundle::from_json(json).map_err(RuntimeError::Portable)?;
+        self.handle_intent(Intent::ImportWorkspaces { profile_id, bundle })
+    }
+
+    // Accepts portable JSON bundles, Netscape bookmark HTML and URL/markdown lists.
+    pub fn import_workspaces_from_file(
+        &mut self,
+        profile_id: ProfileId,
+        path: impl AsRef<Path>,
+    ) -> Result<Patch, RuntimeError<H::Error>> {
+        let path = path.as_ref();
+        let contents = fs::read_to_string(path)
+            .map_err(|error| RuntimeError::PortableFile(format!("{}: {error}", path.display())))?;
+        if contents.trim_start().starts_with('{') {
+            return self.import_workspaces_json(profile_id, &contents);
+        }
+        let bundle = if looks_like_netscape_bookmarks(&contents) {
+            parse_netscape_bookmarks(&contents)
+        } else {
+            let fallback_name = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .filter(|stem| !stem.is_empty())
+                .unwrap_or(IMPORTED_LINKS_WORKSPACE_NAME);
+            parse_url_list(&contents, fallback_name)
+        };
+        self.handle_intent(Intent::ImportWorkspaces { profile_id, bundle })
+    }
+
+    fn unchanged_patch(&self) -> Patch {
+        let revision = self.revision();
+        Patch {
+            ops: Vec::new(),
+            from_revision: revision,
+            to_revision: revision,
+        }
+    }
+
+    // An empty query lists the most recent pages instead of matching text.
+    pub fn search_history(
+        &mut self,
+        profile_id: ProfileId,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<HistoryEntry>, RuntimeError<H::Error>> {
+        let result = if query.trim().is_empty() {
+            self.history.recent(profile_id, limit)
+        } else {
+            self.history.search(profile_id, query, limit)
+        };
+        result.map_err(|error| RuntimeError::History(error.to_string()))
+    }
+
+    pub fn handle_ui_query(&mut self, query: UiQuery) -> String
+    where
+        H::Error: Display,
+    {
+        let result = match (query, self.resolve_active_profile_id()) {
+            (UiQuery::Metrics, _) => Ok(self.metrics.to_json()),
+            (_, None) => return "[]".to_owned(),
+            (UiQuery::History(text), Some(profile_id)) => self
+                .search_history(profile_id, &text, HISTORY_QUERY_LIMIT)
+                .map(|entries| history_entries_json(&entries)),
+            (UiQuery::Suggestions(text), Some(profile_id)) => self
+                .omnibox_suggestions(profile_id, &text, SUGGESTION_LIMIT)
+                .map(|suggestions| suggestions_json(&suggestions)),
+        };
+        result.unwrap_or_else(|error| {
+            eprintln!("switchboard-app: UI query failed: {error}");
+            "[]".to_owned()
+        })
+    }
+
+    pub fn omnibox_suggestions(
+        &mut self,
+        profile_id: ProfileId,
+        text: &str,
+        limit: usize,
+    ) -> Result<Vec<Suggestion>, RuntimeError<H::Error>> {
+        let history = if text.trim().is_empty() {
+            Vec::new()
+        } else {
+            self.history
+                .search(profile_id, text, SUGGESTION_HISTORY_CANDIDATES)
+                .map_err(|error| RuntimeError::History(error.to_string()))?
+        };
+        Ok(suggest(
+            self.engine.state(),
+            profile_id,
+            text,
+            &history,
+            unix_time_ms(),
+            limit,
+        ))
+    }
+
+    // Pages ask through the host; the reducer answers from site settings or
+    // parks the request on the tab until the user decides.
+    fn handle_permission_request(
+        &mut self,
+        tab_id: TabId,
+        origin: String,
+        kind: PermissionKind,
+        request_id: PermissionRequestId,
+    ) -> Result<Patch, RuntimeError<H::Error>> {
+        if !self.engine.state().tabs.contains_key(&tab_id) {
+            self.host
+                .complete_permission_request(request_id, false)
+                .map_err(RuntimeError::Host)?;
+            return Ok(self.unchanged_patch());
+        }
+        self.handle_intent(Intent::ObservePermissionRequest {
+            tab_id,
+            request_id,
+            origin,
+            kind,
+        })
+    }
+
+    // Picks a free file name in the profile's download directory, records the
+    // download and hands the path back to the host.
+    fn handle_download_started(
+        &mut self,
+        tab_id: TabId,
+        host_download_id: u64,
+        url: String,
+        suggested_name: Option<&str>,
+        total_bytes: Option<u64>,
+    ) -> Result<Patch, RuntimeError<H::Error>> {
+        let state = self.engine.state();
+        let Some(tab) = state.tabs.get(&tab_id) else {
+            return Ok(self.unchanged_patch());
+        };
+        let directory = download_directory(state, tab.profile_id)
+            .map(PathBuf::from)
+            .unwrap_or_else(default_download_directory);
+        // Completed files count as taken even before they show up on disk.
+        let taken_paths: BTreeSet<&str> = state
+            .downloads
+            .values()
+            .filter(|download| {
+                !matches!(
+                    download.state,
+                    DownloadState::Failed | DownloadState::Cancelled
+                )
+            })
+            .map(|download| download.target_path.as_str())
+            .collect();
+        let file_name = unique_file_name(&download_file_name(&url, suggested_name), |name| {
+            let path = directory.join(name);
+            path.exists() || taken_paths.contains(path.to_string_lossy().as_ref())
+        });
+        let target_path = directory.join(file_name).to_string_lossy().into_owned();
+
+        let patch = self.handle_intent(Intent::ObserveDownloadStarted {
+            tab_id,
+            url,
+            target_path: target_path.clone(),
+            total_bytes,
+        })?;
+        for op in &patch.ops {
+            if let PatchOp::UpsertDownload(download) = op {
+                self.host
+                    .begin_download(host_download_id, download.id, &target_path)
+                    .map_err(RuntimeError::Host)?;
             }
         }
+        Ok(patch)
+    }
+
+    pub fn handle_intent(&mut self, intent: Intent) -> Result<Patch, RuntimeError<H::Error>> {
+        let mut typed_tab_id = None;
+        let typed_url = match &intent {
+            Intent::Navigate { tab_id, .. } => {
+                typed_tab_id = Some(*tab_id);
+                None
+            }
+            Intent::NewTab { url: Some(url), .. } => normalize_navigation_url(url).ok(),
+            _ => None,
+        };
+
+        let deleted_profile_id = match &intent {
+            Intent::DeleteProfile { profile_id } => Some(*profile_id),
+            _ => None,
+        };
 
-        let patch = self.engine.dispatch(intent).map_err(RuntimeError::Engine)?;
+        let previous_active_tab_id = self.resolve_active_tab_id();
+        let prior_states: BTreeMap<TabId, TabRuntimeState> = self
+            .engine
+            .state()
+            .tabs
+            .values()
+            .map(|tab| (tab.id, tab.runtime_state))
+            .collect();
+        let started = Instant::now();
+        let patch = self.engine.dispatch(intent).map_err(|error| match error {
+            EngineError::Reduce(ReduceError::NavigationBlocked(blocked)) => {
+                RuntimeError::BlockedContentNavigation(blocked)
+            }
+            error => RuntimeError::Engine(error),
+        })?;
+        self.metrics.dispatch_latency.record(started.elapsed());
+        if let Some(elapsed) = self.engine.persistence().take_last_commit() {
+            self.metrics.commit_latency.record(elapsed);
+        }
+        self.metrics.record_patch(patch.ops.len());
+        let active_tab_id = self.resolve_active_tab_id();
+        if active_tab_id != previous_active_tab_id {
+            // A tab that did not exist before was opened, not restored.
+            if let Some(prior_state) = active_tab_id.and_then(|tab_id| prior_states.get(&tab_id)) {
+                self.metrics.record_restore(*prior_state);
+            }
+        }
+        if let Some(tab_id) = typed_tab_id {
+            self.typed_navigations.insert(tab_id);
+        }
+        if let Some(profile_id) = deleted_profile_id {
+            self.favicons
+                .remove_profile(profile_id)
+                .map_err(|error| RuntimeError::Favicon(error.to_string()))?;
+        }
+        self.apply_history_ops(&patch, typed_url.as_deref())?;
+        // Tabs without a live view are reloaded by the sync below creating
+        // one.
+        for op in &patch.ops {
+            if let PatchOp::TabReloaded { tab_id } = op {
+                if let Some(binding) = self.tab_bindings.get(tab_id) {
+                    self.host
+                        .navigate_content_view(binding.content.view_id, *tab_id, &binding.last_url)
+                        .map_err(RuntimeError::Host)?;
+                }
+            }
+        }
         self.sync_runtime_views()?;
+        for op in &patch.ops {
+            match op {
+                PatchOp::WorkspacesExported { path, bundle } => {
+                    write_portable_file(Path::new(path), bundle)?;
+                }
+                PatchOp::PermissionRequestSettled {
+                    request_id,
+                    granted,
+                    ..
+                } => {
+                    self.host
+                        .complete_permission_request(*request_id, *granted)
+                        .map_err(RuntimeError::Host)?;
+                }
+                PatchOp::DownloadCommandIssued {
+                    download_id,
+                    command,
+                } => {
+                    self.host
+                        .control_download(*download_id, *command)
+                        .map_err(RuntimeError::Host)?;
+                }
+                PatchOp::DownloadFolderOpened { target_path, .. } => {
+                    self.host
+                        .open_download_folder(target_path)
+                        .map_err(RuntimeError::Host)?;
+                }
+                _ => {}
+            }
+        }
+        if patch.ops.iter().any(|op| {
+            matches!(op, PatchOp::SettingChanged { key, .. }
+                if key.starts_with(CONTENT_FILTER_ENABLED_PROFILE_PREFIX)
+                    || key.starts_with(CONTENT_FILTER_ALLOWLIST_PROFILE_PREFIX))
+        }) {
+            self.refresh_request_filters()?;
+        }
         Ok(patch)
     }
 
+    fn apply_history_ops(
+        &mut self,
+        patch: &Patch,
+        typed_url: Option<&str>,
+    ) -> Result<(), RuntimeError<H::Error>> {
+        let mut retention_changed = false;
+        for op in &patch.ops {
+            match op {
+                PatchOp::HistoryCleared { profile_id, scope } => self
+                    .history
+                    .clear(*profile_id, scope)
+                    .map_err(|error| RuntimeError::History(error.to_string()))?,
+                PatchOp::UpsertTab(tab) if typed_url == Some(tab.url.as_str()) => {
+                    self.typed_navigations.insert(tab.id);
+                }
+                PatchOp::RemoveTab { tab_id, .. } => {
+                    self.typed_navigations.remove(tab_id);
+                }
+                PatchOp::SettingChanged { key, .. } => {
+                    retention_changed |= key == HISTORY_RETENTION_DAYS_SETTING_KEY
+                        || key == HISTORY_MAX_VISITS_SETTING_KEY;
+                }
+                _ => {}
+            }
+        }
+        if retention_changed {
+            self.history
+                .set_retention(history_retention(self.engine.state()));
+            self.history
+                .prune(unix_time_ms())
+                .map_err(|error| RuntimeError::History(error.to_string()))?;
+        }
+        Ok(())
+    }
+
+    fn update_history_title(&mut self, tab_id: TabId) -> Result<(), RuntimeError<H::Error>> {
+        let state = self.engine.state();
+        let Some(tab) = state.tabs.get(&tab_id) else {
+            return Ok(());
+        };
+        if tab.title.is_empty()
+            || !is_web_url(&tab.url)
+            || is_ephemeral_profile(state, tab.profile_id)
+        {
+            return Ok(());
+        }
+        let (profile_id, url, title) = (tab.profile_id, tab.url.clone(), tab.title.clone());
+        self.history
+            .update_latest_title(profile_id, &url, &title)
+            .map_err(|error| RuntimeError::History(error.to_string()))
+    }
+
+    fn record_history_visit(
+        &mut self,
+        tab_id: TabId,
+        previous_url: &str,
+    ) -> Result<(), RuntimeError<H::Error>> {
+        let typed = self.typed_navigations.remove(&tab_id);
+        let state = self.engine.state();
+        let Some(tab) = state.tabs.get(&tab_id) else {
+            return Ok(());
+        };
+        if !is_web_url(&tab.url)
+            || tab.blocked_navigation.is_some()
+            || is_ephemeral_profile(state, tab.profile_id)
+        {
+            return Ok(());
+        }
+        let transition = if typed {
+            VisitTransition::Typed
+        } else if tab.url == previous_url {
+            VisitTransition::Reload
+        } else {
+            VisitTransition::Link
+        };
+        let (profile_id, url, title) = (tab.profile_id, tab.url.clone(), tab.title.clone());
+        self.history
+            .record_visit(profile_id, &url, &title, unix_time_ms(), transition)
+            .map_err(|error| RuntimeError::History(error.to_string()))
+    }
+
     pub fn handle_content_event(
         &mut self,
         event: ContentEvent,
     ) -> Result<Patch, RuntimeError<H::Error>> {
-        let (intent, tab_id, should_capture_thumbnail) = match event {
-            ContentEvent::UrlChanged { tab_id, url } => {
-                (Intent::ObserveTabUrl { tab_id, url }, tab_id, false)
-            }
-            ContentEvent::TitleChanged { tab_id, title } => {
-                (Intent::ObserveTabTitle { tab_id, title }, tab_id, false)
-            }
+        let (intent, tab_id, should_capture_thumbnail, history_update) = match event {
+            ContentEvent::UrlChanged { tab_id, url } => (
+                Intent::ObserveTabUrl { tab_id, url },
+                tab_id,
+                false,
+                HistoryUpdate::Visit,
+            ),
+            ContentEvent::TitleChanged { tab_id, title } => (
+                Intent::ObserveTabTitle { tab_id, title },
+                tab_id,
+                false,
+                HistoryUpdate::Title,
+            ),
             ContentEvent::LoadingChanged { tab_id, is_loading } => (
                 Intent::ObserveTabLoading { tab_id, is_loading },
                 tab_id,
                 !is_loading,
+                HistoryUpdate::None,
+            ),
+            ContentEvent::AudioStateChanged { tab_id, audible } => (
+                Intent::ObserveTabAudible { tab_id, audible },
+                tab_id,
+                false,
+                HistoryUpdate::None,
+            ),
+            ContentEvent::MemoryEstimated { tab_id, bytes } => (
+                Intent::ObserveTabMemory { tab_id, bytes },
+                tab_id,
+                false,
+                HistoryUpdate::None,
+            ),
+            ContentEvent::MemoryPressureChanged { level } => {
+                return self.handle_intent(Intent::MemoryPressure { level })
+            }
+            ContentEvent::LoadFailed {
+                tab_id,
+                url,
+                error_code,
+            } => (
+                Intent::ObserveTabLoadFailed {
+                    tab_id,
+                    url,
+                    error_code,
+                },
+                tab_id,
+                false,
+                HistoryUpdate::None,
             ),
+            ContentEvent::RendererCrashed { tab_id } => return self.handle_renderer_crash(tab_id),
+            ContentEvent::FaviconChanged { tab_id, urls } => {
+                return self.handle_favicon_changed(tab_id, &urls)
+            }
+            ContentEvent::FaviconDownloaded {
+                tab_id,
+                icon_url,
+                data,
+            } => return self.handle_favicon_downloaded(tab_id, icon_url, data),
+            ContentEvent::CrashReloadDue { tab_id } => {
+                let still_crashed = self
+                    .engine
+                    .state()
+                    .tabs
+                    .get(&tab_id)
+                    .is_some_and(|tab| tab.status == TabStatus::Crashed);
+                if !still_crashed || self.resolve_active_tab_id() == Some(tab_id) {
+                    return Ok(self.unchanged_patch());
+                }
+                return self.handle_intent(Intent::ReloadTab { tab_id });
+            }
+            ContentEvent::StartupRestoreDue { tab_id } => {
+                let still_discarded = self
+                    .engine
+                    .state()
+                    .tabs
+                    .get(&tab_id)
+                    .is_some_and(|tab| tab.runtime_state == TabRuntimeState::Discarded);
+                if !still_discarded {
+                    return Ok(self.unchanged_patch());
+                }
+                return self.handle_intent(Intent::WarmTab { tab_id });
+            }
+            ContentEvent::LifecycleTickDue => {
+                let now = self.engine.clock().now_ms();
+                let patch = self.handle_intent(Intent::LifecycleTick { now })?;
+                // Fresh estimates feed the next pass of the policy.
+                for (tab_id, binding) in &self.tab_bindings {
+                    self.host
+                        .request_memory_estimate(binding.content.view_id, *tab_id)
+                        .map_err(RuntimeError::Host)?;
+                }
+                self.host
+                    .schedule_lifecycle_tick(LIFECYCLE_TICK_INTERVAL_MS)
+                    .map_err(RuntimeError::Host)?;
+                return Ok(patch);
+            }
+            ContentEvent::PermissionRequested {
+                tab_id,
+                origin,
+                kind,
+                request_id,
+            } => return self.handle_permission_request(tab_id, origin, kind, request_id),
+            ContentEvent::NewWindowRequested {
+                opener_tab_id,
+                url,
+                disposition,
+                user_gesture,
+            } => {
+                if !self.engine.state().tabs.contains_key(&opener_tab_id) {
+                    return Ok(self.unchanged_patch());
+                }
+                return self.handle_intent(Intent::ObserveNewWindowRequest {
+                    opener_tab_id,
+                    url,
+                    disposition,
+                    user_gesture,
+                });
+            }
+            ContentEvent::DownloadStarted {
+                tab_id,
+                host_download_id,
+                url,
+                suggested_name,
+                total_bytes,
+            } => {
+                return self.handle_download_started(
+                    tab_id,
+                    host_download_id,
+                    url,
+                    suggested_name.as_deref(),
+                    total_bytes,
+                )
+            }
+            ContentEvent::DownloadProgress {
+                download_id,
+                received_bytes,
+                total_bytes,
+                state,
+            } => {
+                if !self.engine.state().downloads.contains_key(&download_id) {
+                    return Ok(self.unchanged_patch());
+                }
+                return self.handle_intent(Intent::ObserveDownloadProgress {
+                    download_id,
+                    received_bytes,
+                    total_bytes,
+                    state,
+                });
+            }
         };
 
         if !self.engine.state().tabs.contains_key(&tab_id) {
@@ -268,7 +951,19 @@ impl<H: CefHost + 'static> AppRuntime<H> {
             });
         }
 
+        let previous_url = self
+            .engine
+            .state()
+            .tabs
+            .get(&tab_id)
+            .map(|tab| tab.url.clone())
+            .unwrap_or_default();
         let patch = self.handle_intent(intent)?;
+        match history_update {
+            HistoryUpdate::Visit => self.record_history_visit(tab_id, &previous_url)?,
+            HistoryUpdate::Title => self.update_history_title(tab_id)?,
+            HistoryUpdate::None => {}
+        }
         if should_capture_thumbnail {
             self.capture_thumbnail_for_tab(tab_id)?;
             self.cleanup_thumbnail_storage()?;
@@ -276,6 +971,101 @@ impl<H: CefHost + 'static> AppRuntime<H> {
         Ok(patch)
     }
 
+    // The dead view is dropped by the sync that follows the crash. Background
+    // tabs are reloaded after a growing delay; the visible tab waits for the
+    // user.
+    fn handle_renderer_crash(&mut self, tab_id: TabId) -> Result<Patch, RuntimeError<H::Error>> {
+        if !self.engine.state().tabs.contains_key(&tab_id) {
+            return Ok(self.unchanged_patch());
+        }
+        let patch = self.handle_intent(Intent::ObserveRendererCrashed { tab_id })?;
+        let crash_count = self.engine.state().tabs[&tab_id].crash_count;
+        if patch.ops.is_empty() || self.resolve_active_tab_id() == Some(tab_id) {
+            return Ok(patch);
+        }
+        if let Some(delay_ms) = crash_reload_delay_ms(crash_count) {
+            self.host
+                .schedule_crash_reload(tab_id, delay_ms)
+                .map_err(RuntimeError::Host)?;
+        }
+        Ok(patch)
+    }
+
+    fn handle_favicon_changed(
+        &mut self,
+        tab_id: TabId,
+        urls: &[String],
+    ) -> Result<Patch, RuntimeError<H::Error>> {
+        let Some(profile_id) = self
+            .engine
+            .state()
+            .tabs
+            .get(&tab_id)
+            .map(|tab| tab.profile_id)
+        else {
+            return Ok(self.unchanged_patch());
+        };
+        let Some(icon_url) = preferred_favicon_url(urls) else {
+            self.pending_favicons.remove(&tab_id);
+            return self.handle_intent(Intent::ObserveTabFavicon {
+                tab_id,
+                favicon_key: None,
+            });
+        };
+        let key = favicon_key(profile_id, icon_url);
+        if self.favicons.contains(&key) {
+            self.pending_favicons.remove(&tab_id);
+            return self.handle_intent(Intent::ObserveTabFavicon {
+                tab_id,
+                favicon_key: Some(key),
+            });
+        }
+        self.host
+            .download_favicon(tab_id, icon_url)
+            .map_err(RuntimeError::Host)?;
+        self.pending_favicons.insert(tab_id, icon_url.to_owned());
+        Ok(self.unchanged_patch())
+    }
+
+    // Answers for an icon the page has since replaced are dropped.
+    fn handle_favicon_downloaded(
+        &mut self,
+        tab_id: TabId,
+        icon_url: String,
+        data: Option<Vec<u8>>,
+    ) -> Result<Patch, RuntimeError<H::Error>> {
+        if self.pending_favicons.get(&tab_id) != Some(&icon_url) {
+            return Ok(self.unchanged_patch());
+        }
+        self.pending_favicons.remove(&tab_id);
+        let state = self.engine.state();
+        let Some(profile_id) = state.tabs.get(&tab_id).map(|tab| tab.profile_id) else {
+            return Ok(self.unchanged_patch());
+        };
+        let persist = !is_ephemeral_profile(state, profile_id);
+        let Some(data) = data
+            .filter(|data| data.len() <= MAX_FAVICON_BYTES && favicon_mime_type(data).is_some())
+        else {
+            return self.handle_intent(Intent::ObserveTabFavicon {
+                tab_id,
+                favicon_key: None,
+            });
+        };
+        let key = favicon_key(profile_id, &icon_url);
+        self.favicons
+            .store(&key, &data, persist)
+            .map_err(|error| RuntimeError::Favicon(error.to_string()))?;
+        self.handle_intent(Intent::ObserveTabFavicon {
+            tab_id,
+            favicon_key: Some(key),
+        })
+    }
+
+    pub fn favicon_resource(&self, key: &str) -> Option<(&'static str, Vec<u8>)> {
+        let bytes = self.favicons.load(key)?;
+        Some((favicon_mime_type(&bytes)?, bytes))
+    }
+
     pub fn handle_window_event(
         &mut self,
         event: WindowEvent,
@@ -356,7 +1146,7 @@ impl<H: CefHost + 'static> AppRuntime<H> {
         let active_profile_id = self.resolve_active_profile_id();
         let active_tab_id = self.resolve_active_tab_id();
 
-        let desired_live_tabs: Vec<(TabId, ProfileId, String)> = self
+        let desired_live_tabs: Vec<(TabId, ProfileId, String, bool)> = self
             .engine
             .state()
             .tabs
@@ -365,13 +1155,19 @@ impl<H: CefHost + 'static> AppRuntime<H> {
                 matches!(
                     tab.runtime_state,
                     TabRuntimeState::Active | TabRuntimeState::Warm
-                )
+                ) && tab.status != TabStatus::Crashed
+            })
+            .map(|tab| {
+                let url = match tab.blocked_navigation {
+                    Some(_) => BLANK_PAGE_URL.to_owned(),
+                    None => tab.url.clone(),
+                };
+                (tab.id, tab.profile_id, url, tab.muted)
             })
-            .map(|tab| (tab.id, tab.profile_id, tab.url.clone()))
             .collect();
         let desired_live_ids: BTreeSet<TabId> = desired_live_tabs
             .iter()
-            .map(|(tab_id, _, _)| *tab_id)
+            .map(|(tab_id, ..)| *tab_id)
             .collect();
 
         let stale_tabs: Vec<TabId> = self
@@ -385,12 +1181,21 @@ impl<H: CefHost + 'static> AppRuntime<H> {
                 self.host
                     .destroy_content_view(binding.content.view_id)
                     .map_err(RuntimeError::Host)?;
+                self.metrics.views_destroyed += 1;
             }
         }
 
-        for (tab_id, profile_id, url) in desired_live_tabs {
+        for (tab_id, profile_id, url, muted) in desired_live_tabs {
             match self.tab_bindings.get(&tab_id).cloned() {
                 Some(existing) => {
+                    if existing.muted != muted {
+                        self.host
+                            .set_audio_muted(existing.content.view_id, muted)
+                            .map_err(RuntimeError::Host)?;
+                        if let Some(binding) = self.tab_bindings.get_mut(&tab_id) {
+                            binding.muted = muted;
+                        }
+                    }
                     if existing.last_url != url {
                         self.host
                             .navigate_content_view(existing.content.view_id, tab_id, &url)
@@ -406,6 +1211,13 @@ impl<H: CefHost + 'static> AppRuntime<H> {
                         .host
                         .create_content_view(self.window_id, tab_id, &url)
                         .map_err(RuntimeError::Host)?;
+                    self.metrics.views_created += 1;
+                    self.apply_request_filter(view_id, profile_id)?;
+                    if muted {
+                        self.host
+                            .set_audio_muted(view_id, true)
+                            .map_err(RuntimeError::Host)?;
+                    }
                     self.tab_bindings.insert(
                         tab_id,
                         LiveTabBinding {
@@ -414,6 +1226,7 @@ impl<H: CefHost + 'static> AppRuntime<H> {
                                 profile_id,
                             },
                             last_url: url,
+                            muted,
                         },
                     );
                 }
@@ -436,6 +1249,34 @@ impl<H: CefHost + 'static> AppRuntime<H> {
         Ok(())
     }
 
+    fn apply_request_filter(
+        &mut self,
+        view_id: ContentViewId,
+        profile_id: ProfileId,
+    ) -> Result<(), RuntimeError<H::Error>> {
+        let filter = ProfileContentFilter::for_profile(
+            self.engine.state(),
+            profile_id,
+            &self.content_filter,
+        )
+        .map(|filter| Arc::new(filter) as Arc<dyn RequestFilter>);
+        self.host
+            .set_request_filter(view_id, filter)
+            .map_err(RuntimeError::Host)
+    }
+
+    fn refresh_request_filters(&mut self) -> Result<(), RuntimeError<H::Error>> {
+        let bindings: Vec<ContentBinding> = self
+            .tab_bindings
+            .values()
+            .map(|binding| binding.content)
+            .collect();
+        for binding in bindings {
+            self.apply_request_filter(binding.view_id, binding.profile_id)?;
+        }
+        Ok(())
+    }
+
     fn capture_thumbnail_for_tab(&mut self, tab_id: TabId) -> Result<(), RuntimeError<H::Error>> {
         let Some(tab) = self.engine.state().tabs.get(&tab_id).cloned() else {
             self.thumbnail_lru.retain(|candidate| *candidate != tab_id);
@@ -448,6 +1289,7 @@ impl<H: CefHost + 'static> AppRuntime<H> {
                 data_url: Some(data_url),
             })
             .map_err(RuntimeError::Engine)?;
+        self.metrics.thumbnail_captures += 1;
         self.touch_thumbnail_lru(tab_id);
         Ok(())
     }
@@ -597,14 +1439,223 @@ impl<H: CefHost + 'static> AppRuntime<H> {
             json.push_str("\"loading\":");
             json.push_str(if tab.loading { "true" } else { "false" });
             json.push(',');
+            json.push_str("\"keep_alive\":");
+            json.push_str(if tab.keep_alive { "true" } else { "false" });
+            json.push(',');
+            json.push_str("\"muted\":");
+            json.push_str(if tab.muted { "true" } else { "false" });
+            json.push(',');
+            json.push_str("\"audible\":");
+            json.push_str(if tab.audible { "true" } else { "false" });
+            json.push(',');
             json.push_str("\"thumbnail_data_url\":");
             match &tab.thumbnail_data_url {
                 Some(value) => push_json_string(&mut json, value),
                 None => json.push_str("null"),
             }
+            json.push(',');
+            json.push_str("\"blocked_navigation\":");
+            match &tab.blocked_navigation {
+                Some(blocked) => {
+                    json.push_str("{\"url\":");
+                    push_json_string(&mut json, &blocked.url);
+                    json.push_str(",\"reason\":");
+                    push_json_string(&mut json, blocked.reason.as_str());
+                    json.push_str(",\"detail\":");
+                    push_json_string(&mut json, &blocked.reason.to_string());
+                    json.push('}');
+                }
+                None => json.push_str("null"),
+            }
+            json.push_str(",\"pending_permissions\":[");
+            for (index, pending) in tab.pending_permissions.iter().enumerate() {
+                if index > 0 {
+                    json.push(',');
+                }
+                json.push_str("{\"request_id\":");
+                json.push_str(&pending.request_id.0.to_string());
+                json.push_str(",\"origin\":");
+                push_json_string(&mut json, &pending.origin);
+                json.push_str(",\"kind\":");
+                push_json_string(&mut json, pending.kind.as_str());
+                json.push('}');
+            }
+            json.push(']');
+            json.push_str(",\"opener_tab_id\":");
+            match tab.opener_tab_id {
+                Some(opener_tab_id) => json.push_str(&opener_tab_id.0.to_string()),
+                None => json.push_str("null"),
+            }
+            json.push_str(",\"favicon_url\":");
+            match &tab.favicon_key {
+                Some(key) => push_json_string(&mut json, &format!("{FAVICON_URL_PREFIX}{key}")),
+                None => json.push_str("null"),
+            }
+            json.push_str(",\"status\":{\"kind\":");
+            push_json_string(&mut json, tab.status.as_str());
+            if let TabStatus::LoadFailed { url, error_code } = &tab.status {
+                json.push_str(",\"url\":");
+                push_json_string(&mut json, url);
+                json.push_str(",\"error_code\":");
+                json.push_str(&error_code.to_string());
+            }
+            json.push('}');
             json.push_str("}");
         }
         json.push_str("],");
+        json.push_str("\"bookmark_folders\":[");
+        let mut first = true;
+        for folder in state.bookmark_folders.values() {
+            if !first {
+                json.push(',');
+            }
+            first = false;
+            json.push('{');
+            json.push_str("\"id\":");
+            json.push_str(&folder.id.0.to_string());
+            json.push(',');
+            json.push_str("\"profile_id\":");
+            json.push_str(&folder.profile_id.0.to_string());
+            json.push(',');
+            json.push_str("\"parent_id\":");
+            match folder.parent_id {
+                Some(parent_id) => json.push_str(&parent_id.0.to_string()),
+                None => json.push_str("null"),
+            }
+            json.push(',');
+            json.push_str("\"name\":");
+            push_json_string(&mut json, &folder.name);
+            json.push('}');
+        }
+        json.push_str("],");
+        json.push_str("\"bookmarks\":[");
+        let mut first = true;
+        for bookmark in state.bookmarks.values() {
+            if !first {
+                json.push(',');
+            }
+            first = false;
+            json.push('{');
+            json.push_str("\"id\":");
+            json.push_str(&bookmark.id.0.to_string());
+            json.push(',');
+            json.push_str("\"profile_id\":");
+            json.push_str(&bookmark.profile_id.0.to_string());
+            json.push(',');
+            json.push_str("\"folder_id\":");
+            match bookmark.folder_id {
+                Some(folder_id) => json.push_str(&folder_id.0.to_string()),
+                None => json.push_str("null"),
+            }
+            json.push(',');
+            json.push_str("\"position\":");
+            json.push_str(&bookmark.position.to_string());
+            json.push(',');
+            json.push_str("\"url\":");
+            push_json_string(&mut json, &bookmark.url);
+            json.push(',');
+            json.push_str("\"title\":");
+            push_json_string(&mut json, &bookmark.title);
+            json.push('}');
+        }
+        json.push_str("],");
+        json.push_str("\"search_engines\":[");
+        let mut first = true;
+        for engine in state.search_engines.values() {
+            if !first {
+                json.push(',');
+            }
+            first = false;
+            json.push('{');
+            json.push_str("\"id\":");
+            json.push_str(&engine.id.0.to_string());
+            json.push(',');
+            json.push_str("\"profile_id\":");
+            json.push_str(&engine.profile_id.0.to_string());
+            json.push(',');
+            json.push_str("\"name\":");
+            push_json_string(&mut json, &engine.name);
+            json.push(',');
+            json.push_str("\"keyword\":");
+            push_json_string(&mut json, &engine.keyword);
+            json.push(',');
+            json.push_str("\"url_template\":");
+            push_json_string(&mut json, &engine.url_template);
+            json.push(',');
+            json.push_str("\"is_default\":");
+            json.push_str(if engine.is_default { "true" } else { "false" });
+            json.push(',');
+            json.push_str("\"builtin\":");
+            json.push_str(if engine.builtin { "true" } else { "false" });
+            json.push('}');
+        }
+        json.push_str("],");
+        json.push_str("\"https_exceptions\":[");
+        let mut first = true;
+        for (profile_id, hosts) in &state.https_exceptions {
+            for host in hosts {
+                if !first {
+                    json.push(',');
+                }
+                first = false;
+                json.push_str("{\"profile_id\":");
+                json.push_str(&profile_id.0.to_string());
+                json.push_str(",\"host\":");
+                push_json_string(&mut json, host);
+                json.push('}');
+            }
+        }
+        json.push_str("],");
+        json.push_str("\"site_permissions\":[");
+        let mut first = true;
+        for (profile_id, entries) in &state.site_permissions {
+            for ((origin, kind), setting) in entries {
+                if !first {
+                    json.push(',');
+                }
+                first = false;
+                json.push_str("{\"profile_id\":");
+                json.push_str(&profile_id.0.to_string());
+                json.push_str(",\"origin\":");
+                push_json_string(&mut json, origin);
+                json.push_str(",\"kind\":");
+                push_json_string(&mut json, kind.as_str());
+                json.push_str(",\"setting\":");
+                push_json_string(&mut json, setting.as_str());
+                json.push('}');
+            }
+        }
+        json.push_str("],");
+        json.push_str("\"downloads\":[");
+        for (index, download) in state.downloads.values().rev().enumerate() {
+            if index > 0 {
+                json.push(',');
+            }
+            json.push_str("{\"id\":");
+            json.push_str(&download.id.0.to_string());
+            json.push_str(",\"profile_id\":");
+            json.push_str(&download.profile_id.0.to_string());
+            json.push_str(",\"tab_id\":");
+            match download.tab_id {
+                Some(tab_id) => json.push_str(&tab_id.0.to_string()),
+                None => json.push_str("null"),
+            }
+            json.push_str(",\"url\":");
+            push_json_string(&mut json, &download.url);
+            json.push_str(",\"target_path\":");
+            push_json_string(&mut json, &download.target_path);
+            json.push_str(",\"received_bytes\":");
+            json.push_str(&download.received_bytes.to_string());
+            json.push_str(",\"total_bytes\":");
+            match download.total_bytes {
+                Some(total_bytes) => json.push_str(&total_bytes.to_string()),
+                None => json.push_str("null"),
+            }
+            json.push_str(",\"state\":");
+            push_json_string(&mut json, download.state.as_str());
+            json.push('}');
+        }
+        json.push_str("],");
         json.push_str("\"settings\":{");
         let mut first = true;
         for (key, value) in &state.settings {
@@ -634,6 +1685,25 @@ fn restored_window_size(state: &BrowserState) -> WindowSize {
     WindowSize { width, height }
 }
 
+fn history_retention(state: &BrowserState) -> RetentionPolicy {
+    let defaults = RetentionPolicy::default();
+    RetentionPolicy {
+        max_age_days: setting_int(state, HISTORY_RETENTION_DAYS_SETTING_KEY)
+            .filter(|days| *days > 0)
+            .unwrap_or(defaults.max_age_days),
+        max_visits_per_profile: setting_int(state, HISTORY_MAX_VISITS_SETTING_KEY)
+            .filter(|visits| *visits > 0)
+            .unwrap_or(defaults.max_visits_per_profile),
+    }
+}
+
+// Used until a profile sets its own download directory.
+fn default_download_directory() -> PathBuf {
+    std::env::var_os("HOME")
+        .map(|home| PathBuf::from(home).join("Downloads"))
+        .unwrap_or_else(std::env::temp_dir)
+}
+
 fn setting_int(state: &BrowserState, key: &str) -> Option<i64> {
     match state.settings.get(key) {
         Some(SettingValue::Int(value)) => Some(*value),
@@ -700,11 +1770,13 @@ fn ensure_bootstrap_state(state: &mut BrowserState) -> WorkspaceId {
             .expect("bootstrap profile must exist");
         ensure_default_settings(state);
         state.recompute_next_ids();
+        ensure_search_engines(state);
         return workspace_id;
     }
 
     ensure_default_settings(state);
     state.recompute_next_ids();
+    ensure_search_engines(state);
 
     if state
         .active_profile_id
@@ -818,15 +1890,48 @@ impl<HError: Display> Display for RuntimeError<HError> {
                 write!(f, "no active workspace available for UI navigation")
             }
             Self::NoActiveProfile => write!(f, "no active profile available for UI command"),
-            Self::BlockedContentNavigation(url) => {
-                write!(f, "content navigation blocked for url: {url}")
+            Self::BlockedContentNavigation(blocked) => {
+                write!(
+                    f,
+                    "navigation to {} blocked: {}",
+                    blocked.url, blocked.reason
+                )
             }
+            Self::Portable(err) => write!(f, "workspace bundle error: {err}"),
+            Self::PortableFile(message) => write!(f, "workspace bundle file error: {message}"),
+            Self::History(message) => write!(f, "history error: {message}"),
+            Self::Favicon(message) => write!(f, "favicon cache error: {message}"),
         }
     }
 }
 
 impl<HError: Error + 'static> Error for RuntimeError<HError> {}
 
+fn write_portable_file<HError>(
+    path: &Path,
+    bundle: &PortableBundle,
+) -> Result<(), RuntimeError<HError>> {
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(str::to_ascii_lowercase);
+    let contents = match extension.as_deref() {
+        Some("html" | "htm") => to_netscape_html(bundle),
+        Some("md" | "markdown" | "txt") => to_markdown(bundle),
+        _ => bundle.to_json(),
+    };
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        fs::create_dir_all(parent).map_err(|error| {
+            RuntimeError::PortableFile(format!("{}: {error}", parent.display()))
+        })?;
+    }
+    fs::write(path, contents)
+        .map_err(|error| RuntimeError::PortableFile(format!("{}: {error}", path.display())))
+}
+
 fn build_thumbnail_data_url(title: &str, url: &str) -> String {
     let title_line = if title.trim().is_empty() {
         "Untitled Tab"
@@ -872,6 +1977,53 @@ fn escape_xml(value: &str) -> String {
         .replace('\'', "&apos;")
 }
 
+fn history_entries_json(entries: &[HistoryEntry]) -> String {
+    let mut json = String::from("[");
+    for (index, entry) in entries.iter().enumerate() {
+        if index > 0 {
+            json.push(',');
+        }
+        json.push_str("{\"url\":");
+        push_json_string(&mut json, &entry.url);
+        json.push_str(",\"title\":");
+        push_json_string(&mut json, &entry.title);
+        json.push_str(&format!(
+            ",\"visit_count\":{},\"typed_count\":{},\"last_visited_at_ms\":{}}}",
+            entry.visit_count, entry.typed_count, entry.last_visited_at_ms
+        ));
+    }
+    json.push(']');
+    json
+}
+
+fn suggestions_json(suggestions: &[Suggestion]) -> String {
+    let mut json = String::from("[");
+    for (index, suggestion) in suggestions.iter().enumerate() {
+        if index > 0 {
+            json.push(',');
+        }
+        json.push_str("{\"kind\":");
+        push_json_string(&mut json, suggestion.kind.as_str());
+        if let SuggestionKind::SwitchToTab {
+            tab_id,
+            workspace_id,
+        } = suggestion.kind
+        {
+            json.push_str(&format!(
+                ",\"tab_id\":{},\"workspace_id\":{}",
+                tab_id.0, workspace_id.0
+            ));
+        }
+        json.push_str(",\"url\":");
+        push_json_string(&mut json, &suggestion.url);
+        json.push_str(",\"title\":");
+        push_json_string(&mut json, &suggestion.title);
+        json.push_str(&format!(",\"score\":{}}}", suggestion.score));
+    }
+    json.push(']');
+    json
+}
+
 fn push_json_string(json: &mut String, value: &str) {
     json.push('"');
     for ch in value.chars() {
@@ -907,15 +2059,25 @@ fn push_json_setting_value(json: &mut String, value: &SettingValue) {
 mod tests {
     use std::cell::RefCell;
     use std::rc::Rc;
+    use std::sync::Arc;
 
     use crate::bridge::UiCommand;
     use crate::host::{
-        CefHost, ContentEvent, ContentViewId, HostError, HostEvent, MockCefHost, UiViewId,
+        CefHost, ContentEvent, ContentViewId, HostError, HostEvent, MockCefHost, UiQuery, UiViewId,
         WindowEvent, WindowId, WindowSize,
     };
-    use switchboard_core::{Intent, SettingValue, TabId, TabRuntimeState};
+    use crate::metrics::RuntimeMetrics;
+    use switchboard_core::url::is_ui_url;
+    use switchboard_core::{
+        DownloadCommand, DownloadId, DownloadState, FilterDecision, FilterEngine, FilterRequest,
+        HistoryClearScope, Intent, MemoryPressureLevel, PatchOp, PermissionDecision,
+        PermissionKind, PermissionRequestId, PortableError, RequestFilter, ResourceType,
+        SettingValue, TabId, TabRuntimeState, TabStatus, WindowDisposition,
+    };
 
-    use super::{AppRuntime, RuntimeError};
+    use super::{
+        AppRuntime, RuntimeError, LIFECYCLE_TICK_INTERVAL_MS, STARTUP_RESTORE_INTERVAL_MS,
+    };
 
     #[derive(Clone)]
     struct RecordingHost {
@@ -959,7 +2121,7 @@ mod tests {
             window_id: WindowId,
             url: &str,
         ) -> Result<UiViewId, Self::Error> {
-            if !url.starts_with("app://ui") {
+            if !is_ui_url(url) {
                 return Err(HostError::InvalidUiUrl(url.to_owned()));
             }
             self.next_ui_view_id += 1;
@@ -1028,6 +2190,89 @@ mod tests {
             Ok(())
         }
 
+        fn set_request_filter(
+            &mut self,
+            _view_id: ContentViewId,
+            _filter: Option<Arc<dyn RequestFilter>>,
+        ) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn complete_permission_request(
+            &mut self,
+            request_id: PermissionRequestId,
+            granted: bool,
+        ) -> Result<(), Self::Error> {
+            self.events
+                .borrow_mut()
+                .push(HostEvent::PermissionRequestCompleted {
+                    request_id,
+                    granted,
+                });
+            Ok(())
+        }
+
+        fn begin_download(
+            &mut self,
+            _host_download_id: u64,
+            _download_id: DownloadId,
+            _target_path: &str,
+        ) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn control_download(
+            &mut self,
+            _download_id: DownloadId,
+            _command: DownloadCommand,
+        ) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn open_download_folder(&mut self, _target_path: &str) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn schedule_crash_reload(
+            &mut self,
+            _tab_id: TabId,
+            _delay_ms: u64,
+        ) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn schedule_lifecycle_tick(&mut self, _delay_ms: u64) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn schedule_startup_restore(
+            &mut self,
+            _tab_id: TabId,
+            _delay_ms: u64,
+        ) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn request_memory_estimate(
+            &mut self,
+            _view_id: ContentViewId,
+            _tab_id: TabId,
+        ) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn set_audio_muted(
+            &mut self,
+            _view_id: ContentViewId,
+            _muted: bool,
+        ) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn download_favicon(&mut self, _tab_id: TabId, _icon_url: &str) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
         fn run_event_loop(&mut self) -> Result<(), Self::Error> {
             Ok(())
         }
@@ -1040,7 +2285,7 @@ mod tests {
 
         assert_eq!(runtime.revision(), 0);
         assert_eq!(runtime.ui_view_id().0, 1);
-        assert_eq!(runtime.host().events().len(), 2);
+        assert_eq!(runtime.host().events().len(), 3);
         assert!(matches!(
             &runtime.host().events()[0],
             HostEvent::WindowCreated { .. }
@@ -1049,80 +2294,325 @@ mod tests {
             &runtime.host().events()[1],
             HostEvent::UiViewCreated { url, .. } if url.starts_with("app://ui")
         ));
+        assert_eq!(
+            runtime.host().events()[2],
+            HostEvent::LifecycleTickScheduled {
+                delay_ms: LIFECYCLE_TICK_INTERVAL_MS
+            }
+        );
     }
 
     #[test]
-    fn first_navigation_creates_content_view_then_reuses_it() {
+    fn lifecycle_ticks_reschedule_themselves() {
         let host = MockCefHost::default();
         let mut runtime = AppRuntime::bootstrap(host, "0.1.0").expect("bootstrap should succeed");
         let workspace_id = runtime.default_workspace_id();
+        for url in ["https://one.example/", "https://two.example/"] {
+            runtime
+                .handle_ui_command(UiCommand::NewTab {
+                    workspace_id: workspace_id.0,
+                    url: Some(url.to_owned()),
+                    make_active: true,
+                })
+                .expect("tab should be created");
+        }
+        let background = runtime.engine().state().workspaces[&workspace_id].tab_order[0];
+        assert!(runtime.tab_bindings.contains_key(&background));
 
         runtime
-            .handle_ui_command(UiCommand::NewTab {
-                workspace_id: workspace_id.0,
-                url: None,
-                make_active: true,
-            })
-            .expect("new tab should succeed");
-        let tab_id = runtime
-            .active_tab_id(workspace_id)
-            .expect("new tab should be active");
-
-        runtime
-            .handle_ui_command(UiCommand::Navigate {
-                tab_id: tab_id.0,
-                url: "https://example.com".to_owned(),
+            .handle_intent(Intent::SettingSet {
+                key: "lifecycle.warm_idle_timeout_secs".to_owned(),
+                value: SettingValue::Int(0),
             })
-            .expect("first navigation should create content view");
+            .expect("setting should apply");
         runtime
-            .handle_ui_command(UiCommand::Navigate {
-                tab_id: tab_id.0,
-                url: "https://rust-lang.org".to_owned(),
+            .handle_content_event(ContentEvent::LifecycleTickDue)
+            .expect("tick should apply");
+        assert!(runtime.tab_bindings.contains_key(&background));
+        assert_eq!(
+            runtime.host().events().last(),
+            Some(&HostEvent::LifecycleTickScheduled {
+                delay_ms: LIFECYCLE_TICK_INTERVAL_MS
             })
-            .expect("second navigation should reuse content view");
-
-        let content_create_count = runtime
-            .host()
-            .events()
-            .iter()
-            .filter(|event| matches!(event, HostEvent::ContentViewCreated { .. }))
-            .count();
-        let content_navigate_count = runtime
-            .host()
-            .events()
-            .iter()
-            .filter(|event| matches!(event, HostEvent::ContentNavigated { .. }))
-            .count();
-
-        assert_eq!(content_create_count, 1);
-        assert_eq!(content_navigate_count, 2);
+        );
     }
 
     #[test]
-    fn blocks_content_navigation_to_app_scheme() {
+    fn startup_restores_are_staggered_and_fresh_starts_reuse_an_empty_workspace() {
         let host = MockCefHost::default();
         let mut runtime = AppRuntime::bootstrap(host, "0.1.0").expect("bootstrap should succeed");
         let workspace_id = runtime.default_workspace_id();
+        for url in [
+            "https://one.example/",
+            "https://two.example/",
+            "https://three.example/",
+        ] {
+            runtime
+                .handle_ui_command(UiCommand::NewTab {
+                    workspace_id: workspace_id.0,
+                    url: Some(url.to_owned()),
+                    make_active: true,
+                })
+                .expect("tab should be created");
+        }
+        let tab_order = runtime.engine().state().workspaces[&workspace_id]
+            .tab_order
+            .clone();
+        for (key, value) in [
+            ("warm_pool_budget", SettingValue::Int(1)),
+            (
+                "startup.restore_mode",
+                SettingValue::Text("workspace".to_owned()),
+            ),
+        ] {
+            runtime
+                .handle_intent(Intent::SettingSet {
+                    key: key.to_owned(),
+                    value,
+                })
+                .expect("setting should apply");
+        }
+        assert!(!runtime.tab_bindings.contains_key(&tab_order[0]));
 
+        let events_before = runtime.host().events().len();
         runtime
-            .handle_ui_command(UiCommand::NewTab {
-                workspace_id: workspace_id.0,
-                url: None,
-                make_active: true,
+            .apply_startup_restore_mode()
+            .expect("restores should be scheduled");
+        assert_eq!(
+            &runtime.host().events()[events_before..],
+            &[HostEvent::StartupRestoreScheduled {
+                tab_id: tab_order[0],
+                delay_ms: STARTUP_RESTORE_INTERVAL_MS,
+            }]
+        );
+        runtime
+            .handle_content_event(ContentEvent::StartupRestoreDue {
+                tab_id: tab_order[0],
             })
-            .expect("new tab should succeed");
-        let tab_id = runtime
-            .active_tab_id(workspace_id)
-            .expect("new tab should be active");
-
-        let result = runtime.handle_ui_command(UiCommand::Navigate {
+            .expect("restore should apply");
+        assert!(runtime.tab_bindings.contains_key(&tab_order[0]));
+        assert!(!runtime.tab_bindings.contains_key(&tab_order[1]));
+        let revision = runtime.revision();
+        runtime
+            .handle_content_event(ContentEvent::StartupRestoreDue {
+                tab_id: tab_order[2],
+            })
+            .expect("a live tab needs no restore");
+        assert_eq!(runtime.revision(), revision);
+
+        runtime
+            .handle_intent(Intent::SettingSet {
+                key: "startup.restore_mode".to_owned(),
+                value: SettingValue::Text("fresh".to_owned()),
+            })
+            .expect("setting should apply");
+        runtime
+            .apply_startup_restore_mode()
+            .expect("fresh start should apply");
+        let fresh_workspace_id = runtime.default_workspace_id();
+        assert_ne!(fresh_workspace_id, workspace_id);
+        assert_eq!(runtime.active_tab_id(fresh_workspace_id), None);
+        assert_eq!(runtime.resolve_active_tab_id(), None);
+        runtime
+            .apply_startup_restore_mode()
+            .expect("fresh start should apply");
+        assert_eq!(runtime.default_workspace_id(), fresh_workspace_id);
+        let profile_id = runtime.engine().state().workspaces[&workspace_id].profile_id;
+        assert_eq!(
+            runtime.engine().state().profiles[&profile_id]
+                .workspace_order
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn metrics_count_dispatches_restores_and_views_until_reset() {
+        let host = MockCefHost::default();
+        let mut runtime = AppRuntime::bootstrap(host, "0.1.0").expect("bootstrap should succeed");
+        let workspace_id = runtime.default_workspace_id();
+        for url in [
+            "https://one.example/",
+            "https://two.example/",
+            "https://three.example/",
+        ] {
+            runtime
+                .handle_ui_command(UiCommand::NewTab {
+                    workspace_id: workspace_id.0,
+                    url: Some(url.to_owned()),
+                    make_active: true,
+                })
+                .expect("tab should be created");
+        }
+        let tab_order = runtime.engine().state().workspaces[&workspace_id]
+            .tab_order
+            .clone();
+        runtime
+            .handle_intent(Intent::SettingSet {
+                key: "warm_pool_budget".to_owned(),
+                value: SettingValue::Int(1),
+            })
+            .expect("setting should apply");
+        for tab_id in [tab_order[1], tab_order[0]] {
+            runtime
+                .handle_ui_command(UiCommand::ActivateTab { tab_id: tab_id.0 })
+                .expect("tab should activate");
+        }
+
+        let metrics = runtime.metrics.clone();
+        assert_eq!(metrics.dispatch_latency.count(), 6);
+        assert_eq!(metrics.commit_latency.count(), 6);
+        assert_eq!(metrics.patches, 6);
+        assert!(metrics.patch_ops >= metrics.largest_patch && metrics.largest_patch > 0);
+        assert_eq!(metrics.restores_from_warm, 1);
+        assert_eq!(metrics.restores_from_discarded, 1);
+        assert_eq!(metrics.views_created, 4);
+        assert_eq!(metrics.views_destroyed, 2);
+
+        let json = runtime.handle_ui_query(UiQuery::Metrics);
+        assert!(json.contains("\"restores\":{\"warm\":1,\"discarded\":1,\"restoring\":0}"));
+        assert!(json.contains("\"warm_hit_rate\":0.5000"));
+        runtime
+            .handle_ui_command(UiCommand::ResetMetrics)
+            .expect("metrics should reset");
+        assert_eq!(runtime.metrics, RuntimeMetrics::default());
+        assert!(runtime
+            .handle_ui_query(UiQuery::Metrics)
+            .contains("\"warm_hit_rate\":null"));
+    }
+
+    #[test]
+    fn lifecycle_ticks_sample_memory_and_large_tabs_leave_the_warm_pool() {
+        let host = MockCefHost::default();
+        let mut runtime = AppRuntime::bootstrap(host, "0.1.0").expect("bootstrap should succeed");
+        let workspace_id = runtime.default_workspace_id();
+        for url in ["https://canvas.example/", "https://notes.example/"] {
+            runtime
+                .handle_ui_command(UiCommand::NewTab {
+                    workspace_id: workspace_id.0,
+                    url: Some(url.to_owned()),
+                    make_active: true,
+                })
+                .expect("tab should be created");
+        }
+        let background = runtime.engine().state().workspaces[&workspace_id].tab_order[0];
+        let view_id = runtime.tab_bindings[&background].content.view_id;
+
+        runtime
+            .handle_content_event(ContentEvent::LifecycleTickDue)
+            .expect("tick should apply");
+        assert!(runtime
+            .host()
+            .events()
+            .contains(&HostEvent::MemoryEstimateRequested {
+                view_id,
+                tab_id: background,
+            }));
+
+        runtime
+            .handle_content_event(ContentEvent::MemoryPressureChanged {
+                level: MemoryPressureLevel::Critical,
+            })
+            .expect("pressure should apply");
+        assert!(!runtime.tab_bindings.contains_key(&background));
+        runtime
+            .handle_content_event(ContentEvent::MemoryPressureChanged {
+                level: MemoryPressureLevel::Normal,
+            })
+            .expect("pressure should apply");
+        let view_id = runtime.tab_bindings[&background].content.view_id;
+
+        runtime
+            .handle_intent(Intent::SettingSet {
+                key: "lifecycle.warm_pool_bytes".to_owned(),
+                value: SettingValue::Int(512 * 1024 * 1024),
+            })
+            .expect("setting should apply");
+        runtime
+            .handle_content_event(ContentEvent::MemoryEstimated {
+                tab_id: background,
+                bytes: 2 * 1024 * 1024 * 1024,
+            })
+            .expect("estimate should apply");
+        assert!(!runtime.tab_bindings.contains_key(&background));
+        assert!(runtime
+            .host()
+            .events()
+            .contains(&HostEvent::ContentViewDestroyed { view_id }));
+    }
+
+    #[test]
+    fn first_navigation_creates_content_view_then_reuses_it() {
+        let host = MockCefHost::default();
+        let mut runtime = AppRuntime::bootstrap(host, "0.1.0").expect("bootstrap should succeed");
+        let workspace_id = runtime.default_workspace_id();
+
+        runtime
+            .handle_ui_command(UiCommand::NewTab {
+                workspace_id: workspace_id.0,
+                url: None,
+                make_active: true,
+            })
+            .expect("new tab should succeed");
+        let tab_id = runtime
+            .active_tab_id(workspace_id)
+            .expect("new tab should be active");
+
+        runtime
+            .handle_ui_command(UiCommand::Navigate {
+                tab_id: tab_id.0,
+                url: "https://example.com".to_owned(),
+            })
+            .expect("first navigation should create content view");
+        runtime
+            .handle_ui_command(UiCommand::Navigate {
+                tab_id: tab_id.0,
+                url: "https://rust-lang.org".to_owned(),
+            })
+            .expect("second navigation should reuse content view");
+
+        let content_create_count = runtime
+            .host()
+            .events()
+            .iter()
+            .filter(|event| matches!(event, HostEvent::ContentViewCreated { .. }))
+            .count();
+        let content_navigate_count = runtime
+            .host()
+            .events()
+            .iter()
+            .filter(|event| matches!(event, HostEvent::ContentNavigated { .. }))
+            .count();
+
+        assert_eq!(content_create_count, 1);
+        assert_eq!(content_navigate_count, 2);
+    }
+
+    #[test]
+    fn blocks_content_navigation_to_app_scheme() {
+        let host = MockCefHost::default();
+        let mut runtime = AppRuntime::bootstrap(host, "0.1.0").expect("bootstrap should succeed");
+        let workspace_id = runtime.default_workspace_id();
+
+        runtime
+            .handle_ui_command(UiCommand::NewTab {
+                workspace_id: workspace_id.0,
+                url: None,
+                make_active: true,
+            })
+            .expect("new tab should succeed");
+        let tab_id = runtime
+            .active_tab_id(workspace_id)
+            .expect("new tab should be active");
+
+        let result = runtime.handle_ui_command(UiCommand::Navigate {
             tab_id: tab_id.0,
             url: "app://ui/settings".to_owned(),
         });
 
         assert!(matches!(
             result,
-            Err(RuntimeError::BlockedContentNavigation(url)) if url == "app://ui/settings"
+            Err(RuntimeError::BlockedContentNavigation(blocked)) if blocked.url == "app://ui/settings"
         ));
     }
 
@@ -1156,6 +2646,49 @@ mod tests {
         assert_eq!(content_create_count, 1);
     }
 
+    #[test]
+    fn navigate_input_resolves_keywords_through_profile_search_engines() {
+        let host = MockCefHost::default();
+        let mut runtime = AppRuntime::bootstrap(host, "0.1.0").expect("bootstrap should succeed");
+        let workspace_id = runtime.default_workspace_id();
+        let profile_id = runtime
+            .engine()
+            .state()
+            .active_profile_id
+            .expect("bootstrap profile should be active");
+
+        runtime
+            .handle_ui_command(UiCommand::AddSearchEngine {
+                profile_id: profile_id.0,
+                name: "Rust Docs".to_owned(),
+                keyword: "rs".to_owned(),
+                url_template: "https://docs.rs/releases/search?query={searchTerms}".to_owned(),
+            })
+            .expect("custom engine should be added");
+        runtime
+            .handle_ui_command(UiCommand::NavigateInput {
+                text: "rs serde json".to_owned(),
+            })
+            .expect("keyword search should navigate");
+
+        let active_tab_id = runtime
+            .active_tab_id(workspace_id)
+            .expect("navigation should leave an active tab");
+        assert_eq!(
+            runtime
+                .engine()
+                .state()
+                .tabs
+                .get(&active_tab_id)
+                .expect("active tab should exist")
+                .url,
+            "https://docs.rs/releases/search?query=serde%20json"
+        );
+        assert!(runtime
+            .ui_shell_state_json()
+            .contains("\"keyword\":\"rs\",\"url_template\":\"https://docs.rs/releases/search?query={searchTerms}\""));
+    }
+
     #[test]
     fn navigate_active_creates_tab_for_empty_workspace() {
         let host = MockCefHost::default();
@@ -1178,159 +2711,1053 @@ mod tests {
             .expect("second workspace should exist");
 
         runtime
-            .handle_ui_command(UiCommand::SwitchWorkspace {
-                workspace_id: second_workspace_id.0,
+            .handle_ui_command(UiCommand::SwitchWorkspace {
+                workspace_id: second_workspace_id.0,
+            })
+            .expect("switch workspace should succeed");
+        runtime
+            .handle_ui_command(UiCommand::NavigateActive {
+                url: "https://example.com".to_owned(),
+            })
+            .expect("navigate active should create a tab");
+
+        let active_tab_id = runtime
+            .active_tab_id(second_workspace_id)
+            .expect("new tab should be active in second workspace");
+        let active_tab = runtime
+            .engine()
+            .state()
+            .tabs
+            .get(&active_tab_id)
+            .expect("new tab should exist");
+        assert_eq!(active_tab.url, "https://example.com");
+    }
+
+    #[test]
+    fn activate_tab_updates_content_view_to_selected_tab() {
+        let host = MockCefHost::default();
+        let mut runtime = AppRuntime::bootstrap(host, "0.1.0").expect("bootstrap should succeed");
+        let workspace_id = runtime.default_workspace_id();
+
+        runtime
+            .handle_ui_command(UiCommand::NewTab {
+                workspace_id: workspace_id.0,
+                url: Some("https://one.example".to_owned()),
+                make_active: true,
+            })
+            .expect("first tab should succeed");
+        let first_tab_id = runtime
+            .active_tab_id(workspace_id)
+            .expect("first tab should be active");
+
+        runtime
+            .handle_ui_command(UiCommand::NewTab {
+                workspace_id: workspace_id.0,
+                url: Some("https://two.example".to_owned()),
+                make_active: true,
+            })
+            .expect("second tab should succeed");
+
+        runtime
+            .handle_ui_command(UiCommand::ActivateTab {
+                tab_id: first_tab_id.0,
+            })
+            .expect("activate tab should succeed");
+
+        let content_create_count = runtime
+            .host()
+            .events()
+            .iter()
+            .filter(|event| matches!(event, HostEvent::ContentViewCreated { .. }))
+            .count();
+        let content_navigate_count = runtime
+            .host()
+            .events()
+            .iter()
+            .filter(|event| matches!(event, HostEvent::ContentNavigated { .. }))
+            .count();
+        assert_eq!(content_create_count, 2);
+        assert_eq!(content_navigate_count, 0);
+    }
+
+    #[test]
+    fn activate_active_tab_is_noop_for_content_view() {
+        let host = MockCefHost::default();
+        let mut runtime = AppRuntime::bootstrap(host, "0.1.0").expect("bootstrap should succeed");
+        let workspace_id = runtime.default_workspace_id();
+
+        runtime
+            .handle_ui_command(UiCommand::NewTab {
+                workspace_id: workspace_id.0,
+                url: Some("https://one.example".to_owned()),
+                make_active: true,
+            })
+            .expect("tab should be created");
+        let active_tab_id = runtime
+            .active_tab_id(workspace_id)
+            .expect("tab should be active");
+        let revision_before = runtime.revision();
+
+        let patch = runtime
+            .handle_ui_command(UiCommand::ActivateTab {
+                tab_id: active_tab_id.0,
+            })
+            .expect("activate active tab should succeed");
+
+        assert!(
+            patch.ops.is_empty(),
+            "activate on already-active tab should no-op"
+        );
+        assert_eq!(runtime.revision(), revision_before);
+
+        let content_create_count = runtime
+            .host()
+            .events()
+            .iter()
+            .filter(|event| matches!(event, HostEvent::ContentViewCreated { .. }))
+            .count();
+        let content_navigate_count = runtime
+            .host()
+            .events()
+            .iter()
+            .filter(|event| matches!(event, HostEvent::ContentNavigated { .. }))
+            .count();
+        assert_eq!(content_create_count, 1);
+        assert_eq!(content_navigate_count, 0);
+    }
+
+    #[test]
+    fn content_events_update_tab_metadata() {
+        let host = MockCefHost::default();
+        let mut runtime = AppRuntime::bootstrap(host, "0.1.0").expect("bootstrap should succeed");
+        let workspace_id = runtime.default_workspace_id();
+
+        runtime
+            .handle_ui_command(UiCommand::NewTab {
+                workspace_id: workspace_id.0,
+                url: Some("https://one.example".to_owned()),
+                make_active: true,
+            })
+            .expect("tab should be created");
+        let tab_id = runtime
+            .active_tab_id(workspace_id)
+            .expect("tab should be active");
+
+        runtime
+            .handle_content_event(ContentEvent::TitleChanged {
+                tab_id,
+                title: "One Example".to_owned(),
+            })
+            .expect("title event should apply");
+        runtime
+            .handle_content_event(ContentEvent::LoadingChanged {
+                tab_id,
+                is_loading: true,
+            })
+            .expect("loading event should apply");
+
+        let tab = runtime
+            .engine()
+            .state()
+            .tabs
+            .get(&tab_id)
+            .expect("tab should exist");
+        assert_eq!(tab.title, "One Example");
+        assert!(tab.loading);
+    }
+
+    #[test]
+    fn content_url_changes_record_history_except_for_ephemeral_profiles() {
+        let host = MockCefHost::default();
+        let mut runtime = AppRuntime::bootstrap(host, "0.1.0").expect("bootstrap should succeed");
+        let workspace_id = runtime.default_workspace_id();
+        let profile_id = runtime
+            .resolve_active_profile_id()
+            .expect("profile should be active");
+
+        runtime
+            .handle_ui_command(UiCommand::NewTab {
+                workspace_id: workspace_id.0,
+                url: Some("https://one.example/docs".to_owned()),
+                make_active: true,
+            })
+            .expect("tab should be created");
+        let tab_id = runtime
+            .active_tab_id(workspace_id)
+            .expect("tab should be active");
+        for url in ["https://one.example/docs", "https://one.example/docs"] {
+            runtime
+                .handle_content_event(ContentEvent::UrlChanged {
+                    tab_id,
+                    url: url.to_owned(),
+                })
+                .expect("url event should apply");
+        }
+        runtime
+            .handle_content_event(ContentEvent::TitleChanged {
+                tab_id,
+                title: "Example Docs".to_owned(),
+            })
+            .expect("title event should apply");
+
+        let entries = runtime
+            .search_history(profile_id, "docs", 10)
+            .expect("history search should succeed");
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].visit_count, 2);
+        assert_eq!(entries[0].typed_count, 1);
+        assert_eq!(entries[0].title, "Example Docs");
+        assert!(runtime
+            .handle_ui_query(UiQuery::History(String::new()))
+            .contains("\"visit_count\":2"));
+
+        runtime
+            .handle_ui_command(UiCommand::SettingSet {
+                key: format!("ephemeral.profile.{}", profile_id.0),
+                value: SettingValue::Bool(true),
+            })
+            .expect("setting should apply");
+        runtime
+            .handle_content_event(ContentEvent::UrlChanged {
+                tab_id,
+                url: "https://two.example/private".to_owned(),
+            })
+            .expect("url event should apply");
+        assert!(runtime
+            .search_history(profile_id, "private", 10)
+            .expect("history search should succeed")
+            .is_empty());
+
+        runtime
+            .handle_ui_command(UiCommand::ClearHistory {
+                profile_id: profile_id.0,
+                scope: HistoryClearScope::Domain("one.example".to_owned()),
+            })
+            .expect("history clear should succeed");
+        assert!(runtime
+            .search_history(profile_id, "", 10)
+            .expect("history listing should succeed")
+            .is_empty());
+    }
+
+    #[test]
+    fn https_only_upgrades_content_and_continue_records_exception() {
+        let host = MockCefHost::default();
+        let mut runtime = AppRuntime::bootstrap(host, "0.1.0").expect("bootstrap should succeed");
+        let workspace_id = runtime.default_workspace_id();
+        let profile_id = runtime
+            .resolve_active_profile_id()
+            .expect("profile should be active");
+        runtime
+            .handle_ui_command(UiCommand::SettingSet {
+                key: format!("navigation.https_only.profile.{}", profile_id.0),
+                value: SettingValue::Bool(true),
+            })
+            .expect("setting should apply");
+        runtime
+            .handle_ui_command(UiCommand::NewTab {
+                workspace_id: workspace_id.0,
+                url: Some("https://one.example/".to_owned()),
+                make_active: true,
+            })
+            .expect("tab should be created");
+        let tab_id = runtime
+            .active_tab_id(workspace_id)
+            .expect("tab should be active");
+        let last_navigation = |runtime: &AppRuntime<MockCefHost>| {
+            runtime
+                .host()
+                .events()
+                .iter()
+                .rev()
+                .find_map(|event| match event {
+                    HostEvent::ContentNavigated { url, .. } => Some(url.clone()),
+                    _ => None,
+                })
+        };
+
+        runtime
+            .handle_content_event(ContentEvent::UrlChanged {
+                tab_id,
+                url: "http://plain.example/".to_owned(),
+            })
+            .expect("insecure link should be upgraded");
+        assert_eq!(
+            last_navigation(&runtime).as_deref(),
+            Some("https://plain.example/")
+        );
+
+        runtime
+            .handle_content_event(ContentEvent::LoadFailed {
+                tab_id,
+                url: "https://plain.example/".to_owned(),
+                error_code: -107,
+            })
+            .expect("load failure should apply");
+        assert_eq!(last_navigation(&runtime).as_deref(), Some("about:blank"));
+        assert!(runtime
+            .ui_shell_state_json()
+            .contains("\"reason\":\"https_unavailable\""));
+
+        runtime
+            .handle_ui_command(UiCommand::ContinueInsecure { tab_id: tab_id.0 })
+            .expect("continue should succeed");
+        assert_eq!(
+            last_navigation(&runtime).as_deref(),
+            Some("http://plain.example/")
+        );
+        assert!(runtime.ui_shell_state_json().contains(&format!(
+            "\"https_exceptions\":[{{\"profile_id\":{},\"host\":\"plain.example\"}}]",
+            profile_id.0
+        )));
+    }
+
+    #[test]
+    fn permission_requests_follow_site_settings_and_queue_prompts() {
+        let host = MockCefHost::default();
+        let mut runtime = AppRuntime::bootstrap(host, "0.1.0").expect("bootstrap should succeed");
+        let workspace_id = runtime.default_workspace_id();
+        let profile_id = runtime
+            .resolve_active_profile_id()
+            .expect("profile should be active");
+        runtime
+            .handle_ui_command(UiCommand::NewTab {
+                workspace_id: workspace_id.0,
+                url: Some("https://maps.example/".to_owned()),
+                make_active: true,
+            })
+            .expect("tab should be created");
+        let tab_id = runtime
+            .active_tab_id(workspace_id)
+            .expect("tab should be active");
+
+        let request = |runtime: &mut AppRuntime<MockCefHost>, id: u64, origin: &str, kind| {
+            runtime
+                .handle_content_event(ContentEvent::PermissionRequested {
+                    tab_id,
+                    origin: origin.to_owned(),
+                    kind,
+                    request_id: PermissionRequestId(id),
+                })
+                .expect("request should be handled");
+        };
+        let completed = |runtime: &AppRuntime<MockCefHost>| -> Vec<(u64, bool)> {
+            runtime
+                .host()
+                .events()
+                .iter()
+                .filter_map(|event| match event {
+                    HostEvent::PermissionRequestCompleted {
+                        request_id,
+                        granted,
+                    } => Some((request_id.0, *granted)),
+                    _ => None,
+                })
+                .collect()
+        };
+
+        request(
+            &mut runtime,
+            1,
+            "https://maps.example/",
+            PermissionKind::Geolocation,
+        );
+        request(
+            &mut runtime,
+            2,
+            "https://maps.example/a",
+            PermissionKind::Geolocation,
+        );
+        request(
+            &mut runtime,
+            3,
+            "https://maps.example",
+            PermissionKind::Camera,
+        );
+        assert!(completed(&runtime).is_empty());
+        assert!(runtime.ui_shell_state_json().contains(
+            "\"pending_permissions\":[{\"request_id\":1,\"origin\":\"https://maps.example\",\"kind\":\"geolocation\"},"
+        ));
+
+        runtime
+            .handle_ui_command(UiCommand::ResolvePermission {
+                request_id: 1,
+                decision: PermissionDecision::Deny,
+                remember: false,
+            })
+            .expect("decision should apply");
+        assert_eq!(completed(&runtime), vec![(1, false)]);
+
+        runtime
+            .handle_ui_command(UiCommand::ResolvePermission {
+                request_id: 2,
+                decision: PermissionDecision::Allow,
+                remember: true,
+            })
+            .expect("decision should apply");
+        assert_eq!(completed(&runtime), vec![(1, false), (2, true)]);
+        let json = runtime.ui_shell_state_json();
+        assert!(json.contains(
+            "\"pending_permissions\":[{\"request_id\":3,\"origin\":\"https://maps.example\",\"kind\":\"camera\"}]"
+        ));
+        assert!(json.contains(&format!(
+            "\"site_permissions\":[{{\"profile_id\":{},\"origin\":\"https://maps.example\",\"kind\":\"geolocation\",\"setting\":\"allow\"}}]",
+            profile_id.0
+        )));
+        assert!(runtime
+            .handle_ui_command(UiCommand::ResolvePermission {
+                request_id: 2,
+                decision: PermissionDecision::Deny,
+                remember: false,
+            })
+            .is_err());
+
+        request(
+            &mut runtime,
+            4,
+            "https://maps.example",
+            PermissionKind::Geolocation,
+        );
+        request(
+            &mut runtime,
+            5,
+            "file:///etc/passwd",
+            PermissionKind::Clipboard,
+        );
+        request(
+            &mut runtime,
+            6,
+            "https://maps.example",
+            PermissionKind::Popups,
+        );
+        assert_eq!(
+            completed(&runtime)[2..],
+            [(4, true), (5, false), (6, false)]
+        );
+
+        runtime
+            .handle_ui_command(UiCommand::CloseTab { tab_id: tab_id.0 })
+            .expect("tab should close");
+        assert_eq!(completed(&runtime).last(), Some(&(3, false)));
+        request(
+            &mut runtime,
+            7,
+            "https://maps.example",
+            PermissionKind::Camera,
+        );
+        assert_eq!(completed(&runtime).last(), Some(&(7, false)));
+    }
+
+    #[test]
+    fn new_window_requests_open_tabs_next_to_their_opener() {
+        let host = MockCefHost::default();
+        let mut runtime = AppRuntime::bootstrap(host, "0.1.0").expect("bootstrap should succeed");
+        let workspace_id = runtime.default_workspace_id();
+        runtime
+            .handle_ui_command(UiCommand::NewTab {
+                workspace_id: workspace_id.0,
+                url: Some("https://news.example/".to_owned()),
+                make_active: true,
+            })
+            .expect("tab should be created");
+        let opener = runtime
+            .active_tab_id(workspace_id)
+            .expect("tab should be active");
+
+        let patch = runtime
+            .handle_content_event(ContentEvent::NewWindowRequested {
+                opener_tab_id: opener,
+                url: "https://news.example/story".to_owned(),
+                disposition: WindowDisposition::ForegroundTab,
+                user_gesture: true,
+            })
+            .expect("request should open a tab");
+        let opened = runtime
+            .active_tab_id(workspace_id)
+            .expect("opened tab should be active");
+        assert_ne!(opened, opener);
+        assert!(patch.ops.iter().any(|op| matches!(
+            op,
+            PatchOp::UpsertTab(tab) if tab.id == opened && tab.opener_tab_id == Some(opener)
+        )));
+        assert!(runtime.host().events().iter().any(|event| matches!(
+            event,
+            HostEvent::ContentViewCreated { tab_id, url, .. }
+                if *tab_id == opened && url == "https://news.example/story"
+        )));
+        assert!(runtime
+            .ui_shell_state_json()
+            .contains(&format!("\"opener_tab_id\":{}", opener.0)));
+
+        let tab_count = runtime.engine().state().tabs.len();
+        runtime
+            .handle_content_event(ContentEvent::NewWindowRequested {
+                opener_tab_id: opened,
+                url: "https://ads.example/".to_owned(),
+                disposition: WindowDisposition::Popup,
+                user_gesture: false,
+            })
+            .expect("blocked popup is not an error");
+        assert_eq!(runtime.engine().state().tabs.len(), tab_count);
+        assert_eq!(runtime.active_tab_id(workspace_id), Some(opened));
+    }
+
+    #[test]
+    fn crashed_tabs_drop_their_view_and_background_ones_reload_with_backoff() {
+        let host = MockCefHost::default();
+        let mut runtime = AppRuntime::bootstrap(host, "0.1.0").expect("bootstrap should succeed");
+        let workspace_id = runtime.default_workspace_id();
+        for url in ["https://background.example/", "https://front.example/"] {
+            runtime
+                .handle_ui_command(UiCommand::NewTab {
+                    workspace_id: workspace_id.0,
+                    url: Some(url.to_owned()),
+                    make_active: true,
+                })
+                .expect("tab should be created");
+        }
+        let front = runtime
+            .active_tab_id(workspace_id)
+            .expect("tab should be active");
+        let background = runtime.engine().state().workspaces[&workspace_id].tab_order[0];
+        let view_of = |runtime: &AppRuntime<MockCefHost>, tab_id| {
+            runtime
+                .tab_bindings
+                .get(&tab_id)
+                .map(|binding| binding.content.view_id)
+        };
+        let crashed_view = view_of(&runtime, background).expect("warm tab should be live");
+
+        runtime
+            .handle_content_event(ContentEvent::RendererCrashed { tab_id: background })
+            .expect("crash should be handled");
+        assert_eq!(view_of(&runtime, background), None);
+        let events = runtime.host().events();
+        assert!(events.contains(&HostEvent::ContentViewDestroyed {
+            view_id: crashed_view
+        }));
+        assert_eq!(
+            events.last(),
+            Some(&HostEvent::CrashReloadScheduled {
+                tab_id: background,
+                delay_ms: 2_000,
+            })
+        );
+        assert!(runtime
+            .ui_shell_state_json()
+            .contains("\"status\":{\"kind\":\"crashed\"}"));
+
+        runtime
+            .handle_content_event(ContentEvent::CrashReloadDue { tab_id: background })
+            .expect("reload should run");
+        assert!(view_of(&runtime, background).is_some_and(|view_id| view_id != crashed_view));
+        assert_eq!(
+            runtime.engine().state().tabs[&background].status,
+            TabStatus::Ok
+        );
+
+        let event_count = runtime.host().events().len();
+        runtime
+            .handle_content_event(ContentEvent::RendererCrashed { tab_id: front })
+            .expect("crash should be handled");
+        assert!(!runtime.host().events()[event_count..]
+            .iter()
+            .any(|event| matches!(event, HostEvent::CrashReloadScheduled { .. })));
+        runtime
+            .handle_ui_command(UiCommand::ReloadTab { tab_id: front.0 })
+            .expect("reload should recreate the view");
+        let front_view = view_of(&runtime, front).expect("view should be recreated");
+
+        runtime
+            .handle_content_event(ContentEvent::LoadFailed {
+                tab_id: front,
+                url: "https://front.example/".to_owned(),
+                error_code: -105,
+            })
+            .expect("failure should be handled");
+        assert!(runtime.ui_shell_state_json().contains(
+            "\"status\":{\"kind\":\"load_failed\",\"url\":\"https://front.example/\",\"error_code\":-105}"
+        ));
+        runtime
+            .handle_ui_command(UiCommand::ReloadTab { tab_id: front.0 })
+            .expect("reload should renavigate");
+        assert_eq!(view_of(&runtime, front), Some(front_view));
+        assert_eq!(
+            runtime.host().events().last(),
+            Some(&HostEvent::ContentNavigated {
+                view_id: front_view,
+                tab_id: front,
+                url: "https://front.example/".to_owned(),
+            })
+        );
+    }
+
+    #[test]
+    fn favicons_are_downloaded_once_and_served_from_the_cache() {
+        let host = MockCefHost::default();
+        let mut runtime = AppRuntime::bootstrap(host, "0.1.0").expect("bootstrap should succeed");
+        let workspace_id = runtime.default_workspace_id();
+        for _ in 0..2 {
+            runtime
+                .handle_ui_command(UiCommand::NewTab {
+                    workspace_id: workspace_id.0,
+                    url: Some("https://news.example/".to_owned()),
+                    make_active: true,
+                })
+                .expect("tab should be created");
+        }
+        let tab_order = runtime.engine().state().workspaces[&workspace_id]
+            .tab_order
+            .clone();
+        let (first, second) = (tab_order[0], tab_order[1]);
+        let icon_url = "https://news.example/favicon.png";
+        let png = b"\x89PNG\r\n\x1a\nicon".to_vec();
+        let requests = |runtime: &AppRuntime<MockCefHost>| {
+            runtime
+                .host()
+                .events()
+                .iter()
+                .filter(|event| matches!(event, HostEvent::FaviconRequested { .. }))
+                .count()
+        };
+
+        runtime
+            .handle_content_event(ContentEvent::FaviconChanged {
+                tab_id: first,
+                urls: vec![icon_url.to_owned()],
+            })
+            .expect("favicon change should be handled");
+        assert_eq!(
+            runtime.host().events().last(),
+            Some(&HostEvent::FaviconRequested {
+                tab_id: first,
+                icon_url: icon_url.to_owned(),
+            })
+        );
+        runtime
+            .handle_content_event(ContentEvent::FaviconDownloaded {
+                tab_id: first,
+                icon_url: "https://news.example/old.png".to_owned(),
+                data: Some(png.clone()),
+            })
+            .expect("stale icon should be ignored");
+        assert_eq!(runtime.engine().state().tabs[&first].favicon_key, None);
+        runtime
+            .handle_content_event(ContentEvent::FaviconDownloaded {
+                tab_id: first,
+                icon_url: icon_url.to_owned(),
+                data: Some(png.clone()),
+            })
+            .expect("icon should be stored");
+        let key = runtime.engine().state().tabs[&first]
+            .favicon_key
+            .clone()
+            .expect("tab should carry a favicon key");
+        assert_eq!(
+            runtime.favicon_resource(&key),
+            Some(("image/png", png.clone()))
+        );
+        assert!(runtime
+            .ui_shell_state_json()
+            .contains(&format!("\"favicon_url\":\"app://favicon/{key}\"")));
+
+        runtime
+            .handle_content_event(ContentEvent::FaviconChanged {
+                tab_id: second,
+                urls: vec![icon_url.to_owned()],
+            })
+            .expect("favicon change should be handled");
+        assert_eq!(requests(&runtime), 1);
+        assert_eq!(
+            runtime.engine().state().tabs[&second].favicon_key,
+            Some(key)
+        );
+
+        runtime
+            .handle_content_event(ContentEvent::FaviconChanged {
+                tab_id: second,
+                urls: vec!["https://news.example/page.png".to_owned()],
+            })
+            .expect("favicon change should be handled");
+        runtime
+            .handle_content_event(ContentEvent::FaviconDownloaded {
+                tab_id: second,
+                icon_url: "https://news.example/page.png".to_owned(),
+                data: Some(b"<html>not found</html>".to_vec()),
+            })
+            .expect("non-image answer should be handled");
+        assert_eq!(runtime.engine().state().tabs[&second].favicon_key, None);
+    }
+
+    #[test]
+    fn mute_commands_reach_live_views_and_audio_state_is_reported() {
+        let host = MockCefHost::default();
+        let mut runtime = AppRuntime::bootstrap(host, "0.1.0").expect("bootstrap should succeed");
+        let workspace_id = runtime.default_workspace_id();
+        for url in ["https://music.example/", "https://news.example/"] {
+            runtime
+                .handle_ui_command(UiCommand::NewTab {
+                    workspace_id: workspace_id.0,
+                    url: Some(url.to_owned()),
+                    make_active: true,
+                })
+                .expect("tab should be created");
+        }
+        let tab_order = runtime.engine().state().workspaces[&workspace_id]
+            .tab_order
+            .clone();
+        let (music, news) = (tab_order[0], tab_order[1]);
+        let view_of = |runtime: &AppRuntime<MockCefHost>, tab_id| {
+            runtime.tab_bindings[&tab_id].content.view_id
+        };
+
+        runtime
+            .handle_content_event(ContentEvent::AudioStateChanged {
+                tab_id: music,
+                audible: true,
+            })
+            .expect("audio state should be observed");
+        assert!(runtime
+            .ui_shell_state_json()
+            .contains("\"muted\":false,\"audible\":true"));
+
+        runtime
+            .handle_ui_command(UiCommand::MuteOtherTabs { tab_id: news.0 })
+            .expect("other tabs should mute");
+        assert_eq!(
+            runtime.host().events().last(),
+            Some(&HostEvent::AudioMuted {
+                view_id: view_of(&runtime, music),
+                muted: true,
+            })
+        );
+        runtime
+            .handle_ui_command(UiCommand::SetWorkspaceMuted {
+                workspace_id: workspace_id.0,
+                muted: false,
+            })
+            .expect("workspace should unmute");
+        let unmuted: Vec<&HostEvent> = runtime
+            .host()
+            .events()
+            .iter()
+            .filter(|event| matches!(event, HostEvent::AudioMuted { muted: false, .. }))
+            .collect();
+        assert_eq!(
+            unmuted,
+            vec![&HostEvent::AudioMuted {
+                view_id: view_of(&runtime, music),
+                muted: false,
+            }]
+        );
+
+        runtime
+            .handle_ui_command(UiCommand::SetTabMuted {
+                tab_id: news.0,
+                muted: true,
+            })
+            .expect("tab should mute");
+        assert!(runtime.engine().state().tabs[&news].muted);
+        assert_eq!(
+            runtime.host().events().last(),
+            Some(&HostEvent::AudioMuted {
+                view_id: view_of(&runtime, news),
+                muted: true,
+            })
+        );
+    }
+
+    #[test]
+    fn downloads_get_unique_paths_and_follow_host_progress() {
+        let host = MockCefHost::default();
+        let mut runtime = AppRuntime::bootstrap(host, "0.1.0").expect("bootstrap should succeed");
+        let workspace_id = runtime.default_workspace_id();
+        let profile_id = runtime
+            .resolve_active_profile_id()
+            .expect("profile should be active");
+        let directory =
+            std::env::temp_dir().join(format!("switchboard_downloads_test_{}", std::process::id()));
+        runtime
+            .handle_ui_command(UiCommand::SettingSet {
+                key: format!("downloads.directory.profile.{}", profile_id.0),
+                value: SettingValue::Text(directory.to_string_lossy().into_owned()),
+            })
+            .expect("setting should apply");
+        runtime
+            .handle_ui_command(UiCommand::NewTab {
+                workspace_id: workspace_id.0,
+                url: Some("https://files.example/".to_owned()),
+                make_active: true,
+            })
+            .expect("tab should be created");
+        let tab_id = runtime
+            .active_tab_id(workspace_id)
+            .expect("tab should be active");
+
+        let start = |runtime: &mut AppRuntime<MockCefHost>| {
+            let event = runtime.host_mut().start_download(
+                tab_id,
+                "https://files.example/get?id=7",
+                Some("report.pdf"),
+                Some(1000),
+            );
+            runtime
+                .handle_content_event(event)
+                .expect("download should start");
+            match runtime.host().events().last() {
+                Some(HostEvent::DownloadBegun {
+                    download_id,
+                    target_path,
+                }) => (*download_id, target_path.clone()),
+                other => panic!("expected download to begin, got {other:?}"),
+            }
+        };
+        let (first, first_path) = start(&mut runtime);
+        let (second, second_path) = start(&mut runtime);
+        assert_eq!(first_path, directory.join("report.pdf").to_string_lossy());
+        assert_eq!(
+            second_path,
+            directory.join("report (1).pdf").to_string_lossy()
+        );
+
+        let event = runtime
+            .host_mut()
+            .advance_download(first, 400)
+            .expect("download should be running");
+        runtime
+            .handle_content_event(event)
+            .expect("progress should apply");
+        runtime
+            .handle_ui_command(UiCommand::PauseDownload {
+                download_id: first.0,
             })
-            .expect("switch workspace should succeed");
+            .expect("download should pause");
+        assert!(runtime.host_mut().advance_download(first, 400).is_none());
+        assert!(runtime
+            .ui_shell_state_json()
+            .contains("\"received_bytes\":400,\"total_bytes\":1000,\"state\":\"paused\""));
+
         runtime
-            .handle_ui_command(UiCommand::NavigateActive {
-                url: "https://example.com".to_owned(),
+            .handle_ui_command(UiCommand::ResumeDownload {
+                download_id: first.0,
             })
-            .expect("navigate active should create a tab");
+            .expect("download should resume");
+        while let Some(event) = runtime.host_mut().advance_download(first, 400) {
+            runtime
+                .handle_content_event(event)
+                .expect("progress should apply");
+        }
+        let download = &runtime.engine().state().downloads[&first];
+        assert_eq!(
+            (download.received_bytes, download.state),
+            (1000, DownloadState::Complete)
+        );
 
-        let active_tab_id = runtime
-            .active_tab_id(second_workspace_id)
-            .expect("new tab should be active in second workspace");
-        let active_tab = runtime
-            .engine()
-            .state()
-            .tabs
-            .get(&active_tab_id)
-            .expect("new tab should exist");
-        assert_eq!(active_tab.url, "https://example.com");
+        runtime
+            .handle_ui_command(UiCommand::CancelDownload {
+                download_id: second.0,
+            })
+            .expect("download should cancel");
+        assert!(runtime.host_mut().advance_download(second, 400).is_none());
+        runtime
+            .handle_ui_command(UiCommand::OpenDownloadFolder {
+                download_id: first.0,
+            })
+            .expect("folder should open");
+        assert!(runtime.host().events().ends_with(&[
+            HostEvent::DownloadControlled {
+                download_id: second,
+                command: DownloadCommand::Cancel,
+            },
+            HostEvent::DownloadFolderOpened {
+                target_path: first_path,
+            },
+        ]));
+
+        let (third, third_path) = start(&mut runtime);
+        assert_eq!(third_path, second_path);
+        let event = runtime
+            .host_mut()
+            .fail_download(third)
+            .expect("download should be running");
+        runtime
+            .handle_content_event(event)
+            .expect("failure should apply");
+        assert_eq!(
+            runtime.engine().state().downloads[&third].state,
+            DownloadState::Failed
+        );
     }
 
     #[test]
-    fn activate_tab_updates_content_view_to_selected_tab() {
+    fn content_filter_follows_profile_toggle_and_site_allowlist() {
         let host = MockCefHost::default();
         let mut runtime = AppRuntime::bootstrap(host, "0.1.0").expect("bootstrap should succeed");
+        runtime
+            .set_content_filter(FilterEngine::parse("||ads.example.net^$third-party"))
+            .expect("filter should install");
         let workspace_id = runtime.default_workspace_id();
-
+        let profile_id = runtime
+            .resolve_active_profile_id()
+            .expect("profile should be active");
         runtime
             .handle_ui_command(UiCommand::NewTab {
                 workspace_id: workspace_id.0,
-                url: Some("https://one.example".to_owned()),
+                url: Some("https://news.example/".to_owned()),
                 make_active: true,
             })
-            .expect("first tab should succeed");
-        let first_tab_id = runtime
-            .active_tab_id(workspace_id)
-            .expect("first tab should be active");
+            .expect("tab should be created");
+        let view_id = runtime
+            .host()
+            .events()
+            .iter()
+            .rev()
+            .find_map(|event| match event {
+                HostEvent::ContentViewCreated { view_id, .. } => Some(*view_id),
+                _ => None,
+            })
+            .expect("content view should be created");
+        let ad_request = FilterRequest {
+            url: "https://ads.example.net/banner.js",
+            source_url: Some("https://news.example/"),
+            resource_type: ResourceType::Script,
+        };
+        assert_eq!(
+            runtime.host().filter_request(view_id, &ad_request),
+            FilterDecision::Block
+        );
 
         runtime
-            .handle_ui_command(UiCommand::NewTab {
-                workspace_id: workspace_id.0,
-                url: Some("https://two.example".to_owned()),
-                make_active: true,
+            .handle_ui_command(UiCommand::SettingSet {
+                key: format!("content_filter.allowlist.profile.{}", profile_id.0),
+                value: SettingValue::Text("news.example".to_owned()),
             })
-            .expect("second tab should succeed");
+            .expect("allowlist should apply");
+        assert_eq!(
+            runtime.host().filter_request(view_id, &ad_request),
+            FilterDecision::Allow
+        );
 
         runtime
-            .handle_ui_command(UiCommand::ActivateTab {
-                tab_id: first_tab_id.0,
+            .handle_ui_command(UiCommand::SettingSet {
+                key: format!("content_filter.allowlist.profile.{}", profile_id.0),
+                value: SettingValue::Text(String::new()),
             })
-            .expect("activate tab should succeed");
-
-        let content_create_count = runtime
-            .host()
-            .events()
-            .iter()
-            .filter(|event| matches!(event, HostEvent::ContentViewCreated { .. }))
-            .count();
-        let content_navigate_count = runtime
-            .host()
-            .events()
-            .iter()
-            .filter(|event| matches!(event, HostEvent::ContentNavigated { .. }))
-            .count();
-        assert_eq!(content_create_count, 2);
-        assert_eq!(content_navigate_count, 0);
+            .expect("allowlist should clear");
+        runtime
+            .handle_ui_command(UiCommand::SettingSet {
+                key: format!("content_filter.enabled.profile.{}", profile_id.0),
+                value: SettingValue::Bool(false),
+            })
+            .expect("toggle should apply");
+        assert_eq!(
+            runtime.host().filter_request(view_id, &ad_request),
+            FilterDecision::Allow
+        );
     }
 
     #[test]
-    fn activate_active_tab_is_noop_for_content_view() {
+    fn blocked_content_navigation_shows_blank_page_and_skips_history() {
         let host = MockCefHost::default();
         let mut runtime = AppRuntime::bootstrap(host, "0.1.0").expect("bootstrap should succeed");
         let workspace_id = runtime.default_workspace_id();
-
+        let profile_id = runtime
+            .resolve_active_profile_id()
+            .expect("profile should be active");
+        runtime
+            .handle_ui_command(UiCommand::SettingSet {
+                key: format!("navigation.blocked_domains.profile.{}", profile_id.0),
+                value: SettingValue::Text("blocked.example".to_owned()),
+            })
+            .expect("setting should apply");
         runtime
             .handle_ui_command(UiCommand::NewTab {
                 workspace_id: workspace_id.0,
-                url: Some("https://one.example".to_owned()),
+                url: Some("https://one.example/".to_owned()),
                 make_active: true,
             })
             .expect("tab should be created");
-        let active_tab_id = runtime
+        let tab_id = runtime
             .active_tab_id(workspace_id)
             .expect("tab should be active");
-        let revision_before = runtime.revision();
-
-        let patch = runtime
-            .handle_ui_command(UiCommand::ActivateTab {
-                tab_id: active_tab_id.0,
-            })
-            .expect("activate active tab should succeed");
 
-        assert!(
-            patch.ops.is_empty(),
-            "activate on already-active tab should no-op"
-        );
-        assert_eq!(runtime.revision(), revision_before);
+        assert!(matches!(
+            runtime.handle_ui_command(UiCommand::Navigate {
+                tab_id: tab_id.0,
+                url: "https://www.blocked.example/".to_owned(),
+            }),
+            Err(RuntimeError::BlockedContentNavigation(blocked))
+                if blocked.reason.as_str() == "domain"
+        ));
 
-        let content_create_count = runtime
-            .host()
-            .events()
-            .iter()
-            .filter(|event| matches!(event, HostEvent::ContentViewCreated { .. }))
-            .count();
-        let content_navigate_count = runtime
+        runtime
+            .handle_content_event(ContentEvent::UrlChanged {
+                tab_id,
+                url: "https://blocked.example/landing".to_owned(),
+            })
+            .expect("redirect should be recorded as blocked");
+        let last_navigation = runtime
             .host()
             .events()
             .iter()
-            .filter(|event| matches!(event, HostEvent::ContentNavigated { .. }))
-            .count();
-        assert_eq!(content_create_count, 1);
-        assert_eq!(content_navigate_count, 0);
+            .rev()
+            .find_map(|event| match event {
+                HostEvent::ContentNavigated { url, .. } => Some(url.clone()),
+                _ => None,
+            });
+        assert_eq!(last_navigation.as_deref(), Some("about:blank"));
+        assert!(runtime
+            .ui_shell_state_json()
+            .contains("\"blocked_navigation\":{\"url\":\"https://blocked.example/landing\",\"reason\":\"domain\""));
+        assert!(runtime
+            .search_history(profile_id, "blocked", 10)
+            .expect("history search should succeed")
+            .is_empty());
     }
 
     #[test]
-    fn content_events_update_tab_metadata() {
+    fn suggestions_query_offers_switch_to_tab_across_workspaces() {
         let host = MockCefHost::default();
         let mut runtime = AppRuntime::bootstrap(host, "0.1.0").expect("bootstrap should succeed");
-        let workspace_id = runtime.default_workspace_id();
-
+        let first_workspace_id = runtime.default_workspace_id();
         runtime
             .handle_ui_command(UiCommand::NewTab {
-                workspace_id: workspace_id.0,
-                url: Some("https://one.example".to_owned()),
+                workspace_id: first_workspace_id.0,
+                url: Some("https://crates.io/crates/serde".to_owned()),
                 make_active: true,
             })
             .expect("tab should be created");
-        let tab_id = runtime
-            .active_tab_id(workspace_id)
+        let serde_tab_id = runtime
+            .active_tab_id(first_workspace_id)
             .expect("tab should be active");
-
         runtime
-            .handle_content_event(ContentEvent::TitleChanged {
-                tab_id,
-                title: "One Example".to_owned(),
+            .handle_ui_command(UiCommand::NewWorkspace {
+                name: "Second".to_owned(),
             })
-            .expect("title event should apply");
+            .expect("workspace should be created");
+        let second_workspace_id = runtime
+            .resolve_active_workspace_id()
+            .expect("workspace should be active");
         runtime
-            .handle_content_event(ContentEvent::LoadingChanged {
-                tab_id,
-                is_loading: true,
+            .handle_ui_command(UiCommand::NewTab {
+                workspace_id: second_workspace_id.0,
+                url: Some("https://example.com".to_owned()),
+                make_active: true,
             })
-            .expect("loading event should apply");
+            .expect("tab should be created");
 
-        let tab = runtime
-            .engine()
-            .state()
-            .tabs
-            .get(&tab_id)
-            .expect("tab should exist");
-        assert_eq!(tab.title, "One Example");
-        assert!(tab.loading);
+        let json = runtime.handle_ui_query(UiQuery::Suggestions("serde".to_owned()));
+        assert!(json.starts_with(&format!(
+            "[{{\"kind\":\"switch_to_tab\",\"tab_id\":{},\"workspace_id\":{}",
+            serde_tab_id.0, first_workspace_id.0
+        )));
+        assert!(
+            json.contains("\"kind\":\"search\",\"url\":\"https://www.google.com/search?q=serde\"")
+        );
+        assert_eq!(
+            runtime.handle_ui_query(UiQuery::Suggestions(" ".to_owned())),
+            "[]"
+        );
     }
 
     #[test]
@@ -1719,20 +4146,43 @@ mod tests {
             })
             .expect("switching back to default profile should succeed");
 
-        let content_create_count = runtime
-            .host()
-            .events()
-            .iter()
-            .filter(|event| matches!(event, HostEvent::ContentViewCreated { .. }))
-            .count();
-        let content_destroy_count = runtime
-            .host()
-            .events()
-            .iter()
-            .filter(|event| matches!(event, HostEvent::ContentViewDestroyed { .. }))
-            .count();
-        assert!(content_create_count >= 2);
-        assert!(content_destroy_count >= 1);
+        let count_events = |runtime: &AppRuntime<MockCefHost>, created: bool| {
+            runtime
+                .host()
+                .events()
+                .iter()
+                .filter(|event| match event {
+                    HostEvent::ContentViewCreated { .. } => created,
+                    HostEvent::ContentViewDestroyed { .. } => !created,
+                    _ => false,
+                })
+                .count()
+        };
+        let second_tab_id = runtime
+            .active_tab_id(second_workspace_id)
+            .expect("second profile tab should be active");
+        // The background profile's last tab stays warm, bound but hidden.
+        assert_eq!(count_events(&runtime, true), 2);
+        assert_eq!(count_events(&runtime, false), 0);
+        assert_eq!(
+            runtime.tab_bindings[&second_tab_id].content.profile_id,
+            second_profile_id
+        );
+        runtime
+            .handle_ui_command(UiCommand::SwitchProfile {
+                profile_id: second_profile_id.0,
+            })
+            .expect("switching to the second profile should succeed");
+        assert_eq!(count_events(&runtime, true), 2);
+
+        runtime
+            .handle_intent(Intent::SettingSet {
+                key: "lifecycle.background_profile_warm_budget".to_owned(),
+                value: SettingValue::Int(0),
+            })
+            .expect("setting should apply");
+        assert_eq!(count_events(&runtime, false), 1);
+        assert!(runtime.tab_bindings.contains_key(&second_tab_id));
     }
 
     #[test]
@@ -1771,4 +4221,201 @@ mod tests {
             .expect("thumbnail placeholder should be captured");
         assert!(data_url.starts_with("data:image/svg+xml;utf8,"));
     }
+
+    #[test]
+    fn imported_workspace_file_creates_no_content_views() {
+        let host = MockCefHost::default();
+        let mut runtime = AppRuntime::bootstrap(host, "0.1.0").expect("bootstrap should succeed");
+        let workspace_id = runtime.default_workspace_id();
+        for index in 0..5 {
+            runtime
+                .handle_ui_command(UiCommand::NewTab {
+                    workspace_id: workspace_id.0,
+                    url: Some(format!("https://export.example/{index}")),
+                    make_active: index == 0,
+                })
+                .expect("tab should be created");
+        }
+
+        let mut path = std::env::temp_dir();
+        path.push(format!(
+            "switchboard_workspace_export_{}_{}.json",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .expect("system time should be after unix epoch")
+                .as_nanos()
+        ));
+        runtime
+            .handle_ui_command(UiCommand::ExportWorkspace {
+                workspace_id: workspace_id.0,
+                path: path.display().to_string(),
+            })
+            .expect("export should succeed");
+
+        let profile_id = runtime
+            .engine()
+            .state()
+            .active_profile_id
+            .expect("profile should be active");
+        let content_views_before = runtime
+            .host()
+            .events()
+            .iter()
+            .filter(|event| matches!(event, HostEvent::ContentViewCreated { .. }))
+            .count();
+        let patch = runtime
+            .handle_ui_command(UiCommand::ImportWorkspaces {
+                profile_id: profile_id.0,
+                path: path.display().to_string(),
+            })
+            .expect("import should succeed");
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(patch.to_revision, patch.from_revision + 1);
+        let content_views_after = runtime
+            .host()
+            .events()
+            .iter()
+            .filter(|event| matches!(event, HostEvent::ContentViewCreated { .. }))
+            .count();
+        assert_eq!(content_views_after, content_views_before);
+
+        let state = runtime.engine().state();
+        let profile = state
+            .profiles
+            .get(&profile_id)
+            .expect("profile should exist");
+        assert_eq!(profile.workspace_order.len(), 2);
+        let imported = state
+            .workspaces
+            .get(&profile.workspace_order[1])
+            .expect("imported workspace should exist");
+        assert_eq!(imported.tab_order.len(), 5);
+        assert!(imported.tab_order.iter().all(|tab_id| {
+            state.tabs.get(tab_id).map(|tab| tab.runtime_state) == Some(TabRuntimeState::Discarded)
+        }));
+    }
+
+    #[test]
+    fn import_rejects_malformed_bundle() {
+        let host = MockCefHost::default();
+        let mut runtime = AppRuntime::bootstrap(host, "0.1.0").expect("bootstrap should succeed");
+        let profile_id = runtime
+            .engine()
+            .state()
+            .active_profile_id
+            .expect("profile should be active");
+        let revision = runtime.revision();
+
+        let result =
+            runtime.import_workspaces_json(profile_id, "{\"format\":\"switchboard.workspaces\"");
+        assert!(matches!(
+            result,
+            Err(RuntimeError::Portable(PortableError::Malformed(_)))
+        ));
+        assert_eq!(runtime.revision(), revision);
+    }
+
+    #[test]
+    fn bookmark_html_import_and_markdown_export_use_file_format() {
+        let host = MockCefHost::default();
+        let mut runtime = AppRuntime::bootstrap(host, "0.1.0").expect("bootstrap should succeed");
+        let profile_id = runtime
+            .engine()
+            .state()
+            .active_profile_id
+            .expect("profile should be active");
+
+        let unique = format!(
+            "{}_{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .expect("system time should be after unix epoch")
+                .as_nanos()
+        );
+        let mut html_path = std::env::temp_dir();
+        html_path.push(format!("switchboard_bookmarks_{unique}.html"));
+        std::fs::write(
+            &html_path,
+            "<!DOCTYPE NETSCAPE-Bookmark-file-1>\n<DL><p>\n<DT><H3>Team</H3>\n<DL><p>\n\
+             <DT><A HREF=\"https://team.example/wiki\">Wiki</A>\n\
+             <DT><A HREF=\"https://team.example/board\">Board</A>\n</DL><p>\n</DL><p>\n",
+        )
+        .expect("fixture should be written");
+        runtime
+            .import_workspaces_from_file(profile_id, &html_path)
+            .expect("bookmark import should succeed");
+        let _ = std::fs::remove_file(&html_path);
+
+        let imported_workspace_id = *runtime
+            .engine()
+            .state()
+            .profiles
+            .get(&profile_id)
+            .expect("profile should exist")
+            .workspace_order
+            .last()
+            .expect("imported workspace should exist");
+        let imported = runtime
+            .engine()
+            .state()
+            .workspaces
+            .get(&imported_workspace_id)
+            .expect("imported workspace should exist");
+        assert_eq!(imported.name, "Team");
+        assert_eq!(imported.tab_order.len(), 2);
+
+        let mut markdown_path = std::env::temp_dir();
+        markdown_path.push(format!("switchboard_workspace_{unique}.md"));
+        runtime
+            .handle_ui_command(UiCommand::ExportWorkspace {
+                workspace_id: imported_workspace_id.0,
+                path: markdown_path.display().to_string(),
+            })
+            .expect("markdown export should succeed");
+        let markdown =
+            std::fs::read_to_string(&markdown_path).expect("markdown export should be readable");
+        let _ = std::fs::remove_file(&markdown_path);
+        assert_eq!(
+            markdown,
+            "## Team\n\n- [Wiki](https://team.example/wiki)\n- [Board](https://team.example/board)\n"
+        );
+    }
+
+    #[test]
+    fn shell_state_json_exposes_bookmarks() {
+        let host = MockCefHost::default();
+        let mut runtime = AppRuntime::bootstrap(host, "0.1.0").expect("bootstrap should succeed");
+        let profile_id = runtime
+            .engine()
+            .state()
+            .active_profile_id
+            .expect("profile should be active");
+
+        runtime
+            .handle_ui_command(UiCommand::CreateBookmarkFolder {
+                profile_id: profile_id.0,
+                parent_id: None,
+                name: "Docs".to_owned(),
+            })
+            .expect("folder should be created");
+        runtime
+            .handle_ui_command(UiCommand::AddBookmark {
+                profile_id: profile_id.0,
+                folder_id: Some(1),
+                url: "https://docs.example".to_owned(),
+                title: "Docs \"home\"".to_owned(),
+            })
+            .expect("bookmark should be added");
+
+        let json = runtime.ui_shell_state_json();
+        assert!(json.contains(
+            "\"bookmark_folders\":[{\"id\":1,\"profile_id\":1,\"parent_id\":null,\"name\":\"Docs\"}]"
+        ));
+        assert!(json.contains(
+            "\"bookmarks\":[{\"id\":1,\"profile_id\":1,\"folder_id\":1,\"position\":0,\"url\":\"https://docs.example\",\"title\":\"Docs \\\"home\\\"\"}]"
+        ));
+    }
 }
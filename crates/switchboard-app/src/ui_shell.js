@@ -4,6 +4,13 @@ const key = "switchboard.active_uri";
 const input = document.getElementById("url");
 const backButton = document.getElementById("nav-back");
 const forwardButton = document.getElementById("nav-forward");
+const continueInsecureButton = document.getElementById("nav-continue-insecure");
+const reloadTabButton = document.getElementById("nav-reload-tab");
+const permissionPrompt = document.getElementById("nav-permission");
+const permissionPromptLabel = document.getElementById("nav-permission-label");
+const permissionAllowButton = document.getElementById("nav-permission-allow");
+const permissionBlockButton = document.getElementById("nav-permission-block");
+const permissionRememberInput = document.getElementById("nav-permission-remember");
 const profileMenuButton = document.getElementById("profile-menu-button");
 const profileMenuLabel = document.getElementById("profile-menu-label");
 const profileMenuPopover = document.getElementById("profile-menu-popover");
@@ -21,8 +28,10 @@ const workspaceTitleWrap = document.getElementById("workspace-title-wrap");
 const workspaceTitle = document.getElementById("workspace-title");
 const workspaceTitleInput = document.getElementById("workspace-title-input");
 const workspaceDelete = document.getElementById("workspace-delete");
+const workspaceMute = document.getElementById("workspace-mute");
 const tabList = document.getElementById("tab-list");
 const tabNew = document.getElementById("tab-new");
+const bookmarkToggle = document.getElementById("bookmark-toggle");
 const devtoolsToggle = document.getElementById("devtools-toggle");
 const settingsToggle = document.getElementById("settings-toggle");
 const settingsBackdrop = document.getElementById("settings-backdrop");
@@ -30,17 +39,26 @@ const commandBackdrop = document.getElementById("command-backdrop");
 const commandPanel = document.getElementById("command-panel");
 const commandForm = document.getElementById("command-form");
 const commandInput = document.getElementById("command-input");
+const commandSuggestions = document.getElementById("command-suggestions");
 const settingsPanel = document.getElementById("settings-panel");
 const settingsClose = document.getElementById("settings-close");
 const settingsSearchEngine = document.getElementById("settings-search-engine");
 const settingsHomepage = document.getElementById("settings-homepage");
 const settingsNewTabBehavior = document.getElementById("settings-new-tab-behavior");
+const settingsStartupRestoreMode = document.getElementById("settings-startup-restore-mode");
 const settingsCustomUrlField = document.getElementById("settings-custom-url-field");
 const settingsCustomUrl = document.getElementById("settings-custom-url");
 const settingsKeybindingCloseTab = document.getElementById("settings-keybinding-close-tab");
 const settingsKeybindingCommand = document.getElementById("settings-keybinding-command");
 const settingsKeybindingFocusNav = document.getElementById("settings-keybinding-focus-nav");
 const settingsKeybindingDevTools = document.getElementById("settings-keybinding-devtools");
+const settingsPortablePath = document.getElementById("settings-portable-path");
+const settingsExportWorkspace = document.getElementById("settings-export-workspace");
+const settingsExportProfile = document.getElementById("settings-export-profile");
+const settingsImportWorkspaces = document.getElementById("settings-import-workspaces");
+const settingsBookmarks = document.getElementById("settings-bookmarks");
+const settingsBookmarkFolderName = document.getElementById("settings-bookmark-folder-name");
+const settingsBookmarkFolderCreate = document.getElementById("settings-bookmark-folder-create");
 const settingsPasswordManagerProfileNote = document.getElementById(
   "settings-password-manager-profile-note"
 );
@@ -56,14 +74,18 @@ const settingsPasswordManagerSavePrompt = document.getElementById(
 const settingsPasswordManagerFallback = document.getElementById(
   "settings-password-manager-fallback"
 );
+const settingsHttpsOnly = document.getElementById("settings-https-only");
+const settingsContentFilter = document.getElementById("settings-content-filter");
+const settingsDownloadDirectory = document.getElementById("settings-download-directory");
+const settingsDownloads = document.getElementById("settings-downloads");
 
 const TAB_ROW_HEIGHT = 56;
 const TAB_OVERSCAN = 6;
 const TAB_LIST_PADDING_Y = 8;
-const SEARCH_ENGINE_SETTING_KEY = "search_engine";
 const HOMEPAGE_SETTING_KEY = "homepage";
 const NEW_TAB_BEHAVIOR_SETTING_KEY = "new_tab_behavior";
 const NEW_TAB_CUSTOM_URL_SETTING_KEY = "new_tab_custom_url";
+const STARTUP_RESTORE_MODE_SETTING_KEY = "startup.restore_mode";
 const KEYBINDING_CLOSE_TAB_SETTING_KEY = "keybinding_close_tab";
 const KEYBINDING_COMMAND_PALETTE_SETTING_KEY = "keybinding_command_palette";
 const KEYBINDING_FOCUS_NAVIGATION_SETTING_KEY = "keybinding_focus_navigation";
@@ -77,10 +99,13 @@ const PASSWORD_MANAGER_PROVIDER_PROFILE_PREFIX = "password_manager.provider.prof
 const PASSWORD_MANAGER_AUTOFILL_PROFILE_PREFIX = "password_manager.autofill.profile.";
 const PASSWORD_MANAGER_SAVE_PROMPT_PROFILE_PREFIX = "password_manager.save_prompt.profile.";
 const PASSWORD_MANAGER_FALLBACK_PROFILE_PREFIX = "password_manager.fallback.profile.";
-const DEFAULT_SEARCH_ENGINE = "google";
+const HTTPS_ONLY_PROFILE_PREFIX = "navigation.https_only.profile.";
+const CONTENT_FILTER_ENABLED_PROFILE_PREFIX = "content_filter.enabled.profile.";
+const DOWNLOAD_DIRECTORY_PROFILE_PREFIX = "downloads.directory.profile.";
 const DEFAULT_HOMEPAGE = "https://youtube.com";
 const DEFAULT_NEW_TAB_BEHAVIOR = "homepage";
 const DEFAULT_NEW_TAB_CUSTOM_URL = "https://example.com";
+const DEFAULT_STARTUP_RESTORE_MODE = "lazy";
 const DEFAULT_KEYBINDING_CLOSE_TAB = "mod+w";
 const DEFAULT_KEYBINDING_COMMAND_PALETTE = "space";
 const DEFAULT_KEYBINDING_FOCUS_NAVIGATION = "mod+l";
@@ -88,14 +113,6 @@ const DEFAULT_KEYBINDING_TOGGLE_DEVTOOLS = "mod+shift+i";
 const PASSWORD_MANAGER_PROVIDERS = new Set(["builtin", "bitwarden", "1password", "lastpass", "none"]);
 const PASSWORD_MANAGER_SWITCHES = new Set(["enabled", "disabled"]);
 const PASSWORD_MANAGER_FALLBACKS = new Set(["builtin", "prompt", "none"]);
-const SEARCH_ENGINE_URLS = Object.freeze({
-  google: "https://www.google.com/search?q=%s",
-  duckduckgo: "https://duckduckgo.com/?q=%s",
-  bing: "https://www.bing.com/search?q=%s",
-  brave: "https://search.brave.com/search?q=%s",
-  kagi: "https://kagi.com/search?q=%s",
-  startpage: "https://www.startpage.com/do/dsearch?query=%s",
-});
 const KEYBINDING_MODIFIERS = Object.freeze(["mod", "ctrl", "meta", "alt", "shift"]);
 const KEYBINDING_SPECIAL_KEYS = new Set([
   "space",
@@ -134,6 +151,8 @@ let profileEditorMode = null;
 let profileEditorTargetId = null;
 let settingsPanelOpen = false;
 let commandPanelOpen = false;
+let commandSuggestionItems = [];
+let commandSuggestionIndex = 0;
 let uiOverlayVisible = false;
 
 function send(payload) {
@@ -152,11 +171,6 @@ function normalizeUrl(value) {
   return `https://${raw}`;
 }
 
-function normalizeSearchEngine(value) {
-  const candidate = (value || "").trim().toLowerCase();
-  return SEARCH_ENGINE_URLS[candidate] ? candidate : DEFAULT_SEARCH_ENGINE;
-}
-
 function normalizeNewTabBehavior(value) {
   const candidate = (value || "").trim().toLowerCase();
   if (
@@ -170,6 +184,19 @@ function normalizeNewTabBehavior(value) {
   return DEFAULT_NEW_TAB_BEHAVIOR;
 }
 
+function normalizeStartupRestoreMode(value) {
+  const candidate = (value || "").trim().toLowerCase();
+  if (
+    candidate === "lazy" ||
+    candidate === "pinned" ||
+    candidate === "workspace" ||
+    candidate === "fresh"
+  ) {
+    return candidate;
+  }
+  return DEFAULT_STARTUP_RESTORE_MODE;
+}
+
 function normalizePasswordManagerProvider(value) {
   const candidate = (value || "").trim().toLowerCase();
   return PASSWORD_MANAGER_PROVIDERS.has(candidate) ? candidate : "builtin";
@@ -222,44 +249,10 @@ function setLocalSettingValue(keyName, value) {
   shellState.settings[keyName] = value;
 }
 
-function searchUrlForQuery(query) {
-  const engine = normalizeSearchEngine(
-    shellSettingText(SEARCH_ENGINE_SETTING_KEY, DEFAULT_SEARCH_ENGINE)
-  );
-  const template = SEARCH_ENGINE_URLS[engine] || SEARCH_ENGINE_URLS[DEFAULT_SEARCH_ENGINE];
-  return template.replace("%s", encodeURIComponent(query));
-}
-
-function normalizeNavigationInput(value) {
-  const raw = (value || "").trim();
-  if (!raw) return "";
-
-  if (raw.startsWith("http://") || raw.startsWith("https://")) {
-    return raw;
-  }
-
-  if (/^[a-zA-Z][a-zA-Z0-9+.-]*:/.test(raw)) {
-    return searchUrlForQuery(raw);
-  }
-
-  if (/\s/.test(raw)) {
-    return searchUrlForQuery(raw);
-  }
-
-  const lower = raw.toLowerCase();
-  if (
-    lower.startsWith("localhost") ||
-    lower.startsWith("127.0.0.1") ||
-    lower.startsWith("[::1]")
-  ) {
-    return `http://${raw}`;
-  }
-
-  if (raw.includes(".")) {
-    return `https://${raw}`;
-  }
-
-  return searchUrlForQuery(raw);
+function searchEnginesForProfile(sourceState = shellState) {
+  const profileId = activeProfileIdFromState(sourceState);
+  if (!profileId || !sourceState || !Array.isArray(sourceState.search_engines)) return [];
+  return sourceState.search_engines.filter((engine) => String(engine.profile_id) === profileId);
 }
 
 function normalizeConfiguredUrl(value, fallback) {
@@ -387,6 +380,7 @@ function setActiveUri(next, pushHistory) {
   activeUri = next;
   localStorage.setItem(key, activeUri);
   renderUri();
+  syncBookmarkToggle();
 }
 
 function navigateTo(next, pushHistory) {
@@ -396,10 +390,20 @@ function navigateTo(next, pushHistory) {
   queueStateRefresh();
 }
 
+// The host classifies the text as a URL, hostname or search query.
+function navigateFromText(text) {
+  const raw = (text || "").trim();
+  if (!raw) return;
+  send(`navigate_input ${raw}`);
+  const hostUri = normalizeUrl(send("query_active_uri"));
+  if (hostUri) {
+    setActiveUri(hostUri, true);
+  }
+  queueStateRefresh();
+}
+
 function navigateFromInput() {
-  const next = normalizeNavigationInput(input.value);
-  if (!next) return;
-  navigateTo(next, true);
+  navigateFromText(input.value);
 }
 
 function goBack() {
@@ -439,15 +443,30 @@ function parseShellState(raw) {
   }
 }
 
+function syncSearchEngineOptions(state) {
+  const engines = searchEnginesForProfile(state);
+  const signature = engines.map((engine) => `${engine.id}:${engine.name}:${engine.keyword}`).join("|");
+  if (settingsSearchEngine.dataset.signature !== signature) {
+    settingsSearchEngine.replaceChildren(
+      ...engines.map((engine) => {
+        const option = document.createElement("option");
+        option.value = String(engine.id);
+        option.textContent = `${engine.name} (${engine.keyword})`;
+        return option;
+      })
+    );
+    settingsSearchEngine.dataset.signature = signature;
+  }
+  const selected = engines.find((engine) => engine.is_default) || engines[0];
+  if (selected && settingsSearchEngine.value !== String(selected.id)) {
+    settingsSearchEngine.value = String(selected.id);
+  }
+}
+
 function syncSettingsControlsFromState(state) {
   if (!state || !state.settings || typeof state.settings !== "object") return;
 
-  const searchEngine = normalizeSearchEngine(
-    shellSettingText(SEARCH_ENGINE_SETTING_KEY, DEFAULT_SEARCH_ENGINE, state)
-  );
-  if (settingsSearchEngine.value !== searchEngine) {
-    settingsSearchEngine.value = searchEngine;
-  }
+  syncSearchEngineOptions(state);
 
   const homepage = normalizeConfiguredUrl(
     shellSettingText(HOMEPAGE_SETTING_KEY, DEFAULT_HOMEPAGE, state),
@@ -464,6 +483,13 @@ function syncSettingsControlsFromState(state) {
     settingsNewTabBehavior.value = behavior;
   }
 
+  const restoreMode = normalizeStartupRestoreMode(
+    shellSettingText(STARTUP_RESTORE_MODE_SETTING_KEY, DEFAULT_STARTUP_RESTORE_MODE, state)
+  );
+  if (settingsStartupRestoreMode.value !== restoreMode) {
+    settingsStartupRestoreMode.value = restoreMode;
+  }
+
   const customUrl = normalizeConfiguredUrl(
     shellSettingText(NEW_TAB_CUSTOM_URL_SETTING_KEY, DEFAULT_NEW_TAB_CUSTOM_URL, state),
     DEFAULT_NEW_TAB_CUSTOM_URL
@@ -608,9 +634,104 @@ function syncSettingsControlsFromState(state) {
   settingsPasswordManagerSavePrompt.disabled = !passwordControlsEnabled;
   settingsPasswordManagerFallback.disabled = !passwordControlsEnabled;
 
+  const httpsOnlyKey = profileScopedSettingKey(HTTPS_ONLY_PROFILE_PREFIX, state);
+  const httpsOnly = httpsOnlyKey && state.settings[httpsOnlyKey] === true ? "on" : "off";
+  if (document.activeElement !== settingsHttpsOnly && settingsHttpsOnly.value !== httpsOnly) {
+    settingsHttpsOnly.value = httpsOnly;
+  }
+  settingsHttpsOnly.disabled = !activeProfileId;
+
+  const contentFilterKey = profileScopedSettingKey(CONTENT_FILTER_ENABLED_PROFILE_PREFIX, state);
+  const contentFilter =
+    contentFilterKey && state.settings[contentFilterKey] === false ? "off" : "on";
+  if (
+    document.activeElement !== settingsContentFilter &&
+    settingsContentFilter.value !== contentFilter
+  ) {
+    settingsContentFilter.value = contentFilter;
+  }
+  settingsContentFilter.disabled = !activeProfileId;
+
+  const downloadDirectoryKey = profileScopedSettingKey(DOWNLOAD_DIRECTORY_PROFILE_PREFIX, state);
+  const downloadDirectory = downloadDirectoryKey
+    ? shellSettingText(downloadDirectoryKey, "", state)
+    : "";
+  if (
+    document.activeElement !== settingsDownloadDirectory &&
+    settingsDownloadDirectory.value !== downloadDirectory
+  ) {
+    settingsDownloadDirectory.value = downloadDirectory;
+  }
+  settingsDownloadDirectory.disabled = !activeProfileId;
+  renderDownloads(state, activeProfileId);
+
   settingsCustomUrlField.hidden = behavior !== "custom";
 }
 
+function formatDownloadBytes(bytes) {
+  if (bytes >= 1024 * 1024) return `${(bytes / (1024 * 1024)).toFixed(1)} MB`;
+  if (bytes >= 1024) return `${Math.round(bytes / 1024)} KB`;
+  return `${bytes} B`;
+}
+
+function downloadStatusText(download) {
+  const received = formatDownloadBytes(download.received_bytes);
+  const progress =
+    download.total_bytes === null
+      ? received
+      : `${received} of ${formatDownloadBytes(download.total_bytes)}`;
+  switch (download.state) {
+    case "in_progress":
+      return progress;
+    case "paused":
+      return `Paused, ${progress}`;
+    case "complete":
+      return formatDownloadBytes(download.received_bytes);
+    case "failed":
+      return "Failed";
+    default:
+      return "Cancelled";
+  }
+}
+
+function renderDownloads(state, activeProfileId) {
+  const downloads = Array.isArray(state.downloads)
+    ? state.downloads.filter((download) => download.profile_id === activeProfileId)
+    : [];
+  settingsDownloads.replaceChildren();
+  for (const download of downloads) {
+    const item = document.createElement("li");
+    item.className = "settings-download";
+    const label = document.createElement("div");
+    label.className = "settings-download-name";
+    label.textContent = download.target_path.split(/[\\/]/).pop() || download.url;
+    label.title = download.target_path;
+    const status = document.createElement("span");
+    status.className = "settings-download-status";
+    status.textContent = downloadStatusText(download);
+    label.appendChild(status);
+    const actions = document.createElement("div");
+    actions.className = "settings-download-actions";
+    const actionNames = {
+      in_progress: [["Pause", "pause_download"], ["Cancel", "cancel_download"]],
+      paused: [["Resume", "resume_download"], ["Cancel", "cancel_download"]],
+      complete: [["Show", "open_download_folder"], ["Remove", "remove_download"]],
+    }[download.state] || [["Remove", "remove_download"]];
+    for (const [text, command] of actionNames) {
+      const button = document.createElement("button");
+      button.type = "button";
+      button.textContent = text;
+      button.addEventListener("click", () => {
+        send(`${command} ${download.id}`);
+        queueStateRefresh();
+      });
+      actions.appendChild(button);
+    }
+    item.append(label, actions);
+    settingsDownloads.appendChild(item);
+  }
+}
+
 function commitTextSetting(keyName, value) {
   const sanitized = (value || "").replace(/\r?\n/g, " ").trim();
   if (!sanitized) return;
@@ -678,6 +799,203 @@ function commitPasswordManagerFallbackSetting() {
   commitActiveProfileTextSetting(PASSWORD_MANAGER_FALLBACK_PROFILE_PREFIX, normalized);
 }
 
+function portableFilePath() {
+  const path = settingsPortablePath.value.replace(/\r?\n/g, " ").trim();
+  if (!path) settingsPortablePath.focus();
+  return path;
+}
+
+function exportActiveWorkspace() {
+  if (!shellState) return;
+  const { activeWorkspace } = deriveActiveContext(shellState);
+  const path = portableFilePath();
+  if (!activeWorkspace || !path) return;
+  send(`export_workspace ${activeWorkspace.id} ${path}`);
+}
+
+function exportActiveProfile() {
+  const profileId = activeProfileIdFromState();
+  const path = portableFilePath();
+  if (!profileId || !path) return;
+  send(`export_profile ${profileId} ${path}`);
+}
+
+function importWorkspacesIntoActiveProfile() {
+  const profileId = activeProfileIdFromState();
+  const path = portableFilePath();
+  if (!profileId || !path) return;
+  send(`import_workspaces ${profileId} ${path}`);
+  queueStateRefresh();
+}
+
+function activeProfileBookmarks(state = shellState) {
+  const profileId = activeProfileIdFromState(state);
+  if (!profileId) return { folders: [], bookmarks: [] };
+  const inProfile = (item) => String(item.profile_id) === profileId;
+  return {
+    folders: (state.bookmark_folders || []).filter(inProfile),
+    bookmarks: (state.bookmarks || []).filter(inProfile),
+  };
+}
+
+function bookmarkForActiveUri() {
+  const { bookmarks } = activeProfileBookmarks();
+  return bookmarks.find((bookmark) => bookmark.url === activeUri) || null;
+}
+
+function syncBookmarkToggle() {
+  const bookmarked = bookmarkForActiveUri() !== null;
+  bookmarkToggle.textContent = bookmarked ? "Bookmarked" : "Bookmark";
+  bookmarkToggle.setAttribute("aria-pressed", bookmarked ? "true" : "false");
+}
+
+function toggleActiveBookmark() {
+  const profileId = activeProfileIdFromState();
+  if (!profileId || !/^https?:\/\//.test(activeUri)) return;
+  const existing = bookmarkForActiveUri();
+  if (existing) {
+    send(`remove_bookmark ${existing.id}`);
+  } else {
+    const { activeTab } = deriveActiveContext(shellState);
+    const title = ((activeTab && activeTab.title) || "").replace(/\r?\n/g, " ");
+    send(`add_bookmark ${profileId} root ${activeUri} ${title}`);
+  }
+  queueStateRefresh();
+}
+
+function createBookmarkFolder() {
+  const profileId = activeProfileIdFromState();
+  const name = settingsBookmarkFolderName.value.replace(/\r?\n/g, " ").trim();
+  if (!profileId || !name) return;
+  settingsBookmarkFolderName.value = "";
+  send(`create_bookmark_folder ${profileId} root ${name}`);
+  queueStateRefresh();
+}
+
+function bookmarkCommandButton(text, command) {
+  const button = document.createElement("button");
+  button.type = "button";
+  button.textContent = text;
+  button.addEventListener("click", () => {
+    send(command);
+    queueStateRefresh();
+  });
+  return button;
+}
+
+function bookmarkRenameButton(command, current) {
+  const button = document.createElement("button");
+  button.type = "button";
+  button.textContent = "Rename";
+  button.addEventListener("click", () => {
+    const next = (window.prompt("Rename", current) || "").replace(/\r?\n/g, " ").trim();
+    if (!next || next === current) return;
+    send(`${command} ${next}`);
+    queueStateRefresh();
+  });
+  return button;
+}
+
+function bookmarkRow(className, label, title, controls) {
+  const item = document.createElement("li");
+  item.className = className;
+  const name = document.createElement("div");
+  name.className = "settings-bookmark-name";
+  name.textContent = label;
+  name.title = title;
+  const actions = document.createElement("div");
+  actions.className = "settings-bookmark-actions";
+  actions.append(...controls);
+  item.append(name, actions);
+  return item;
+}
+
+function renderBookmarks(state) {
+  const { folders, bookmarks } = activeProfileBookmarks(state);
+  const inFolder = (folderId) =>
+    bookmarks
+      .filter((bookmark) => bookmark.folder_id === folderId)
+      .sort((left, right) => left.position - right.position);
+  const folderSelect = (bookmark) => {
+    const select = document.createElement("select");
+    select.setAttribute("aria-label", "Bookmark folder");
+    const choices = [["root", "Top level"], ...folders.map((folder) => [String(folder.id), folder.name])];
+    for (const [value, text] of choices) {
+      const option = document.createElement("option");
+      option.value = value;
+      option.textContent = text;
+      select.appendChild(option);
+    }
+    select.value = bookmark.folder_id === null ? "root" : String(bookmark.folder_id);
+    select.addEventListener("change", () => {
+      const folderId = select.value === "root" ? null : Number(select.value);
+      send(`move_bookmark ${bookmark.id} ${select.value} ${inFolder(folderId).length}`);
+      queueStateRefresh();
+    });
+    return select;
+  };
+  const rows = [];
+  const appendBookmarks = (folderId) => {
+    const siblings = inFolder(folderId);
+    const folderArg = folderId === null ? "root" : folderId;
+    siblings.forEach((bookmark, index) => {
+      const controls = [folderSelect(bookmark)];
+      if (index > 0) {
+        controls.push(
+          bookmarkCommandButton("Up", `move_bookmark ${bookmark.id} ${folderArg} ${index - 1}`)
+        );
+      }
+      if (index < siblings.length - 1) {
+        controls.push(
+          bookmarkCommandButton("Down", `move_bookmark ${bookmark.id} ${folderArg} ${index + 1}`)
+        );
+      }
+      controls.push(
+        bookmarkRenameButton(`rename_bookmark ${bookmark.id}`, bookmark.title),
+        bookmarkCommandButton("Remove", `remove_bookmark ${bookmark.id}`)
+      );
+      const className = folderId === null ? "settings-bookmark" : "settings-bookmark nested";
+      rows.push(bookmarkRow(className, bookmark.title || bookmark.url, bookmark.url, controls));
+    });
+  };
+  appendBookmarks(null);
+  for (const folder of folders) {
+    rows.push(
+      bookmarkRow("settings-bookmark folder", folder.name, folder.name, [
+        bookmarkCommandButton("Open as Workspace", `open_bookmark_folder ${folder.id}`),
+        bookmarkRenameButton(`rename_bookmark_folder ${folder.id}`, folder.name),
+        bookmarkCommandButton("Remove", `remove_bookmark_folder ${folder.id}`),
+      ])
+    );
+    appendBookmarks(folder.id);
+  }
+  settingsBookmarks.replaceChildren(...rows);
+}
+
+function commitHttpsOnlySetting() {
+  const profileKey = profileScopedSettingKey(HTTPS_ONLY_PROFILE_PREFIX);
+  if (!profileKey) return;
+  const enabled = settingsHttpsOnly.value === "on";
+  setLocalSettingValue(profileKey, enabled);
+  send(`setting_set_bool ${profileKey} ${enabled}`);
+  queueStateRefresh();
+}
+
+function commitDownloadDirectorySetting() {
+  const profileKey = profileScopedSettingKey(DOWNLOAD_DIRECTORY_PROFILE_PREFIX);
+  if (!profileKey) return;
+  commitTextSetting(profileKey, settingsDownloadDirectory.value);
+}
+
+function commitContentFilterSetting() {
+  const profileKey = profileScopedSettingKey(CONTENT_FILTER_ENABLED_PROFILE_PREFIX);
+  if (!profileKey) return;
+  const enabled = settingsContentFilter.value === "on";
+  setLocalSettingValue(profileKey, enabled);
+  send(`setting_set_bool ${profileKey} ${enabled}`);
+  queueStateRefresh();
+}
+
 function syncUiOverlayVisibility() {
   const nextVisible = settingsPanelOpen || commandPanelOpen;
   if (nextVisible === uiOverlayVisible) return;
@@ -720,6 +1038,7 @@ function openCommandPanel() {
   commandBackdrop.hidden = false;
   commandPanel.hidden = false;
   commandInput.value = activeUri;
+  renderCommandSuggestions([]);
   syncUiOverlayVisibility();
   window.requestAnimationFrame(() => {
     commandInput.focus();
@@ -735,11 +1054,91 @@ function closeCommandPanel() {
   syncUiOverlayVisibility();
 }
 
+const SUGGESTION_KIND_LABELS = Object.freeze({
+  navigate: "Go",
+  switch_to_tab: "Tab",
+  bookmark: "Bookmark",
+  history: "History",
+  search: "Search",
+});
+
+function refreshCommandSuggestions() {
+  const text = commandInput.value.trim();
+  if (!text) {
+    renderCommandSuggestions([]);
+    return;
+  }
+  let items = [];
+  try {
+    const parsed = JSON.parse(send(`query_suggestions ${text}`) || "[]");
+    items = Array.isArray(parsed) ? parsed : [];
+  } catch (_error) {
+    items = [];
+  }
+  renderCommandSuggestions(items);
+}
+
+function renderCommandSuggestions(items) {
+  commandSuggestionItems = items;
+  commandSuggestionIndex = 0;
+  commandSuggestions.replaceChildren(
+    ...items.map((item, index) => {
+      const row = document.createElement("li");
+      row.className = "command-suggestion";
+      row.setAttribute("role", "option");
+      row.dataset.index = String(index);
+      const kind = document.createElement("span");
+      kind.className = "command-suggestion-kind";
+      kind.textContent = SUGGESTION_KIND_LABELS[item.kind] || "";
+      const title = document.createElement("span");
+      title.className = "command-suggestion-title";
+      title.textContent = item.title || item.url || "";
+      const url = document.createElement("span");
+      url.className = "command-suggestion-url";
+      url.textContent = item.kind === "search" ? "" : item.url || "";
+      row.append(kind, title, url);
+      return row;
+    })
+  );
+  commandSuggestions.hidden = items.length === 0;
+  syncCommandSuggestionSelection();
+}
+
+function syncCommandSuggestionSelection() {
+  Array.from(commandSuggestions.children).forEach((row, index) => {
+    const selected = index === commandSuggestionIndex;
+    row.classList.toggle("selected", selected);
+    row.setAttribute("aria-selected", selected ? "true" : "false");
+  });
+}
+
+function moveCommandSuggestionSelection(delta) {
+  if (commandSuggestionItems.length === 0) return;
+  const count = commandSuggestionItems.length;
+  commandSuggestionIndex = (commandSuggestionIndex + delta + count) % count;
+  syncCommandSuggestionSelection();
+}
+
+function openCommandSuggestion(item) {
+  closeCommandPanel();
+  if (item.kind === "switch_to_tab" && item.tab_id !== undefined) {
+    send(`activate_tab ${item.tab_id}`);
+    queueStateRefresh();
+    return;
+  }
+  navigateTo(item.url, true);
+}
+
 function navigateFromCommandPanel() {
-  const next = normalizeNavigationInput(commandInput.value);
-  if (!next) return;
+  const selected = commandSuggestionItems[commandSuggestionIndex];
+  if (selected && selected.url) {
+    openCommandSuggestion(selected);
+    return;
+  }
+  const text = commandInput.value;
+  if (!text.trim()) return;
   closeCommandPanel();
-  navigateTo(next, true);
+  navigateFromText(text);
 }
 
 function focusTopNavigationInput() {
@@ -943,10 +1342,21 @@ function createTabButton(tab, isActive) {
   if (tab.loading) {
     button.classList.add("loading");
   }
+  if (tab.status && tab.status.kind !== "ok") {
+    button.classList.add(tab.status.kind === "crashed" ? "crashed" : "load-failed");
+  }
+  button.dataset.keepAlive = tab.keep_alive ? "1" : "0";
+  if (tab.keep_alive) {
+    button.classList.add("keep-alive");
+    button.title = "Kept alive (Alt-click to allow discarding)";
+  }
 
   const icon = document.createElement("span");
   icon.className = "tab-icon";
-  if (tab.thumbnail_data_url) {
+  if (tab.favicon_url) {
+    icon.classList.add("favicon");
+    icon.style.backgroundImage = `url("${tab.favicon_url}")`;
+  } else if (tab.thumbnail_data_url) {
     icon.classList.add("thumbnail");
     icon.style.backgroundImage = `url("${tab.thumbnail_data_url}")`;
   }
@@ -968,6 +1378,20 @@ function createTabButton(tab, isActive) {
 
   button.appendChild(content);
 
+  if (tab.audible || tab.muted) {
+    button.classList.add("has-audio");
+    const audio = document.createElement("button");
+    audio.type = "button";
+    audio.className = "tab-audio";
+    audio.classList.toggle("muted", Boolean(tab.muted));
+    audio.dataset.tabId = String(tab.id);
+    audio.dataset.muted = tab.muted ? "1" : "0";
+    audio.setAttribute("aria-label", tab.muted ? "Unmute tab" : "Mute tab");
+    audio.title = tab.muted ? "Unmute tab" : "Mute tab (Shift: mute other tabs)";
+    audio.textContent = tab.muted ? "🔇" : "🔊";
+    button.appendChild(audio);
+  }
+
   const close = document.createElement("button");
   close.type = "button";
   close.className = "tab-close";
@@ -1125,11 +1549,34 @@ function renderShellState(state) {
   workspaceTitleWrap.classList.toggle("disabled", !activeWorkspaceId);
   workspaceTitleWrap.setAttribute("tabindex", activeWorkspaceId ? "0" : "-1");
   workspaceDelete.disabled = !activeWorkspaceId || orderedWorkspaces.length <= 1;
-
+  const workspaceMuted = orderedTabs.length > 0 && orderedTabs.every((tab) => tab.muted);
+  workspaceMute.disabled = orderedTabs.length === 0;
+  workspaceMute.dataset.muted = workspaceMuted ? "1" : "0";
+  workspaceMute.textContent = workspaceMuted ? "Unmute" : "Mute";
+
+  const blockedNavigation = activeTab ? activeTab.blocked_navigation : null;
+  input.classList.toggle("blocked", Boolean(blockedNavigation));
+  input.title = blockedNavigation ? `Blocked: ${blockedNavigation.detail}` : "";
+  continueInsecureButton.hidden =
+    !blockedNavigation || blockedNavigation.reason !== "https_unavailable";
+  const tabStatus = activeTab && activeTab.status ? activeTab.status : null;
+  reloadTabButton.hidden = !tabStatus || tabStatus.kind === "ok";
+  reloadTabButton.textContent =
+    tabStatus && tabStatus.kind === "crashed"
+      ? "Tab crashed · Reload"
+      : `Load failed (${tabStatus ? tabStatus.error_code : ""}) · Retry`;
+  const pendingPermission =
+    activeTab && activeTab.pending_permissions ? activeTab.pending_permissions[0] : null;
+  permissionPrompt.hidden = !pendingPermission;
+  permissionPromptLabel.textContent = pendingPermission
+    ? `${pendingPermission.origin} wants ${pendingPermission.kind}`
+    : "";
   if (activeTab && activeTab.url && document.activeElement !== input) {
-    setActiveUri(normalizeUrl(activeTab.url), false);
+    setActiveUri(blockedNavigation ? blockedNavigation.url : normalizeUrl(activeTab.url), false);
   }
   syncSettingsControlsFromState(state);
+  renderBookmarks(state);
+  syncBookmarkToggle();
 }
 
 function syncShellStateFromHost(force) {
@@ -1360,6 +1807,15 @@ function deleteActiveWorkspace() {
   queueStateRefresh();
 }
 
+function toggleActiveWorkspaceMuted() {
+  if (!shellState) return;
+  const { activeWorkspace } = deriveActiveContext(shellState);
+  if (!activeWorkspace) return;
+  const command = workspaceMute.dataset.muted === "1" ? "unmute_workspace" : "mute_workspace";
+  send(`${command} ${activeWorkspace.id}`);
+  queueStateRefresh();
+}
+
 function createTabInActiveWorkspace() {
   if (!shellState) return;
   const { activeWorkspace } = deriveActiveContext(shellState);
@@ -1378,6 +1834,21 @@ function handleWorkspaceClick(event) {
 }
 
 function handleTabClick(event) {
+  const audioTarget = event.target.closest(".tab-audio");
+  if (audioTarget) {
+    event.preventDefault();
+    event.stopPropagation();
+    const tabId = audioTarget.dataset.tabId;
+    if (!tabId) return;
+    if (event.shiftKey) {
+      send(`mute_other_tabs ${tabId}`);
+    } else {
+      send(`${audioTarget.dataset.muted === "1" ? "unmute_tab" : "mute_tab"} ${tabId}`);
+    }
+    queueStateRefresh();
+    return;
+  }
+
   const closeTarget = event.target.closest(".tab-close");
   if (closeTarget) {
     event.preventDefault();
@@ -1391,6 +1862,14 @@ function handleTabClick(event) {
 
   const target = event.target.closest(".tab-item");
   if (!target) return;
+  if (event.altKey) {
+    event.preventDefault();
+    const tabId = target.dataset.tabId;
+    if (!tabId) return;
+    send(`${target.dataset.keepAlive === "1" ? "allow_discard_tab" : "keep_alive_tab"} ${tabId}`);
+    queueStateRefresh();
+    return;
+  }
   if (target.classList.contains("active")) return;
   const tabId = target.dataset.tabId;
   if (!tabId) return;
@@ -1483,9 +1962,10 @@ settingsPanel.addEventListener("keydown", (event) => {
   settingsToggle.focus();
 });
 settingsSearchEngine.addEventListener("change", () => {
-  const next = normalizeSearchEngine(settingsSearchEngine.value);
-  settingsSearchEngine.value = next;
-  commitTextSetting(SEARCH_ENGINE_SETTING_KEY, next);
+  const engineId = settingsSearchEngine.value;
+  if (!engineId) return;
+  send(`set_default_search_engine ${engineId}`);
+  queueStateRefresh();
 });
 settingsNewTabBehavior.addEventListener("change", () => {
   const next = normalizeNewTabBehavior(settingsNewTabBehavior.value);
@@ -1493,6 +1973,11 @@ settingsNewTabBehavior.addEventListener("change", () => {
   settingsCustomUrlField.hidden = next !== "custom";
   commitTextSetting(NEW_TAB_BEHAVIOR_SETTING_KEY, next);
 });
+settingsStartupRestoreMode.addEventListener("change", () => {
+  const next = normalizeStartupRestoreMode(settingsStartupRestoreMode.value);
+  settingsStartupRestoreMode.value = next;
+  commitTextSetting(STARTUP_RESTORE_MODE_SETTING_KEY, next);
+});
 settingsHomepage.addEventListener("keydown", (event) => {
   if (event.key !== "Enter") return;
   event.preventDefault();
@@ -1569,6 +2054,55 @@ settingsPasswordManagerSavePrompt.addEventListener("change", () => {
 settingsPasswordManagerFallback.addEventListener("change", () => {
   commitPasswordManagerFallbackSetting();
 });
+bookmarkToggle.addEventListener("click", toggleActiveBookmark);
+settingsBookmarkFolderCreate.addEventListener("click", createBookmarkFolder);
+settingsExportWorkspace.addEventListener("click", exportActiveWorkspace);
+settingsExportProfile.addEventListener("click", exportActiveProfile);
+settingsImportWorkspaces.addEventListener("click", importWorkspacesIntoActiveProfile);
+settingsHttpsOnly.addEventListener("change", () => {
+  commitHttpsOnlySetting();
+});
+settingsContentFilter.addEventListener("change", () => {
+  commitContentFilterSetting();
+});
+settingsDownloadDirectory.addEventListener("keydown", (event) => {
+  if (event.key !== "Enter") return;
+  event.preventDefault();
+  settingsDownloadDirectory.blur();
+});
+settingsDownloadDirectory.addEventListener("blur", () => {
+  commitDownloadDirectorySetting();
+});
+continueInsecureButton.addEventListener("click", () => {
+  if (!shellState) return;
+  const { activeTab } = deriveActiveContext(shellState);
+  if (!activeTab) return;
+  send(`continue_insecure ${activeTab.id}`);
+  queueStateRefresh();
+});
+reloadTabButton.addEventListener("click", () => {
+  if (!shellState) return;
+  const { activeTab } = deriveActiveContext(shellState);
+  if (!activeTab) return;
+  send(`reload_tab ${activeTab.id}`);
+  queueStateRefresh();
+});
+function resolvePendingPermission(decision) {
+  if (!shellState) return;
+  const { activeTab } = deriveActiveContext(shellState);
+  const pending = activeTab && activeTab.pending_permissions ? activeTab.pending_permissions[0] : null;
+  if (!pending) return;
+  const remember = permissionRememberInput.checked ? " remember" : "";
+  send(`resolve_permission ${pending.request_id} ${decision}${remember}`);
+  queueStateRefresh();
+}
+
+permissionAllowButton.addEventListener("click", () => {
+  resolvePendingPermission("allow");
+});
+permissionBlockButton.addEventListener("click", () => {
+  resolvePendingPermission("deny");
+});
 commandBackdrop.addEventListener("click", () => {
   closeCommandPanel();
 });
@@ -1577,10 +2111,23 @@ commandForm.addEventListener("submit", (event) => {
   navigateFromCommandPanel();
 });
 commandPanel.addEventListener("keydown", (event) => {
+  if (event.key === "ArrowDown" || event.key === "ArrowUp") {
+    event.preventDefault();
+    moveCommandSuggestionSelection(event.key === "ArrowDown" ? 1 : -1);
+    return;
+  }
   if (event.key !== "Escape") return;
   event.preventDefault();
   closeCommandPanel();
 });
+commandInput.addEventListener("input", refreshCommandSuggestions);
+commandSuggestions.addEventListener("mousedown", (event) => {
+  const row = event.target.closest(".command-suggestion");
+  if (!row) return;
+  event.preventDefault();
+  const item = commandSuggestionItems[Number(row.dataset.index)];
+  if (item && item.url) openCommandSuggestion(item);
+});
 profileNew.addEventListener("click", createProfile);
 profileMenuButton.addEventListener("click", (event) => {
   event.stopPropagation();
@@ -1666,6 +2213,7 @@ profileEditorInput.addEventListener("keydown", (event) => {
 });
 workspaceNew.addEventListener("click", createWorkspace);
 workspaceDelete.addEventListener("click", deleteActiveWorkspace);
+workspaceMute.addEventListener("click", toggleActiveWorkspaceMuted);
 workspaceTitleWrap.addEventListener("click", () => {
   if (workspaceTitleWrap.classList.contains("disabled")) return;
   if (workspaceTitleWrap.classList.contains("editing")) return;
@@ -8,7 +8,8 @@ use crate::raw::{
     cef_api_hash_fn, cef_api_version_fn, cef_browser_host_create_browser_fn, cef_currently_on_fn,
     cef_do_message_loop_work_fn, cef_execute_process_fn, cef_initialize_fn, cef_post_task_fn,
     cef_quit_message_loop_fn, cef_register_scheme_handler_factory_fn, cef_run_message_loop_fn,
-    cef_shutdown_fn, cef_string_utf16_clear_fn, cef_string_utf16_set_fn,
+    cef_shutdown_fn, cef_string_list_size_fn, cef_string_list_value_fn,
+    cef_string_userfree_utf16_free_fn, cef_string_utf16_clear_fn, cef_string_utf16_set_fn,
 };
 
 const RTLD_LAZY: i32 = 0x1;
@@ -55,6 +56,9 @@ pub struct CefApi {
     pub cef_register_scheme_handler_factory: cef_register_scheme_handler_factory_fn,
     pub cef_string_utf16_set: cef_string_utf16_set_fn,
     pub cef_string_utf16_clear: cef_string_utf16_clear_fn,
+    pub cef_string_userfree_utf16_free: cef_string_userfree_utf16_free_fn,
+    pub cef_string_list_size: cef_string_list_size_fn,
+    pub cef_string_list_value: cef_string_list_value_fn,
 }
 
 pub struct CefLibrary {
@@ -130,6 +134,18 @@ impl CefLibrary {
                     &load,
                     "cef_string_utf16_clear",
                 )?,
+                cef_string_userfree_utf16_free: load_symbol::<cef_string_userfree_utf16_free_fn>(
+                    &load,
+                    "cef_string_userfree_utf16_free",
+                )?,
+                cef_string_list_size: load_symbol::<cef_string_list_size_fn>(
+                    &load,
+                    "cef_string_list_size",
+                )?,
+                cef_string_list_value: load_symbol::<cef_string_list_value_fn>(
+                    &load,
+                    "cef_string_list_value",
+                )?,
             };
 
             Ok(Self { handle, api })
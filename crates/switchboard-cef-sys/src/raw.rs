@@ -19,6 +19,16 @@ pub type cef_string_multimap_t = *mut c_void;
 pub type cef_window_handle_t = *mut c_void;
 pub type cef_cursor_handle_t = *mut c_void;
 pub type cef_cursor_type_t = c_uint;
+pub type cef_return_value_t = c_uint;
+pub type cef_resource_type_t = c_uint;
+pub type cef_referrer_policy_t = c_uint;
+pub type cef_transition_type_t = c_uint;
+pub type cef_window_open_disposition_t = c_uint;
+pub type cef_permission_request_result_t = c_uint;
+pub type cef_download_interrupt_reason_t = c_uint;
+pub type cef_color_type_t = c_uint;
+pub type cef_alpha_type_t = c_uint;
+pub type cef_termination_status_t = c_uint;
 
 pub const CEF_RUNTIME_STYLE_DEFAULT: cef_runtime_style_t = 0;
 pub const CEF_RUNTIME_STYLE_CHROME: cef_runtime_style_t = 1;
@@ -34,6 +44,39 @@ pub const CEF_SCHEME_OPTION_SECURE: cef_scheme_options_t = 8;
 pub const CEF_SCHEME_OPTION_CORS_ENABLED: cef_scheme_options_t = 16;
 pub const CEF_SCHEME_OPTION_CSP_BYPASSING: cef_scheme_options_t = 32;
 pub const CEF_SCHEME_OPTION_FETCH_ENABLED: cef_scheme_options_t = 64;
+pub const RV_CANCEL: cef_return_value_t = 0;
+pub const RV_CONTINUE: cef_return_value_t = 1;
+pub const RT_MAIN_FRAME: cef_resource_type_t = 0;
+pub const RT_SUB_FRAME: cef_resource_type_t = 1;
+pub const RT_STYLESHEET: cef_resource_type_t = 2;
+pub const RT_SCRIPT: cef_resource_type_t = 3;
+pub const RT_IMAGE: cef_resource_type_t = 4;
+pub const RT_FONT_RESOURCE: cef_resource_type_t = 5;
+pub const RT_SUB_RESOURCE: cef_resource_type_t = 6;
+pub const RT_OBJECT: cef_resource_type_t = 7;
+pub const RT_MEDIA: cef_resource_type_t = 8;
+pub const RT_WORKER: cef_resource_type_t = 9;
+pub const RT_SHARED_WORKER: cef_resource_type_t = 10;
+pub const RT_PREFETCH: cef_resource_type_t = 11;
+pub const RT_FAVICON: cef_resource_type_t = 12;
+pub const RT_XHR: cef_resource_type_t = 13;
+pub const RT_PING: cef_resource_type_t = 14;
+pub const RT_SERVICE_WORKER: cef_resource_type_t = 15;
+pub const RT_CSP_REPORT: cef_resource_type_t = 16;
+pub const RT_PLUGIN_RESOURCE: cef_resource_type_t = 17;
+pub const CEF_PERMISSION_RESULT_ACCEPT: cef_permission_request_result_t = 0;
+pub const CEF_PERMISSION_RESULT_DENY: cef_permission_request_result_t = 1;
+pub const CEF_MEDIA_PERMISSION_DEVICE_AUDIO_CAPTURE: u32 = 1 << 0;
+pub const CEF_MEDIA_PERMISSION_DEVICE_VIDEO_CAPTURE: u32 = 1 << 1;
+pub const CEF_PERMISSION_TYPE_CAMERA_STREAM: u32 = 1 << 2;
+pub const CEF_PERMISSION_TYPE_CLIPBOARD: u32 = 1 << 4;
+pub const CEF_PERMISSION_TYPE_GEOLOCATION: u32 = 1 << 8;
+pub const CEF_PERMISSION_TYPE_MIC_STREAM: u32 = 1 << 12;
+pub const CEF_PERMISSION_TYPE_NOTIFICATIONS: u32 = 1 << 15;
+pub const CEF_WOD_NEW_FOREGROUND_TAB: cef_window_open_disposition_t = 3;
+pub const CEF_WOD_NEW_BACKGROUND_TAB: cef_window_open_disposition_t = 4;
+pub const CEF_WOD_NEW_POPUP: cef_window_open_disposition_t = 5;
+pub const CEF_WOD_NEW_WINDOW: cef_window_open_disposition_t = 6;
 
 #[repr(C)]
 #[derive(Clone, Copy)]
@@ -68,6 +111,12 @@ pub struct cef_point_t {
     pub y: c_int,
 }
 
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct cef_basetime_t {
+    pub val: i64,
+}
+
 #[repr(C)]
 #[derive(Clone, Copy)]
 pub struct cef_base_ref_counted_t {
@@ -102,10 +151,48 @@ pub struct cef_render_process_handler_t {
 }
 
 #[repr(C)]
-pub struct cef_audio_handler_t {
+pub struct cef_audio_parameters_t {
     pub _private: [u8; 0],
 }
 
+#[repr(C)]
+pub struct cef_audio_handler_t {
+    pub base: cef_base_ref_counted_t,
+    pub get_audio_parameters: Option<
+        unsafe extern "C" fn(
+            self_: *mut cef_audio_handler_t,
+            browser: *mut cef_browser_t,
+            params: *mut cef_audio_parameters_t,
+        ) -> c_int,
+    >,
+    pub on_audio_stream_started: Option<
+        unsafe extern "C" fn(
+            self_: *mut cef_audio_handler_t,
+            browser: *mut cef_browser_t,
+            params: *const cef_audio_parameters_t,
+            channels: c_int,
+        ),
+    >,
+    pub on_audio_stream_packet: Option<
+        unsafe extern "C" fn(
+            self_: *mut cef_audio_handler_t,
+            browser: *mut cef_browser_t,
+            data: *mut *const f32,
+            frames: c_int,
+            pts: i64,
+        ),
+    >,
+    pub on_audio_stream_stopped:
+        Option<unsafe extern "C" fn(self_: *mut cef_audio_handler_t, browser: *mut cef_browser_t)>,
+    pub on_audio_stream_error: Option<
+        unsafe extern "C" fn(
+            self_: *mut cef_audio_handler_t,
+            browser: *mut cef_browser_t,
+            message: *const cef_string_t,
+        ),
+    >,
+}
+
 #[repr(C)]
 pub struct cef_command_handler_t {
     pub _private: [u8; 0],
@@ -229,9 +316,85 @@ pub struct cef_display_handler_t {
     >,
 }
 
+#[repr(C)]
+pub struct cef_download_item_t {
+    pub base: cef_base_ref_counted_t,
+    pub is_valid: Option<unsafe extern "C" fn(self_: *mut cef_download_item_t) -> c_int>,
+    pub is_in_progress: Option<unsafe extern "C" fn(self_: *mut cef_download_item_t) -> c_int>,
+    pub is_complete: Option<unsafe extern "C" fn(self_: *mut cef_download_item_t) -> c_int>,
+    pub is_canceled: Option<unsafe extern "C" fn(self_: *mut cef_download_item_t) -> c_int>,
+    pub is_interrupted: Option<unsafe extern "C" fn(self_: *mut cef_download_item_t) -> c_int>,
+    pub get_interrupt_reason: Option<
+        unsafe extern "C" fn(self_: *mut cef_download_item_t) -> cef_download_interrupt_reason_t,
+    >,
+    pub get_current_speed: Option<unsafe extern "C" fn(self_: *mut cef_download_item_t) -> i64>,
+    pub get_percent_complete:
+        Option<unsafe extern "C" fn(self_: *mut cef_download_item_t) -> c_int>,
+    pub get_total_bytes: Option<unsafe extern "C" fn(self_: *mut cef_download_item_t) -> i64>,
+    pub get_received_bytes: Option<unsafe extern "C" fn(self_: *mut cef_download_item_t) -> i64>,
+    pub get_start_time:
+        Option<unsafe extern "C" fn(self_: *mut cef_download_item_t) -> cef_basetime_t>,
+    pub get_end_time:
+        Option<unsafe extern "C" fn(self_: *mut cef_download_item_t) -> cef_basetime_t>,
+    pub get_full_path:
+        Option<unsafe extern "C" fn(self_: *mut cef_download_item_t) -> cef_string_userfree_t>,
+    pub get_id: Option<unsafe extern "C" fn(self_: *mut cef_download_item_t) -> u32>,
+    pub get_url:
+        Option<unsafe extern "C" fn(self_: *mut cef_download_item_t) -> cef_string_userfree_t>,
+    pub get_original_url:
+        Option<unsafe extern "C" fn(self_: *mut cef_download_item_t) -> cef_string_userfree_t>,
+    pub get_suggested_file_name:
+        Option<unsafe extern "C" fn(self_: *mut cef_download_item_t) -> cef_string_userfree_t>,
+}
+
+#[repr(C)]
+pub struct cef_before_download_callback_t {
+    pub base: cef_base_ref_counted_t,
+    pub cont: Option<
+        unsafe extern "C" fn(
+            self_: *mut cef_before_download_callback_t,
+            download_path: *const cef_string_t,
+            show_dialog: c_int,
+        ),
+    >,
+}
+
+#[repr(C)]
+pub struct cef_download_item_callback_t {
+    pub base: cef_base_ref_counted_t,
+    pub cancel: Option<unsafe extern "C" fn(self_: *mut cef_download_item_callback_t)>,
+    pub pause: Option<unsafe extern "C" fn(self_: *mut cef_download_item_callback_t)>,
+    pub resume: Option<unsafe extern "C" fn(self_: *mut cef_download_item_callback_t)>,
+}
+
 #[repr(C)]
 pub struct cef_download_handler_t {
-    pub _private: [u8; 0],
+    pub base: cef_base_ref_counted_t,
+    pub can_download: Option<
+        unsafe extern "C" fn(
+            self_: *mut cef_download_handler_t,
+            browser: *mut cef_browser_t,
+            url: *const cef_string_t,
+            request_method: *const cef_string_t,
+        ) -> c_int,
+    >,
+    pub on_before_download: Option<
+        unsafe extern "C" fn(
+            self_: *mut cef_download_handler_t,
+            browser: *mut cef_browser_t,
+            download_item: *mut cef_download_item_t,
+            suggested_name: *const cef_string_t,
+            callback: *mut cef_before_download_callback_t,
+        ) -> c_int,
+    >,
+    pub on_download_updated: Option<
+        unsafe extern "C" fn(
+            self_: *mut cef_download_handler_t,
+            browser: *mut cef_browser_t,
+            download_item: *mut cef_download_item_t,
+            callback: *mut cef_download_item_callback_t,
+        ),
+    >,
 }
 
 #[repr(C)]
@@ -254,9 +417,57 @@ pub struct cef_frame_handler_t {
     pub _private: [u8; 0],
 }
 
+#[repr(C)]
+pub struct cef_media_access_callback_t {
+    pub base: cef_base_ref_counted_t,
+    pub cont: Option<
+        unsafe extern "C" fn(self_: *mut cef_media_access_callback_t, allowed_permissions: u32),
+    >,
+    pub cancel: Option<unsafe extern "C" fn(self_: *mut cef_media_access_callback_t)>,
+}
+
+#[repr(C)]
+pub struct cef_permission_prompt_callback_t {
+    pub base: cef_base_ref_counted_t,
+    pub cont: Option<
+        unsafe extern "C" fn(
+            self_: *mut cef_permission_prompt_callback_t,
+            result: cef_permission_request_result_t,
+        ),
+    >,
+}
+
 #[repr(C)]
 pub struct cef_permission_handler_t {
-    pub _private: [u8; 0],
+    pub base: cef_base_ref_counted_t,
+    pub on_request_media_access_permission: Option<
+        unsafe extern "C" fn(
+            self_: *mut cef_permission_handler_t,
+            browser: *mut cef_browser_t,
+            frame: *mut cef_frame_t,
+            requesting_origin: *const cef_string_t,
+            requested_permissions: u32,
+            callback: *mut cef_media_access_callback_t,
+        ) -> c_int,
+    >,
+    pub on_show_permission_prompt: Option<
+        unsafe extern "C" fn(
+            self_: *mut cef_permission_handler_t,
+            browser: *mut cef_browser_t,
+            prompt_id: u64,
+            requesting_origin: *const cef_string_t,
+            requested_permissions: u32,
+            callback: *mut cef_permission_prompt_callback_t,
+        ) -> c_int,
+    >,
+    pub on_dismiss_permission_prompt: Option<
+        unsafe extern "C" fn(
+            self_: *mut cef_permission_handler_t,
+            browser: *mut cef_browser_t,
+            prompt_id: u64,
+            result: cef_permission_request_result_t,
+        ),
+    >,
 }
 
 #[repr(C)]
@@ -309,13 +520,101 @@ pub struct cef_keyboard_handler_t {
 }
 
 #[repr(C)]
-pub struct cef_life_span_handler_t {
+pub struct cef_popup_features_t {
     pub _private: [u8; 0],
 }
 
+pub type cef_on_before_popup_fn = unsafe extern "C" fn(
+    self_: *mut cef_life_span_handler_t,
+    browser: *mut cef_browser_t,
+    frame: *mut cef_frame_t,
+    popup_id: c_int,
+    target_url: *const cef_string_t,
+    target_frame_name: *const cef_string_t,
+    target_disposition: cef_window_open_disposition_t,
+    user_gesture: c_int,
+    popup_features: *const cef_popup_features_t,
+    window_info: *mut cef_window_info_t,
+    client: *mut *mut cef_client_t,
+    settings: *mut cef_browser_settings_t,
+    extra_info: *mut *mut cef_dictionary_value_t,
+    no_javascript_access: *mut c_int,
+) -> c_int;
+
+#[repr(C)]
+pub struct cef_life_span_handler_t {
+    pub base: cef_base_ref_counted_t,
+    pub on_before_popup: Option<cef_on_before_popup_fn>,
+    pub on_before_popup_aborted: Option<
+        unsafe extern "C" fn(
+            self_: *mut cef_life_span_handler_t,
+            browser: *mut cef_browser_t,
+            popup_id: c_int,
+        ),
+    >,
+    pub on_before_dev_tools_popup: Option<
+        unsafe extern "C" fn(
+            self_: *mut cef_life_span_handler_t,
+            browser: *mut cef_browser_t,
+            window_info: *mut cef_window_info_t,
+            client: *mut *mut cef_client_t,
+            settings: *mut cef_browser_settings_t,
+            extra_info: *mut *mut cef_dictionary_value_t,
+            use_default_window: *mut c_int,
+        ),
+    >,
+    pub on_after_created: Option<
+        unsafe extern "C" fn(self_: *mut cef_life_span_handler_t, browser: *mut cef_browser_t),
+    >,
+    pub do_close: Option<
+        unsafe extern "C" fn(
+            self_: *mut cef_life_span_handler_t,
+            browser: *mut cef_browser_t,
+        ) -> c_int,
+    >,
+    pub on_before_close: Option<
+        unsafe extern "C" fn(self_: *mut cef_life_span_handler_t, browser: *mut cef_browser_t),
+    >,
+}
+
 #[repr(C)]
 pub struct cef_load_handler_t {
-    pub _private: [u8; 0],
+    pub base: cef_base_ref_counted_t,
+    pub on_loading_state_change: Option<
+        unsafe extern "C" fn(
+            self_: *mut cef_load_handler_t,
+            browser: *mut cef_browser_t,
+            is_loading: c_int,
+            can_go_back: c_int,
+            can_go_forward: c_int,
+        ),
+    >,
+    pub on_load_start: Option<
+        unsafe extern "C" fn(
+            self_: *mut cef_load_handler_t,
+            browser: *mut cef_browser_t,
+            frame: *mut cef_frame_t,
+            transition_type: cef_transition_type_t,
+        ),
+    >,
+    pub on_load_end: Option<
+        unsafe extern "C" fn(
+            self_: *mut cef_load_handler_t,
+            browser: *mut cef_browser_t,
+            frame: *mut cef_frame_t,
+            http_status_code: c_int,
+        ),
+    >,
+    pub on_load_error: Option<
+        unsafe extern "C" fn(
+            self_: *mut cef_load_handler_t,
+            browser: *mut cef_browser_t,
+            frame: *mut cef_frame_t,
+            error_code: cef_errorcode_t,
+            error_text: *const cef_string_t,
+            failed_url: *const cef_string_t,
+        ),
+    >,
 }
 
 #[repr(C)]
@@ -323,9 +622,118 @@ pub struct cef_run_file_dialog_callback_t {
     pub _private: [u8; 0],
 }
 
+#[repr(C)]
+pub struct cef_binary_value_t {
+    pub base: cef_base_ref_counted_t,
+    pub is_valid: Option<unsafe extern "C" fn(self_: *mut cef_binary_value_t) -> c_int>,
+    pub is_owned: Option<unsafe extern "C" fn(self_: *mut cef_binary_value_t) -> c_int>,
+    pub is_same: Option<
+        unsafe extern "C" fn(
+            self_: *mut cef_binary_value_t,
+            that: *mut cef_binary_value_t,
+        ) -> c_int,
+    >,
+    pub is_equal: Option<
+        unsafe extern "C" fn(
+            self_: *mut cef_binary_value_t,
+            that: *mut cef_binary_value_t,
+        ) -> c_int,
+    >,
+    pub copy:
+        Option<unsafe extern "C" fn(self_: *mut cef_binary_value_t) -> *mut cef_binary_value_t>,
+    pub get_raw_data: Option<unsafe extern "C" fn(self_: *mut cef_binary_value_t) -> *const c_void>,
+    pub get_size: Option<unsafe extern "C" fn(self_: *mut cef_binary_value_t) -> usize>,
+    pub get_data: Option<
+        unsafe extern "C" fn(
+            self_: *mut cef_binary_value_t,
+            buffer: *mut c_void,
+            buffer_size: usize,
+            data_offset: usize,
+        ) -> usize,
+    >,
+}
+
+#[repr(C)]
+pub struct cef_image_t {
+    pub base: cef_base_ref_counted_t,
+    pub is_empty: Option<unsafe extern "C" fn(self_: *mut cef_image_t) -> c_int>,
+    pub is_same:
+        Option<unsafe extern "C" fn(self_: *mut cef_image_t, that: *mut cef_image_t) -> c_int>,
+    pub add_bitmap: Option<
+        unsafe extern "C" fn(
+            self_: *mut cef_image_t,
+            scale_factor: f32,
+            pixel_width: c_int,
+            pixel_height: c_int,
+            color_type: cef_color_type_t,
+            alpha_type: cef_alpha_type_t,
+            pixel_data: *const c_void,
+            pixel_data_size: usize,
+        ) -> c_int,
+    >,
+    pub add_png: Option<
+        unsafe extern "C" fn(
+            self_: *mut cef_image_t,
+            scale_factor: f32,
+            png_data: *const c_void,
+            png_data_size: usize,
+        ) -> c_int,
+    >,
+    pub add_jpeg: Option<
+        unsafe extern "C" fn(
+            self_: *mut cef_image_t,
+            scale_factor: f32,
+            jpeg_data: *const c_void,
+            jpeg_data_size: usize,
+        ) -> c_int,
+    >,
+    pub get_width: Option<unsafe extern "C" fn(self_: *mut cef_image_t) -> usize>,
+    pub get_height: Option<unsafe extern "C" fn(self_: *mut cef_image_t) -> usize>,
+    pub has_representation:
+        Option<unsafe extern "C" fn(self_: *mut cef_image_t, scale_factor: f32) -> c_int>,
+    pub remove_representation:
+        Option<unsafe extern "C" fn(self_: *mut cef_image_t, scale_factor: f32) -> c_int>,
+    pub get_representation_info: Option<
+        unsafe extern "C" fn(
+            self_: *mut cef_image_t,
+            scale_factor: f32,
+            actual_scale_factor: *mut f32,
+            pixel_width: *mut c_int,
+            pixel_height: *mut c_int,
+        ) -> c_int,
+    >,
+    pub get_as_bitmap: Option<
+        unsafe extern "C" fn(
+            self_: *mut cef_image_t,
+            scale_factor: f32,
+            color_type: cef_color_type_t,
+            alpha_type: cef_alpha_type_t,
+            pixel_width: *mut c_int,
+            pixel_height: *mut c_int,
+        ) -> *mut cef_binary_value_t,
+    >,
+    pub get_as_png: Option<
+        unsafe extern "C" fn(
+            self_: *mut cef_image_t,
+            scale_factor: f32,
+            with_transparency: c_int,
+            pixel_width: *mut c_int,
+            pixel_height: *mut c_int,
+        ) -> *mut cef_binary_value_t,
+    >,
+}
+
 #[repr(C)]
 pub struct cef_download_image_callback_t {
-    pub _private: [u8; 0],
+    pub base: cef_base_ref_counted_t,
+    pub on_download_image_finished: Option<
+        unsafe extern "C" fn(
+            self_: *mut cef_download_image_callback_t,
+            image_url: *const cef_string_t,
+            http_status_code: c_int,
+            image: *mut cef_image_t,
+        ),
+    >,
 }
 
 #[repr(C)]
@@ -340,12 +748,51 @@ pub struct cef_pdf_print_callback_t {
 
 #[repr(C)]
 pub struct cef_registration_t {
-    pub _private: [u8; 0],
+    pub base: cef_base_ref_counted_t,
 }
 
 #[repr(C)]
 pub struct cef_dev_tools_message_observer_t {
-    pub _private: [u8; 0],
+    pub base: cef_base_ref_counted_t,
+    pub on_dev_tools_message: Option<
+        unsafe extern "C" fn(
+            self_: *mut cef_dev_tools_message_observer_t,
+            browser: *mut cef_browser_t,
+            message: *const c_void,
+            message_size: usize,
+        ) -> c_int,
+    >,
+    pub on_dev_tools_method_result: Option<
+        unsafe extern "C" fn(
+            self_: *mut cef_dev_tools_message_observer_t,
+            browser: *mut cef_browser_t,
+            message_id: c_int,
+            success: c_int,
+            result: *const c_void,
+            result_size: usize,
+        ),
+    >,
+    pub on_dev_tools_event: Option<
+        unsafe extern "C" fn(
+            self_: *mut cef_dev_tools_message_observer_t,
+            browser: *mut cef_browser_t,
+            method: *const cef_string_t,
+            params: *const c_void,
+            params_size: usize,
+        ),
+    >,
+    pub on_dev_tools_agent_attached: Option<
+        unsafe extern "C" fn(
+            self_: *mut cef_dev_tools_message_observer_t,
+            browser: *mut cef_browser_t,
+        ),
+    >,
+    pub on_dev_tools_agent_detached: Option<
+        unsafe extern "C" fn(
+            self_: *mut cef_dev_tools_message_observer_t,
+            browser: *mut cef_browser_t,
+        ),
+    >,
 }
 
 #[repr(C)]
@@ -359,10 +806,159 @@ pub struct cef_render_handler_t {
 }
 
 #[repr(C)]
-pub struct cef_request_handler_t {
+pub struct cef_cookie_access_filter_t {
+    pub _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct cef_post_data_t {
+    pub _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct cef_resource_request_handler_t {
+    pub base: cef_base_ref_counted_t,
+    pub get_cookie_access_filter: Option<
+        unsafe extern "C" fn(
+            self_: *mut cef_resource_request_handler_t,
+            browser: *mut cef_browser_t,
+            frame: *mut cef_frame_t,
+            request: *mut cef_request_t,
+        ) -> *mut cef_cookie_access_filter_t,
+    >,
+    pub on_before_resource_load: Option<
+        unsafe extern "C" fn(
+            self_: *mut cef_resource_request_handler_t,
+            browser: *mut cef_browser_t,
+            frame: *mut cef_frame_t,
+            request: *mut cef_request_t,
+            callback: *mut cef_callback_t,
+        ) -> cef_return_value_t,
+    >,
+}
+
+#[repr(C)]
+pub struct cef_auth_callback_t {
+    pub _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct cef_sslinfo_t {
+    pub _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct cef_x509certificate_t {
+    pub _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct cef_select_client_certificate_callback_t {
     pub _private: [u8; 0],
 }
 
+#[repr(C)]
+pub struct cef_unresponsive_process_callback_t {
+    pub _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct cef_request_handler_t {
+    pub base: cef_base_ref_counted_t,
+    pub on_before_browse: Option<
+        unsafe extern "C" fn(
+            self_: *mut cef_request_handler_t,
+            browser: *mut cef_browser_t,
+            frame: *mut cef_frame_t,
+            request: *mut cef_request_t,
+            user_gesture: c_int,
+            is_redirect: c_int,
+        ) -> c_int,
+    >,
+    pub on_open_urlfrom_tab: Option<
+        unsafe extern "C" fn(
+            self_: *mut cef_request_handler_t,
+            browser: *mut cef_browser_t,
+            frame: *mut cef_frame_t,
+            target_url: *const cef_string_t,
+            target_disposition: cef_window_open_disposition_t,
+            user_gesture: c_int,
+        ) -> c_int,
+    >,
+    pub get_resource_request_handler: Option<
+        unsafe extern "C" fn(
+            self_: *mut cef_request_handler_t,
+            browser: *mut cef_browser_t,
+            frame: *mut cef_frame_t,
+            request: *mut cef_request_t,
+            is_navigation: c_int,
+            is_download: c_int,
+            request_initiator: *const cef_string_t,
+            disable_default_handling: *mut c_int,
+        ) -> *mut cef_resource_request_handler_t,
+    >,
+    pub get_auth_credentials: Option<
+        unsafe extern "C" fn(
+            self_: *mut cef_request_handler_t,
+            browser: *mut cef_browser_t,
+            origin_url: *const cef_string_t,
+            is_proxy: c_int,
+            host: *const cef_string_t,
+            port: c_int,
+            realm: *const cef_string_t,
+            scheme: *const cef_string_t,
+            callback: *mut cef_auth_callback_t,
+        ) -> c_int,
+    >,
+    pub on_certificate_error: Option<
+        unsafe extern "C" fn(
+            self_: *mut cef_request_handler_t,
+            browser: *mut cef_browser_t,
+            cert_error: cef_errorcode_t,
+            request_url: *const cef_string_t,
+            ssl_info: *mut cef_sslinfo_t,
+            callback: *mut cef_callback_t,
+        ) -> c_int,
+    >,
+    pub on_select_client_certificate: Option<
+        unsafe extern "C" fn(
+            self_: *mut cef_request_handler_t,
+            browser: *mut cef_browser_t,
+            is_proxy: c_int,
+            host: *const cef_string_t,
+            port: c_int,
+            certificates_count: usize,
+            certificates: *const *mut cef_x509certificate_t,
+            callback: *mut cef_select_client_certificate_callback_t,
+        ) -> c_int,
+    >,
+    pub on_render_view_ready: Option<
+        unsafe extern "C" fn(self_: *mut cef_request_handler_t, browser: *mut cef_browser_t),
+    >,
+    pub on_render_process_unresponsive: Option<
+        unsafe extern "C" fn(
+            self_: *mut cef_request_handler_t,
+            browser: *mut cef_browser_t,
+            callback: *mut cef_unresponsive_process_callback_t,
+        ) -> c_int,
+    >,
+    pub on_render_process_responsive: Option<
+        unsafe extern "C" fn(self_: *mut cef_request_handler_t, browser: *mut cef_browser_t),
+    >,
+    pub on_render_process_terminated: Option<
+        unsafe extern "C" fn(
+            self_: *mut cef_request_handler_t,
+            browser: *mut cef_browser_t,
+            status: cef_termination_status_t,
+            error_code: c_int,
+            error_string: *const cef_string_t,
+        ),
+    >,
+    pub on_document_available_in_main_frame: Option<
+        unsafe extern "C" fn(self_: *mut cef_request_handler_t, browser: *mut cef_browser_t),
+    >,
+}
+
 #[repr(C)]
 pub struct cef_browser_host_t {
     pub base: cef_base_ref_counted_t,
@@ -468,6 +1064,16 @@ pub struct cef_browser_host_t {
             observer: *mut cef_dev_tools_message_observer_t,
         ) -> *mut cef_registration_t,
     >,
+    pub set_auto_resize_enabled: Option<
+        unsafe extern "C" fn(
+            self_: *mut cef_browser_host_t,
+            enabled: c_int,
+            min_size: *const cef_size_t,
+            max_size: *const cef_size_t,
+        ),
+    >,
+    pub set_audio_muted: Option<unsafe extern "C" fn(self_: *mut cef_browser_host_t, mute: c_int)>,
+    pub is_audio_muted: Option<unsafe extern "C" fn(self_: *mut cef_browser_host_t) -> c_int>,
 }
 
 #[repr(C)]
@@ -539,6 +1145,11 @@ pub struct cef_frame_t {
     >,
     pub is_main: Option<unsafe extern "C" fn(self_: *mut cef_frame_t) -> c_int>,
     pub is_focused: Option<unsafe extern "C" fn(self_: *mut cef_frame_t) -> c_int>,
+    pub get_name: Option<unsafe extern "C" fn(self_: *mut cef_frame_t) -> cef_string_userfree_t>,
+    pub get_identifier:
+        Option<unsafe extern "C" fn(self_: *mut cef_frame_t) -> cef_string_userfree_t>,
+    pub get_parent: Option<unsafe extern "C" fn(self_: *mut cef_frame_t) -> *mut cef_frame_t>,
+    pub get_url: Option<unsafe extern "C" fn(self_: *mut cef_frame_t) -> cef_string_userfree_t>,
 }
 
 #[repr(C)]
@@ -548,7 +1159,67 @@ pub struct cef_process_message_t {
 
 #[repr(C)]
 pub struct cef_request_t {
-    pub _private: [u8; 0],
+    pub base: cef_base_ref_counted_t,
+    pub is_read_only: Option<unsafe extern "C" fn(self_: *mut cef_request_t) -> c_int>,
+    pub get_url: Option<unsafe extern "C" fn(self_: *mut cef_request_t) -> cef_string_userfree_t>,
+    pub set_url: Option<unsafe extern "C" fn(self_: *mut cef_request_t, url: *const cef_string_t)>,
+    pub get_method:
+        Option<unsafe extern "C" fn(self_: *mut cef_request_t) -> cef_string_userfree_t>,
+    pub set_method:
+        Option<unsafe extern "C" fn(self_: *mut cef_request_t, method: *const cef_string_t)>,
+    pub set_referrer: Option<
+        unsafe extern "C" fn(
+            self_: *mut cef_request_t,
+            referrer_url: *const cef_string_t,
+            policy: cef_referrer_policy_t,
+        ),
+    >,
+    pub get_referrer_url:
+        Option<unsafe extern "C" fn(self_: *mut cef_request_t) -> cef_string_userfree_t>,
+    pub get_referrer_policy:
+        Option<unsafe extern "C" fn(self_: *mut cef_request_t) -> cef_referrer_policy_t>,
+    pub get_post_data:
+        Option<unsafe extern "C" fn(self_: *mut cef_request_t) -> *mut cef_post_data_t>,
+    pub set_post_data:
+        Option<unsafe extern "C" fn(self_: *mut cef_request_t, post_data: *mut cef_post_data_t)>,
+    pub get_header_map:
+        Option<unsafe extern "C" fn(self_: *mut cef_request_t, header_map: cef_string_multimap_t)>,
+    pub set_header_map:
+        Option<unsafe extern "C" fn(self_: *mut cef_request_t, header_map: cef_string_multimap_t)>,
+    pub get_header_by_name: Option<
+        unsafe extern "C" fn(
+            self_: *mut cef_request_t,
+            name: *const cef_string_t,
+        ) -> cef_string_userfree_t,
+    >,
+    pub set_header_by_name: Option<
+        unsafe extern "C" fn(
+            self_: *mut cef_request_t,
+            name: *const cef_string_t,
+            value: *const cef_string_t,
+            overwrite: c_int,
+        ),
+    >,
+    pub set: Option<
+        unsafe extern "C" fn(
+            self_: *mut cef_request_t,
+            url: *const cef_string_t,
+            method: *const cef_string_t,
+            post_data: *mut cef_post_data_t,
+            header_map: cef_string_multimap_t,
+        ),
+    >,
+    pub get_flags: Option<unsafe extern "C" fn(self_: *mut cef_request_t) -> c_int>,
+    pub set_flags: Option<unsafe extern "C" fn(self_: *mut cef_request_t, flags: c_int)>,
+    pub get_first_party_for_cookies:
+        Option<unsafe extern "C" fn(self_: *mut cef_request_t) -> cef_string_userfree_t>,
+    pub set_first_party_for_cookies:
+        Option<unsafe extern "C" fn(self_: *mut cef_request_t, url: *const cef_string_t)>,
+    pub get_resource_type:
+        Option<unsafe extern "C" fn(self_: *mut cef_request_t) -> cef_resource_type_t>,
+    pub get_transition_type:
+        Option<unsafe extern "C" fn(self_: *mut cef_request_t) -> cef_transition_type_t>,
+    pub get_identifier: Option<unsafe extern "C" fn(self_: *mut cef_request_t) -> u64>,
 }
 
 #[repr(C)]
@@ -922,6 +1593,10 @@ pub type cef_string_utf16_set_fn = unsafe extern "C" fn(
     copy: c_int,
 ) -> c_int;
 pub type cef_string_utf16_clear_fn = unsafe extern "C" fn(str_: *mut cef_string_utf16_t);
+pub type cef_string_userfree_utf16_free_fn = unsafe extern "C" fn(str_: cef_string_userfree_t);
+pub type cef_string_list_size_fn = unsafe extern "C" fn(list: cef_string_list_t) -> usize;
+pub type cef_string_list_value_fn =
+    unsafe extern "C" fn(list: cef_string_list_t, index: usize, value: *mut cef_string_t) -> c_int;
 
 pub type cef_browser_host_create_browser_fn = unsafe extern "C" fn(
     window_info: *const cef_window_info_t,
@@ -0,0 +1,760 @@
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use crate::history::profile_setting_key;
+use crate::ids::ProfileId;
+use crate::navigation::{domain_rules, DomainRule};
+use crate::state::{BrowserState, SettingValue};
+use crate::url::web_host;
+
+// Filtering is on unless the profile's bool setting is false. The allowlist is
+// a text setting in the same format as the navigation domain lists.
+pub const CONTENT_FILTER_ENABLED_PROFILE_PREFIX: &str = "content_filter.enabled.profile.";
+pub const CONTENT_FILTER_ALLOWLIST_PROFILE_PREFIX: &str = "content_filter.allowlist.profile.";
+
+const MIN_TOKEN_LEN: usize = 3;
+// Second-level labels that usually sit under a country code, e.g. `co.uk`.
+const SHARED_SECOND_LEVEL_LABELS: &[&str] = &["ac", "co", "com", "edu", "gov", "net", "org"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResourceType {
+    Document,
+    Subdocument,
+    Script,
+    Image,
+    Stylesheet,
+    Xhr,
+    Font,
+    Media,
+    Object,
+    Ping,
+    Other,
+}
+
+impl ResourceType {
+    fn mask(self) -> u16 {
+        1 << self as u16
+    }
+
+    fn from_option(name: &str) -> Option<Self> {
+        Some(match name {
+            "subdocument" => Self::Subdocument,
+            "script" => Self::Script,
+            "image" => Self::Image,
+            "stylesheet" => Self::Stylesheet,
+            "xmlhttprequest" => Self::Xhr,
+            "font" => Self::Font,
+            "media" => Self::Media,
+            "object" => Self::Object,
+            "ping" => Self::Ping,
+            "other" => Self::Other,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FilterRequest<'a> {
+    pub url: &'a str,
+    // Top-level document that issued the request; None for navigations.
+    pub source_url: Option<&'a str>,
+    pub resource_type: ResourceType,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterDecision {
+    Allow,
+    Block,
+}
+
+// Called by the CEF host from its resource-request handler, which runs off
+// the UI thread.
+pub trait RequestFilter: Debug + Send + Sync {
+    fn check(&self, request: &FilterRequest<'_>) -> FilterDecision;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Anchor {
+    None,
+    Start,
+    Host,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Rule {
+    anchor: Anchor,
+    // Lowercase literals between `*` wildcards; `^` stays in place and is
+    // matched as a separator.
+    segments: Vec<Vec<u8>>,
+    end_anchor: bool,
+    third_party: Option<bool>,
+    // Zero means every resource type.
+    resource_types: u16,
+    include_domains: Vec<String>,
+    exclude_domains: Vec<String>,
+}
+
+enum ParsedLine {
+    Ignored,
+    Unsupported,
+    Block(Rule),
+    Exception(Rule),
+}
+
+#[derive(Debug, Default)]
+struct RuleSet {
+    rules: Vec<Rule>,
+    by_token: HashMap<String, Vec<usize>>,
+    // Rules without a usable token, checked against every request.
+    untokenized: Vec<usize>,
+}
+
+impl RuleSet {
+    fn insert(&mut self, rule: Rule) {
+        let index = self.rules.len();
+        // Prefer the least crowded bucket so tokens shared by many rules,
+        // like a common domain label, don't turn lookups into a scan.
+        let token = rule.index_tokens().into_iter().min_by_key(|token| {
+            let bucket = self.by_token.get(token.as_str()).map_or(0, Vec::len);
+            (bucket, usize::MAX - token.len())
+        });
+        match token {
+            Some(token) => self.by_token.entry(token).or_default().push(index),
+            None => self.untokenized.push(index),
+        }
+        self.rules.push(rule);
+    }
+
+    fn matches(&self, request: &RequestContext<'_>) -> bool {
+        let check = |index: &usize| {
+            let rule = &self.rules[*index];
+            rule.applies_to(request) && rule.matches_url(request)
+        };
+        request
+            .tokens
+            .iter()
+            .filter_map(|token| self.by_token.get(*token))
+            .flatten()
+            .any(check)
+            || self.untokenized.iter().any(check)
+    }
+}
+
+struct RequestContext<'a> {
+    url: &'a [u8],
+    host_start: usize,
+    host_end: usize,
+    source_host: Option<String>,
+    third_party: bool,
+    resource_type: ResourceType,
+    tokens: Vec<&'a str>,
+}
+
+// Compiled ABP/EasyList subset: `||` domain anchors, `|` anchors, `*` and `^`,
+// `@@` exceptions, `$third-party`, `$domain=` and resource type options.
+// Cosmetic, regex and rules with other options are skipped.
+#[derive(Debug, Default)]
+pub struct FilterEngine {
+    blocks: RuleSet,
+    exceptions: RuleSet,
+    skipped: usize,
+}
+
+impl FilterEngine {
+    pub fn parse(list: &str) -> Self {
+        let mut engine = Self::default();
+        engine.extend(list);
+        engine
+    }
+
+    pub fn extend(&mut self, list: &str) {
+        for line in list.lines() {
+            match parse_line(line) {
+                ParsedLine::Ignored => {}
+                ParsedLine::Unsupported => self.skipped += 1,
+                ParsedLine::Block(rule) => self.blocks.insert(rule),
+                ParsedLine::Exception(rule) => self.exceptions.insert(rule),
+            }
+        }
+    }
+
+    pub fn rule_count(&self) -> usize {
+        self.blocks.rules.len() + self.exceptions.rules.len()
+    }
+
+    pub fn skipped_count(&self) -> usize {
+        self.skipped
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.blocks.rules.is_empty()
+    }
+}
+
+impl RequestFilter for FilterEngine {
+    fn check(&self, request: &FilterRequest<'_>) -> FilterDecision {
+        if self.is_empty() {
+            return FilterDecision::Allow;
+        }
+        let url = request.url.trim().to_ascii_lowercase();
+        let Some(context) = RequestContext::new(&url, request) else {
+            return FilterDecision::Allow;
+        };
+        if self.blocks.matches(&context) && !self.exceptions.matches(&context) {
+            FilterDecision::Block
+        } else {
+            FilterDecision::Allow
+        }
+    }
+}
+
+// A profile's view of the shared engine: disabled profiles get no filter and
+// allowlisted sites load everything.
+#[derive(Debug)]
+pub struct ProfileContentFilter {
+    engine: Arc<FilterEngine>,
+    allowlist: Vec<DomainRule>,
+}
+
+impl ProfileContentFilter {
+    pub fn for_profile(
+        state: &BrowserState,
+        profile_id: ProfileId,
+        engine: &Arc<FilterEngine>,
+    ) -> Option<Self> {
+        let enabled_key = profile_setting_key(CONTENT_FILTER_ENABLED_PROFILE_PREFIX, profile_id);
+        if matches!(
+            state.settings.get(&enabled_key),
+            Some(SettingValue::Bool(false))
+        ) || engine.is_empty()
+        {
+            return None;
+        }
+        Some(Self {
+            engine: Arc::clone(engine),
+            allowlist: domain_rules(state, CONTENT_FILTER_ALLOWLIST_PROFILE_PREFIX, profile_id),
+        })
+    }
+}
+
+impl RequestFilter for ProfileContentFilter {
+    fn check(&self, request: &FilterRequest<'_>) -> FilterDecision {
+        let site = web_host(request.source_url.unwrap_or(request.url));
+        if site.is_some_and(|site| self.allowlist.iter().any(|rule| rule.matches(&site))) {
+            return FilterDecision::Allow;
+        }
+        self.engine.check(request)
+    }
+}
+
+impl<'a> RequestContext<'a> {
+    fn new(url: &'a str, request: &FilterRequest<'_>) -> Option<Self> {
+        let host_start = url.find("://")? + 3;
+        let host_end = url[host_start..]
+            .find(['/', '?', '#', ':'])
+            .map_or(url.len(), |offset| host_start + offset);
+        let host = &url[host_start..host_end];
+        let source_host = request.source_url.and_then(web_host);
+        let third_party = source_host
+            .as_deref()
+            .is_some_and(|source| registrable_domain(source) != registrable_domain(host));
+        let mut tokens: Vec<&str> = url
+            .split(|ch: char| !(ch.is_ascii() && is_token_char(ch as u8)))
+            .filter(|token| token.len() >= MIN_TOKEN_LEN)
+            .collect();
+        tokens.sort_unstable();
+        tokens.dedup();
+        Some(Self {
+            url: url.as_bytes(),
+            host_start,
+            host_end,
+            source_host,
+            third_party,
+            resource_type: request.resource_type,
+            tokens,
+        })
+    }
+}
+
+impl Rule {
+    fn applies_to(&self, request: &RequestContext<'_>) -> bool {
+        if self.resource_types != 0 && self.resource_types & request.resource_type.mask() == 0 {
+            return false;
+        }
+        if self
+            .third_party
+            .is_some_and(|third_party| third_party != request.third_party)
+        {
+            return false;
+        }
+        let source = request.source_host.as_deref();
+        if !self.include_domains.is_empty()
+            && !source.is_some_and(|source| host_in(source, &self.include_domains))
+        {
+            return false;
+        }
+        !source.is_some_and(|source| host_in(source, &self.exclude_domains))
+    }
+
+    fn matches_url(&self, request: &RequestContext<'_>) -> bool {
+        let url = request.url;
+        match self.anchor {
+            Anchor::Start => self.matches_from(url, 0, true),
+            Anchor::None => self.matches_from(url, 0, false),
+            Anchor::Host => (request.host_start..request.host_end)
+                .filter(|&start| start == request.host_start || url[start - 1] == b'.')
+                .any(|start| self.matches_from(url, start, true)),
+        }
+    }
+
+    // Leftmost matching of each literal is enough for `*`-only globs.
+    fn matches_from(&self, url: &[u8], start: usize, anchored: bool) -> bool {
+        let last = self.segments.len() - 1;
+        let mut pos = start;
+        for (index, segment) in self.segments.iter().enumerate() {
+            let end = if index == 0 && anchored {
+                segment_at(url, pos, segment)
+            } else if index == last && self.end_anchor {
+                (pos..=url.len())
+                    .filter_map(|from| segment_at(url, from, segment))
+                    .find(|&end| end == url.len())
+            } else {
+                (pos..=url.len()).find_map(|from| segment_at(url, from, segment))
+            };
+            match end {
+                Some(end) => pos = end,
+                None => return false,
+            }
+        }
+        !self.end_anchor || pos == url.len()
+    }
+
+    // Literal runs that must line up with a whole URL token, so a request only
+    // has to look up its own tokens.
+    fn index_tokens(&self) -> Vec<String> {
+        let last = self.segments.len() - 1;
+        let mut tokens = Vec::new();
+        for (index, segment) in self.segments.iter().enumerate() {
+            let starts_bounded = index == 0 && self.anchor != Anchor::None;
+            let ends_bounded = index == last && self.end_anchor;
+            let mut run_start = 0;
+            for end in 0..=segment.len() {
+                if end < segment.len() && is_token_char(segment[end]) {
+                    continue;
+                }
+                let bounded =
+                    (run_start > 0 || starts_bounded) && (end < segment.len() || ends_bounded);
+                if bounded && end - run_start >= MIN_TOKEN_LEN {
+                    tokens.push(String::from_utf8_lossy(&segment[run_start..end]).into_owned());
+                }
+                run_start = end + 1;
+            }
+        }
+        tokens
+    }
+}
+
+fn parse_line(line: &str) -> ParsedLine {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('!') || line.starts_with('[') {
+        return ParsedLine::Ignored;
+    }
+    if ["##", "#@#", "#?#", "#$#"]
+        .iter()
+        .any(|marker| line.contains(marker))
+    {
+        return ParsedLine::Unsupported;
+    }
+    let (exception, body) = match line.strip_prefix("@@") {
+        Some(body) => (true, body),
+        None => (false, line),
+    };
+    let (pattern, options) = match body.rfind('$') {
+        Some(index) => (&body[..index], Some(&body[index + 1..])),
+        None => (body, None),
+    };
+    match parse_rule(pattern, options) {
+        Some(rule) if exception => ParsedLine::Exception(rule),
+        Some(rule) => ParsedLine::Block(rule),
+        None => ParsedLine::Unsupported,
+    }
+}
+
+fn parse_rule(pattern: &str, options: Option<&str>) -> Option<Rule> {
+    if pattern.len() > 1 && pattern.starts_with('/') && pattern.ends_with('/') {
+        return None;
+    }
+    let pattern = pattern.to_ascii_lowercase();
+    let (mut anchor, rest) = if let Some(rest) = pattern.strip_prefix("||") {
+        (Anchor::Host, rest)
+    } else if let Some(rest) = pattern.strip_prefix('|') {
+        (Anchor::Start, rest)
+    } else {
+        (Anchor::None, pattern.as_str())
+    };
+    let (mut end_anchor, rest) = match rest.strip_suffix('|') {
+        Some(rest) => (true, rest),
+        None => (false, rest),
+    };
+    if rest.starts_with('*') {
+        anchor = Anchor::None;
+    }
+    if rest.ends_with('*') {
+        end_anchor = false;
+    }
+    if rest.contains('|') {
+        return None;
+    }
+    let segments: Vec<Vec<u8>> = rest
+        .split('*')
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| segment.as_bytes().to_vec())
+        .collect();
+    if segments.is_empty() {
+        return None;
+    }
+
+    let mut rule = Rule {
+        anchor,
+        segments,
+        end_anchor,
+        third_party: None,
+        resource_types: 0,
+        include_domains: Vec::new(),
+        exclude_domains: Vec::new(),
+    };
+    let mut excluded_types = 0;
+    for option in options.into_iter().flat_map(|options| options.split(',')) {
+        let option = option.trim().to_ascii_lowercase();
+        let (negated, name) = match option.strip_prefix('~') {
+            Some(name) => (true, name),
+            None => (false, option.as_str()),
+        };
+        match name {
+            "third-party" | "3p" => rule.third_party = Some(!negated),
+            "first-party" | "1p" => rule.third_party = Some(negated),
+            _ if !negated && name.starts_with("domain=") => {
+                for domain in name["domain=".len()..].split('|') {
+                    match domain.strip_prefix('~') {
+                        Some(domain) => rule.exclude_domains.push(domain.to_owned()),
+                        None => rule.include_domains.push(domain.to_owned()),
+                    }
+                }
+            }
+            _ => {
+                let resource_type = ResourceType::from_option(name)?;
+                if negated {
+                    excluded_types |= resource_type.mask();
+                } else {
+                    rule.resource_types |= resource_type.mask();
+                }
+            }
+        }
+    }
+    if excluded_types != 0 {
+        if rule.resource_types == 0 {
+            rule.resource_types = !0;
+        }
+        rule.resource_types &= !excluded_types;
+    }
+    Some(rule)
+}
+
+// Matches `segment` at `pos`, returning where it ends. `^` matches one
+// separator character or the end of the URL.
+fn segment_at(url: &[u8], pos: usize, segment: &[u8]) -> Option<usize> {
+    let mut at = pos;
+    for (index, &expected) in segment.iter().enumerate() {
+        if expected == b'^' {
+            if at == url.len() {
+                return segment[index..]
+                    .iter()
+                    .all(|&rest| rest == b'^')
+                    .then_some(at);
+            }
+            if !is_separator(url[at]) {
+                return None;
+            }
+        } else if url.get(at) != Some(&expected) {
+            return None;
+        }
+        at += 1;
+    }
+    Some(at)
+}
+
+fn is_token_char(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || byte == b'%'
+}
+
+fn is_separator(byte: u8) -> bool {
+    !(byte.is_ascii_alphanumeric() || matches!(byte, b'_' | b'-' | b'.' | b'%'))
+}
+
+fn host_in(host: &str, domains: &[String]) -> bool {
+    domains.iter().any(|domain| {
+        host == domain
+            || host
+                .strip_suffix(domain.as_str())
+                .is_some_and(|prefix| prefix.ends_with('.'))
+    })
+}
+
+// Approximates the public suffix list well enough to tell first- from
+// third-party requests without shipping it.
+fn registrable_domain(host: &str) -> &str {
+    if host.parse::<std::net::IpAddr>().is_ok() {
+        return host;
+    }
+    let labels: Vec<&str> = host.rsplitn(4, '.').collect();
+    let keep = match labels.as_slice() {
+        [tld, second, _, ..] if tld.len() == 2 && SHARED_SECOND_LEVEL_LABELS.contains(second) => 3,
+        _ => 2,
+    };
+    match labels.get(keep) {
+        Some(_) => {
+            let suffix_len: usize = labels[..keep].iter().map(|label| label.len() + 1).sum();
+            &host[host.len() + 1 - suffix_len..]
+        }
+        None => host,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::history::profile_setting_key;
+    use crate::state::{BrowserState, SettingValue};
+
+    use super::{
+        registrable_domain, FilterDecision, FilterEngine, FilterRequest, ProfileContentFilter,
+        RequestFilter, ResourceType, CONTENT_FILTER_ALLOWLIST_PROFILE_PREFIX,
+        CONTENT_FILTER_ENABLED_PROFILE_PREFIX,
+    };
+
+    const FIXTURE: &str = include_str!("../tests/fixtures/filter_list.txt");
+
+    fn request<'a>(
+        url: &'a str,
+        source_url: Option<&'a str>,
+        resource_type: ResourceType,
+    ) -> FilterRequest<'a> {
+        FilterRequest {
+            url,
+            source_url,
+            resource_type,
+        }
+    }
+
+    #[test]
+    fn fixture_list_parses_supported_rules_and_skips_the_rest() {
+        let engine = FilterEngine::parse(FIXTURE);
+        assert_eq!(engine.rule_count(), 17);
+        assert_eq!(engine.skipped_count(), 5);
+    }
+
+    #[test]
+    fn fixture_list_blocks_and_allows_expected_requests() {
+        let engine = FilterEngine::parse(FIXTURE);
+        let news = Some("https://news.example/story");
+        let cases = [
+            (
+                "https://ads.example.net/banner.js",
+                news,
+                ResourceType::Script,
+                true,
+            ),
+            (
+                "https://cdn.ads.example.net/x.png",
+                news,
+                ResourceType::Image,
+                true,
+            ),
+            (
+                "https://notads.example.net/x.png",
+                news,
+                ResourceType::Image,
+                false,
+            ),
+            (
+                "https://news.example/ads/top.gif",
+                news,
+                ResourceType::Image,
+                true,
+            ),
+            (
+                "https://news.example/uploads/top.gif",
+                news,
+                ResourceType::Image,
+                false,
+            ),
+            (
+                "https://metrics.example.org/collect?id=1",
+                news,
+                ResourceType::Xhr,
+                true,
+            ),
+            (
+                "https://metrics.example.org/collect/extra",
+                news,
+                ResourceType::Xhr,
+                false,
+            ),
+            (
+                "https://widgets.example.com/w.js",
+                news,
+                ResourceType::Script,
+                true,
+            ),
+            // First-party use of a third-party-only rule.
+            (
+                "https://widgets.example.com/w.js",
+                Some("https://www.example.com/"),
+                ResourceType::Script,
+                false,
+            ),
+            (
+                "https://track.example.com/p.gif",
+                news,
+                ResourceType::Image,
+                true,
+            ),
+            (
+                "https://track.example.com/p.js",
+                news,
+                ResourceType::Script,
+                false,
+            ),
+            // `$domain=` scoping.
+            (
+                "https://cdn.example/promo.js",
+                news,
+                ResourceType::Script,
+                true,
+            ),
+            (
+                "https://cdn.example/promo.js",
+                Some("https://shop.example/"),
+                ResourceType::Script,
+                false,
+            ),
+            (
+                "https://cdn.example/promo.js",
+                Some("https://m.news.example/"),
+                ResourceType::Script,
+                true,
+            ),
+            (
+                "https://cdn.example/promo.js",
+                Some("https://live.news.example/"),
+                ResourceType::Script,
+                false,
+            ),
+            // Exceptions win over blocks.
+            (
+                "https://ads.example.net/consent/cmp.js",
+                news,
+                ResourceType::Script,
+                false,
+            ),
+            (
+                "https://news.example/ads/allowed/logo.png",
+                news,
+                ResourceType::Image,
+                false,
+            ),
+            // `|` start anchors and wildcards.
+            (
+                "http://popunder.example/go",
+                news,
+                ResourceType::Subdocument,
+                true,
+            ),
+            (
+                "https://popunder.example/go",
+                news,
+                ResourceType::Subdocument,
+                false,
+            ),
+            (
+                "https://img.example/pixel/1x1/spacer.gif",
+                news,
+                ResourceType::Image,
+                true,
+            ),
+            // `~type` negation.
+            ("https://beacon.example/b", news, ResourceType::Ping, true),
+            (
+                "https://beacon.example/b",
+                news,
+                ResourceType::Script,
+                false,
+            ),
+        ];
+        for (url, source, resource_type, blocked) in cases {
+            let expected = if blocked {
+                FilterDecision::Block
+            } else {
+                FilterDecision::Allow
+            };
+            assert_eq!(
+                engine.check(&request(url, source, resource_type)),
+                expected,
+                "url {url:?} from {source:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn profile_filter_respects_disable_and_site_allowlist() {
+        let engine = Arc::new(FilterEngine::parse("||ads.example.net^"));
+        let mut state = BrowserState::default();
+        let filtered = state.add_profile("Filtered");
+        let disabled = state.add_profile("Disabled");
+        state.settings.insert(
+            profile_setting_key(CONTENT_FILTER_ENABLED_PROFILE_PREFIX, disabled),
+            SettingValue::Bool(false),
+        );
+        state.settings.insert(
+            profile_setting_key(CONTENT_FILTER_ALLOWLIST_PROFILE_PREFIX, filtered),
+            SettingValue::Text("friendly.example".to_owned()),
+        );
+
+        assert!(ProfileContentFilter::for_profile(&state, disabled, &engine).is_none());
+        let filter = ProfileContentFilter::for_profile(&state, filtered, &engine)
+            .expect("filter should be enabled by default");
+        let ad = "https://ads.example.net/a.js";
+        assert_eq!(
+            filter.check(&request(
+                ad,
+                Some("https://news.example/"),
+                ResourceType::Script
+            )),
+            FilterDecision::Block
+        );
+        assert_eq!(
+            filter.check(&request(
+                ad,
+                Some("https://www.friendly.example/"),
+                ResourceType::Script
+            )),
+            FilterDecision::Allow
+        );
+    }
+
+    #[test]
+    fn registrable_domain_handles_country_code_second_levels() {
+        let cases = [
+            ("example.com", "example.com"),
+            ("a.b.example.com", "example.com"),
+            ("news.bbc.co.uk", "bbc.co.uk"),
+            ("localhost", "localhost"),
+            ("10.0.0.1", "10.0.0.1"),
+        ];
+        for (host, expected) in cases {
+            assert_eq!(registrable_domain(host), expected, "host {host:?}");
+        }
+    }
+}
@@ -0,0 +1,195 @@
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use crate::history::profile_setting_key;
+use crate::ids::{DownloadId, ProfileId, TabId};
+use crate::state::{BrowserState, SettingValue};
+
+// Text setting holding the directory new downloads are saved to. Unset or
+// blank falls back to the platform default chosen by the app.
+pub const DOWNLOAD_DIRECTORY_PROFILE_PREFIX: &str = "downloads.directory.profile.";
+
+const FALLBACK_FILE_NAME: &str = "download";
+const MAX_FILE_NAME_CHARS: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DownloadState {
+    InProgress,
+    Paused,
+    Complete,
+    Failed,
+    Cancelled,
+}
+
+impl DownloadState {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::InProgress => "in_progress",
+            Self::Paused => "paused",
+            Self::Complete => "complete",
+            Self::Failed => "failed",
+            Self::Cancelled => "cancelled",
+        }
+    }
+
+    pub fn parse(input: &str) -> Option<Self> {
+        match input.trim().to_ascii_lowercase().as_str() {
+            "in_progress" => Some(Self::InProgress),
+            "paused" => Some(Self::Paused),
+            "complete" => Some(Self::Complete),
+            "failed" => Some(Self::Failed),
+            "cancelled" => Some(Self::Cancelled),
+            _ => None,
+        }
+    }
+
+    // Finished downloads no longer change and are the only ones persisted.
+    pub fn is_finished(self) -> bool {
+        matches!(self, Self::Complete | Self::Failed | Self::Cancelled)
+    }
+}
+
+impl Display for DownloadState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Download {
+    pub id: DownloadId,
+    pub profile_id: ProfileId,
+    // Tab that started the download. It may since have been closed, and is
+    // not persisted.
+    pub tab_id: Option<TabId>,
+    pub url: String,
+    pub target_path: String,
+    pub received_bytes: u64,
+    // None while the server has not sent a length.
+    pub total_bytes: Option<u64>,
+    pub state: DownloadState,
+}
+
+// Transfer control the host has to carry out; emitted by the reducer and
+// applied by the runtime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DownloadCommand {
+    Pause,
+    Resume,
+    Cancel,
+}
+
+pub fn download_directory(state: &BrowserState, profile_id: ProfileId) -> Option<&str> {
+    match state.settings.get(&profile_setting_key(
+        DOWNLOAD_DIRECTORY_PROFILE_PREFIX,
+        profile_id,
+    )) {
+        Some(SettingValue::Text(directory)) if !directory.trim().is_empty() => {
+            Some(directory.trim())
+        }
+        _ => None,
+    }
+}
+
+// Picks a safe file name from the server's suggestion, falling back to the
+// last path segment of the URL. Separators, control characters and leading
+// dots are dropped so the name cannot leave the download directory.
+pub fn download_file_name(url: &str, suggested_name: Option<&str>) -> String {
+    let from_url = url
+        .split(['?', '#'])
+        .next()
+        .and_then(|path| path.rsplit('/').next());
+    [suggested_name, from_url]
+        .into_iter()
+        .flatten()
+        .map(sanitize_file_name)
+        .find(|name| !name.is_empty())
+        .unwrap_or_else(|| FALLBACK_FILE_NAME.to_owned())
+}
+
+fn sanitize_file_name(name: &str) -> String {
+    let cleaned: String = name
+        .chars()
+        .map(|ch| match ch {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            ch if ch.is_control() => '_',
+            ch => ch,
+        })
+        .take(MAX_FILE_NAME_CHARS)
+        .collect();
+    cleaned
+        .trim()
+        .trim_start_matches('.')
+        .trim_end_matches('.')
+        .trim()
+        .to_owned()
+}
+
+// `report.pdf`, then `report (1).pdf`, `report (2).pdf`, ... until `taken`
+// accepts one.
+pub fn unique_file_name(file_name: &str, mut taken: impl FnMut(&str) -> bool) -> String {
+    if !taken(file_name) {
+        return file_name.to_owned();
+    }
+    let (stem, extension) = match file_name.rfind('.') {
+        Some(dot) if dot > 0 => file_name.split_at(dot),
+        _ => (file_name, ""),
+    };
+    (1..)
+        .map(|counter| format!("{stem} ({counter}){extension}"))
+        .find(|candidate| !taken(candidate))
+        .expect("counter space is unbounded")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{download_file_name, unique_file_name, DownloadState};
+
+    #[test]
+    fn file_names_come_from_suggestion_or_url_and_stay_in_directory() {
+        let cases = [
+            (
+                "https://cdn.example/files/report.pdf?x=1",
+                None,
+                "report.pdf",
+            ),
+            (
+                "https://cdn.example/files/report.pdf",
+                Some("Q3 report.pdf"),
+                "Q3 report.pdf",
+            ),
+            (
+                "https://cdn.example/a",
+                Some("../../etc/passwd"),
+                "_.._etc_passwd",
+            ),
+            ("https://cdn.example/a", Some(".hidden"), "hidden"),
+            ("https://cdn.example/", Some("  "), "download"),
+            ("https://cdn.example/dir/", None, "download"),
+        ];
+        for (url, suggested, expected) in cases {
+            assert_eq!(
+                download_file_name(url, suggested),
+                expected,
+                "{url} {suggested:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn unique_names_count_up_before_the_extension() {
+        let existing = ["report.pdf", "report (1).pdf", "archive"];
+        let taken = |name: &str| existing.contains(&name);
+        assert_eq!(unique_file_name("notes.txt", taken), "notes.txt");
+        assert_eq!(unique_file_name("report.pdf", taken), "report (2).pdf");
+        assert_eq!(unique_file_name("archive", taken), "archive (1)");
+        for state in [
+            DownloadState::InProgress,
+            DownloadState::Paused,
+            DownloadState::Complete,
+            DownloadState::Failed,
+            DownloadState::Cancelled,
+        ] {
+            assert_eq!(DownloadState::parse(state.as_str()), Some(state));
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use std::convert::Infallible;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use crate::intent::Intent;
+use crate::lifecycle::{LifecyclePolicy, WarmLruPolicy};
 use crate::patch::{Patch, Snapshot};
 use crate::reducer::{apply_intent, ReduceError};
 use crate::state::BrowserState;
@@ -22,35 +24,83 @@ impl Persistence for NoopPersistence {
     }
 }
 
+// Source of the timestamps the reducer records, in milliseconds since the
+// Unix epoch. Tests swap in a clock they can move by hand.
+pub trait Clock {
+    fn now_ms(&self) -> i64;
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now_ms(&self) -> i64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_millis() as i64)
+            .unwrap_or(0)
+    }
+}
+
 #[derive(Debug)]
 pub enum EngineError<E> {
     Reduce(ReduceError),
     Persist(E),
 }
 
-pub struct Engine<P: Persistence> {
+pub struct Engine<P: Persistence, L: LifecyclePolicy = WarmLruPolicy, C: Clock = SystemClock> {
     state: BrowserState,
     revision: u64,
     persistence: P,
+    policy: L,
+    clock: C,
 }
 
 impl<P: Persistence> Engine<P> {
     pub fn new(persistence: P) -> Self {
-        Self {
-            state: BrowserState::default(),
-            revision: 0,
-            persistence,
-        }
+        Self::with_state(persistence, BrowserState::default(), 0)
     }
 
     pub fn with_state(persistence: P, state: BrowserState, revision: u64) -> Self {
+        Self::with_policy(persistence, state, revision, WarmLruPolicy)
+    }
+}
+
+impl<P: Persistence, L: LifecyclePolicy> Engine<P, L> {
+    pub fn with_policy(persistence: P, state: BrowserState, revision: u64, policy: L) -> Self {
         Self {
             state,
             revision,
             persistence,
+            policy,
+            clock: SystemClock,
+        }
+    }
+}
+
+impl<P: Persistence, L: LifecyclePolicy, C: Clock> Engine<P, L, C> {
+    pub fn with_clock<D: Clock>(self, clock: D) -> Engine<P, L, D> {
+        Engine {
+            state: self.state,
+            revision: self.revision,
+            persistence: self.persistence,
+            policy: self.policy,
+            clock,
         }
     }
 
+    pub fn persistence(&self) -> &P {
+        &self.persistence
+    }
+
+    pub fn policy(&self) -> &L {
+        &self.policy
+    }
+
+    pub fn clock(&self) -> &C {
+        &self.clock
+    }
+
     pub fn revision(&self) -> u64 {
         self.revision
     }
@@ -68,7 +118,9 @@ impl<P: Persistence> Engine<P> {
 
     pub fn dispatch(&mut self, intent: Intent) -> Result<Patch, EngineError<P::Error>> {
         let from_revision = self.revision;
-        let ops = apply_intent(&mut self.state, intent).map_err(EngineError::Reduce)?;
+        let now_ms = self.clock.now_ms();
+        let ops = apply_intent(&mut self.state, intent, &self.policy, now_ms)
+            .map_err(EngineError::Reduce)?;
 
         // Contract: write to persistence before emitting the resulting patch.
         self.persistence
@@ -92,22 +144,61 @@ impl<P: Persistence> Engine<P> {
 
 #[cfg(test)]
 mod tests {
-    use std::collections::BTreeSet;
+    use std::collections::{BTreeMap, BTreeSet};
 
     use crate::ids::TabId;
     use crate::ids::{ProfileId, WorkspaceId};
     use crate::patch::PatchOp;
-    use crate::{BrowserState, Intent, NoopPersistence, SettingValue, TabRuntimeState};
+    use crate::site_settings::{site_permission, site_permissions};
+    use crate::{
+        BrowserState, DownloadCommand, DownloadState, HistoryClearScope, Intent, NoopPersistence,
+        PendingPermission, PermissionDecision, PermissionKind, PermissionRequestId,
+        PermissionSetting, SettingValue, SitePermission, TabRuntimeState, TabStatus,
+        WindowDisposition,
+    };
 
     use super::{Engine, EngineError};
+    use crate::lifecycle::{
+        startup_restore_mode, startup_restore_tabs, LifecycleContext, LifecyclePolicy,
+        MemoryPressureLevel, PrewarmPolicy, StartupRestoreMode, WarmLruPolicy,
+    };
+    use crate::Clock;
 
     fn seeded_engine() -> (Engine<NoopPersistence>, crate::ids::WorkspaceId) {
+        seeded_engine_with_policy(WarmLruPolicy)
+    }
+
+    fn seeded_engine_with_policy<L: LifecyclePolicy>(
+        policy: L,
+    ) -> (Engine<NoopPersistence, L>, crate::ids::WorkspaceId) {
         let mut state = BrowserState::default();
         let profile_id = state.add_profile("Default");
         let workspace_id = state
             .add_workspace(profile_id, "Main")
             .expect("profile should exist");
-        (Engine::with_state(NoopPersistence, state, 0), workspace_id)
+        (
+            Engine::with_policy(NoopPersistence, state, 0, policy),
+            workspace_id,
+        )
+    }
+
+    // Keeps pinned tabs of the active profile warm on top of the LRU pool.
+    struct PinnedStayWarm;
+
+    impl LifecyclePolicy for PinnedStayWarm {
+        fn desired_states(
+            &self,
+            state: &BrowserState,
+            context: &LifecycleContext,
+        ) -> BTreeMap<TabId, TabRuntimeState> {
+            let mut desired = WarmLruPolicy.desired_states(state, context);
+            for tab in state.tabs.values() {
+                if tab.pinned && Some(tab.profile_id) == context.active_profile_id {
+                    desired.entry(tab.id).or_insert(TabRuntimeState::Warm);
+                }
+            }
+            desired
+        }
     }
 
     fn first_tab_id(
@@ -140,7 +231,21 @@ mod tests {
         }
     }
 
+    fn background_profile_warm_budget(state: &BrowserState) -> usize {
+        match state
+            .settings
+            .get("lifecycle.background_profile_warm_budget")
+        {
+            Some(SettingValue::Int(value)) => (*value).clamp(0, 32) as usize,
+            _ => 2,
+        }
+    }
+
     fn assert_lifecycle_invariants(state: &BrowserState) {
+        assert_lifecycle_invariants_with_warm_limit(state, warm_pool_budget(state));
+    }
+
+    fn assert_lifecycle_invariants_with_warm_limit(state: &BrowserState, warm_limit: usize) {
         if let Some(active_profile_id) = state.active_profile_id {
             assert!(
                 state.profiles.contains_key(&active_profile_id),
@@ -247,7 +352,7 @@ mod tests {
 
         let mut active_count = 0usize;
         let mut warm_count_active_profile = 0usize;
-        let warm_budget = warm_pool_budget(state);
+        let mut warm_count_background_profiles: BTreeMap<ProfileId, usize> = BTreeMap::new();
 
         for tab in state.tabs.values() {
             match tab.runtime_state {
@@ -265,13 +370,18 @@ mod tests {
                     );
                 }
                 TabRuntimeState::Warm => {
-                    assert_eq!(
-                        Some(tab.profile_id),
-                        active_profile_id,
-                        "warm tabs must belong to active profile"
-                    );
                     assert_ne!(Some(tab.id), active_tab_id, "active tab must not be Warm");
-                    warm_count_active_profile += 1;
+                    // Playing tabs stay warm outside every budget.
+                    if tab.audible && !tab.muted {
+                        continue;
+                    }
+                    if Some(tab.profile_id) == active_profile_id {
+                        warm_count_active_profile += 1;
+                    } else {
+                        *warm_count_background_profiles
+                            .entry(tab.profile_id)
+                            .or_default() += 1;
+                    }
                 }
                 TabRuntimeState::Discarded => {}
                 TabRuntimeState::Restoring => {
@@ -286,11 +396,19 @@ mod tests {
             "must have exactly one active tab for current active workspace/profile"
         );
         assert!(
-            warm_count_active_profile <= warm_budget,
-            "warm tab count {} exceeded budget {}",
+            warm_count_active_profile <= warm_limit,
+            "warm tab count {} exceeded limit {}",
             warm_count_active_profile,
-            warm_budget
+            warm_limit
         );
+        for (profile_id, warm_count) in warm_count_background_profiles {
+            assert!(
+                warm_count <= background_profile_warm_budget(state),
+                "background profile {:?} kept {} warm tabs",
+                profile_id,
+                warm_count
+            );
+        }
     }
 
     #[test]
@@ -606,7 +724,7 @@ mod tests {
     }
 
     #[test]
-    fn switching_profiles_discards_inactive_profile_tabs() {
+    fn switching_profiles_keeps_recent_background_tabs_warm() {
         let (mut engine, first_workspace_id) = seeded_engine();
         let first_profile_id = engine
             .state()
@@ -678,8 +796,8 @@ mod tests {
         assert_eq!(second_profile_tab.runtime_state, TabRuntimeState::Active);
         assert_eq!(
             first_profile_tab.runtime_state,
-            TabRuntimeState::Discarded,
-            "inactive profile tabs should be discarded"
+            TabRuntimeState::Warm,
+            "recent tabs of the background profile should stay warm"
         );
 
         engine
@@ -698,12 +816,104 @@ mod tests {
             .get(&second_profile_tab_id)
             .expect("second profile tab should still exist");
         assert_eq!(first_profile_tab.runtime_state, TabRuntimeState::Active);
-        assert_eq!(second_profile_tab.runtime_state, TabRuntimeState::Discarded);
+        assert_eq!(second_profile_tab.runtime_state, TabRuntimeState::Warm);
+
+        engine
+            .dispatch(Intent::SettingSet {
+                key: "lifecycle.background_profile_warm_budget".to_owned(),
+                value: SettingValue::Int(0),
+            })
+            .expect("background budget should be configured");
+        assert_eq!(
+            engine.state().tabs[&second_profile_tab_id].runtime_state,
+            TabRuntimeState::Discarded,
+            "a zero budget discards every background profile tab"
+        );
+        assert_lifecycle_invariants(engine.state());
     }
 
     #[test]
     fn lifecycle_policy_stress_under_profile_workspace_tab_churn() {
         let (mut engine, first_workspace_id) = seeded_engine();
+        churn_profiles_workspaces_and_tabs(
+            &mut engine,
+            first_workspace_id,
+            false,
+            &assert_lifecycle_invariants,
+        );
+    }
+
+    #[test]
+    fn custom_lifecycle_policy_keeps_pinned_tabs_warm_under_churn() {
+        let (mut engine, first_workspace_id) = seeded_engine_with_policy(PinnedStayWarm);
+        churn_profiles_workspaces_and_tabs(
+            &mut engine,
+            first_workspace_id,
+            true,
+            &|state: &BrowserState| {
+                let pinned: Vec<&crate::Tab> = state
+                    .tabs
+                    .values()
+                    .filter(|tab| tab.pinned && Some(tab.profile_id) == state.active_profile_id)
+                    .collect();
+                assert!(
+                    pinned
+                        .iter()
+                        .all(|tab| tab.runtime_state != TabRuntimeState::Discarded),
+                    "pinned tabs of the active profile must stay live"
+                );
+                assert_lifecycle_invariants_with_warm_limit(
+                    state,
+                    warm_pool_budget(state) + pinned.len(),
+                );
+            },
+        );
+
+        engine
+            .dispatch(Intent::SettingSet {
+                key: "warm_pool_budget".to_owned(),
+                value: SettingValue::Int(0),
+            })
+            .expect("warm pool budget should be configured");
+        let workspace_id = engine.state().profiles[&engine
+            .state()
+            .active_profile_id
+            .expect("a profile should be active")]
+            .active_workspace_id
+            .expect("active profile should have a workspace");
+        engine
+            .dispatch(Intent::NewTab {
+                workspace_id,
+                url: Some("https://pinned.example/".to_owned()),
+                make_active: false,
+            })
+            .expect("background tab creation should succeed");
+        let background_tab_id = *engine.state().workspaces[&workspace_id]
+            .tab_order
+            .last()
+            .expect("workspace should have the new tab");
+        assert_eq!(
+            engine.state().tabs[&background_tab_id].runtime_state,
+            TabRuntimeState::Discarded
+        );
+        engine
+            .dispatch(Intent::PinTab {
+                tab_id: background_tab_id,
+                pinned: true,
+            })
+            .expect("pin tab should succeed");
+        assert_eq!(
+            engine.state().tabs[&background_tab_id].runtime_state,
+            TabRuntimeState::Warm
+        );
+    }
+
+    fn churn_profiles_workspaces_and_tabs<L: LifecyclePolicy>(
+        engine: &mut Engine<NoopPersistence, L>,
+        first_workspace_id: WorkspaceId,
+        toggle_pins: bool,
+        assert_invariants: &dyn Fn(&BrowserState),
+    ) {
         let mut seed = 0x5A17_C0DE_D15C_AFE5u64;
         let first_profile_id = engine
             .state()
@@ -724,7 +934,7 @@ mod tests {
                 })
                 .expect("profile creation should succeed");
         }
-        assert_lifecycle_invariants(engine.state());
+        assert_invariants(engine.state());
 
         let profile_ids: Vec<ProfileId> = engine.state().profiles.keys().copied().collect();
         assert!(profile_ids.len() >= 3);
@@ -740,7 +950,7 @@ mod tests {
                     .expect("workspace creation should succeed");
             }
         }
-        assert_lifecycle_invariants(engine.state());
+        assert_invariants(engine.state());
 
         let all_workspace_ids: Vec<WorkspaceId> =
             engine.state().workspaces.keys().copied().collect();
@@ -763,11 +973,11 @@ mod tests {
                     })
                     .expect("background tab creation should succeed");
             }
-            assert_lifecycle_invariants(engine.state());
+            assert_invariants(engine.state());
         }
 
         for step in 0..1500usize {
-            let op = rand_index(&mut seed, 5);
+            let op = rand_index(&mut seed, if toggle_pins { 6 } else { 5 });
             match op {
                 0 => {
                     let profile_ids: Vec<ProfileId> =
@@ -807,7 +1017,7 @@ mod tests {
                         })
                         .expect("new tab should succeed");
                 }
-                _ => {
+                4 => {
                     let tab_ids: Vec<TabId> = engine.state().tabs.keys().copied().collect();
                     if !tab_ids.is_empty() {
                         let tab_id = tab_ids[rand_index(&mut seed, tab_ids.len())];
@@ -816,8 +1026,18 @@ mod tests {
                             .expect("close tab should succeed");
                     }
                 }
+                _ => {
+                    let tab_ids: Vec<TabId> = engine.state().tabs.keys().copied().collect();
+                    if !tab_ids.is_empty() {
+                        let tab_id = tab_ids[rand_index(&mut seed, tab_ids.len())];
+                        let pinned = !engine.state().tabs[&tab_id].pinned;
+                        engine
+                            .dispatch(Intent::PinTab { tab_id, pinned })
+                            .expect("pin tab should succeed");
+                    }
+                }
             }
-            assert_lifecycle_invariants(engine.state());
+            assert_invariants(engine.state());
         }
     }
 
@@ -972,4 +1192,1989 @@ mod tests {
             .expect("new tab should exist");
         assert_eq!(tab.url, "https://custom.example/path");
     }
+
+    #[test]
+    fn import_workspaces_remaps_ids_and_keeps_tabs_discarded() {
+        let (mut source, source_workspace_id) = seeded_engine();
+        for index in 0..150 {
+            source
+                .dispatch(Intent::NewTab {
+                    workspace_id: source_workspace_id,
+                    url: Some(format!("https://example.com/{index}")),
+                    make_active: index == 3,
+                })
+                .expect("tab should be created");
+        }
+        let pinned_tab_id = first_tab_id(&source, source_workspace_id);
+        source
+            .dispatch(Intent::PinTab {
+                tab_id: pinned_tab_id,
+                pinned: true,
+            })
+            .expect("pin should succeed");
+        let source_profile_id = source
+            .state()
+            .active_profile_id
+            .expect("profile should be active");
+        let bundle = crate::portable::export_profile(source.state(), source_profile_id)
+            .expect("export should succeed");
+        let bundle = crate::PortableBundle::from_json(&bundle.to_json())
+            .expect("exported bundle should parse");
+        assert_eq!(bundle.tab_count(), 150);
+
+        let (mut target, target_workspace_id) = seeded_engine();
+        target
+            .dispatch(Intent::NewTab {
+                workspace_id: target_workspace_id,
+                url: Some("https://already-open.example".to_owned()),
+                make_active: true,
+            })
+            .expect("tab should be created");
+        let target_profile_id = target
+            .state()
+            .active_profile_id
+            .expect("profile should be active");
+        let existing_tab_ids: BTreeSet<TabId> = target.state().tabs.keys().copied().collect();
+
+        let patch = target
+            .dispatch(Intent::ImportWorkspaces {
+                profile_id: target_profile_id,
+                bundle,
+            })
+            .expect("import should succeed");
+        assert_eq!(patch.to_revision, patch.from_revision + 1);
+
+        let state = target.state();
+        let profile = state
+            .profiles
+            .get(&target_profile_id)
+            .expect("profile should exist");
+        assert_eq!(profile.workspace_order.len(), 2);
+        assert_eq!(profile.active_workspace_id, Some(target_workspace_id));
+        let imported_workspace_id = profile.workspace_order[1];
+        assert_ne!(imported_workspace_id, target_workspace_id);
+        let imported = state
+            .workspaces
+            .get(&imported_workspace_id)
+            .expect("imported workspace should exist");
+        assert_eq!(imported.name, "Main");
+        assert_eq!(imported.tab_order.len(), 150);
+        assert_eq!(imported.active_tab_id, Some(imported.tab_order[3]));
+
+        for (index, tab_id) in imported.tab_order.iter().enumerate() {
+            assert!(!existing_tab_ids.contains(tab_id));
+            let tab = state.tabs.get(tab_id).expect("imported tab should exist");
+            assert_eq!(tab.url, format!("https://example.com/{index}"));
+            assert_eq!(tab.profile_id, target_profile_id);
+            assert_eq!(tab.workspace_id, imported_workspace_id);
+            assert_eq!(tab.pinned, index == 0);
+            assert_eq!(tab.runtime_state, TabRuntimeState::Discarded);
+        }
+        assert_lifecycle_invariants(state);
+    }
+
+    #[test]
+    fn export_workspace_emits_bundle_for_the_runtime() {
+        let (mut engine, workspace_id) = seeded_engine();
+        engine
+            .dispatch(Intent::NewTab {
+                workspace_id,
+                url: Some("https://export.example".to_owned()),
+                make_active: true,
+            })
+            .expect("tab should be created");
+        let expected = crate::portable::export_workspace(engine.state(), workspace_id)
+            .expect("export should succeed");
+
+        let patch = engine
+            .dispatch(Intent::ExportWorkspace {
+                workspace_id,
+                path: "/tmp/workspace.json".to_owned(),
+            })
+            .expect("export intent should succeed");
+        assert_eq!(
+            patch.ops,
+            vec![PatchOp::WorkspacesExported {
+                path: "/tmp/workspace.json".to_owned(),
+                bundle: expected,
+            }]
+        );
+
+        let result = engine.dispatch(Intent::ExportWorkspace {
+            workspace_id: WorkspaceId(99),
+            path: "/tmp/missing.json".to_owned(),
+        });
+        assert!(matches!(
+            result,
+            Err(EngineError::Reduce(crate::ReduceError::WorkspaceNotFound(
+                WorkspaceId(99)
+            )))
+        ));
+    }
+
+    #[test]
+    fn import_workspaces_into_missing_profile_fails() {
+        let (mut engine, workspace_id) = seeded_engine();
+        let bundle = crate::portable::export_workspace(engine.state(), workspace_id)
+            .expect("export should succeed");
+        let result = engine.dispatch(Intent::ImportWorkspaces {
+            profile_id: ProfileId(99),
+            bundle,
+        });
+        assert!(matches!(
+            result,
+            Err(EngineError::Reduce(crate::ReduceError::ProfileNotFound(
+                ProfileId(99)
+            )))
+        ));
+    }
+
+    fn bookmark_positions(
+        state: &BrowserState,
+        profile_id: ProfileId,
+        folder_id: Option<crate::BookmarkFolderId>,
+    ) -> Vec<(String, usize)> {
+        state
+            .bookmarks_in_folder(profile_id, folder_id)
+            .into_iter()
+            .map(|bookmark| (bookmark.title.clone(), bookmark.position))
+            .collect()
+    }
+
+    #[test]
+    fn bookmarks_keep_dense_positions_across_moves_and_removals() {
+        let (mut engine, _) = seeded_engine();
+        let profile_id = engine
+            .state()
+            .active_profile_id
+            .expect("profile should be active");
+        engine
+            .dispatch(Intent::CreateBookmarkFolder {
+                profile_id,
+                parent_id: None,
+                name: "Reading".to_owned(),
+            })
+            .expect("folder should be created");
+        let folder_id = *engine
+            .state()
+            .bookmark_folders
+            .keys()
+            .next()
+            .expect("folder should exist");
+        for title in ["a", "b", "c"] {
+            engine
+                .dispatch(Intent::AddBookmark {
+                    profile_id,
+                    folder_id: None,
+                    url: format!("https://{title}.example"),
+                    title: title.to_owned(),
+                })
+                .expect("bookmark should be added");
+        }
+        let bookmark_b = engine
+            .state()
+            .bookmarks
+            .values()
+            .find(|bookmark| bookmark.title == "b")
+            .map(|bookmark| bookmark.id)
+            .expect("bookmark b should exist");
+
+        let patch = engine
+            .dispatch(Intent::MoveBookmark {
+                bookmark_id: bookmark_b,
+                folder_id: Some(folder_id),
+                index: 0,
+            })
+            .expect("move should succeed");
+        assert!(patch.ops.iter().any(|op| matches!(
+            op,
+            PatchOp::UpsertBookmark(bookmark) if bookmark.id == bookmark_b
+        )));
+        assert_eq!(
+            bookmark_positions(engine.state(), profile_id, None),
+            vec![("a".to_owned(), 0), ("c".to_owned(), 1)]
+        );
+        assert_eq!(
+            bookmark_positions(engine.state(), profile_id, Some(folder_id)),
+            vec![("b".to_owned(), 0)]
+        );
+
+        let bookmark_c = engine
+            .state()
+            .bookmarks
+            .values()
+            .find(|bookmark| bookmark.title == "c")
+            .map(|bookmark| bookmark.id)
+            .expect("bookmark c should exist");
+        engine
+            .dispatch(Intent::MoveBookmark {
+                bookmark_id: bookmark_c,
+                folder_id: None,
+                index: 0,
+            })
+            .expect("reorder should succeed");
+        engine
+            .dispatch(Intent::RenameBookmark {
+                bookmark_id: bookmark_c,
+                title: "c2".to_owned(),
+            })
+            .expect("rename should succeed");
+        assert_eq!(
+            bookmark_positions(engine.state(), profile_id, None),
+            vec![("c2".to_owned(), 0), ("a".to_owned(), 1)]
+        );
+
+        engine
+            .dispatch(Intent::RemoveBookmark {
+                bookmark_id: bookmark_c,
+            })
+            .expect("remove should succeed");
+        assert_eq!(
+            bookmark_positions(engine.state(), profile_id, None),
+            vec![("a".to_owned(), 0)]
+        );
+    }
+
+    #[test]
+    fn bookmark_folders_are_profile_scoped_and_removed_recursively() {
+        let (mut engine, _) = seeded_engine();
+        let profile_id = engine
+            .state()
+            .active_profile_id
+            .expect("profile should be active");
+        engine
+            .dispatch(Intent::CreateBookmarkFolder {
+                profile_id,
+                parent_id: None,
+                name: "Outer".to_owned(),
+            })
+            .expect("outer folder should be created");
+        let outer_id = *engine
+            .state()
+            .bookmark_folders
+            .keys()
+            .next_back()
+            .expect("outer folder should exist");
+        engine
+            .dispatch(Intent::CreateBookmarkFolder {
+                profile_id,
+                parent_id: Some(outer_id),
+                name: "Inner".to_owned(),
+            })
+            .expect("inner folder should be created");
+        let inner_id = *engine
+            .state()
+            .bookmark_folders
+            .keys()
+            .next_back()
+            .expect("inner folder should exist");
+        engine
+            .dispatch(Intent::AddBookmark {
+                profile_id,
+                folder_id: Some(inner_id),
+                url: "https://inner.example".to_owned(),
+                title: "Inner link".to_owned(),
+            })
+            .expect("bookmark should be added");
+
+        engine
+            .dispatch(Intent::NewProfile {
+                name: "Other".to_owned(),
+            })
+            .expect("profile should be created");
+        let other_profile_id = engine
+            .state()
+            .active_profile_id
+            .expect("new profile should be active");
+        let result = engine.dispatch(Intent::AddBookmark {
+            profile_id: other_profile_id,
+            folder_id: Some(inner_id),
+            url: "https://leak.example".to_owned(),
+            title: "Leak".to_owned(),
+        });
+        assert!(matches!(
+            result,
+            Err(EngineError::Reduce(
+                crate::ReduceError::BookmarkFolderProfileMismatch { .. }
+            ))
+        ));
+
+        engine
+            .dispatch(Intent::RemoveBookmarkFolder {
+                folder_id: outer_id,
+            })
+            .expect("folder removal should succeed");
+        assert!(engine.state().bookmark_folders.is_empty());
+        assert!(engine.state().bookmarks.is_empty());
+    }
+
+    #[test]
+    fn open_bookmark_folder_as_workspace_creates_discarded_tabs() {
+        let (mut engine, workspace_id) = seeded_engine();
+        engine
+            .dispatch(Intent::NewTab {
+                workspace_id,
+                url: Some("https://current.example".to_owned()),
+                make_active: true,
+            })
+            .expect("tab should be created");
+        let profile_id = engine
+            .state()
+            .active_profile_id
+            .expect("profile should be active");
+        engine
+            .dispatch(Intent::CreateBookmarkFolder {
+                profile_id,
+                parent_id: None,
+                name: "Sprint".to_owned(),
+            })
+            .expect("folder should be created");
+        let folder_id = *engine
+            .state()
+            .bookmark_folders
+            .keys()
+            .next()
+            .expect("folder should exist");
+        engine
+            .dispatch(Intent::CreateBookmarkFolder {
+                profile_id,
+                parent_id: Some(folder_id),
+                name: "Nested".to_owned(),
+            })
+            .expect("nested folder should be created");
+        let nested_id = *engine
+            .state()
+            .bookmark_folders
+            .keys()
+            .next_back()
+            .expect("nested folder should exist");
+        for (folder, url) in [
+            (nested_id, "https://nested.example"),
+            (folder_id, "https://board.example"),
+            (folder_id, "https://spec.example"),
+        ] {
+            engine
+                .dispatch(Intent::AddBookmark {
+                    profile_id,
+                    folder_id: Some(folder),
+                    url: url.to_owned(),
+                    title: String::new(),
+                })
+                .expect("bookmark should be added");
+        }
+
+        engine
+            .dispatch(Intent::OpenBookmarkFolderAsWorkspace { folder_id })
+            .expect("folder should open as workspace");
+
+        let state = engine.state();
+        let profile = state
+            .profiles
+            .get(&profile_id)
+            .expect("profile should exist");
+        assert_eq!(profile.active_workspace_id, Some(workspace_id));
+        let opened = state
+            .workspaces
+            .get(
+                profile
+                    .workspace_order
+                    .last()
+                    .expect("workspace should exist"),
+            )
+            .expect("opened workspace should exist");
+        assert_eq!(opened.name, "Sprint");
+        let urls: Vec<_> = opened
+            .tab_order
+            .iter()
+            .map(|tab_id| {
+                let tab = state.tabs.get(tab_id).expect("tab should exist");
+                assert_eq!(tab.runtime_state, TabRuntimeState::Discarded);
+                tab.url.as_str()
+            })
+            .collect();
+        assert_eq!(
+            urls,
+            vec![
+                "https://board.example",
+                "https://spec.example",
+                "https://nested.example"
+            ]
+        );
+        assert_lifecycle_invariants(state);
+    }
+
+    #[test]
+    fn deleting_profile_drops_its_bookmarks() {
+        let (mut engine, _) = seeded_engine();
+        let first_profile_id = engine
+            .state()
+            .active_profile_id
+            .expect("profile should be active");
+        engine
+            .dispatch(Intent::NewProfile {
+                name: "Second".to_owned(),
+            })
+            .expect("profile should be created");
+        engine
+            .dispatch(Intent::AddBookmark {
+                profile_id: first_profile_id,
+                folder_id: None,
+                url: "https://first.example".to_owned(),
+                title: "First".to_owned(),
+            })
+            .expect("bookmark should be added");
+
+        let patch = engine
+            .dispatch(Intent::DeleteProfile {
+                profile_id: first_profile_id,
+            })
+            .expect("profile should be deleted");
+        assert!(patch
+            .ops
+            .iter()
+            .any(|op| matches!(op, PatchOp::RemoveBookmark { .. })));
+        assert!(patch.ops.iter().any(|op| matches!(
+            op,
+            PatchOp::HistoryCleared {
+                profile_id,
+                scope: HistoryClearScope::All,
+            } if *profile_id == first_profile_id
+        )));
+        assert!(engine.state().bookmarks.is_empty());
+    }
+
+    #[test]
+    fn clear_history_validates_scope_and_emits_op() {
+        let mut state = BrowserState::default();
+        let profile_id = state.add_profile("Default");
+        let mut engine = Engine::with_state(NoopPersistence, state, 0);
+
+        let revision = engine.revision();
+        let result = engine.dispatch(Intent::ClearHistory {
+            profile_id,
+            scope: HistoryClearScope::TimeRange {
+                start_ms: 20,
+                end_ms: 10,
+            },
+        });
+        assert!(matches!(
+            result,
+            Err(EngineError::Reduce(
+                crate::ReduceError::InvalidHistoryScope(_)
+            ))
+        ));
+        assert_eq!(engine.revision(), revision);
+
+        let patch = engine
+            .dispatch(Intent::ClearHistory {
+                profile_id,
+                scope: HistoryClearScope::Domain("example.com".to_owned()),
+            })
+            .expect("valid clear should dispatch");
+        assert_eq!(
+            patch.ops,
+            vec![PatchOp::HistoryCleared {
+                profile_id,
+                scope: HistoryClearScope::Domain("example.com".to_owned()),
+            }]
+        );
+    }
+
+    #[test]
+    fn search_engines_are_seeded_per_profile_and_keep_one_default() {
+        let (mut engine, _) = seeded_engine();
+        let patch = engine
+            .dispatch(Intent::NewProfile {
+                name: "Work".to_owned(),
+            })
+            .expect("profile should be created");
+        let profile_id = engine
+            .state()
+            .active_profile_id
+            .expect("new profile should be active");
+        let seeded = patch
+            .ops
+            .iter()
+            .filter(|op| matches!(op, PatchOp::UpsertSearchEngine(_)))
+            .count();
+        assert_eq!(seeded, crate::search::BUILTIN_SEARCH_ENGINES.len());
+
+        let duplicate = engine.dispatch(Intent::AddSearchEngine {
+            profile_id,
+            name: "Gists".to_owned(),
+            keyword: " GH ".to_owned(),
+            url_template: "https://gist.github.com/search?q={searchTerms}".to_owned(),
+            make_default: false,
+        });
+        assert!(matches!(
+            duplicate,
+            Err(EngineError::Reduce(
+                crate::ReduceError::DuplicateSearchKeyword { .. }
+            ))
+        ));
+
+        engine
+            .dispatch(Intent::AddSearchEngine {
+                profile_id,
+                name: "Docs".to_owned(),
+                keyword: "D".to_owned(),
+                url_template: "https://docs.rs/releases/search?query={searchTerms}".to_owned(),
+                make_default: true,
+            })
+            .expect("custom engine should be added");
+        let docs = crate::search::find_by_keyword(engine.state(), profile_id, "d")
+            .expect("keyword should be normalized")
+            .clone();
+        let default = crate::search::default_search_engine(engine.state(), profile_id)
+            .expect("profile should have a default");
+        assert_eq!(default.id, docs.id);
+        assert_eq!(
+            engine
+                .state()
+                .search_engines
+                .values()
+                .filter(|engine| engine.profile_id == profile_id && engine.is_default)
+                .count(),
+            1
+        );
+
+        let removal = engine.dispatch(Intent::RemoveSearchEngine { engine_id: docs.id });
+        assert!(matches!(
+            removal,
+            Err(EngineError::Reduce(
+                crate::ReduceError::CannotRemoveDefaultSearchEngine(_)
+            ))
+        ));
+        let google = crate::search::find_by_keyword(engine.state(), profile_id, "google")
+            .expect("builtin should exist")
+            .id;
+        let builtin_removal = engine.dispatch(Intent::RemoveSearchEngine { engine_id: google });
+        assert!(matches!(
+            builtin_removal,
+            Err(EngineError::Reduce(
+                crate::ReduceError::CannotRemoveBuiltinSearchEngine(_)
+            ))
+        ));
+
+        engine
+            .dispatch(Intent::SetDefaultSearchEngine { engine_id: google })
+            .expect("default should move back");
+        engine
+            .dispatch(Intent::RemoveSearchEngine { engine_id: docs.id })
+            .expect("non-default custom engine should be removable");
+        assert!(!engine.state().search_engines.contains_key(&docs.id));
+
+        let patch = engine
+            .dispatch(Intent::DeleteProfile { profile_id })
+            .expect("profile should be deleted");
+        assert!(patch.ops.iter().any(
+            |op| matches!(op, PatchOp::RemoveSearchEngine { engine_id, .. } if *engine_id == google)
+        ));
+        assert!(engine
+            .state()
+            .search_engines
+            .values()
+            .all(|engine| engine.profile_id != profile_id));
+    }
+
+    #[test]
+    fn navigation_urls_are_validated_and_normalized() {
+        let (mut engine, workspace_id) = seeded_engine();
+        engine
+            .dispatch(Intent::NewTab {
+                workspace_id,
+                url: Some("HTTPS://Example.COM:443/a".to_owned()),
+                make_active: true,
+            })
+            .expect("web url should open");
+        let tab_id = first_tab_id(&engine, workspace_id);
+        assert_eq!(
+            engine
+                .state()
+                .tabs
+                .get(&tab_id)
+                .expect("tab should exist")
+                .url,
+            "https://example.com/a"
+        );
+
+        for url in ["javascript:alert(1)", "file:///etc/passwd", "example.com"] {
+            let revision = engine.revision();
+            let result = engine.dispatch(Intent::Navigate {
+                tab_id,
+                url: url.to_owned(),
+            });
+            assert!(
+                matches!(
+                    result,
+                    Err(EngineError::Reduce(crate::ReduceError::NavigationBlocked(
+                        _
+                    )))
+                ),
+                "{url} should be rejected"
+            );
+            assert_eq!(engine.revision(), revision);
+        }
+    }
+
+    #[test]
+    fn profile_domain_rules_block_navigation_and_content_redirects() {
+        let (mut engine, workspace_id) = seeded_engine();
+        let profile_id = *engine
+            .state()
+            .profiles
+            .keys()
+            .next()
+            .expect("profile should exist");
+        engine
+            .dispatch(Intent::SettingSet {
+                key: format!("navigation.blocked_domains.profile.{}", profile_id.0),
+                value: crate::state::SettingValue::Text("*.ads.test, tracker.test".to_owned()),
+            })
+            .expect("setting should apply");
+        let blocked = engine.dispatch(Intent::NewTab {
+            workspace_id,
+            url: Some("https://cdn.ads.test/x".to_owned()),
+            make_active: true,
+        });
+        assert!(matches!(
+            blocked,
+            Err(EngineError::Reduce(crate::ReduceError::NavigationBlocked(ref b)))
+                if b.reason.as_str() == "domain"
+        ));
+
+        engine
+            .dispatch(Intent::NewTab {
+                workspace_id,
+                url: Some("https://example.com/".to_owned()),
+                make_active: true,
+            })
+            .expect("unlisted domain should open");
+        let tab_id = first_tab_id(&engine, workspace_id);
+        engine
+            .dispatch(Intent::ObserveTabUrl {
+                tab_id,
+                url: "https://www.tracker.test/pixel".to_owned(),
+            })
+            .expect("content redirect should be recorded as blocked");
+        let tab = engine.state().tabs.get(&tab_id).expect("tab should exist");
+        assert_eq!(tab.url, "https://example.com/");
+        let blocked = tab
+            .blocked_navigation
+            .as_ref()
+            .expect("tab should show the blocked page");
+        assert_eq!(blocked.url, "https://www.tracker.test/pixel");
+
+        // The blocked page loads about:blank, which must not clear the state.
+        let revision = engine.revision();
+        engine
+            .dispatch(Intent::ObserveTabUrl {
+                tab_id,
+                url: "about:blank".to_owned(),
+            })
+            .expect("blank page should be observed");
+        assert_eq!(engine.revision(), revision);
+
+        engine
+            .dispatch(Intent::Navigate {
+                tab_id,
+                url: "https://example.com/".to_owned(),
+            })
+            .expect("navigating away should clear the blocked page");
+        let tab = engine.state().tabs.get(&tab_id).expect("tab should exist");
+        assert_eq!(tab.blocked_navigation, None);
+    }
+
+    #[test]
+    fn https_only_upgrades_and_falls_back_through_site_exceptions() {
+        let (mut engine, workspace_id) = seeded_engine();
+        let profile_id = *engine
+            .state()
+            .profiles
+            .keys()
+            .next()
+            .expect("profile should exist");
+        engine
+            .dispatch(Intent::SettingSet {
+                key: format!("navigation.https_only.profile.{}", profile_id.0),
+                value: crate::state::SettingValue::Bool(true),
+            })
+            .expect("setting should apply");
+        engine
+            .dispatch(Intent::NewTab {
+                workspace_id,
+                url: Some("http://legacy.example/login".to_owned()),
+                make_active: true,
+            })
+            .expect("http url should open upgraded");
+        let tab_id = first_tab_id(&engine, workspace_id);
+        let tab = engine.state().tabs.get(&tab_id).expect("tab should exist");
+        assert_eq!(tab.url, "https://legacy.example/login");
+
+        assert!(matches!(
+            engine.dispatch(Intent::ContinueInsecure { tab_id }),
+            Err(EngineError::Reduce(crate::ReduceError::NoInsecureFallback(id))) if id == tab_id
+        ));
+        engine
+            .dispatch(Intent::ObserveTabLoadFailed {
+                tab_id,
+                url: "https://legacy.example/login".to_owned(),
+                error_code: -107,
+            })
+            .expect("load failure should apply");
+        let blocked = engine.state().tabs[&tab_id]
+            .blocked_navigation
+            .clone()
+            .expect("tab should show the https interstitial");
+        assert_eq!(blocked.url, "http://legacy.example/login");
+        assert_eq!(blocked.reason.as_str(), "https_unavailable");
+
+        engine
+            .dispatch(Intent::ContinueInsecure { tab_id })
+            .expect("continuing should succeed");
+        let tab = &engine.state().tabs[&tab_id];
+        assert_eq!(tab.url, "http://legacy.example/login");
+        assert_eq!(tab.blocked_navigation, None);
+        assert!(engine.state().https_exceptions[&profile_id].contains("legacy.example"));
+
+        engine
+            .dispatch(Intent::Navigate {
+                tab_id,
+                url: "http://legacy.example/home".to_owned(),
+            })
+            .expect("excepted host should stay on http");
+        assert_eq!(
+            engine.state().tabs[&tab_id].url,
+            "http://legacy.example/home"
+        );
+
+        engine
+            .dispatch(Intent::RemoveHttpsException {
+                profile_id,
+                host: "LEGACY.example".to_owned(),
+            })
+            .expect("exception should be removable");
+        assert!(engine.state().https_exceptions.is_empty());
+        assert!(matches!(
+            engine.dispatch(Intent::AddHttpsException {
+                profile_id,
+                host: "https://bad/".to_owned(),
+            }),
+            Err(EngineError::Reduce(crate::ReduceError::InvalidUrl(_)))
+        ));
+    }
+
+    #[test]
+    fn site_permissions_are_stored_per_origin_and_settle_pending_prompts() {
+        let (mut engine, workspace_id) = seeded_engine();
+        let profile_id = *engine
+            .state()
+            .profiles
+            .keys()
+            .next()
+            .expect("profile should exist");
+        engine
+            .dispatch(Intent::NewTab {
+                workspace_id,
+                url: Some("https://meet.example/room".to_owned()),
+                make_active: true,
+            })
+            .expect("tab should open");
+        let tab_id = first_tab_id(&engine, workspace_id);
+
+        assert_eq!(
+            site_permission(
+                engine.state(),
+                profile_id,
+                "https://meet.example",
+                PermissionKind::Camera
+            ),
+            PermissionSetting::Ask
+        );
+        for _ in 0..2 {
+            engine
+                .dispatch(Intent::ObservePermissionRequest {
+                    tab_id,
+                    request_id: PermissionRequestId(1),
+                    origin: "https://meet.example/room".to_owned(),
+                    kind: PermissionKind::Camera,
+                })
+                .expect("request should be recorded");
+        }
+        let patch = engine
+            .dispatch(Intent::ObservePermissionRequest {
+                tab_id,
+                request_id: PermissionRequestId(2),
+                origin: "https://meet.example".to_owned(),
+                kind: PermissionKind::Popups,
+            })
+            .expect("blocked-by-default kinds should not prompt");
+        assert_eq!(
+            patch.ops,
+            vec![PatchOp::PermissionRequestSettled {
+                tab_id,
+                request_id: PermissionRequestId(2),
+                granted: false,
+            }]
+        );
+        assert_eq!(
+            engine.state().tabs[&tab_id].pending_permissions,
+            vec![PendingPermission {
+                request_id: PermissionRequestId(1),
+                origin: "https://meet.example".to_owned(),
+                kind: PermissionKind::Camera,
+            }]
+        );
+
+        let patch = engine
+            .dispatch(Intent::SetSitePermission {
+                profile_id,
+                origin: "HTTPS://Meet.Example:443/".to_owned(),
+                kind: PermissionKind::Camera,
+                setting: PermissionSetting::Allow,
+            })
+            .expect("setting should store");
+        assert!(patch.ops.iter().any(|op| matches!(
+            op,
+            PatchOp::UpsertSitePermission { origin, setting: PermissionSetting::Allow, .. }
+                if origin == "https://meet.example"
+        )));
+        assert!(patch.ops.contains(&PatchOp::PermissionRequestSettled {
+            tab_id,
+            request_id: PermissionRequestId(1),
+            granted: true,
+        }));
+        assert!(engine.state().tabs[&tab_id].pending_permissions.is_empty());
+        assert_eq!(
+            site_permission(
+                engine.state(),
+                profile_id,
+                "https://meet.example",
+                PermissionKind::Camera
+            ),
+            PermissionSetting::Allow
+        );
+        engine
+            .dispatch(Intent::SetSitePermission {
+                profile_id,
+                origin: "https://meet.example".to_owned(),
+                kind: PermissionKind::JavaScript,
+                setting: PermissionSetting::Block,
+            })
+            .expect("setting should store");
+        assert_eq!(
+            site_permissions(engine.state(), profile_id, Some("https://meet.example")).len(),
+            2
+        );
+
+        engine
+            .dispatch(Intent::ResetSitePermission {
+                profile_id,
+                origin: "https://meet.example".to_owned(),
+                kind: Some(PermissionKind::Camera),
+            })
+            .expect("reset should apply");
+        assert_eq!(
+            site_permissions(engine.state(), profile_id, None),
+            vec![SitePermission {
+                origin: "https://meet.example".to_owned(),
+                kind: PermissionKind::JavaScript,
+                setting: PermissionSetting::Block,
+            }]
+        );
+        engine
+            .dispatch(Intent::ResetSitePermission {
+                profile_id,
+                origin: "https://meet.example".to_owned(),
+                kind: None,
+            })
+            .expect("reset should apply");
+        assert!(engine.state().site_permissions.is_empty());
+        assert!(matches!(
+            engine.dispatch(Intent::SetSitePermission {
+                profile_id,
+                origin: "meet.example".to_owned(),
+                kind: PermissionKind::Camera,
+                setting: PermissionSetting::Allow,
+            }),
+            Err(EngineError::Reduce(crate::ReduceError::InvalidUrl(_)))
+        ));
+    }
+
+    #[test]
+    fn pending_permissions_resolve_once_or_remember_and_deny_on_close() {
+        let (mut engine, workspace_id) = seeded_engine();
+        let profile_id = *engine
+            .state()
+            .profiles
+            .keys()
+            .next()
+            .expect("profile should exist");
+        for url in ["https://one.example/", "https://two.example/"] {
+            engine
+                .dispatch(Intent::NewTab {
+                    workspace_id,
+                    url: Some(url.to_owned()),
+                    make_active: true,
+                })
+                .expect("tab should open");
+        }
+        let tab_order = engine.state().workspaces[&workspace_id].tab_order.clone();
+        let (first_tab, second_tab) = (tab_order[0], tab_order[1]);
+        let request = |engine: &mut Engine<NoopPersistence>, tab_id, id, origin: &str| {
+            engine
+                .dispatch(Intent::ObservePermissionRequest {
+                    tab_id,
+                    request_id: PermissionRequestId(id),
+                    origin: origin.to_owned(),
+                    kind: PermissionKind::Microphone,
+                })
+                .expect("request should be recorded")
+        };
+
+        request(&mut engine, second_tab, 1, "https://two.example");
+        request(&mut engine, second_tab, 2, "https://two.example");
+        let patch = engine
+            .dispatch(Intent::ResolvePermission {
+                request_id: PermissionRequestId(1),
+                decision: PermissionDecision::Deny,
+                remember: false,
+            })
+            .expect("request should resolve");
+        assert!(patch.ops.contains(&PatchOp::PermissionRequestSettled {
+            tab_id: second_tab,
+            request_id: PermissionRequestId(1),
+            granted: false,
+        }));
+        assert!(engine.state().site_permissions.is_empty());
+        assert_eq!(
+            engine.state().tabs[&second_tab].pending_permissions.len(),
+            1
+        );
+        assert!(matches!(
+            engine.dispatch(Intent::ResolvePermission {
+                request_id: PermissionRequestId(1),
+                decision: PermissionDecision::Allow,
+                remember: false,
+            }),
+            Err(EngineError::Reduce(
+                crate::ReduceError::PermissionRequestNotFound(PermissionRequestId(1))
+            ))
+        ));
+
+        let patch = engine
+            .dispatch(Intent::ResolvePermission {
+                request_id: PermissionRequestId(2),
+                decision: PermissionDecision::Allow,
+                remember: true,
+            })
+            .expect("request should resolve");
+        assert!(patch.ops.contains(&PatchOp::PermissionRequestSettled {
+            tab_id: second_tab,
+            request_id: PermissionRequestId(2),
+            granted: true,
+        }));
+        assert_eq!(
+            site_permission(
+                engine.state(),
+                profile_id,
+                "https://two.example",
+                PermissionKind::Microphone
+            ),
+            PermissionSetting::Allow
+        );
+
+        request(&mut engine, first_tab, 3, "https://one.example");
+        let patch = engine
+            .dispatch(Intent::DiscardTab { tab_id: first_tab })
+            .expect("background tab should discard");
+        assert!(patch.ops.contains(&PatchOp::PermissionRequestSettled {
+            tab_id: first_tab,
+            request_id: PermissionRequestId(3),
+            granted: false,
+        }));
+        assert!(engine.state().tabs[&first_tab]
+            .pending_permissions
+            .is_empty());
+        let patch = request(&mut engine, first_tab, 4, "https://one.example");
+        assert_eq!(
+            patch.ops,
+            vec![PatchOp::PermissionRequestSettled {
+                tab_id: first_tab,
+                request_id: PermissionRequestId(4),
+                granted: false,
+            }]
+        );
+
+        request(&mut engine, second_tab, 5, "https://three.example");
+        let patch = engine
+            .dispatch(Intent::CloseTab { tab_id: second_tab })
+            .expect("tab should close");
+        assert!(patch.ops.contains(&PatchOp::PermissionRequestSettled {
+            tab_id: second_tab,
+            request_id: PermissionRequestId(5),
+            granted: false,
+        }));
+    }
+
+    #[test]
+    fn downloads_track_progress_and_issue_host_commands() {
+        let (mut engine, workspace_id) = seeded_engine();
+        engine
+            .dispatch(Intent::NewTab {
+                workspace_id,
+                url: Some("https://files.example/".to_owned()),
+                make_active: true,
+            })
+            .expect("tab should open");
+        let tab_id = first_tab_id(&engine, workspace_id);
+        let start = |engine: &mut Engine<NoopPersistence>, name: &str| {
+            engine
+                .dispatch(Intent::ObserveDownloadStarted {
+                    tab_id,
+                    url: format!("https://files.example/{name}"),
+                    target_path: format!("/downloads/{name}"),
+                    total_bytes: None,
+                })
+                .expect("download should start");
+            *engine
+                .state()
+                .downloads
+                .keys()
+                .next_back()
+                .expect("download should be recorded")
+        };
+        let first = start(&mut engine, "a.zip");
+        let second = start(&mut engine, "b.zip");
+        assert_ne!(first, second);
+
+        engine
+            .dispatch(Intent::ObserveDownloadProgress {
+                download_id: first,
+                received_bytes: 40,
+                total_bytes: Some(100),
+                state: DownloadState::InProgress,
+            })
+            .expect("progress should apply");
+        let patch = engine
+            .dispatch(Intent::PauseDownload { download_id: first })
+            .expect("download should pause");
+        assert!(patch.ops.contains(&PatchOp::DownloadCommandIssued {
+            download_id: first,
+            command: DownloadCommand::Pause,
+        }));
+        let download = &engine.state().downloads[&first];
+        assert_eq!(
+            (
+                download.state,
+                download.received_bytes,
+                download.total_bytes
+            ),
+            (DownloadState::Paused, 40, Some(100))
+        );
+        assert!(engine
+            .dispatch(Intent::PauseDownload { download_id: first })
+            .expect("pausing twice is a no-op")
+            .ops
+            .is_empty());
+        engine
+            .dispatch(Intent::ResumeDownload { download_id: first })
+            .expect("download should resume");
+        engine
+            .dispatch(Intent::ObserveDownloadProgress {
+                download_id: first,
+                received_bytes: 100,
+                total_bytes: None,
+                state: DownloadState::Complete,
+            })
+            .expect("completion should apply");
+        assert_eq!(engine.state().downloads[&first].total_bytes, Some(100));
+        assert_eq!(
+            engine
+                .dispatch(Intent::OpenDownloadFolder { download_id: first })
+                .expect("folder should open")
+                .ops,
+            vec![PatchOp::DownloadFolderOpened {
+                download_id: first,
+                target_path: "/downloads/a.zip".to_owned(),
+            }]
+        );
+        assert!(matches!(
+            engine.dispatch(Intent::CancelDownload { download_id: first }),
+            Err(EngineError::Reduce(
+                crate::ReduceError::InvalidDownloadTransition {
+                    state: DownloadState::Complete,
+                    ..
+                }
+            ))
+        ));
+
+        engine
+            .dispatch(Intent::CancelDownload {
+                download_id: second,
+            })
+            .expect("download should cancel");
+        let patch = engine
+            .dispatch(Intent::ObserveDownloadProgress {
+                download_id: second,
+                received_bytes: 10,
+                total_bytes: None,
+                state: DownloadState::InProgress,
+            })
+            .expect("late progress should be ignored");
+        assert!(patch.ops.is_empty());
+        assert_eq!(
+            engine.state().downloads[&second].state,
+            DownloadState::Cancelled
+        );
+
+        let third = start(&mut engine, "c.zip");
+        let patch = engine
+            .dispatch(Intent::RemoveDownload { download_id: third })
+            .expect("running download should be removable");
+        assert_eq!(
+            patch.ops,
+            vec![
+                PatchOp::DownloadCommandIssued {
+                    download_id: third,
+                    command: DownloadCommand::Cancel,
+                },
+                PatchOp::RemoveDownload {
+                    download_id: third,
+                    profile_id: engine.state().tabs[&tab_id].profile_id,
+                },
+            ]
+        );
+        assert!(matches!(
+            engine.dispatch(Intent::OpenDownloadFolder { download_id: third }),
+            Err(EngineError::Reduce(crate::ReduceError::DownloadNotFound(id))) if id == third
+        ));
+    }
+
+    #[test]
+    fn new_window_requests_open_after_opener_and_need_gesture_or_popup_permission() {
+        let (mut engine, workspace_id) = seeded_engine();
+        for url in ["https://news.example/", "https://other.example/"] {
+            engine
+                .dispatch(Intent::NewTab {
+                    workspace_id,
+                    url: Some(url.to_owned()),
+                    make_active: false,
+                })
+                .expect("tab should open");
+        }
+        let tab_order = engine.state().workspaces[&workspace_id].tab_order.clone();
+        let (opener, other) = (tab_order[0], tab_order[1]);
+        engine
+            .dispatch(Intent::ActivateTab { tab_id: opener })
+            .expect("opener should activate");
+        let request = |engine: &mut Engine<NoopPersistence>, url: &str, disposition, gesture| {
+            engine
+                .dispatch(Intent::ObserveNewWindowRequest {
+                    opener_tab_id: opener,
+                    url: url.to_owned(),
+                    disposition,
+                    user_gesture: gesture,
+                })
+                .expect("request should be handled")
+        };
+
+        request(
+            &mut engine,
+            "https://news.example/a",
+            WindowDisposition::BackgroundTab,
+            true,
+        );
+        request(
+            &mut engine,
+            "https://news.example/b",
+            WindowDisposition::BackgroundTab,
+            true,
+        );
+        request(
+            &mut engine,
+            "https://news.example/c",
+            WindowDisposition::Popup,
+            true,
+        );
+        let workspace = &engine.state().workspaces[&workspace_id];
+        let urls: Vec<&str> = workspace
+            .tab_order
+            .iter()
+            .map(|id| engine.state().tabs[id].url.as_str())
+            .collect();
+        assert_eq!(
+            urls,
+            vec![
+                "https://news.example/",
+                "https://news.example/a",
+                "https://news.example/b",
+                "https://news.example/c",
+                "https://other.example/",
+            ]
+        );
+        assert_eq!(workspace.tab_order[4], other);
+        let popup = workspace.tab_order[3];
+        assert_eq!(workspace.active_tab_id, Some(popup));
+        assert!(workspace.tab_order[1..4]
+            .iter()
+            .all(|id| engine.state().tabs[id].opener_tab_id == Some(opener)));
+        assert_eq!(
+            engine.state().tabs[&workspace.tab_order[1]].runtime_state,
+            TabRuntimeState::Discarded
+        );
+
+        let tab_count = engine.state().tabs.len();
+        let patch = request(
+            &mut engine,
+            "https://ads.example/",
+            WindowDisposition::Popup,
+            false,
+        );
+        assert_eq!(
+            patch.ops,
+            vec![PatchOp::NewWindowBlocked {
+                opener_tab_id: opener,
+                url: "https://ads.example/".to_owned(),
+            }]
+        );
+        assert_eq!(engine.state().tabs.len(), tab_count);
+
+        let profile_id = engine.state().tabs[&opener].profile_id;
+        engine
+            .dispatch(Intent::SetSitePermission {
+                profile_id,
+                origin: "https://news.example".to_owned(),
+                kind: PermissionKind::Popups,
+                setting: PermissionSetting::Allow,
+            })
+            .expect("setting should store");
+        request(
+            &mut engine,
+            "https://news.example/d",
+            WindowDisposition::Window,
+            false,
+        );
+        assert_eq!(engine.state().tabs.len(), tab_count + 1);
+    }
+
+    #[test]
+    fn load_failures_and_renderer_crashes_set_tab_status() {
+        let (mut engine, workspace_id) = seeded_engine();
+        engine
+            .dispatch(Intent::NewTab {
+                workspace_id,
+                url: Some("https://flaky.example/".to_owned()),
+                make_active: true,
+            })
+            .expect("tab should open");
+        let tab_id = first_tab_id(&engine, workspace_id);
+        let fail = |engine: &mut Engine<NoopPersistence>, error_code| {
+            engine
+                .dispatch(Intent::ObserveTabLoadFailed {
+                    tab_id,
+                    url: "https://flaky.example/".to_owned(),
+                    error_code,
+                })
+                .expect("failure should be observed")
+        };
+
+        assert!(fail(&mut engine, -3).ops.is_empty());
+        fail(&mut engine, -105);
+        assert_eq!(
+            engine.state().tabs[&tab_id].status,
+            TabStatus::LoadFailed {
+                url: "https://flaky.example/".to_owned(),
+                error_code: -105,
+            }
+        );
+        engine
+            .dispatch(Intent::ObserveTabLoading {
+                tab_id,
+                is_loading: true,
+            })
+            .expect("loading should be observed");
+        assert_eq!(engine.state().tabs[&tab_id].status, TabStatus::Ok);
+
+        engine
+            .dispatch(Intent::ObservePermissionRequest {
+                tab_id,
+                request_id: PermissionRequestId(7),
+                origin: "https://flaky.example".to_owned(),
+                kind: PermissionKind::Camera,
+            })
+            .expect("request should be recorded");
+        let patch = engine
+            .dispatch(Intent::ObserveRendererCrashed { tab_id })
+            .expect("crash should be observed");
+        assert!(patch.ops.contains(&PatchOp::PermissionRequestSettled {
+            tab_id,
+            request_id: PermissionRequestId(7),
+            granted: false,
+        }));
+        let tab = &engine.state().tabs[&tab_id];
+        assert_eq!(tab.status, TabStatus::Crashed);
+        assert!(!tab.loading && tab.pending_permissions.is_empty());
+        assert_eq!(tab.crash_count, 1);
+        assert!(fail(&mut engine, -105).ops.is_empty());
+
+        let patch = engine
+            .dispatch(Intent::ReloadTab { tab_id })
+            .expect("tab should reload");
+        assert!(patch.ops.contains(&PatchOp::TabReloaded { tab_id }));
+        assert_eq!(engine.state().tabs[&tab_id].status, TabStatus::Ok);
+        engine
+            .dispatch(Intent::ObserveRendererCrashed { tab_id })
+            .expect("crash should be observed");
+        assert_eq!(engine.state().tabs[&tab_id].crash_count, 2);
+        engine
+            .dispatch(Intent::Navigate {
+                tab_id,
+                url: "https://stable.example/".to_owned(),
+            })
+            .expect("navigation should succeed");
+        let tab = &engine.state().tabs[&tab_id];
+        assert_eq!((tab.status.clone(), tab.crash_count), (TabStatus::Ok, 0));
+
+        assert!(matches!(
+            engine.dispatch(Intent::ReloadTab { tab_id: TabId(999) }),
+            Err(EngineError::Reduce(crate::ReduceError::TabNotFound(TabId(
+                999
+            ))))
+        ));
+        let delays: Vec<Option<u64>> = (0..6).map(crate::state::crash_reload_delay_ms).collect();
+        assert_eq!(
+            delays,
+            vec![
+                None,
+                Some(2_000),
+                Some(4_000),
+                Some(8_000),
+                Some(16_000),
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn audible_tabs_stay_warm_outside_the_budget_until_silent_or_muted() {
+        let (mut engine, workspace_id) = seeded_engine();
+        engine
+            .dispatch(Intent::SettingSet {
+                key: "warm_pool_budget".to_owned(),
+                value: SettingValue::Int(1),
+            })
+            .expect("setting warm pool budget should succeed");
+        let music = engine
+            .dispatch(Intent::NewTab {
+                workspace_id,
+                url: Some("https://music.example/".to_owned()),
+                make_active: true,
+            })
+            .map(|_| first_tab_id(&engine, workspace_id))
+            .expect("tab should open");
+        engine
+            .dispatch(Intent::ObserveTabAudible {
+                tab_id: music,
+                audible: true,
+            })
+            .expect("audio should be observed");
+        for url in ["https://two.example/", "https://three.example/"] {
+            engine
+                .dispatch(Intent::NewTab {
+                    workspace_id,
+                    url: Some(url.to_owned()),
+                    make_active: true,
+                })
+                .expect("tab should open");
+        }
+        let runtime_state =
+            |engine: &Engine<NoopPersistence>, tab_id| engine.state().tabs[&tab_id].runtime_state;
+        let tab_order = engine.state().workspaces[&workspace_id].tab_order.clone();
+        let second = tab_order[1];
+        assert_eq!(runtime_state(&engine, music), TabRuntimeState::Warm);
+        assert_eq!(runtime_state(&engine, second), TabRuntimeState::Warm);
+
+        engine
+            .dispatch(Intent::SetTabMuted {
+                tab_id: music,
+                muted: true,
+            })
+            .expect("tab should mute");
+        let tab = &engine.state().tabs[&music];
+        assert!(tab.muted && !tab.audible);
+        assert_eq!(tab.runtime_state, TabRuntimeState::Discarded);
+
+        engine
+            .dispatch(Intent::SetWorkspaceMuted {
+                workspace_id,
+                muted: false,
+            })
+            .expect("workspace should unmute");
+        assert!(!engine.state().tabs[&music].muted);
+        let patch = engine
+            .dispatch(Intent::MuteOtherTabs { tab_id: second })
+            .expect("other tabs should mute");
+        let muted: Vec<bool> = tab_order
+            .iter()
+            .map(|tab_id| engine.state().tabs[tab_id].muted)
+            .collect();
+        assert_eq!(muted, vec![true, false, true]);
+        assert_eq!(patch.ops.len(), 2);
+        assert!(matches!(
+            engine.dispatch(Intent::SetWorkspaceMuted {
+                workspace_id: WorkspaceId(999),
+                muted: true,
+            }),
+            Err(EngineError::Reduce(crate::ReduceError::WorkspaceNotFound(
+                WorkspaceId(999)
+            )))
+        ));
+    }
+
+    #[test]
+    fn playing_tabs_of_other_profiles_stay_warm_after_switching() {
+        let (mut engine, workspace_id) = seeded_engine();
+        engine
+            .dispatch(Intent::SettingSet {
+                key: "lifecycle.background_profile_warm_budget".to_owned(),
+                value: SettingValue::Int(0),
+            })
+            .expect("background budget should be configured");
+        for url in ["https://music.example/", "https://docs.example/"] {
+            engine
+                .dispatch(Intent::NewTab {
+                    workspace_id,
+                    url: Some(url.to_owned()),
+                    make_active: true,
+                })
+                .expect("tab should open");
+        }
+        let tab_order = engine.state().workspaces[&workspace_id].tab_order.clone();
+        let (music, docs) = (tab_order[0], tab_order[1]);
+        engine
+            .dispatch(Intent::ObserveTabAudible {
+                tab_id: music,
+                audible: true,
+            })
+            .expect("audio should be observed");
+
+        engine
+            .dispatch(Intent::NewProfile {
+                name: "Work".to_owned(),
+            })
+            .expect("second profile should be created");
+        let runtime_state =
+            |engine: &Engine<NoopPersistence>, tab_id| engine.state().tabs[&tab_id].runtime_state;
+        assert_eq!(runtime_state(&engine, music), TabRuntimeState::Warm);
+        assert_eq!(runtime_state(&engine, docs), TabRuntimeState::Discarded);
+        assert_lifecycle_invariants(engine.state());
+
+        engine
+            .dispatch(Intent::SetTabMuted {
+                tab_id: music,
+                muted: true,
+            })
+            .expect("tab should mute");
+        assert_eq!(runtime_state(&engine, music), TabRuntimeState::Discarded);
+    }
+
+    #[test]
+    fn keep_alive_tabs_stay_warm_within_their_own_cap() {
+        let (mut engine, workspace_id) = seeded_engine();
+        for (key, value) in [("warm_pool_budget", 0), ("lifecycle.keep_alive_cap", 2)] {
+            engine
+                .dispatch(Intent::SettingSet {
+                    key: key.to_owned(),
+                    value: SettingValue::Int(value),
+                })
+                .expect("setting should apply");
+        }
+        for idx in 0..4 {
+            engine
+                .dispatch(Intent::NewTab {
+                    workspace_id,
+                    url: Some(format!("https://app-{idx}.example/")),
+                    make_active: true,
+                })
+                .expect("tab should open");
+        }
+        let tab_order = engine.state().workspaces[&workspace_id].tab_order.clone();
+        let (chat, mail, music) = (tab_order[0], tab_order[1], tab_order[2]);
+        for tab_id in [chat, mail] {
+            engine
+                .dispatch(Intent::SetTabKeepAlive {
+                    tab_id,
+                    keep_alive: true,
+                })
+                .expect("keep-alive should be set");
+            assert_eq!(
+                engine.state().tabs[&tab_id].runtime_state,
+                TabRuntimeState::Warm
+            );
+        }
+        assert_eq!(
+            engine.state().tabs[&music].runtime_state,
+            TabRuntimeState::Discarded
+        );
+        assert!(matches!(
+            engine.dispatch(Intent::SetTabKeepAlive {
+                tab_id: music,
+                keep_alive: true,
+            }),
+            Err(EngineError::Reduce(
+                crate::ReduceError::KeepAliveCapExceeded { cap: 2, .. }
+            ))
+        ));
+        assert!(!engine.state().tabs[&music].keep_alive);
+
+        engine
+            .dispatch(Intent::ActivateTab { tab_id: music })
+            .expect("tab should activate");
+        engine
+            .dispatch(Intent::ActivateTab { tab_id: chat })
+            .expect("tab should activate");
+        let runtime_state =
+            |engine: &Engine<NoopPersistence>, tab_id| engine.state().tabs[&tab_id].runtime_state;
+        assert_eq!(runtime_state(&engine, mail), TabRuntimeState::Warm);
+        assert_eq!(runtime_state(&engine, music), TabRuntimeState::Discarded);
+
+        engine
+            .dispatch(Intent::ActivateTab { tab_id: music })
+            .expect("tab should activate");
+        engine
+            .dispatch(Intent::SettingSet {
+                key: "lifecycle.keep_alive_cap".to_owned(),
+                value: SettingValue::Int(1),
+            })
+            .expect("setting should apply");
+        assert_eq!(runtime_state(&engine, chat), TabRuntimeState::Warm);
+        assert_eq!(runtime_state(&engine, mail), TabRuntimeState::Discarded);
+
+        engine
+            .dispatch(Intent::SetTabKeepAlive {
+                tab_id: chat,
+                keep_alive: false,
+            })
+            .expect("keep-alive should clear");
+        assert_eq!(runtime_state(&engine, chat), TabRuntimeState::Discarded);
+        assert_eq!(runtime_state(&engine, mail), TabRuntimeState::Warm);
+    }
+
+    #[test]
+    fn keep_alive_tabs_of_background_profiles_stay_warm_within_their_budget() {
+        let (mut engine, workspace_id) = seeded_engine();
+        engine
+            .dispatch(Intent::SettingSet {
+                key: "lifecycle.background_profile_warm_budget".to_owned(),
+                value: SettingValue::Int(1),
+            })
+            .expect("background budget should be configured");
+        for idx in 0..2 {
+            engine
+                .dispatch(Intent::NewTab {
+                    workspace_id,
+                    url: Some(format!("https://app-{idx}.example/")),
+                    make_active: true,
+                })
+                .expect("tab should open");
+        }
+        let tab_order = engine.state().workspaces[&workspace_id].tab_order.clone();
+        let (chat, recent) = (tab_order[0], tab_order[1]);
+        engine
+            .dispatch(Intent::SetTabKeepAlive {
+                tab_id: chat,
+                keep_alive: true,
+            })
+            .expect("keep-alive should be set");
+
+        engine
+            .dispatch(Intent::NewProfile {
+                name: "Work".to_owned(),
+            })
+            .expect("second profile should be created");
+        let runtime_state =
+            |engine: &Engine<NoopPersistence>, tab_id| engine.state().tabs[&tab_id].runtime_state;
+        assert_eq!(runtime_state(&engine, chat), TabRuntimeState::Warm);
+        assert_eq!(
+            runtime_state(&engine, recent),
+            TabRuntimeState::Discarded,
+            "the keep-alive tab spends the background profile's budget first"
+        );
+        assert_lifecycle_invariants(engine.state());
+    }
+
+    struct ManualClock(std::cell::Cell<i64>);
+
+    impl Clock for ManualClock {
+        fn now_ms(&self) -> i64 {
+            self.0.get()
+        }
+    }
+
+    #[test]
+    fn lifecycle_tick_discards_idle_warm_tabs_but_not_kept_or_playing_ones() {
+        let (engine, workspace_id) = seeded_engine();
+        let mut engine = engine.with_clock(ManualClock(std::cell::Cell::new(0)));
+        let advance_to = |engine: &Engine<NoopPersistence, WarmLruPolicy, ManualClock>,
+                          secs: i64| { engine.clock().0.set(secs * 1000) };
+        engine
+            .dispatch(Intent::SettingSet {
+                key: "lifecycle.warm_idle_timeout_secs".to_owned(),
+                value: SettingValue::Int(60),
+            })
+            .expect("setting should apply");
+        for idx in 0..5 {
+            advance_to(&engine, idx);
+            engine
+                .dispatch(Intent::NewTab {
+                    workspace_id,
+                    url: Some(format!("https://app-{idx}.example/")),
+                    make_active: true,
+                })
+                .expect("tab should open");
+        }
+        let tab_order = engine.state().workspaces[&workspace_id].tab_order.clone();
+        let (kept, playing, idle, recent, active) = (
+            tab_order[0],
+            tab_order[1],
+            tab_order[2],
+            tab_order[3],
+            tab_order[4],
+        );
+        engine
+            .dispatch(Intent::SetTabKeepAlive {
+                tab_id: kept,
+                keep_alive: true,
+            })
+            .expect("keep-alive should be set");
+        engine
+            .dispatch(Intent::ObserveTabAudible {
+                tab_id: playing,
+                audible: true,
+            })
+            .expect("audio should be observed");
+        advance_to(&engine, 30);
+        engine
+            .dispatch(Intent::ActivateTab { tab_id: recent })
+            .expect("tab should activate");
+        advance_to(&engine, 40);
+        engine
+            .dispatch(Intent::ActivateTab { tab_id: active })
+            .expect("tab should activate");
+        assert_eq!(
+            engine.state().tab_last_active_ms.get(&recent),
+            Some(&40_000)
+        );
+
+        let runtime_state = |engine: &Engine<NoopPersistence, WarmLruPolicy, ManualClock>,
+                             tab_id| {
+            engine.state().tabs[&tab_id].runtime_state
+        };
+        engine
+            .dispatch(Intent::LifecycleTick { now: 75_000 })
+            .expect("tick should apply");
+        assert_eq!(runtime_state(&engine, idle), TabRuntimeState::Discarded);
+        assert_eq!(runtime_state(&engine, recent), TabRuntimeState::Warm);
+        assert_eq!(runtime_state(&engine, kept), TabRuntimeState::Warm);
+        assert_eq!(runtime_state(&engine, playing), TabRuntimeState::Warm);
+
+        engine
+            .dispatch(Intent::LifecycleTick { now: 100_000 })
+            .expect("tick should apply");
+        assert_eq!(runtime_state(&engine, recent), TabRuntimeState::Discarded);
+        assert_eq!(runtime_state(&engine, active), TabRuntimeState::Active);
+        assert_eq!(
+            engine.state().tab_last_active_ms.get(&active),
+            Some(&100_000)
+        );
+
+        engine
+            .dispatch(Intent::SettingSet {
+                key: "lifecycle.warm_idle_timeout_secs".to_owned(),
+                value: SettingValue::Int(0),
+            })
+            .expect("setting should apply");
+        engine
+            .dispatch(Intent::ActivateTab { tab_id: idle })
+            .expect("tab should activate");
+        engine
+            .dispatch(Intent::LifecycleTick { now: 1_000_000 })
+            .expect("tick should apply");
+        assert_eq!(runtime_state(&engine, active), TabRuntimeState::Warm);
+    }
+
+    // Replays the same switch trace twenty minutes per step, so every tab the
+    // user leaves has gone idle by the time they come back to it, and counts
+    // the switches that landed on a discarded tab.
+    fn cold_restores_over_switch_trace<L: LifecyclePolicy>(policy: L, prewarm: bool) -> usize {
+        let (engine, first_workspace_id) = seeded_engine_with_policy(policy);
+        let mut engine = engine.with_clock(ManualClock(std::cell::Cell::new(0)));
+        let profile_id = engine
+            .state()
+            .active_profile_id
+            .expect("profile should exist");
+        engine
+            .dispatch(Intent::SettingSet {
+                key: "lifecycle.prewarm".to_owned(),
+                value: SettingValue::Bool(prewarm),
+            })
+            .expect("setting should apply");
+        for url in ["https://docs.example/", "https://chat.example/"] {
+            engine
+                .dispatch(Intent::NewTab {
+                    workspace_id: first_workspace_id,
+                    url: Some(url.to_owned()),
+                    make_active: true,
+                })
+                .expect("tab should open");
+        }
+        engine
+            .dispatch(Intent::NewWorkspace {
+                profile_id,
+                name: "Mail".to_owned(),
+            })
+            .expect("workspace should be created");
+        let mail_workspace_id = *engine.state().profiles[&profile_id]
+            .workspace_order
+            .last()
+            .expect("workspace should exist");
+        engine
+            .dispatch(Intent::NewTab {
+                workspace_id: mail_workspace_id,
+                url: Some("https://mail.example/".to_owned()),
+                make_active: true,
+            })
+            .expect("tab should open");
+        let docs_tab_order = engine.state().workspaces[&first_workspace_id]
+            .tab_order
+            .clone();
+        let (docs, chat) = (docs_tab_order[0], docs_tab_order[1]);
+
+        let mut cold_restores = 0;
+        for step in 0..24 {
+            let now = (step + 1) * 20 * 60 * 1000;
+            engine.clock().0.set(now);
+            engine
+                .dispatch(Intent::LifecycleTick { now })
+                .expect("tick should apply");
+            let intent = match step % 4 {
+                0 => Intent::SwitchWorkspace {
+                    workspace_id: first_workspace_id,
+                },
+                1 => Intent::ActivateTab { tab_id: docs },
+                2 => Intent::ActivateTab { tab_id: chat },
+                _ => Intent::SwitchWorkspace {
+                    workspace_id: mail_workspace_id,
+                },
+            };
+            let target = match &intent {
+                Intent::SwitchWorkspace { workspace_id } => engine.state().workspaces[workspace_id]
+                    .active_tab_id
+                    .expect("workspace should have an active tab"),
+                Intent::ActivateTab { tab_id } => *tab_id,
+                _ => unreachable!(),
+            };
+            if engine.state().tabs[&target].runtime_state == TabRuntimeState::Discarded {
+                cold_restores += 1;
+            }
+            engine.dispatch(intent).expect("switch should apply");
+            assert_lifecycle_invariants(engine.state());
+        }
+        cold_restores
+    }
+
+    #[test]
+    fn prewarming_learned_switches_avoids_cold_restores() {
+        let without_prewarm = cold_restores_over_switch_trace(WarmLruPolicy, true);
+        let with_prewarm = cold_restores_over_switch_trace(PrewarmPolicy::new(WarmLruPolicy), true);
+        let prewarm_disabled =
+            cold_restores_over_switch_trace(PrewarmPolicy::new(WarmLruPolicy), false);
+        assert_eq!(without_prewarm, 12);
+        assert_eq!(prewarm_disabled, without_prewarm);
+        assert!(with_prewarm <= 2, "{with_prewarm} cold restores");
+    }
+
+    #[test]
+    fn startup_restore_modes_pick_tabs_and_warm_tab_restores_them() {
+        let (mut engine, first_workspace_id) = seeded_engine();
+        let profile_id = engine
+            .state()
+            .active_profile_id
+            .expect("profile should exist");
+        engine
+            .dispatch(Intent::NewWorkspace {
+                profile_id,
+                name: "Reading".to_owned(),
+            })
+            .expect("workspace should be created");
+        let second_workspace_id = *engine.state().profiles[&profile_id]
+            .workspace_order
+            .last()
+            .expect("workspace should exist");
+        for (workspace_id, count) in [(second_workspace_id, 2), (first_workspace_id, 3)] {
+            for idx in 0..count {
+                engine
+                    .dispatch(Intent::NewTab {
+                        workspace_id,
+                        url: Some(format!("https://{}-{idx}.example/", workspace_id.0)),
+                        make_active: true,
+                    })
+                    .expect("tab should open");
+            }
+        }
+        let first_tabs = engine.state().workspaces[&first_workspace_id]
+            .tab_order
+            .clone();
+        let second_tabs = engine.state().workspaces[&second_workspace_id]
+            .tab_order
+            .clone();
+        for tab_id in [first_tabs[0], second_tabs[0]] {
+            engine
+                .dispatch(Intent::PinTab {
+                    tab_id,
+                    pinned: true,
+                })
+                .expect("tab should pin");
+        }
+        let set_text = |engine: &mut Engine<NoopPersistence>, key: &str, value: &str| {
+            engine
+                .dispatch(Intent::SettingSet {
+                    key: key.to_owned(),
+                    value: SettingValue::Text(value.to_owned()),
+                })
+                .expect("setting should apply");
+        };
+
+        assert_eq!(
+            startup_restore_mode(engine.state()),
+            StartupRestoreMode::Lazy
+        );
+        assert!(startup_restore_tabs(engine.state()).is_empty());
+        set_text(&mut engine, "startup.restore_mode", "pinned");
+        assert_eq!(
+            startup_restore_tabs(engine.state()),
+            vec![first_tabs[0], second_tabs[0]]
+        );
+        set_text(&mut engine, "startup.restore_mode", "workspace");
+        assert_eq!(
+            startup_restore_tabs(engine.state()),
+            vec![first_tabs[0], first_tabs[1]]
+        );
+        engine
+            .dispatch(Intent::SettingSet {
+                key: "warm_pool_budget".to_owned(),
+                value: SettingValue::Int(1),
+            })
+            .expect("budget should apply");
+        assert_eq!(startup_restore_tabs(engine.state()), vec![first_tabs[0]]);
+        set_text(&mut engine, "startup.restore_mode", "fresh");
+        assert!(startup_restore_tabs(engine.state()).is_empty());
+
+        assert_eq!(
+            engine.state().tabs[&first_tabs[0]].runtime_state,
+            TabRuntimeState::Discarded
+        );
+        engine
+            .dispatch(Intent::WarmTab {
+                tab_id: first_tabs[0],
+            })
+            .expect("tab should warm");
+        assert_eq!(
+            engine.state().tabs[&first_tabs[0]].runtime_state,
+            TabRuntimeState::Warm
+        );
+        assert_eq!(
+            engine.state().tabs[&first_tabs[1]].runtime_state,
+            TabRuntimeState::Discarded
+        );
+        assert_eq!(
+            engine.state().tabs[&first_tabs[2]].runtime_state,
+            TabRuntimeState::Active
+        );
+        assert_lifecycle_invariants(engine.state());
+        assert!(matches!(
+            engine.dispatch(Intent::WarmTab { tab_id: TabId(999) }),
+            Err(EngineError::Reduce(crate::ReduceError::TabNotFound(_)))
+        ));
+    }
+
+    #[test]
+    fn warm_pool_respects_the_byte_budget_and_memory_pressure() {
+        const MB: u64 = 1024 * 1024;
+        let (mut engine, workspace_id) = seeded_engine();
+        engine
+            .dispatch(Intent::SettingSet {
+                key: "lifecycle.warm_pool_bytes".to_owned(),
+                value: SettingValue::Int((300 * MB) as i64),
+            })
+            .expect("setting should apply");
+        for idx in 0..5 {
+            engine
+                .dispatch(Intent::NewTab {
+                    workspace_id,
+                    url: Some(format!("https://app-{idx}.example/")),
+                    make_active: true,
+                })
+                .expect("tab should open");
+        }
+        let tabs = engine.state().workspaces[&workspace_id].tab_order.clone();
+        let warm = |engine: &Engine<NoopPersistence>| -> Vec<TabId> {
+            tabs.iter()
+                .copied()
+                .filter(|tab_id| engine.state().tabs[tab_id].runtime_state == TabRuntimeState::Warm)
+                .collect()
+        };
+        assert_eq!(warm(&engine), tabs[..4].to_vec());
+
+        engine
+            .dispatch(Intent::ObserveTabMemory {
+                tab_id: tabs[3],
+                bytes: 200 * MB,
+            })
+            .expect("estimate should apply");
+        assert_eq!(warm(&engine), vec![tabs[2], tabs[3]]);
+        engine
+            .dispatch(Intent::ObserveTabMemory {
+                tab_id: tabs[2],
+                bytes: 250 * MB,
+            })
+            .expect("estimate should apply");
+        assert_eq!(warm(&engine), vec![tabs[1], tabs[3]]);
+
+        engine
+            .dispatch(Intent::MemoryPressure {
+                level: MemoryPressureLevel::Moderate,
+            })
+            .expect("pressure should apply");
+        assert_eq!(warm(&engine), vec![tabs[0], tabs[1]]);
+        engine
+            .dispatch(Intent::MemoryPressure {
+                level: MemoryPressureLevel::Critical,
+            })
+            .expect("pressure should apply");
+        assert!(warm(&engine).is_empty());
+        assert_eq!(
+            engine.state().tabs[&tabs[4]].runtime_state,
+            TabRuntimeState::Active
+        );
+        engine
+            .dispatch(Intent::MemoryPressure {
+                level: MemoryPressureLevel::Normal,
+            })
+            .expect("pressure should apply");
+        assert_eq!(warm(&engine), vec![tabs[1], tabs[3]]);
+
+        engine
+            .dispatch(Intent::CloseTab { tab_id: tabs[3] })
+            .expect("tab should close");
+        assert!(!engine.state().tab_memory_bytes.contains_key(&tabs[3]));
+        assert!(matches!(
+            engine.dispatch(Intent::ObserveTabMemory {
+                tab_id: tabs[3],
+                bytes: MB,
+            }),
+            Err(EngineError::Reduce(crate::ReduceError::TabNotFound(_)))
+        ));
+    }
 }
@@ -0,0 +1,63 @@
+use crate::ids::ProfileId;
+use crate::state::{BrowserState, SettingValue};
+
+pub const EPHEMERAL_PROFILE_SETTING_PREFIX: &str = "ephemeral.profile.";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VisitTransition {
+    Typed,
+    Link,
+    Reload,
+}
+
+impl VisitTransition {
+    pub fn as_i64(self) -> i64 {
+        match self {
+            Self::Typed => 0,
+            Self::Link => 1,
+            Self::Reload => 2,
+        }
+    }
+
+    pub fn from_i64(value: i64) -> Option<Self> {
+        match value {
+            0 => Some(Self::Typed),
+            1 => Some(Self::Link),
+            2 => Some(Self::Reload),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HistoryClearScope {
+    All,
+    // Half-open range in unix milliseconds: `start_ms <= visited_at < end_ms`.
+    TimeRange { start_ms: i64, end_ms: i64 },
+    // Matches the host and any of its subdomains.
+    Domain(String),
+}
+
+// One row per URL, aggregated over all of its visits in a profile.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryEntry {
+    pub url: String,
+    pub title: String,
+    pub visit_count: u64,
+    pub typed_count: u64,
+    pub last_visited_at_ms: i64,
+}
+
+pub fn profile_setting_key(prefix: &str, profile_id: ProfileId) -> String {
+    format!("{prefix}{}", profile_id.0)
+}
+
+pub fn is_ephemeral_profile(state: &BrowserState, profile_id: ProfileId) -> bool {
+    matches!(
+        state.settings.get(&profile_setting_key(
+            EPHEMERAL_PROFILE_SETTING_PREFIX,
+            profile_id
+        )),
+        Some(SettingValue::Bool(true))
+    )
+}
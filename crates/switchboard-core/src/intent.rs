@@ -1,4 +1,13 @@
-use crate::ids::{ProfileId, TabId, WorkspaceId};
+use crate::downloads::DownloadState;
+use crate::history::HistoryClearScope;
+use crate::ids::{
+    BookmarkFolderId, BookmarkId, DownloadId, PermissionRequestId, ProfileId, SearchEngineId,
+    TabId, WorkspaceId,
+};
+use crate::lifecycle::MemoryPressureLevel;
+use crate::navigation::WindowDisposition;
+use crate::portable::PortableBundle;
+use crate::site_settings::{PermissionDecision, PermissionKind, PermissionSetting};
 use crate::state::SettingValue;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -25,6 +34,41 @@ pub enum Intent {
         tab_id: TabId,
         is_loading: bool,
     },
+    ObserveTabLoadFailed {
+        tab_id: TabId,
+        url: String,
+        error_code: i32,
+    },
+    // Periodic nudge from the runtime so idle warm tabs get discarded even
+    // when nothing else happens. `now` is in milliseconds since the Unix
+    // epoch.
+    LifecycleTick {
+        now: i64,
+    },
+    // Restores a discarded tab in the background as if it had just been
+    // used, for as long as the lifecycle policy has room for it.
+    WarmTab {
+        tab_id: TabId,
+    },
+    // The host's estimate of the memory a live tab's renderer uses.
+    ObserveTabMemory {
+        tab_id: TabId,
+        bytes: u64,
+    },
+    MemoryPressure {
+        level: MemoryPressureLevel,
+    },
+    ObserveTabAudible {
+        tab_id: TabId,
+        audible: bool,
+    },
+    ObserveRendererCrashed {
+        tab_id: TabId,
+    },
+    ObserveTabFavicon {
+        tab_id: TabId,
+        favicon_key: Option<String>,
+    },
     ObserveTabThumbnail {
         tab_id: TabId,
         data_url: Option<String>,
@@ -47,6 +91,25 @@ pub enum Intent {
     CloseTab {
         tab_id: TabId,
     },
+    ReloadTab {
+        tab_id: TabId,
+    },
+    SetTabKeepAlive {
+        tab_id: TabId,
+        keep_alive: bool,
+    },
+    SetTabMuted {
+        tab_id: TabId,
+        muted: bool,
+    },
+    // Mutes every tab of the tab's profile except the tab itself.
+    MuteOtherTabs {
+        tab_id: TabId,
+    },
+    SetWorkspaceMuted {
+        workspace_id: WorkspaceId,
+        muted: bool,
+    },
     ActivateTab {
         tab_id: TabId,
     },
@@ -83,4 +146,151 @@ pub enum Intent {
         key: String,
         value: SettingValue,
     },
+    ExportWorkspace {
+        workspace_id: WorkspaceId,
+        path: String,
+    },
+    ExportProfile {
+        profile_id: ProfileId,
+        path: String,
+    },
+    ImportWorkspaces {
+        profile_id: ProfileId,
+        bundle: PortableBundle,
+    },
+    AddBookmark {
+        profile_id: ProfileId,
+        folder_id: Option<BookmarkFolderId>,
+        url: String,
+        title: String,
+    },
+    RemoveBookmark {
+        bookmark_id: BookmarkId,
+    },
+    MoveBookmark {
+        bookmark_id: BookmarkId,
+        folder_id: Option<BookmarkFolderId>,
+        index: usize,
+    },
+    RenameBookmark {
+        bookmark_id: BookmarkId,
+        title: String,
+    },
+    CreateBookmarkFolder {
+        profile_id: ProfileId,
+        parent_id: Option<BookmarkFolderId>,
+        name: String,
+    },
+    RenameBookmarkFolder {
+        folder_id: BookmarkFolderId,
+        name: String,
+    },
+    RemoveBookmarkFolder {
+        folder_id: BookmarkFolderId,
+    },
+    OpenBookmarkFolderAsWorkspace {
+        folder_id: BookmarkFolderId,
+    },
+    ClearHistory {
+        profile_id: ProfileId,
+        scope: HistoryClearScope,
+    },
+    AddSearchEngine {
+        profile_id: ProfileId,
+        name: String,
+        keyword: String,
+        url_template: String,
+        make_default: bool,
+    },
+    UpdateSearchEngine {
+        engine_id: SearchEngineId,
+        name: String,
+        keyword: String,
+        url_template: String,
+    },
+    RemoveSearchEngine {
+        engine_id: SearchEngineId,
+    },
+    SetDefaultSearchEngine {
+        engine_id: SearchEngineId,
+    },
+    // Leaves the https-only interstitial for its http address and remembers
+    // the host as an exception.
+    ContinueInsecure {
+        tab_id: TabId,
+    },
+    AddHttpsException {
+        profile_id: ProfileId,
+        host: String,
+    },
+    RemoveHttpsException {
+        profile_id: ProfileId,
+        host: String,
+    },
+    // Stores a site setting and settles any matching prompts left on the
+    // profile's tabs.
+    SetSitePermission {
+        profile_id: ProfileId,
+        origin: String,
+        kind: PermissionKind,
+        setting: PermissionSetting,
+    },
+    // Drops the origin's entry for `kind`, or every entry when None.
+    ResetSitePermission {
+        profile_id: ProfileId,
+        origin: String,
+        kind: Option<PermissionKind>,
+    },
+    // A page asked for `kind`. Stored settings answer it at once; `Ask`
+    // leaves it pending on the tab.
+    ObservePermissionRequest {
+        tab_id: TabId,
+        request_id: PermissionRequestId,
+        origin: String,
+        kind: PermissionKind,
+    },
+    // Answers a pending request; `remember` also stores the site setting.
+    ResolvePermission {
+        request_id: PermissionRequestId,
+        decision: PermissionDecision,
+        remember: bool,
+    },
+    // A page in `opener_tab_id` followed a `target=_blank` link or called
+    // `window.open`. Without a user gesture it needs the popups permission.
+    ObserveNewWindowRequest {
+        opener_tab_id: TabId,
+        url: String,
+        disposition: WindowDisposition,
+        user_gesture: bool,
+    },
+    // The host began saving `url` to `target_path` for a page in `tab_id`.
+    ObserveDownloadStarted {
+        tab_id: TabId,
+        url: String,
+        target_path: String,
+        total_bytes: Option<u64>,
+    },
+    ObserveDownloadProgress {
+        download_id: DownloadId,
+        received_bytes: u64,
+        total_bytes: Option<u64>,
+        state: DownloadState,
+    },
+    PauseDownload {
+        download_id: DownloadId,
+    },
+    ResumeDownload {
+        download_id: DownloadId,
+    },
+    CancelDownload {
+        download_id: DownloadId,
+    },
+    // Drops the entry from the list, cancelling it first if still running.
+    // The file on disk is left alone.
+    RemoveDownload {
+        download_id: DownloadId,
+    },
+    OpenDownloadFolder {
+        download_id: DownloadId,
+    },
 }
@@ -0,0 +1,472 @@
+use crate::portable::{PortableBundle, PortableTab, PortableWorkspace};
+use crate::url::is_web_url;
+
+const LOOSE_BOOKMARKS_WORKSPACE: &str = "Imported bookmarks";
+const NETSCAPE_DOCTYPE: &str = "<!DOCTYPE NETSCAPE-Bookmark-file-1>";
+
+pub fn looks_like_netscape_bookmarks(input: &str) -> bool {
+    let head: String = input
+        .chars()
+        .take(512)
+        .collect::<String>()
+        .to_ascii_uppercase();
+    head.contains(NETSCAPE_DOCTYPE.to_ascii_uppercase().as_str()) || head.contains("<DL")
+}
+
+// Top-level folders become workspaces; links in nested folders are flattened
+// into their top-level folder, and links outside any folder share one workspace.
+pub fn parse_netscape_bookmarks(input: &str) -> PortableBundle {
+    let mut workspaces: Vec<PortableWorkspace> = Vec::new();
+    let mut loose_workspace: Option<usize> = None;
+    let mut list_stack: Vec<Option<usize>> = Vec::new();
+    let mut pending_folder: Option<String> = None;
+    let mut cursor = 0;
+
+    while let Some(tag) = next_tag(input, cursor) {
+        cursor = tag.end;
+        match tag.name.as_str() {
+            "DL" if !tag.closing => {
+                let folder_name = pending_folder.take();
+                let workspace_index = match (list_stack.len(), folder_name) {
+                    (1, Some(name)) => {
+                        workspaces.push(empty_workspace(name));
+                        Some(workspaces.len() - 1)
+                    }
+                    _ => list_stack.last().copied().flatten(),
+                };
+                list_stack.push(workspace_index);
+            }
+            "DL" => {
+                list_stack.pop();
+            }
+            "H3" if !tag.closing => {
+                let (text, end) = element_text(input, tag.end, "H3");
+                cursor = end;
+                pending_folder = Some(decode_entities(&text).trim().to_owned());
+            }
+            "A" if !tag.closing => {
+                let (text, end) = element_text(input, tag.end, "A");
+                cursor = end;
+                let Some(url) = tag
+                    .attribute("HREF")
+                    .map(|href| decode_entities(&href).trim().to_owned())
+                    .filter(|url| is_importable_url(url))
+                else {
+                    continue;
+                };
+                let workspace_index = match list_stack.last().copied().flatten() {
+                    Some(index) => index,
+                    None => *loose_workspace.get_or_insert_with(|| {
+                        workspaces.push(empty_workspace(LOOSE_BOOKMARKS_WORKSPACE.to_owned()));
+                        workspaces.len() - 1
+                    }),
+                };
+                workspaces[workspace_index].tabs.push(PortableTab {
+                    url,
+                    title: decode_entities(&text).trim().to_owned(),
+                    pinned: false,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    finish_bundle(workspaces)
+}
+
+// Accepts bare URLs, `<url>`, list bullets and `[title](url)` links. Markdown
+// headings start a new workspace, which mirrors `to_markdown`.
+pub fn parse_url_list(input: &str, default_workspace_name: &str) -> PortableBundle {
+    let mut workspaces: Vec<PortableWorkspace> = Vec::new();
+    for raw_line in input.lines() {
+        let line = raw_line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        if line.starts_with('#') {
+            let level = line.chars().take_while(|ch| *ch == '#').count();
+            if level == 1 && workspaces.is_empty() {
+                // A single leading `#` heading names the export, not a workspace.
+                continue;
+            }
+            let name = line[level..].trim();
+            if !name.is_empty() {
+                workspaces.push(empty_workspace(name.to_owned()));
+            }
+            continue;
+        }
+
+        let item = line
+            .strip_prefix("- ")
+            .or_else(|| line.strip_prefix("* "))
+            .or_else(|| line.strip_prefix("+ "))
+            .unwrap_or(line)
+            .trim();
+        let item = item
+            .strip_prefix("[ ] ")
+            .or_else(|| item.strip_prefix("[x] "))
+            .unwrap_or(item);
+        let Some(tab) = parse_list_item(item) else {
+            continue;
+        };
+        if workspaces.is_empty() {
+            workspaces.push(empty_workspace(default_workspace_name.to_owned()));
+        }
+        workspaces
+            .last_mut()
+            .expect("workspace pushed above")
+            .tabs
+            .push(tab);
+    }
+    finish_bundle(workspaces)
+}
+
+pub fn to_netscape_html(bundle: &PortableBundle) -> String {
+    let mut out = String::new();
+    out.push_str(NETSCAPE_DOCTYPE);
+    out.push('\n');
+    out.push_str("<META HTTP-EQUIV=\"Content-Type\" CONTENT=\"text/html; charset=UTF-8\">\n");
+    out.push_str("<TITLE>Bookmarks</TITLE>\n");
+    out.push_str("<H1>Bookmarks</H1>\n");
+    out.push_str("<DL><p>\n");
+    for workspace in &bundle.workspaces {
+        out.push_str("    <DT><H3>");
+        out.push_str(&encode_entities(&workspace.name));
+        out.push_str("</H3>\n    <DL><p>\n");
+        for tab in &workspace.tabs {
+            out.push_str("        <DT><A HREF=\"");
+            out.push_str(&encode_entities(&tab.url));
+            out.push_str("\">");
+            let title = if tab.title.is_empty() {
+                &tab.url
+            } else {
+                &tab.title
+            };
+            out.push_str(&encode_entities(title));
+            out.push_str("</A>\n");
+        }
+        out.push_str("    </DL><p>\n");
+    }
+    out.push_str("</DL><p>\n");
+    out
+}
+
+pub fn to_markdown(bundle: &PortableBundle) -> String {
+    let mut out = String::new();
+    if let Some(profile_name) = &bundle.profile_name {
+        out.push_str("# ");
+        out.push_str(profile_name);
+        out.push_str("\n\n");
+    }
+    for (index, workspace) in bundle.workspaces.iter().enumerate() {
+        if index > 0 {
+            out.push('\n');
+        }
+        out.push_str("## ");
+        out.push_str(&workspace.name);
+        out.push_str("\n\n");
+        for tab in &workspace.tabs {
+            if tab.title.is_empty() {
+                out.push_str(&format!("- <{}>\n", tab.url));
+            } else {
+                out.push_str(&format!(
+                    "- [{}]({})\n",
+                    escape_markdown_label(&tab.title),
+                    tab.url
+                ));
+            }
+        }
+    }
+    out
+}
+
+fn empty_workspace(name: String) -> PortableWorkspace {
+    let name = if name.is_empty() {
+        LOOSE_BOOKMARKS_WORKSPACE.to_owned()
+    } else {
+        name
+    };
+    PortableWorkspace {
+        name,
+        tabs: Vec::new(),
+        active_tab_index: None,
+    }
+}
+
+fn finish_bundle(mut workspaces: Vec<PortableWorkspace>) -> PortableBundle {
+    workspaces.retain(|workspace| !workspace.tabs.is_empty());
+    PortableBundle {
+        profile_name: None,
+        workspaces,
+    }
+}
+
+fn is_importable_url(url: &str) -> bool {
+    is_web_url(url)
+}
+
+fn parse_list_item(item: &str) -> Option<PortableTab> {
+    if let Some(rest) = item.strip_prefix('[') {
+        let (label, tail) = split_markdown_label(rest)?;
+        let url = tail.strip_prefix('(')?.split(')').next()?.trim();
+        let url = url.split_whitespace().next()?;
+        return is_importable_url(url).then(|| PortableTab {
+            url: url.to_owned(),
+            title: label.replace("\\]", "]").replace("\\[", "["),
+            pinned: false,
+        });
+    }
+    let url = item
+        .strip_prefix('<')
+        .and_then(|rest| rest.strip_suffix('>'))
+        .unwrap_or(item)
+        .split_whitespace()
+        .next()?;
+    is_importable_url(url).then(|| PortableTab {
+        url: url.to_owned(),
+        title: String::new(),
+        pinned: false,
+    })
+}
+
+fn split_markdown_label(rest: &str) -> Option<(&str, &str)> {
+    let mut escaped = false;
+    for (index, ch) in rest.char_indices() {
+        match ch {
+            '\\' if !escaped => escaped = true,
+            ']' if !escaped => return Some((&rest[..index], &rest[index + 1..])),
+            _ => escaped = false,
+        }
+    }
+    None
+}
+
+fn escape_markdown_label(value: &str) -> String {
+    value.replace('[', "\\[").replace(']', "\\]")
+}
+
+struct Tag {
+    name: String,
+    closing: bool,
+    attributes: String,
+    end: usize,
+}
+
+impl Tag {
+    fn attribute(&self, name: &str) -> Option<String> {
+        let upper = self.attributes.to_ascii_uppercase();
+        let mut search_from = 0;
+        while let Some(found) = upper[search_from..].find(name) {
+            let start = search_from + found;
+            search_from = start + name.len();
+            let boundary_ok = start == 0
+                || upper[..start]
+                    .chars()
+                    .next_back()
+                    .is_some_and(char::is_whitespace);
+            let rest = self.attributes[start + name.len()..].trim_start();
+            if !boundary_ok || !rest.starts_with('=') {
+                continue;
+            }
+            let value = rest[1..].trim_start();
+            return Some(match value.chars().next() {
+                Some(quote @ ('"' | '\'')) => value[1..]
+                    .split(quote)
+                    .next()
+                    .unwrap_or_default()
+                    .to_owned(),
+                _ => value
+                    .split(|ch: char| ch.is_whitespace() || ch == '>')
+                    .next()
+                    .unwrap_or_default()
+                    .to_owned(),
+            });
+        }
+        None
+    }
+}
+
+fn next_tag(input: &str, from: usize) -> Option<Tag> {
+    let start = from + input[from..].find('<')?;
+    let body_start = start + 1;
+    let end = body_start + input[body_start..].find('>')?;
+    let body = &input[body_start..end];
+    let (closing, body) = match body.strip_prefix('/') {
+        Some(rest) => (true, rest),
+        None => (false, body),
+    };
+    let name_len = body
+        .find(|ch: char| ch.is_whitespace())
+        .unwrap_or(body.len());
+    Some(Tag {
+        name: body[..name_len].to_ascii_uppercase(),
+        closing,
+        attributes: body[name_len..].to_owned(),
+        end: end + 1,
+    })
+}
+
+fn element_text(input: &str, from: usize, name: &str) -> (String, usize) {
+    let closing = format!("</{name}");
+    let upper = input[from..].to_ascii_uppercase();
+    match upper.find(&closing) {
+        Some(offset) => {
+            let text_end = from + offset;
+            let close_end = input[text_end..]
+                .find('>')
+                .map(|index| text_end + index + 1)
+                .unwrap_or(input.len());
+            (input[from..text_end].to_owned(), close_end)
+        }
+        None => (String::new(), input.len()),
+    }
+}
+
+fn decode_entities(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let Some(semi) = rest.find(';').filter(|index| *index <= 10) else {
+            out.push('&');
+            rest = &rest[1..];
+            continue;
+        };
+        let entity = &rest[1..semi];
+        let decoded = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some(' '),
+            _ => entity
+                .strip_prefix("#x")
+                .or_else(|| entity.strip_prefix("#X"))
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                .and_then(char::from_u32),
+        };
+        match decoded {
+            Some(ch) => {
+                out.push(ch);
+                rest = &rest[semi + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn encode_entities(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        looks_like_netscape_bookmarks, parse_netscape_bookmarks, parse_url_list, to_markdown,
+        to_netscape_html,
+    };
+
+    const CHROME_FIXTURE: &str = include_str!("../tests/fixtures/chrome_bookmarks.html");
+    const FIREFOX_FIXTURE: &str = include_str!("../tests/fixtures/firefox_bookmarks.html");
+    const URL_LIST_FIXTURE: &str = include_str!("../tests/fixtures/url_list.md");
+
+    #[test]
+    fn chrome_export_maps_top_level_folders_to_workspaces() {
+        assert!(looks_like_netscape_bookmarks(CHROME_FIXTURE));
+        let bundle = parse_netscape_bookmarks(CHROME_FIXTURE);
+        let names: Vec<_> = bundle
+            .workspaces
+            .iter()
+            .map(|workspace| workspace.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["Bookmarks bar", "Other bookmarks"]);
+
+        let bar = &bundle.workspaces[0];
+        let urls: Vec<_> = bar.tabs.iter().map(|tab| tab.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            vec![
+                "https://github.com/",
+                "https://doc.rust-lang.org/std/",
+                "https://docs.rs/tokio/latest/tokio/",
+            ]
+        );
+        assert_eq!(bar.tabs[1].title, "Rust std & core");
+        assert_eq!(bar.active_tab_index, None);
+        assert!(bar.tabs.iter().all(|tab| !tab.pinned));
+    }
+
+    #[test]
+    fn firefox_export_skips_non_web_links_and_collects_loose_links() {
+        assert!(looks_like_netscape_bookmarks(FIREFOX_FIXTURE));
+        let bundle = parse_netscape_bookmarks(FIREFOX_FIXTURE);
+        let names: Vec<_> = bundle
+            .workspaces
+            .iter()
+            .map(|workspace| workspace.name.as_str())
+            .collect();
+        assert_eq!(
+            names,
+            vec!["Imported bookmarks", "Bookmarks Toolbar", "Reading"]
+        );
+        let all_urls: Vec<_> = bundle
+            .workspaces
+            .iter()
+            .flat_map(|workspace| workspace.tabs.iter().map(|tab| tab.url.as_str()))
+            .collect();
+        assert!(all_urls.iter().all(|url| url.starts_with("http")));
+        assert_eq!(
+            bundle.workspaces[0].tabs[0].title,
+            "Loose \u{e9}t\u{e9} link"
+        );
+    }
+
+    #[test]
+    fn url_list_supports_bare_angle_and_markdown_links() {
+        assert!(!looks_like_netscape_bookmarks(URL_LIST_FIXTURE));
+        let bundle = parse_url_list(URL_LIST_FIXTURE, "Pasted links");
+        let names: Vec<_> = bundle
+            .workspaces
+            .iter()
+            .map(|workspace| workspace.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["Pasted links", "Design [v2]"]);
+        assert_eq!(bundle.workspaces[0].tabs.len(), 3);
+        assert_eq!(bundle.workspaces[1].tabs[0].title, "Figma [board]");
+        assert_eq!(
+            bundle.workspaces[1].tabs[0].url,
+            "https://figma.com/file/abc"
+        );
+        assert_eq!(bundle.tab_count(), 5);
+    }
+
+    #[test]
+    fn exporters_roundtrip_through_importers() {
+        let bundle = parse_netscape_bookmarks(CHROME_FIXTURE);
+
+        let html = to_netscape_html(&bundle);
+        assert!(looks_like_netscape_bookmarks(&html));
+        assert_eq!(parse_netscape_bookmarks(&html), bundle);
+
+        let markdown = to_markdown(&bundle);
+        assert_eq!(parse_url_list(&markdown, "unused"), bundle);
+    }
+}
@@ -1,13 +1,48 @@
+pub mod content_filter;
+pub mod downloads;
 pub mod engine;
+pub mod history;
 pub mod ids;
 pub mod intent;
+pub mod interchange;
+mod json;
+pub mod lifecycle;
+pub mod navigation;
+pub mod omnibox;
 pub mod patch;
+pub mod portable;
 pub mod reducer;
+pub mod search;
+pub mod site_settings;
 pub mod state;
+pub mod url;
 
-pub use engine::{Engine, EngineError, NoopPersistence, Persistence};
-pub use ids::{ProfileId, TabId, WorkspaceId};
+pub use content_filter::{
+    FilterDecision, FilterEngine, FilterRequest, ProfileContentFilter, RequestFilter, ResourceType,
+};
+pub use downloads::{Download, DownloadCommand, DownloadState};
+pub use engine::{Clock, Engine, EngineError, NoopPersistence, Persistence, SystemClock};
+pub use history::{HistoryClearScope, HistoryEntry, VisitTransition};
+pub use ids::{
+    BookmarkFolderId, BookmarkId, DownloadId, PermissionRequestId, ProfileId, SearchEngineId,
+    TabId, WorkspaceId,
+};
 pub use intent::Intent;
+pub use lifecycle::{
+    LifecycleContext, LifecyclePolicy, MemoryPressureLevel, PrewarmPolicy, StartupRestoreMode,
+    WarmLruPolicy,
+};
+pub use navigation::{
+    NavigationBlockReason, NavigationBlocked, NavigationPolicy, WindowDisposition,
+};
+pub use omnibox::{Suggestion, SuggestionKind};
 pub use patch::{Patch, PatchOp, Snapshot};
+pub use portable::{PortableBundle, PortableError, PortableTab, PortableWorkspace};
 pub use reducer::ReduceError;
-pub use state::{BrowserState, Profile, SettingValue, Tab, TabRuntimeState, Workspace};
+pub use site_settings::{
+    PendingPermission, PermissionDecision, PermissionKind, PermissionSetting, SitePermission,
+};
+pub use state::{
+    Bookmark, BookmarkFolder, BrowserState, Profile, SearchEngine, SettingValue, Tab,
+    TabRuntimeState, TabStatus, Workspace,
+};
@@ -0,0 +1,477 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::ids::{ProfileId, TabId};
+use crate::state::{BrowserState, SettingValue, Tab, TabRuntimeState};
+
+const WARM_POOL_BUDGET_KEY: &str = "warm_pool_budget";
+const DEFAULT_WARM_POOL_BUDGET: usize = 8;
+const MAX_WARM_POOL_BUDGET: usize = 32;
+const KEEP_ALIVE_CAP_KEY: &str = "lifecycle.keep_alive_cap";
+const DEFAULT_KEEP_ALIVE_CAP: usize = 4;
+const WARM_IDLE_TIMEOUT_KEY: &str = "lifecycle.warm_idle_timeout_secs";
+const DEFAULT_WARM_IDLE_TIMEOUT_SECS: i64 = 30 * 60;
+const WARM_POOL_BYTES_KEY: &str = "lifecycle.warm_pool_bytes";
+const DEFAULT_WARM_POOL_BYTES: u64 = 1024 * 1024 * 1024;
+const BACKGROUND_PROFILE_WARM_BUDGET_KEY: &str = "lifecycle.background_profile_warm_budget";
+const DEFAULT_BACKGROUND_PROFILE_WARM_BUDGET: usize = 2;
+const PREWARM_KEY: &str = "lifecycle.prewarm";
+const STARTUP_RESTORE_MODE_KEY: &str = "startup.restore_mode";
+// Guesses beyond the top few mostly warm tabs the user never opens.
+const MAX_PREWARMED_TABS: usize = 2;
+// Charged for tabs the host has not measured yet.
+const UNMEASURED_TAB_BYTES: u64 = 64 * 1024 * 1024;
+
+// Reported by the host when the system runs low on memory; the warm pool
+// shrinks until it is back to Normal.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MemoryPressureLevel {
+    #[default]
+    Normal,
+    // Halves every warm budget.
+    Moderate,
+    // Empties the LRU pools; only keep-alive and playing tabs stay warm.
+    Critical,
+}
+
+// What the app brings back live at launch, besides the active tab.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StartupRestoreMode {
+    // Every other tab waits for a click.
+    #[default]
+    Lazy,
+    // Pinned tabs of the active profile.
+    PinnedWarm,
+    // The active workspace's tabs, up to `warm_pool_budget`.
+    Workspace,
+    // Opens an empty workspace instead of the last active one.
+    Fresh,
+}
+
+impl StartupRestoreMode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Lazy => "lazy",
+            Self::PinnedWarm => "pinned",
+            Self::Workspace => "workspace",
+            Self::Fresh => "fresh",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "lazy" => Some(Self::Lazy),
+            "pinned" => Some(Self::PinnedWarm),
+            "workspace" => Some(Self::Workspace),
+            "fresh" => Some(Self::Fresh),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LifecycleContext {
+    pub active_profile_id: Option<ProfileId>,
+    // The active tab of the active profile's active workspace, already moved
+    // to the front of that profile's warm LRU.
+    pub active_tab_id: Option<TabId>,
+    pub now_ms: i64,
+}
+
+// Decides which tabs keep a live view. The reducer asks after every intent
+// that can change what is visible and applies the difference: tabs missing
+// from the result are discarded, and only the context's active tab may be
+// Active.
+pub trait LifecyclePolicy {
+    fn desired_states(
+        &self,
+        state: &BrowserState,
+        context: &LifecycleContext,
+    ) -> BTreeMap<TabId, TabRuntimeState>;
+}
+
+// One active tab, then every profile's playing tabs, then the active
+// profile's keep-alive tabs, then its most recently used tabs that were
+// active within `lifecycle.warm_idle_timeout_secs`, up to `warm_pool_budget`
+// tabs and `lifecycle.warm_pool_bytes` of estimated memory. Every other
+// profile gets `lifecycle.background_profile_warm_budget` tabs, spent on its
+// keep-alive tabs first and then, while memory lasts, on its recently used
+// ones, so switching back does not reload them. Everything else is
+// discarded.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct WarmLruPolicy;
+
+impl LifecyclePolicy for WarmLruPolicy {
+    fn desired_states(
+        &self,
+        state: &BrowserState,
+        context: &LifecycleContext,
+    ) -> BTreeMap<TabId, TabRuntimeState> {
+        let Some(profile_id) = context.active_profile_id else {
+            return BTreeMap::new();
+        };
+        let in_profile = |tab: &Tab| tab.profile_id == profile_id;
+        // Tabs playing sound stay warm outside the budget, whichever profile
+        // they belong to, so background audio keeps going; muted ones are
+        // fair game.
+        let is_playing = |tab: &Tab| {
+            tab.audible && !tab.muted && tab.runtime_state != TabRuntimeState::Discarded
+        };
+
+        let recent_first: Vec<&Tab> = state
+            .warm_lru
+            .get(&profile_id)
+            .into_iter()
+            .flat_map(|lru| lru.iter().rev())
+            .filter(|tab_id| Some(**tab_id) != context.active_tab_id)
+            .filter_map(|tab_id| state.tabs.get(tab_id))
+            .filter(|tab| in_profile(tab))
+            .collect();
+        let mut warm_set: BTreeSet<TabId> = kept_alive_tabs(
+            state,
+            profile_id,
+            context.active_tab_id,
+            keep_alive_cap(state),
+        )
+        .into_iter()
+        .collect();
+        let background_budget = background_profile_warm_budget(state);
+        let mut background_tabs_left = BTreeMap::new();
+        for other_profile_id in state.profiles.keys().filter(|id| **id != profile_id) {
+            let kept_alive = kept_alive_tabs(
+                state,
+                *other_profile_id,
+                None,
+                keep_alive_cap(state).min(background_budget),
+            );
+            background_tabs_left.insert(*other_profile_id, background_budget - kept_alive.len());
+            warm_set.extend(kept_alive);
+        }
+
+        let idle_timeout_ms = warm_idle_timeout_secs(state).saturating_mul(1000);
+        let is_idle = |tab: &Tab| {
+            idle_timeout_ms > 0
+                && state
+                    .tab_last_active_ms
+                    .get(&tab.id)
+                    .is_some_and(|at| context.now_ms.saturating_sub(*at) >= idle_timeout_ms)
+        };
+
+        warm_set.extend(
+            state
+                .tabs
+                .values()
+                .filter(|tab| is_playing(tab))
+                .map(|tab| tab.id),
+        );
+
+        // Tabs that stay warm anyway spend the byte budget first. The LRU is
+        // then walked newest first, keeping each tab that still fits, so once
+        // memory is tight the large and the old tabs are the ones dropped.
+        let mut bytes_left = under_pressure(state, warm_pool_bytes(state));
+        for tab_id in &warm_set {
+            bytes_left = bytes_left.saturating_sub(tab_memory_estimate(state, *tab_id));
+        }
+        let mut fill =
+            |candidates: Vec<&Tab>, tab_budget: usize, warm_set: &mut BTreeSet<TabId>| {
+                let mut tabs_left = under_pressure(state, tab_budget as u64);
+                for tab in candidates {
+                    if tabs_left == 0 {
+                        break;
+                    }
+                    if warm_set.contains(&tab.id) || is_idle(tab) {
+                        continue;
+                    }
+                    let bytes = tab_memory_estimate(state, tab.id);
+                    if bytes > bytes_left {
+                        continue;
+                    }
+                    bytes_left -= bytes;
+                    tabs_left -= 1;
+                    warm_set.insert(tab.id);
+                }
+            };
+        fill(recent_first, warm_pool_budget(state), &mut warm_set);
+        for (other_profile_id, lru) in &state.warm_lru {
+            if *other_profile_id == profile_id {
+                continue;
+            }
+            let candidates = lru
+                .iter()
+                .rev()
+                .filter_map(|tab_id| state.tabs.get(tab_id))
+                .filter(|tab| tab.profile_id == *other_profile_id)
+                .collect();
+            let tabs_left = background_tabs_left
+                .get(other_profile_id)
+                .copied()
+                .unwrap_or(background_budget);
+            fill(candidates, tabs_left, &mut warm_set);
+        }
+
+        let mut desired: BTreeMap<TabId, TabRuntimeState> = warm_set
+            .into_iter()
+            .map(|tab_id| (tab_id, TabRuntimeState::Warm))
+            .collect();
+        if let Some(tab_id) = context.active_tab_id {
+            desired.insert(tab_id, TabRuntimeState::Active);
+        }
+        desired
+    }
+}
+
+// A profile's keep-alive tabs, most recently used first, at most `cap` of
+// them. The reducer refuses keep-alive past the cap, but the cap can be
+// lowered later; the most recently used keep-alive tabs win.
+fn kept_alive_tabs(
+    state: &BrowserState,
+    profile_id: ProfileId,
+    active_tab_id: Option<TabId>,
+    cap: usize,
+) -> Vec<TabId> {
+    let lru = state.warm_lru.get(&profile_id);
+    let recent_first = lru
+        .into_iter()
+        .flat_map(|lru| lru.iter().rev())
+        .filter_map(|tab_id| state.tabs.get(tab_id));
+    let never_used = state
+        .tabs
+        .values()
+        .filter(|tab| !lru.is_some_and(|lru| lru.contains(&tab.id)));
+    recent_first
+        .chain(never_used)
+        .filter(|tab| {
+            tab.profile_id == profile_id && tab.keep_alive && Some(tab.id) != active_tab_id
+        })
+        .take(cap)
+        .map(|tab| tab.id)
+        .collect()
+}
+
+// Wraps another policy and spends whatever warm budget it leaves unused on
+// the tabs the user is most likely to open next, judged by how often they
+// followed the active tab or workspace before. `lifecycle.prewarm` turns it
+// off.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PrewarmPolicy<P = WarmLruPolicy> {
+    inner: P,
+}
+
+impl<P: LifecyclePolicy> PrewarmPolicy<P> {
+    pub fn new(inner: P) -> Self {
+        Self { inner }
+    }
+}
+
+impl<P: LifecyclePolicy> LifecyclePolicy for PrewarmPolicy<P> {
+    fn desired_states(
+        &self,
+        state: &BrowserState,
+        context: &LifecycleContext,
+    ) -> BTreeMap<TabId, TabRuntimeState> {
+        let mut desired = self.inner.desired_states(state, context);
+        let (Some(profile_id), Some(active_tab_id)) =
+            (context.active_profile_id, context.active_tab_id)
+        else {
+            return desired;
+        };
+        if !prewarm_enabled(state) {
+            return desired;
+        }
+
+        let mut tabs_left = (under_pressure(state, warm_pool_budget(state) as u64) as usize)
+            .saturating_sub(
+                desired
+                    .iter()
+                    .filter(|(tab_id, runtime_state)| {
+                        **runtime_state == TabRuntimeState::Warm
+                            && state
+                                .tabs
+                                .get(tab_id)
+                                .is_some_and(|tab| tab.profile_id == profile_id)
+                    })
+                    .count(),
+            );
+        let mut bytes_left = desired
+            .iter()
+            .filter(|(tab_id, runtime_state)| {
+                **runtime_state == TabRuntimeState::Warm && **tab_id != active_tab_id
+            })
+            .fold(
+                under_pressure(state, warm_pool_bytes(state)),
+                |left, (tab_id, _)| left.saturating_sub(tab_memory_estimate(state, *tab_id)),
+            );
+
+        let mut scores: BTreeMap<TabId, u32> = state
+            .tab_transitions
+            .get(&active_tab_id)
+            .cloned()
+            .unwrap_or_default();
+        if let Some(workspace_id) = state.tabs.get(&active_tab_id).map(|tab| tab.workspace_id) {
+            for (next_workspace_id, count) in state
+                .workspace_transitions
+                .get(&workspace_id)
+                .into_iter()
+                .flatten()
+            {
+                if let Some(tab_id) = state
+                    .workspaces
+                    .get(next_workspace_id)
+                    .and_then(|workspace| workspace.active_tab_id)
+                {
+                    *scores.entry(tab_id).or_default() += count;
+                }
+            }
+        }
+        let mut likely: Vec<(TabId, u32)> = scores
+            .into_iter()
+            .filter(|(tab_id, _)| {
+                !desired.contains_key(tab_id)
+                    && state
+                        .tabs
+                        .get(tab_id)
+                        .is_some_and(|tab| tab.profile_id == profile_id)
+            })
+            .collect();
+        likely.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+        for (tab_id, _) in likely.into_iter().take(MAX_PREWARMED_TABS) {
+            if tabs_left == 0 {
+                break;
+            }
+            let bytes = tab_memory_estimate(state, tab_id);
+            if bytes > bytes_left {
+                continue;
+            }
+            bytes_left -= bytes;
+            tabs_left -= 1;
+            desired.insert(tab_id, TabRuntimeState::Warm);
+        }
+        desired
+    }
+}
+
+// Reported memory pressure shrinks every warm budget.
+fn under_pressure(state: &BrowserState, budget: u64) -> u64 {
+    match state.memory_pressure {
+        MemoryPressureLevel::Normal => budget,
+        MemoryPressureLevel::Moderate => budget / 2,
+        MemoryPressureLevel::Critical => 0,
+    }
+}
+
+// Settings a policy reads; changing one re-runs the policy.
+pub fn is_lifecycle_setting(key: &str) -> bool {
+    matches!(
+        key,
+        WARM_POOL_BUDGET_KEY
+            | KEEP_ALIVE_CAP_KEY
+            | WARM_IDLE_TIMEOUT_KEY
+            | WARM_POOL_BYTES_KEY
+            | BACKGROUND_PROFILE_WARM_BUDGET_KEY
+            | PREWARM_KEY
+    )
+}
+
+pub fn warm_pool_budget(state: &BrowserState) -> usize {
+    match state.settings.get(WARM_POOL_BUDGET_KEY) {
+        Some(SettingValue::Int(value)) => {
+            let clamped = (*value).clamp(0, MAX_WARM_POOL_BUDGET as i64);
+            clamped as usize
+        }
+        _ => DEFAULT_WARM_POOL_BUDGET,
+    }
+}
+
+pub fn background_profile_warm_budget(state: &BrowserState) -> usize {
+    match state.settings.get(BACKGROUND_PROFILE_WARM_BUDGET_KEY) {
+        Some(SettingValue::Int(value)) => {
+            let clamped = (*value).clamp(0, MAX_WARM_POOL_BUDGET as i64);
+            clamped as usize
+        }
+        _ => DEFAULT_BACKGROUND_PROFILE_WARM_BUDGET,
+    }
+}
+
+pub fn prewarm_enabled(state: &BrowserState) -> bool {
+    !matches!(
+        state.settings.get(PREWARM_KEY),
+        Some(SettingValue::Bool(false))
+    )
+}
+
+pub fn keep_alive_cap(state: &BrowserState) -> usize {
+    match state.settings.get(KEEP_ALIVE_CAP_KEY) {
+        Some(SettingValue::Int(value)) => {
+            let clamped = (*value).clamp(0, MAX_WARM_POOL_BUDGET as i64);
+            clamped as usize
+        }
+        _ => DEFAULT_KEEP_ALIVE_CAP,
+    }
+}
+
+// Zero turns idle discarding off.
+pub fn warm_idle_timeout_secs(state: &BrowserState) -> i64 {
+    match state.settings.get(WARM_IDLE_TIMEOUT_KEY) {
+        Some(SettingValue::Int(value)) => (*value).max(0),
+        _ => DEFAULT_WARM_IDLE_TIMEOUT_SECS,
+    }
+}
+
+// Zero lifts the byte limit, leaving only `warm_pool_budget`.
+pub fn warm_pool_bytes(state: &BrowserState) -> u64 {
+    match state.settings.get(WARM_POOL_BYTES_KEY) {
+        Some(SettingValue::Int(value)) if *value <= 0 => u64::MAX,
+        Some(SettingValue::Int(value)) => *value as u64,
+        _ => DEFAULT_WARM_POOL_BYTES,
+    }
+}
+
+pub fn startup_restore_mode(state: &BrowserState) -> StartupRestoreMode {
+    match state.settings.get(STARTUP_RESTORE_MODE_KEY) {
+        Some(SettingValue::Text(value)) => StartupRestoreMode::parse(value).unwrap_or_default(),
+        _ => StartupRestoreMode::default(),
+    }
+}
+
+// The tabs to restore in the background after launch, in the order they
+// should come back. The active tab is live already and is never listed.
+pub fn startup_restore_tabs(state: &BrowserState) -> Vec<TabId> {
+    let Some(profile) = state
+        .active_profile_id
+        .and_then(|profile_id| state.profiles.get(&profile_id))
+    else {
+        return Vec::new();
+    };
+    let active_workspace = profile
+        .active_workspace_id
+        .and_then(|workspace_id| state.workspaces.get(&workspace_id));
+    let active_tab_id = active_workspace.and_then(|workspace| workspace.active_tab_id);
+    let (workspace_ids, pinned_only) = match startup_restore_mode(state) {
+        StartupRestoreMode::Lazy | StartupRestoreMode::Fresh => return Vec::new(),
+        StartupRestoreMode::PinnedWarm => (profile.workspace_order.clone(), true),
+        StartupRestoreMode::Workspace => (
+            active_workspace
+                .map(|workspace| workspace.id)
+                .into_iter()
+                .collect(),
+            false,
+        ),
+    };
+    workspace_ids
+        .iter()
+        .filter_map(|workspace_id| state.workspaces.get(workspace_id))
+        .flat_map(|workspace| workspace.tab_order.iter())
+        .filter_map(|tab_id| state.tabs.get(tab_id))
+        .filter(|tab| Some(tab.id) != active_tab_id && (tab.pinned || !pinned_only))
+        .map(|tab| tab.id)
+        .take(warm_pool_budget(state))
+        .collect()
+}
+
+pub fn tab_memory_estimate(state: &BrowserState, tab_id: TabId) -> u64 {
+    state
+        .tab_memory_bytes
+        .get(&tab_id)
+        .copied()
+        .unwrap_or(UNMEASURED_TAB_BYTES)
+}
@@ -0,0 +1,375 @@
+use std::collections::BTreeSet;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use crate::history::profile_setting_key;
+use crate::ids::ProfileId;
+use crate::state::{BrowserState, SettingValue};
+use crate::url::{
+    is_local_host, normalize_navigation_url, parse_web_url, url_scheme, web_host, UrlError,
+    BLANK_PAGE_URL,
+};
+
+// Per-profile domain rules, stored as text settings holding a comma or
+// whitespace separated list such as `example.com, *.ads.test`.
+pub const BLOCKED_DOMAINS_PROFILE_PREFIX: &str = "navigation.blocked_domains.profile.";
+pub const ALLOWED_DOMAINS_PROFILE_PREFIX: &str = "navigation.allowed_domains.profile.";
+pub const HTTPS_ONLY_PROFILE_PREFIX: &str = "navigation.https_only.profile.";
+
+const ALLOWED_SCHEMES: &[&str] = &["http", "https"];
+
+// Where a page asked for a new window to open. Without multi-window support
+// every disposition except `BackgroundTab` opens a foreground tab.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowDisposition {
+    ForegroundTab,
+    BackgroundTab,
+    Popup,
+    Window,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NavigationBlockReason {
+    InvalidUrl(UrlError),
+    Scheme(String),
+    Domain { host: String, rule: String },
+    // An https-only upgrade failed; `url` is the original http address the
+    // user may continue to.
+    HttpsUnavailable,
+}
+
+impl NavigationBlockReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::InvalidUrl(_) => "invalid_url",
+            Self::Scheme(_) => "scheme",
+            Self::Domain { .. } => "domain",
+            Self::HttpsUnavailable => "https_unavailable",
+        }
+    }
+}
+
+impl Display for NavigationBlockReason {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::InvalidUrl(error) => write!(f, "{error}"),
+            Self::Scheme(scheme) => write!(f, "{scheme}: pages cannot be opened in a tab"),
+            Self::Domain { host, rule } => write!(f, "{host} is blocked by rule {rule}"),
+            Self::HttpsUnavailable => write!(f, "site is not available over https"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NavigationBlocked {
+    pub url: String,
+    pub reason: NavigationBlockReason,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NavigationAllowed {
+    pub url: String,
+    // The http address `url` was upgraded from in https-only mode.
+    pub upgraded_from: Option<String>,
+}
+
+// `*` matches every host, `*.example.com` only subdomains, and a bare
+// `example.com` the domain itself plus its subdomains.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DomainRule(String);
+
+impl DomainRule {
+    pub fn parse(pattern: &str) -> Option<Self> {
+        let pattern = pattern.trim().trim_start_matches('.');
+        if pattern == "*" {
+            return Some(Self("*".to_owned()));
+        }
+        let (wildcard, domain) = match pattern.strip_prefix("*.") {
+            Some(domain) => (true, domain),
+            None => (false, pattern),
+        };
+        if domain.is_empty() || domain.contains(['*', '/', ':', '@']) {
+            return None;
+        }
+        let host = web_host(&format!("https://{domain}"))?;
+        Some(Self(if wildcard { format!("*.{host}") } else { host }))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn matches(&self, host: &str) -> bool {
+        if self.0 == "*" {
+            return true;
+        }
+        let is_subdomain_of = |domain: &str| {
+            host.strip_suffix(domain)
+                .is_some_and(|prefix| prefix.ends_with('.'))
+        };
+        match self.0.strip_prefix("*.") {
+            Some(domain) => is_subdomain_of(domain),
+            None => host == self.0 || is_subdomain_of(&self.0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NavigationPolicy {
+    pub allowed_domains: Vec<DomainRule>,
+    pub blocked_domains: Vec<DomainRule>,
+    pub https_only: bool,
+    // Hosts the user chose to keep visiting over http.
+    pub https_exceptions: BTreeSet<String>,
+}
+
+impl NavigationPolicy {
+    pub fn for_profile(state: &BrowserState, profile_id: ProfileId) -> Self {
+        Self {
+            allowed_domains: domain_rules(state, ALLOWED_DOMAINS_PROFILE_PREFIX, profile_id),
+            blocked_domains: domain_rules(state, BLOCKED_DOMAINS_PROFILE_PREFIX, profile_id),
+            https_only: matches!(
+                state
+                    .settings
+                    .get(&profile_setting_key(HTTPS_ONLY_PROFILE_PREFIX, profile_id)),
+                Some(SettingValue::Bool(true))
+            ),
+            https_exceptions: state
+                .https_exceptions
+                .get(&profile_id)
+                .cloned()
+                .unwrap_or_default(),
+        }
+    }
+
+    // Returns the normalized URL to load. Allow rules win over block rules so
+    // `*` plus a few allowed domains gives an allowlist-only profile.
+    pub fn evaluate(&self, url: &str) -> Result<NavigationAllowed, NavigationBlocked> {
+        let blocked = |reason| NavigationBlocked {
+            url: url.trim().to_owned(),
+            reason,
+        };
+        if let Some(scheme) = url_scheme(url) {
+            let is_blank = url.trim().eq_ignore_ascii_case(BLANK_PAGE_URL);
+            if !is_blank && !ALLOWED_SCHEMES.contains(&scheme.as_str()) {
+                return Err(blocked(NavigationBlockReason::Scheme(scheme)));
+            }
+        }
+        let normalized = normalize_navigation_url(url)
+            .map_err(|error| blocked(NavigationBlockReason::InvalidUrl(error)))?;
+        let Some(host) = web_host(&normalized) else {
+            return Ok(NavigationAllowed {
+                url: normalized,
+                upgraded_from: None,
+            });
+        };
+        let allowed = self.allowed_domains.iter().any(|rule| rule.matches(&host));
+        if !allowed {
+            if let Some(rule) = self.blocked_domains.iter().find(|rule| rule.matches(&host)) {
+                return Err(blocked(NavigationBlockReason::Domain {
+                    host,
+                    rule: rule.as_str().to_owned(),
+                }));
+            }
+        }
+        Ok(self.upgrade_insecure(normalized))
+    }
+
+    // Local hosts and explicit ports are left alone since they rarely serve
+    // the same site over https.
+    fn upgrade_insecure(&self, url: String) -> NavigationAllowed {
+        if self.https_only {
+            if let Ok(mut parsed) = parse_web_url(&url) {
+                if !parsed.secure
+                    && parsed.port.is_none()
+                    && !is_local_host(&parsed.host)
+                    && !self.https_exceptions.contains(&parsed.host)
+                {
+                    parsed.secure = true;
+                    return NavigationAllowed {
+                        url: parsed.to_string(),
+                        upgraded_from: Some(url),
+                    };
+                }
+            }
+        }
+        NavigationAllowed {
+            url,
+            upgraded_from: None,
+        }
+    }
+}
+
+// Canonical host for an https-only exception, accepting `example.com`,
+// `Example.COM:8080` or an IDN form.
+pub fn normalize_exception_host(input: &str) -> Result<String, UrlError> {
+    let trimmed = input.trim();
+    if trimmed.is_empty() {
+        return Err(UrlError::Empty);
+    }
+    if trimmed.contains(['/', '@', '?', '#']) {
+        return Err(UrlError::InvalidHost(trimmed.to_owned()));
+    }
+    Ok(parse_web_url(&format!("http://{trimmed}"))?.host)
+}
+
+pub(crate) fn domain_rules(
+    state: &BrowserState,
+    prefix: &str,
+    profile_id: ProfileId,
+) -> Vec<DomainRule> {
+    match state.settings.get(&profile_setting_key(prefix, profile_id)) {
+        Some(SettingValue::Text(value)) => value
+            .split(|ch: char| ch == ',' || ch.is_whitespace())
+            .filter_map(DomainRule::parse)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::state::{BrowserState, SettingValue};
+    use crate::url::UrlError;
+
+    use super::{
+        normalize_exception_host, DomainRule, NavigationBlockReason, NavigationPolicy,
+        ALLOWED_DOMAINS_PROFILE_PREFIX, BLOCKED_DOMAINS_PROFILE_PREFIX,
+    };
+
+    #[test]
+    fn default_policy_allows_web_and_blank_only() {
+        let policy = NavigationPolicy::default();
+        let cases = [
+            ("https://Example.com/a", Ok("https://example.com/a")),
+            ("http://localhost:3000", Ok("http://localhost:3000")),
+            ("about:blank", Ok("about:blank")),
+            (
+                "about:config",
+                Err(NavigationBlockReason::Scheme("about".to_owned())),
+            ),
+            (
+                "file:///etc/passwd",
+                Err(NavigationBlockReason::Scheme("file".to_owned())),
+            ),
+            (
+                "JavaScript:alert(1)",
+                Err(NavigationBlockReason::Scheme("javascript".to_owned())),
+            ),
+            (
+                "data:text/html,<b>x</b>",
+                Err(NavigationBlockReason::Scheme("data".to_owned())),
+            ),
+            (
+                "app://ui",
+                Err(NavigationBlockReason::Scheme("app".to_owned())),
+            ),
+            (
+                "example.com",
+                Err(NavigationBlockReason::InvalidUrl(UrlError::MissingScheme(
+                    "example.com".to_owned(),
+                ))),
+            ),
+        ];
+        for (url, expected) in cases {
+            assert_eq!(
+                policy
+                    .evaluate(url)
+                    .map(|allowed| allowed.url)
+                    .map_err(|blocked| blocked.reason),
+                expected.map(str::to_owned),
+                "url {url:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn domain_rules_match_wildcards_and_subdomains() {
+        let cases = [
+            ("example.com", "example.com", true),
+            ("example.com", "news.example.com", true),
+            ("example.com", "badexample.com", false),
+            ("*.example.com", "example.com", false),
+            ("*.example.com", "a.b.example.com", true),
+            ("*", "anything.test", true),
+            (".Example.COM", "www.example.com", true),
+            ("münchen.de", "xn--mnchen-3ya.de", true),
+        ];
+        for (pattern, host, expected) in cases {
+            let rule = DomainRule::parse(pattern).expect("pattern should parse");
+            assert_eq!(rule.matches(host), expected, "{pattern} vs {host}");
+        }
+        for invalid in ["", "*.", "ex*ample.com", "https://example.com", "a b"] {
+            assert_eq!(DomainRule::parse(invalid), None, "pattern {invalid:?}");
+        }
+    }
+
+    #[test]
+    fn profile_rules_are_scoped_and_allow_wins() {
+        let mut state = BrowserState::default();
+        let locked = state.add_profile("Kiosk");
+        let other = state.add_profile("Other");
+        state.settings.insert(
+            format!("{BLOCKED_DOMAINS_PROFILE_PREFIX}{}", locked.0),
+            SettingValue::Text("*".to_owned()),
+        );
+        state.settings.insert(
+            format!("{ALLOWED_DOMAINS_PROFILE_PREFIX}{}", locked.0),
+            SettingValue::Text("docs.rs, *.example.com".to_owned()),
+        );
+
+        let policy = NavigationPolicy::for_profile(&state, locked);
+        assert!(policy.evaluate("https://docs.rs/serde").is_ok());
+        assert!(policy.evaluate("https://www.example.com").is_ok());
+        let blocked = policy
+            .evaluate("https://example.com")
+            .expect_err("apex should fall through to the * block rule");
+        assert_eq!(
+            blocked.reason,
+            NavigationBlockReason::Domain {
+                host: "example.com".to_owned(),
+                rule: "*".to_owned(),
+            }
+        );
+
+        let open = NavigationPolicy::for_profile(&state, other);
+        assert!(open.evaluate("https://example.com").is_ok());
+    }
+
+    #[test]
+    fn https_only_upgrades_plain_http_except_local_and_excepted_hosts() {
+        let policy = NavigationPolicy {
+            https_only: true,
+            https_exceptions: ["legacy.example".to_owned()].into_iter().collect(),
+            ..NavigationPolicy::default()
+        };
+        let cases = [
+            (
+                "http://Example.com/a?b",
+                "https://example.com/a?b",
+                Some("http://example.com/a?b"),
+            ),
+            ("https://example.com/", "https://example.com/", None),
+            ("http://legacy.example/", "http://legacy.example/", None),
+            ("http://localhost/", "http://localhost/", None),
+            ("http://192.168.1.1/", "http://192.168.1.1/", None),
+            ("http://example.com:8080/", "http://example.com:8080/", None),
+            ("about:blank", "about:blank", None),
+        ];
+        for (url, expected, upgraded_from) in cases {
+            let allowed = policy.evaluate(url).expect("url should be allowed");
+            assert_eq!(allowed.url, expected, "url {url:?}");
+            assert_eq!(
+                allowed.upgraded_from.as_deref(),
+                upgraded_from,
+                "url {url:?}"
+            );
+        }
+
+        assert_eq!(
+            normalize_exception_host(" Legacy.Example:8080 "),
+            Ok("legacy.example".to_owned())
+        );
+        assert_eq!(normalize_exception_host("  "), Err(UrlError::Empty));
+        assert!(normalize_exception_host("http://legacy.example").is_err());
+    }
+}
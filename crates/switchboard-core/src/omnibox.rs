@@ -0,0 +1,484 @@
+use std::collections::BTreeMap;
+
+use crate::history::HistoryEntry;
+use crate::ids::{ProfileId, TabId, WorkspaceId};
+use crate::search::resolve_search;
+use crate::state::BrowserState;
+use crate::url::classify_input;
+
+// Sources without visit data get a fixed frecency so that an open tab or a
+// bookmark still outranks a page visited once a long time ago.
+const OPEN_TAB_FRECENCY: u64 = 2_000;
+const BOOKMARK_FRECENCY: u64 = 1_500;
+const MAX_VISIT_POINTS: u64 = 100;
+const TYPED_VISIT_POINTS: u64 = 2;
+const MS_PER_DAY: i64 = 24 * 60 * 60 * 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SuggestionKind {
+    Navigate,
+    SwitchToTab {
+        tab_id: TabId,
+        workspace_id: WorkspaceId,
+    },
+    Bookmark,
+    History,
+    Search,
+}
+
+impl SuggestionKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Navigate => "navigate",
+            Self::SwitchToTab { .. } => "switch_to_tab",
+            Self::Bookmark => "bookmark",
+            Self::History => "history",
+            Self::Search => "search",
+        }
+    }
+
+    fn tie_break_rank(self) -> u8 {
+        match self {
+            Self::Navigate => 0,
+            Self::SwitchToTab { .. } => 1,
+            Self::Bookmark => 2,
+            Self::History => 3,
+            Self::Search => 4,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Suggestion {
+    pub kind: SuggestionKind,
+    pub url: String,
+    pub title: String,
+    pub score: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum MatchQuality {
+    Substring = 1,
+    WordPrefix = 2,
+    UrlPrefix = 4,
+}
+
+// Ranks open tabs, bookmarks and the supplied history entries for `text`.
+// The typed URL (when the input looks like one) always comes first and the
+// search fallback always comes last; everything in between is ordered by
+// frecency scaled by how well the text matched.
+pub fn suggest(
+    state: &BrowserState,
+    profile_id: ProfileId,
+    text: &str,
+    history: &[HistoryEntry],
+    now_ms: i64,
+    limit: usize,
+) -> Vec<Suggestion> {
+    let query = text.trim();
+    if query.is_empty() || limit == 0 {
+        return Vec::new();
+    }
+    let terms: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+    let typed_target = navigation_target(query);
+
+    let mut ranked: BTreeMap<String, Suggestion> = BTreeMap::new();
+    let history_frecency: BTreeMap<&str, u64> = history
+        .iter()
+        .map(|entry| (entry.url.as_str(), history_entry_frecency(entry, now_ms)))
+        .collect();
+
+    let active_tab_id = state
+        .active_workspace_id()
+        .and_then(|workspace_id| state.workspaces.get(&workspace_id))
+        .and_then(|workspace| workspace.active_tab_id);
+    for tab in state.tabs.values() {
+        if tab.profile_id != profile_id || Some(tab.id) == active_tab_id {
+            continue;
+        }
+        let Some(quality) = match_quality(&terms, query, &tab.url, &tab.title) else {
+            continue;
+        };
+        let frecency =
+            OPEN_TAB_FRECENCY + history_frecency.get(tab.url.as_str()).copied().unwrap_or(0);
+        offer(
+            &mut ranked,
+            Suggestion {
+                kind: SuggestionKind::SwitchToTab {
+                    tab_id: tab.id,
+                    workspace_id: tab.workspace_id,
+                },
+                url: tab.url.clone(),
+                title: tab.title.clone(),
+                score: frecency * quality as u64,
+            },
+        );
+    }
+
+    for bookmark in state.bookmarks.values() {
+        if bookmark.profile_id != profile_id {
+            continue;
+        }
+        let Some(quality) = match_quality(&terms, query, &bookmark.url, &bookmark.title) else {
+            continue;
+        };
+        let frecency = BOOKMARK_FRECENCY
+            + history_frecency
+                .get(bookmark.url.as_str())
+                .copied()
+                .unwrap_or(0);
+        offer(
+            &mut ranked,
+            Suggestion {
+                kind: SuggestionKind::Bookmark,
+                url: bookmark.url.clone(),
+                title: bookmark.title.clone(),
+                score: frecency * quality as u64,
+            },
+        );
+    }
+
+    for entry in history {
+        let Some(quality) = match_quality(&terms, query, &entry.url, &entry.title) else {
+            continue;
+        };
+        offer(
+            &mut ranked,
+            Suggestion {
+                kind: SuggestionKind::History,
+                url: entry.url.clone(),
+                title: entry.title.clone(),
+                score: history_entry_frecency(entry, now_ms) * quality as u64,
+            },
+        );
+    }
+
+    let mut middle: Vec<Suggestion> = ranked.into_values().collect();
+    middle.sort_by(|left, right| {
+        right
+            .score
+            .cmp(&left.score)
+            .then_with(|| left.kind.tie_break_rank().cmp(&right.kind.tie_break_rank()))
+            .then_with(|| left.url.cmp(&right.url))
+    });
+
+    let mut suggestions = Vec::with_capacity(limit);
+    if let Some(url) = &typed_target {
+        middle.retain(|suggestion| &suggestion.url != url);
+        suggestions.push(Suggestion {
+            kind: SuggestionKind::Navigate,
+            url: url.clone(),
+            title: String::new(),
+            score: 0,
+        });
+    }
+    let search_slots = usize::from(typed_target.is_none());
+    let middle_slots = limit.saturating_sub(suggestions.len() + search_slots);
+    suggestions.extend(middle.into_iter().take(middle_slots));
+    if typed_target.is_none() {
+        let search = resolve_search(state, profile_id, query);
+        suggestions.push(Suggestion {
+            kind: SuggestionKind::Search,
+            url: search.url,
+            title: format!("Search {} for {}", search.engine_name, search.terms),
+            score: 0,
+        });
+    }
+    suggestions.truncate(limit);
+    suggestions
+}
+
+// Keeps the strongest suggestion per URL; an open tab always wins so the
+// UI offers "switch to tab" instead of opening a duplicate.
+fn offer(ranked: &mut BTreeMap<String, Suggestion>, candidate: Suggestion) {
+    match ranked.get_mut(&candidate.url) {
+        Some(existing) => {
+            let existing_is_tab = matches!(existing.kind, SuggestionKind::SwitchToTab { .. });
+            if !existing_is_tab && candidate.score > existing.score {
+                *existing = candidate;
+            } else if existing.title.is_empty() && !candidate.title.is_empty() {
+                existing.title = candidate.title;
+            }
+        }
+        None => {
+            ranked.insert(candidate.url.clone(), candidate);
+        }
+    }
+}
+
+pub fn history_entry_frecency(entry: &HistoryEntry, now_ms: i64) -> u64 {
+    let visit_points = entry
+        .visit_count
+        .saturating_add(entry.typed_count.saturating_mul(TYPED_VISIT_POINTS))
+        .min(MAX_VISIT_POINTS);
+    let age_days = now_ms.saturating_sub(entry.last_visited_at_ms).max(0) / MS_PER_DAY;
+    let recency_weight = match age_days {
+        0..=3 => 100,
+        4..=14 => 70,
+        15..=31 => 50,
+        32..=90 => 30,
+        _ => 10,
+    };
+    visit_points * recency_weight
+}
+
+fn match_quality(terms: &[String], query: &str, url: &str, title: &str) -> Option<MatchQuality> {
+    let lowered_url = url.to_lowercase();
+    let url_text = strip_url_decoration(&lowered_url);
+    let title_text = title.to_lowercase();
+    if !terms
+        .iter()
+        .all(|term| url_text.contains(term.as_str()) || title_text.contains(term.as_str()))
+    {
+        return None;
+    }
+    let lowered_query = query.to_lowercase();
+    if !lowered_query.contains(char::is_whitespace)
+        && url_text.starts_with(strip_url_decoration(&lowered_query))
+    {
+        return Some(MatchQuality::UrlPrefix);
+    }
+    let first_term = terms[0].as_str();
+    let is_word_prefix = |text: &str| {
+        text.split(|ch: char| !ch.is_alphanumeric())
+            .any(|word| word.starts_with(first_term))
+    };
+    if is_word_prefix(url_text) || is_word_prefix(&title_text) {
+        return Some(MatchQuality::WordPrefix);
+    }
+    Some(MatchQuality::Substring)
+}
+
+fn strip_url_decoration(url: &str) -> &str {
+    let without_scheme = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))
+        .unwrap_or(url);
+    without_scheme
+        .strip_prefix("www.")
+        .unwrap_or(without_scheme)
+}
+
+// Mirrors the shell's navigation input rules: explicit http(s) URLs, local
+// hosts and dotted hostnames navigate; anything else is a search.
+pub fn navigation_target(text: &str) -> Option<String> {
+    classify_input(text)?.navigable_url().map(str::to_owned)
+}
+
+// What the navigation input resolves to: a URL to load directly, or a search
+// through the keyword-selected or default engine.
+pub fn resolve_navigation_input(
+    state: &BrowserState,
+    profile_id: ProfileId,
+    text: &str,
+) -> Option<String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+    Some(navigation_target(text).unwrap_or_else(|| resolve_search(state, profile_id, text).url))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::history::HistoryEntry;
+    use crate::search::ensure_search_engines;
+    use crate::state::{Bookmark, BrowserState, SettingValue, Tab, TabRuntimeState, TabStatus};
+    use crate::BookmarkId;
+
+    use super::{
+        history_entry_frecency, navigation_target, resolve_navigation_input, suggest,
+        SuggestionKind, MS_PER_DAY,
+    };
+
+    const NOW_MS: i64 = 400 * MS_PER_DAY;
+
+    fn history_entry(
+        url: &str,
+        title: &str,
+        visits: u64,
+        typed: u64,
+        age_days: i64,
+    ) -> HistoryEntry {
+        HistoryEntry {
+            url: url.to_owned(),
+            title: title.to_owned(),
+            visit_count: visits,
+            typed_count: typed,
+            last_visited_at_ms: NOW_MS - age_days * MS_PER_DAY,
+        }
+    }
+
+    fn state_with_tabs(urls: &[(&str, &str)]) -> BrowserState {
+        let mut state = BrowserState::default();
+        let profile_id = state.add_profile("Default");
+        let workspace_id = state
+            .add_workspace(profile_id, "Main")
+            .expect("profile should exist");
+        state.active_profile_id = Some(profile_id);
+        for (url, title) in urls {
+            let tab_id = state.allocate_tab_id();
+            state.tabs.insert(
+                tab_id,
+                Tab {
+                    id: tab_id,
+                    profile_id,
+                    workspace_id,
+                    url: (*url).to_owned(),
+                    title: (*title).to_owned(),
+                    loading: false,
+                    thumbnail_data_url: None,
+                    favicon_key: None,
+                    pinned: false,
+                    keep_alive: false,
+                    muted: false,
+                    audible: false,
+                    runtime_state: TabRuntimeState::Discarded,
+                    blocked_navigation: None,
+                    https_upgraded_from: None,
+                    pending_permissions: Vec::new(),
+                    opener_tab_id: None,
+                    status: TabStatus::Ok,
+                    crash_count: 0,
+                },
+            );
+            state
+                .workspaces
+                .get_mut(&workspace_id)
+                .expect("workspace should exist")
+                .tab_order
+                .push(tab_id);
+        }
+        state
+    }
+
+    #[test]
+    fn frecency_prefers_recent_and_typed_visits() {
+        let fresh = history_entry("https://a.test", "", 5, 0, 1);
+        let stale = history_entry("https://b.test", "", 5, 0, 60);
+        let typed = history_entry("https://c.test", "", 5, 2, 1);
+        assert_eq!(history_entry_frecency(&fresh, NOW_MS), 500);
+        assert_eq!(history_entry_frecency(&stale, NOW_MS), 150);
+        assert_eq!(history_entry_frecency(&typed, NOW_MS), 900);
+    }
+
+    #[test]
+    fn ranks_tabs_bookmarks_and_history_with_search_fallback_last() {
+        let mut state = state_with_tabs(&[("https://docs.rs/serde", "serde - Rust")]);
+        let profile_id = state.active_profile_id.expect("profile should be active");
+        state.bookmarks.insert(
+            BookmarkId(1),
+            Bookmark {
+                id: BookmarkId(1),
+                profile_id,
+                folder_id: None,
+                url: "https://serde.rs/".to_owned(),
+                title: "Serde guide".to_owned(),
+                position: 0,
+            },
+        );
+        state.settings.insert(
+            "search_engine".to_owned(),
+            SettingValue::Text("duckduckgo".to_owned()),
+        );
+        ensure_search_engines(&mut state);
+        let history = vec![
+            history_entry("https://docs.rs/serde", "serde - Rust", 40, 10, 1),
+            history_entry("https://example.com/serde-tips", "Tips", 3, 0, 2),
+            history_entry(
+                "https://github.com/serde-rs/serde",
+                "serde-rs/serde",
+                9,
+                0,
+                100,
+            ),
+            history_entry("https://unrelated.test", "Nothing", 50, 50, 0),
+        ];
+
+        let suggestions = suggest(&state, profile_id, "serde", &history, NOW_MS, 8);
+        let urls: Vec<_> = suggestions.iter().map(|s| s.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            vec![
+                "https://docs.rs/serde",
+                "https://serde.rs/",
+                "https://example.com/serde-tips",
+                "https://github.com/serde-rs/serde",
+                "https://duckduckgo.com/?q=serde",
+            ]
+        );
+        assert!(matches!(
+            suggestions[0].kind,
+            SuggestionKind::SwitchToTab { .. }
+        ));
+        assert_eq!(suggestions[1].kind, SuggestionKind::Bookmark);
+        assert_eq!(suggestions[4].kind, SuggestionKind::Search);
+        assert_eq!(suggestions[4].title, "Search DuckDuckGo for serde");
+    }
+
+    #[test]
+    fn url_input_navigates_first_and_skips_search() {
+        let state = state_with_tabs(&[]);
+        let profile_id = state.active_profile_id.expect("profile should be active");
+        let history = vec![
+            history_entry("https://example.com/", "Example", 2, 0, 1),
+            history_entry("https://example.com/docs", "Docs", 1, 0, 1),
+        ];
+
+        let suggestions = suggest(&state, profile_id, "example.com", &history, NOW_MS, 5);
+        assert_eq!(suggestions[0].kind, SuggestionKind::Navigate);
+        assert_eq!(suggestions[0].url, "https://example.com");
+        assert!(suggestions
+            .iter()
+            .all(|suggestion| suggestion.kind != SuggestionKind::Search));
+        assert_eq!(suggestions[1].url, "https://example.com/");
+    }
+
+    #[test]
+    fn results_are_deterministic_and_respect_limit() {
+        let state = state_with_tabs(&[]);
+        let profile_id = state.active_profile_id.expect("profile should be active");
+        let history: Vec<_> = ["b", "a", "c"]
+            .iter()
+            .map(|name| history_entry(&format!("https://{name}.rust.test"), "rust", 1, 0, 1))
+            .collect();
+
+        let first = suggest(&state, profile_id, "rust", &history, NOW_MS, 3);
+        let second = suggest(&state, profile_id, "rust", &history, NOW_MS, 3);
+        assert_eq!(first, second);
+        let urls: Vec<_> = first.iter().map(|s| s.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            vec![
+                "https://a.rust.test",
+                "https://b.rust.test",
+                "https://www.google.com/search?q=rust"
+            ]
+        );
+        assert!(suggest(&state, profile_id, "   ", &history, NOW_MS, 3).is_empty());
+    }
+
+    #[test]
+    fn navigation_target_matches_shell_rules() {
+        assert_eq!(
+            navigation_target("localhost:3000"),
+            Some("http://localhost:3000".to_owned())
+        );
+        assert_eq!(navigation_target("rust lang"), None);
+        assert_eq!(navigation_target("mailto:me@example.com"), None);
+        assert_eq!(navigation_target("tokio"), None);
+
+        let mut state = state_with_tabs(&[]);
+        ensure_search_engines(&mut state);
+        let profile_id = state.active_profile_id.expect("profile should be active");
+        assert_eq!(
+            resolve_navigation_input(&state, profile_id, "gh a&b c").as_deref(),
+            Some("https://github.com/search?q=a%26b%20c")
+        );
+        assert_eq!(
+            resolve_navigation_input(&state, profile_id, "docs.rs").as_deref(),
+            Some("https://docs.rs")
+        );
+        assert_eq!(resolve_navigation_input(&state, profile_id, "  "), None);
+    }
+}
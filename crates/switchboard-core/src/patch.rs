@@ -1,5 +1,14 @@
-use crate::ids::{ProfileId, TabId, WorkspaceId};
-use crate::state::{BrowserState, Profile, SettingValue, Tab, Workspace};
+use crate::downloads::{Download, DownloadCommand};
+use crate::history::HistoryClearScope;
+use crate::ids::{
+    BookmarkFolderId, BookmarkId, DownloadId, PermissionRequestId, ProfileId, SearchEngineId,
+    TabId, WorkspaceId,
+};
+use crate::portable::PortableBundle;
+use crate::site_settings::{PermissionKind, PermissionSetting};
+use crate::state::{
+    Bookmark, BookmarkFolder, BrowserState, Profile, SearchEngine, SettingValue, Tab, Workspace,
+};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Snapshot {
@@ -42,4 +51,79 @@ pub enum PatchOp {
         key: String,
         value: SettingValue,
     },
+    // The runtime writes the bundle to `path`.
+    WorkspacesExported {
+        path: String,
+        bundle: PortableBundle,
+    },
+    UpsertBookmark(Bookmark),
+    RemoveBookmark {
+        bookmark_id: BookmarkId,
+        profile_id: ProfileId,
+    },
+    UpsertBookmarkFolder(BookmarkFolder),
+    RemoveBookmarkFolder {
+        folder_id: BookmarkFolderId,
+        profile_id: ProfileId,
+    },
+    UpsertSearchEngine(SearchEngine),
+    RemoveSearchEngine {
+        engine_id: SearchEngineId,
+        profile_id: ProfileId,
+    },
+    UpsertHttpsException {
+        profile_id: ProfileId,
+        host: String,
+    },
+    RemoveHttpsException {
+        profile_id: ProfileId,
+        host: String,
+    },
+    UpsertSitePermission {
+        profile_id: ProfileId,
+        origin: String,
+        kind: PermissionKind,
+        setting: PermissionSetting,
+    },
+    RemoveSitePermission {
+        profile_id: ProfileId,
+        origin: String,
+        kind: PermissionKind,
+    },
+    // The host is holding the request open; the runtime completes it.
+    PermissionRequestSettled {
+        tab_id: TabId,
+        request_id: PermissionRequestId,
+        granted: bool,
+    },
+    // The runtime reloads the tab's live view; a tab without one gets a new
+    // view when it is next synced.
+    TabReloaded {
+        tab_id: TabId,
+    },
+    UpsertDownload(Download),
+    RemoveDownload {
+        download_id: DownloadId,
+        profile_id: ProfileId,
+    },
+    // The runtime forwards this to the host, which owns the transfer.
+    DownloadCommandIssued {
+        download_id: DownloadId,
+        command: DownloadCommand,
+    },
+    // Asks the host to show the file in its folder.
+    DownloadFolderOpened {
+        download_id: DownloadId,
+        target_path: String,
+    },
+    // A new-window request was refused for lack of a user gesture.
+    NewWindowBlocked {
+        opener_tab_id: TabId,
+        url: String,
+    },
+    // History lives outside BrowserState; the runtime applies this to its store.
+    HistoryCleared {
+        profile_id: ProfileId,
+        scope: HistoryClearScope,
+    },
 }
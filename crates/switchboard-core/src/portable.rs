@@ -0,0 +1,334 @@
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use crate::ids::{ProfileId, WorkspaceId};
+use crate::json::{self, JsonValue};
+use crate::state::{BrowserState, Workspace};
+use crate::url::normalize_navigation_url;
+
+pub const PORTABLE_FORMAT: &str = "switchboard.workspaces";
+pub const PORTABLE_FORMAT_VERSION: u64 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PortableTab {
+    pub url: String,
+    pub title: String,
+    pub pinned: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PortableWorkspace {
+    pub name: String,
+    pub tabs: Vec<PortableTab>,
+    pub active_tab_index: Option<usize>,
+}
+
+// Workspaces are the grouping unit in the state model, so a bundle is an
+// ordered list of them rather than a flat tab list with group labels.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PortableBundle {
+    pub profile_name: Option<String>,
+    pub workspaces: Vec<PortableWorkspace>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PortableError {
+    ProfileNotFound(ProfileId),
+    WorkspaceNotFound(WorkspaceId),
+    Malformed(String),
+    UnsupportedFormat(String),
+    UnsupportedVersion(u64),
+    MissingField(&'static str),
+    BlockedUrl(String),
+}
+
+impl Display for PortableError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::ProfileNotFound(profile_id) => write!(f, "{profile_id} does not exist"),
+            Self::WorkspaceNotFound(workspace_id) => write!(f, "{workspace_id} does not exist"),
+            Self::Malformed(message) => write!(f, "malformed workspace file: {message}"),
+            Self::UnsupportedFormat(format) => {
+                write!(f, "unsupported workspace file format: {format}")
+            }
+            Self::UnsupportedVersion(version) => write!(
+                f,
+                "workspace file version {version} is newer than supported version {PORTABLE_FORMAT_VERSION}"
+            ),
+            Self::MissingField(field) => write!(f, "workspace file is missing field: {field}"),
+            Self::BlockedUrl(url) => write!(f, "workspace file contains blocked url: {url}"),
+        }
+    }
+}
+
+impl std::error::Error for PortableError {}
+
+pub fn export_workspace(
+    state: &BrowserState,
+    workspace_id: WorkspaceId,
+) -> Result<PortableBundle, PortableError> {
+    let workspace = state
+        .workspaces
+        .get(&workspace_id)
+        .ok_or(PortableError::WorkspaceNotFound(workspace_id))?;
+    Ok(PortableBundle {
+        profile_name: None,
+        workspaces: vec![portable_workspace(state, workspace)],
+    })
+}
+
+pub fn export_profile(
+    state: &BrowserState,
+    profile_id: ProfileId,
+) -> Result<PortableBundle, PortableError> {
+    let profile = state
+        .profiles
+        .get(&profile_id)
+        .ok_or(PortableError::ProfileNotFound(profile_id))?;
+    let workspaces = profile
+        .workspace_order
+        .iter()
+        .filter_map(|workspace_id| state.workspaces.get(workspace_id))
+        .map(|workspace| portable_workspace(state, workspace))
+        .collect();
+    Ok(PortableBundle {
+        profile_name: Some(profile.name.clone()),
+        workspaces,
+    })
+}
+
+fn portable_workspace(state: &BrowserState, workspace: &Workspace) -> PortableWorkspace {
+    let live_tab_ids: Vec<_> = workspace
+        .tab_order
+        .iter()
+        .filter(|tab_id| state.tabs.contains_key(tab_id))
+        .copied()
+        .collect();
+    let tabs = live_tab_ids
+        .iter()
+        .filter_map(|tab_id| state.tabs.get(tab_id))
+        .map(|tab| PortableTab {
+            url: tab.url.clone(),
+            title: tab.title.clone(),
+            pinned: tab.pinned,
+        })
+        .collect();
+    let active_tab_index = workspace
+        .active_tab_id
+        .and_then(|active| live_tab_ids.iter().position(|tab_id| *tab_id == active));
+    PortableWorkspace {
+        name: workspace.name.clone(),
+        tabs,
+        active_tab_index,
+    }
+}
+
+impl PortableBundle {
+    pub fn tab_count(&self) -> usize {
+        self.workspaces
+            .iter()
+            .map(|workspace| workspace.tabs.len())
+            .sum()
+    }
+
+    pub fn to_json(&self) -> String {
+        let mut out = String::from("{\"format\":");
+        json::push_string(&mut out, PORTABLE_FORMAT);
+        out.push_str(&format!(",\"version\":{PORTABLE_FORMAT_VERSION}"));
+        if let Some(profile_name) = &self.profile_name {
+            out.push_str(",\"profile_name\":");
+            json::push_string(&mut out, profile_name);
+        }
+        out.push_str(",\"workspaces\":[");
+        for (workspace_index, workspace) in self.workspaces.iter().enumerate() {
+            if workspace_index > 0 {
+                out.push(',');
+            }
+            out.push_str("{\"name\":");
+            json::push_string(&mut out, &workspace.name);
+            out.push_str(",\"active_tab_index\":");
+            match workspace.active_tab_index {
+                Some(index) => out.push_str(&index.to_string()),
+                None => out.push_str("null"),
+            }
+            out.push_str(",\"tabs\":[");
+            for (tab_index, tab) in workspace.tabs.iter().enumerate() {
+                if tab_index > 0 {
+                    out.push(',');
+                }
+                out.push_str("{\"url\":");
+                json::push_string(&mut out, &tab.url);
+                out.push_str(",\"title\":");
+                json::push_string(&mut out, &tab.title);
+                out.push_str(&format!(",\"pinned\":{}}}", tab.pinned));
+            }
+            out.push_str("]}");
+        }
+        out.push_str("]}");
+        out
+    }
+
+    pub fn from_json(input: &str) -> Result<Self, PortableError> {
+        let root =
+            json::parse(input).map_err(|error| PortableError::Malformed(error.to_string()))?;
+        let format = root
+            .get("format")
+            .and_then(JsonValue::as_str)
+            .ok_or(PortableError::MissingField("format"))?;
+        if format != PORTABLE_FORMAT {
+            return Err(PortableError::UnsupportedFormat(format.to_owned()));
+        }
+        let version = root
+            .get("version")
+            .and_then(JsonValue::as_u64)
+            .ok_or(PortableError::MissingField("version"))?;
+        if version == 0 || version > PORTABLE_FORMAT_VERSION {
+            return Err(PortableError::UnsupportedVersion(version));
+        }
+        let profile_name = root
+            .get("profile_name")
+            .and_then(JsonValue::as_str)
+            .map(str::to_owned);
+        let workspaces = root
+            .get("workspaces")
+            .and_then(JsonValue::as_array)
+            .ok_or(PortableError::MissingField("workspaces"))?
+            .iter()
+            .map(parse_workspace)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self {
+            profile_name,
+            workspaces,
+        })
+    }
+}
+
+fn parse_workspace(value: &JsonValue) -> Result<PortableWorkspace, PortableError> {
+    let name = value
+        .get("name")
+        .and_then(JsonValue::as_str)
+        .ok_or(PortableError::MissingField("workspaces[].name"))?
+        .to_owned();
+    let tabs = value
+        .get("tabs")
+        .and_then(JsonValue::as_array)
+        .ok_or(PortableError::MissingField("workspaces[].tabs"))?
+        .iter()
+        .map(parse_tab)
+        .collect::<Result<Vec<_>, _>>()?;
+    let active_tab_index = value
+        .get("active_tab_index")
+        .and_then(JsonValue::as_u64)
+        .map(|index| index as usize)
+        .filter(|index| *index < tabs.len());
+    Ok(PortableWorkspace {
+        name,
+        tabs,
+        active_tab_index,
+    })
+}
+
+fn parse_tab(value: &JsonValue) -> Result<PortableTab, PortableError> {
+    let url = value
+        .get("url")
+        .and_then(JsonValue::as_str)
+        .map(str::trim)
+        .filter(|url| !url.is_empty())
+        .ok_or(PortableError::MissingField("workspaces[].tabs[].url"))?;
+    let url =
+        normalize_navigation_url(url).map_err(|_| PortableError::BlockedUrl(url.to_owned()))?;
+    let title = value
+        .get("title")
+        .and_then(JsonValue::as_str)
+        .unwrap_or_default()
+        .to_owned();
+    let pinned = value
+        .get("pinned")
+        .and_then(JsonValue::as_bool)
+        .unwrap_or(false);
+    Ok(PortableTab { url, title, pinned })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PortableBundle, PortableError, PortableTab, PortableWorkspace};
+
+    fn sample_bundle() -> PortableBundle {
+        PortableBundle {
+            profile_name: Some("Work \"Team\"".to_owned()),
+            workspaces: vec![
+                PortableWorkspace {
+                    name: "Research".to_owned(),
+                    tabs: vec![
+                        PortableTab {
+                            url: "https://example.com/a?q=1&x=\u{e9}".to_owned(),
+                            title: "A\ttab\n".to_owned(),
+                            pinned: true,
+                        },
+                        PortableTab {
+                            url: "https://example.com/b".to_owned(),
+                            title: String::new(),
+                            pinned: false,
+                        },
+                    ],
+                    active_tab_index: Some(1),
+                },
+                PortableWorkspace {
+                    name: "Empty".to_owned(),
+                    tabs: Vec::new(),
+                    active_tab_index: None,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn bundle_json_roundtrip_preserves_order_and_flags() {
+        let bundle = sample_bundle();
+        let parsed = PortableBundle::from_json(&bundle.to_json()).expect("bundle should parse");
+        assert_eq!(parsed, bundle);
+        assert_eq!(parsed.tab_count(), 2);
+    }
+
+    #[test]
+    fn rejects_newer_versions_and_foreign_formats() {
+        let newer = sample_bundle()
+            .to_json()
+            .replace("\"version\":1", "\"version\":99");
+        assert_eq!(
+            PortableBundle::from_json(&newer),
+            Err(PortableError::UnsupportedVersion(99))
+        );
+
+        let foreign = r#"{"format":"other","version":1,"workspaces":[]}"#;
+        assert!(matches!(
+            PortableBundle::from_json(foreign),
+            Err(PortableError::UnsupportedFormat(_))
+        ));
+        assert!(matches!(
+            PortableBundle::from_json("{\"format\":"),
+            Err(PortableError::Malformed(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_internal_scheme_tabs() {
+        let input = r#"{"format":"switchboard.workspaces","version":1,"workspaces":[
+            {"name":"W","tabs":[{"url":"app://ui","title":"x","pinned":false}]}
+        ]}"#;
+        assert_eq!(
+            PortableBundle::from_json(input),
+            Err(PortableError::BlockedUrl("app://ui".to_owned()))
+        );
+    }
+
+    #[test]
+    fn out_of_range_active_index_is_dropped() {
+        let input = r#"{"format":"switchboard.workspaces","version":1,"workspaces":[
+            {"name":"W","active_tab_index":5,"tabs":[{"url":"https://a.test"}]}
+        ]}"#;
+        let bundle = PortableBundle::from_json(input).expect("bundle should parse");
+        assert_eq!(bundle.workspaces[0].active_tab_index, None);
+        assert_eq!(bundle.workspaces[0].tabs[0].title, "");
+        assert!(!bundle.workspaces[0].tabs[0].pinned);
+    }
+}
@@ -1,9 +1,31 @@
 use std::collections::BTreeSet;
 
-use crate::ids::{ProfileId, TabId, WorkspaceId};
+use crate::downloads::{Download, DownloadCommand, DownloadState};
+use crate::history::HistoryClearScope;
+use crate::ids::{
+    BookmarkFolderId, BookmarkId, DownloadId, PermissionRequestId, ProfileId, SearchEngineId,
+    TabId, WorkspaceId,
+};
 use crate::intent::Intent;
+use crate::lifecycle::{is_lifecycle_setting, keep_alive_cap, LifecycleContext, LifecyclePolicy};
+use crate::navigation::{
+    normalize_exception_host, NavigationAllowed, NavigationBlockReason, NavigationBlocked,
+    NavigationPolicy, WindowDisposition,
+};
 use crate::patch::PatchOp;
-use crate::state::{BrowserState, SettingValue, Tab, TabRuntimeState, Workspace};
+use crate::portable::{export_profile, export_workspace, PortableTab, PortableWorkspace};
+use crate::search::{normalize_keyword, seed_search_engines, validate_search_engine};
+use crate::site_settings::{
+    normalize_origin, site_permission, PendingPermission, PermissionDecision, PermissionKind,
+    PermissionSetting,
+};
+use crate::state::{
+    Bookmark, BookmarkFolder, BrowserState, SearchEngine, SettingValue, Tab, TabRuntimeState,
+    TabStatus, Workspace,
+};
+use crate::url::{
+    normalize_configured_url, normalize_navigation_url, web_host, UrlError, BLANK_PAGE_URL,
+};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ReduceError {
@@ -18,18 +40,76 @@ pub enum ReduceError {
         to_profile: ProfileId,
     },
     CannotDiscardActiveTab(TabId),
+    BookmarkNotFound(BookmarkId),
+    BookmarkFolderNotFound(BookmarkFolderId),
+    BookmarkFolderProfileMismatch {
+        folder_id: BookmarkFolderId,
+        profile_id: ProfileId,
+    },
+    InvalidHistoryScope(HistoryClearScope),
+    SearchEngineNotFound(SearchEngineId),
+    InvalidSearchEngine(String),
+    DuplicateSearchKeyword {
+        profile_id: ProfileId,
+        keyword: String,
+    },
+    CannotRemoveBuiltinSearchEngine(SearchEngineId),
+    CannotRemoveDefaultSearchEngine(SearchEngineId),
+    InvalidUrl(UrlError),
+    NavigationBlocked(NavigationBlocked),
+    NoInsecureFallback(TabId),
+    PermissionRequestNotFound(PermissionRequestId),
+    DownloadNotFound(DownloadId),
+    InvalidDownloadTransition {
+        download_id: DownloadId,
+        state: DownloadState,
+    },
+    KeepAliveCapExceeded {
+        profile_id: ProfileId,
+        cap: usize,
+    },
 }
 
-const WARM_POOL_BUDGET_KEY: &str = "warm_pool_budget";
-const DEFAULT_WARM_POOL_BUDGET: usize = 8;
-const MAX_WARM_POOL_BUDGET: usize = 32;
 const HOMEPAGE_KEY: &str = "homepage";
 const NEW_TAB_BEHAVIOR_KEY: &str = "new_tab_behavior";
 const NEW_TAB_CUSTOM_URL_KEY: &str = "new_tab_custom_url";
+// CEF's ERR_ABORTED: the load was stopped or replaced by another navigation,
+// which is not a failure worth showing.
+const LOAD_ABORTED_ERROR_CODE: i32 = -3;
 
-pub fn apply_intent(state: &mut BrowserState, intent: Intent) -> Result<Vec<PatchOp>, ReduceError> {
+pub fn apply_intent(
+    state: &mut BrowserState,
+    intent: Intent,
+    policy: &dyn LifecyclePolicy,
+    now_ms: i64,
+) -> Result<Vec<PatchOp>, ReduceError> {
+    let now_ms = match intent {
+        Intent::LifecycleTick { now } => now,
+        _ => now_ms,
+    };
     let mut ops = Vec::new();
     let mut should_enforce_lifecycle = false;
+    let open_permission_requests: Vec<(TabId, PermissionRequestId)> = state
+        .tabs
+        .values()
+        .flat_map(|tab| {
+            tab.pending_permissions
+                .iter()
+                .map(move |pending| (tab.id, pending.request_id))
+        })
+        .collect();
+    let previously_active: Vec<TabId> = state
+        .tabs
+        .values()
+        .filter(|tab| tab.runtime_state == TabRuntimeState::Active)
+        .map(|tab| tab.id)
+        .collect();
+    let previous_focus = matches!(
+        intent,
+        Intent::ActivateTab { .. } | Intent::SwitchWorkspace { .. }
+    )
+    .then(|| active_focus(state))
+    .flatten();
 
     match intent {
         Intent::UiReady { .. } => {
@@ -55,6 +135,9 @@ pub fn apply_intent(state: &mut BrowserState, intent: Intent) -> Result<Vec<Patc
                 .ok_or(ReduceError::WorkspaceNotFound(workspace_id))?;
             ops.push(PatchOp::UpsertProfile(profile));
             ops.push(PatchOp::UpsertWorkspace(workspace));
+            for engine in seed_search_engines(state, profile_id) {
+                ops.push(PatchOp::UpsertSearchEngine(engine));
+            }
             ops.push(PatchOp::SetActiveProfile { profile_id });
             ops.push(PatchOp::SetActiveWorkspace {
                 profile_id,
@@ -105,6 +188,76 @@ pub fn apply_intent(state: &mut BrowserState, intent: Intent) -> Result<Vec<Patc
                 }
             }
 
+            state.bookmarks.retain(|bookmark_id, bookmark| {
+                if bookmark.profile_id != profile_id {
+                    return true;
+                }
+                ops.push(PatchOp::RemoveBookmark {
+                    bookmark_id: *bookmark_id,
+                    profile_id,
+                });
+                false
+            });
+            state.bookmark_folders.retain(|folder_id, folder| {
+                if folder.profile_id != profile_id {
+                    return true;
+                }
+                ops.push(PatchOp::RemoveBookmarkFolder {
+                    folder_id: *folder_id,
+                    profile_id,
+                });
+                false
+            });
+            state.search_engines.retain(|engine_id, engine| {
+                if engine.profile_id != profile_id {
+                    return true;
+                }
+                ops.push(PatchOp::RemoveSearchEngine {
+                    engine_id: *engine_id,
+                    profile_id,
+                });
+                false
+            });
+            for host in state
+                .https_exceptions
+                .remove(&profile_id)
+                .unwrap_or_default()
+            {
+                ops.push(PatchOp::RemoveHttpsException { profile_id, host });
+            }
+            for (origin, kind) in state
+                .site_permissions
+                .remove(&profile_id)
+                .unwrap_or_default()
+                .into_keys()
+            {
+                ops.push(PatchOp::RemoveSitePermission {
+                    profile_id,
+                    origin,
+                    kind,
+                });
+            }
+            state.downloads.retain(|download_id, download| {
+                if download.profile_id != profile_id {
+                    return true;
+                }
+                if !download.state.is_finished() {
+                    ops.push(PatchOp::DownloadCommandIssued {
+                        download_id: *download_id,
+                        command: DownloadCommand::Cancel,
+                    });
+                }
+                ops.push(PatchOp::RemoveDownload {
+                    download_id: *download_id,
+                    profile_id,
+                });
+                false
+            });
+            ops.push(PatchOp::HistoryCleared {
+                profile_id,
+                scope: HistoryClearScope::All,
+            });
+
             state.profiles.remove(&profile_id);
 
             if state.active_profile_id == Some(profile_id) {
@@ -144,7 +297,7 @@ pub fn apply_intent(state: &mut BrowserState, intent: Intent) -> Result<Vec<Patc
                 .ok_or(ReduceError::ProfileNotFound(profile_id))?;
             let active_workspace_id = profile.active_workspace_id;
             if state.active_profile_id == Some(profile_id) {
-                enforce_lifecycle_policy(state, &mut ops);
+                enforce_lifecycle_policy(state, policy, now_ms, &mut ops);
                 return Ok(ops);
             }
             state.active_profile_id = Some(profile_id);
@@ -211,6 +364,20 @@ pub fn apply_intent(state: &mut BrowserState, intent: Intent) -> Result<Vec<Patc
             tab.thumbnail_data_url = data_url;
             ops.push(PatchOp::UpsertTab(tab.clone()));
         }
+        Intent::ObserveTabFavicon {
+            tab_id,
+            favicon_key,
+        } => {
+            let tab = state
+                .tabs
+                .get_mut(&tab_id)
+                .ok_or(ReduceError::TabNotFound(tab_id))?;
+            if tab.favicon_key == favicon_key {
+                return Ok(ops);
+            }
+            tab.favicon_key = favicon_key;
+            ops.push(PatchOp::UpsertTab(tab.clone()));
+        }
         Intent::NewWorkspace { profile_id, name } => {
             should_enforce_lifecycle = true;
             if !state.profiles.contains_key(&profile_id) {
@@ -327,101 +494,99 @@ pub fn apply_intent(state: &mut BrowserState, intent: Intent) -> Result<Vec<Patc
             make_active,
         } => {
             should_enforce_lifecycle = true;
-            let profile_id = state
-                .workspaces
-                .get(&workspace_id)
-                .ok_or(ReduceError::WorkspaceNotFound(workspace_id))?
-                .profile_id;
-            let previous_active_tab = state
-                .workspaces
-                .get(&workspace_id)
-                .and_then(|workspace| workspace.active_tab_id);
-
-            if make_active {
-                if let Some(active_tab_id) = previous_active_tab {
-                    if let Some(active_tab) = state.tabs.get_mut(&active_tab_id) {
-                        active_tab.runtime_state = TabRuntimeState::Warm;
-                        ops.push(PatchOp::UpsertTab(active_tab.clone()));
-                    }
-                }
+            open_tab(state, workspace_id, url, make_active, None, &mut ops)?;
+        }
+        Intent::ObserveNewWindowRequest {
+            opener_tab_id,
+            url,
+            disposition,
+            user_gesture,
+        } => {
+            let opener = state
+                .tabs
+                .get(&opener_tab_id)
+                .ok_or(ReduceError::TabNotFound(opener_tab_id))?;
+            let popups_allowed = normalize_origin(&opener.url).is_ok_and(|origin| {
+                site_permission(state, opener.profile_id, &origin, PermissionKind::Popups)
+                    == PermissionSetting::Allow
+            });
+            if !user_gesture && !popups_allowed {
+                ops.push(PatchOp::NewWindowBlocked { opener_tab_id, url });
+                return Ok(ops);
             }
-
-            let tab_id = state.allocate_tab_id();
-            let resolved_url = url.unwrap_or_else(|| resolve_new_tab_url(state, workspace_id));
-            let tab = Tab {
-                id: tab_id,
-                profile_id,
+            should_enforce_lifecycle = true;
+            let workspace_id = opener.workspace_id;
+            open_tab(
+                state,
                 workspace_id,
-                url: resolved_url,
-                title: String::new(),
-                loading: false,
-                thumbnail_data_url: None,
-                pinned: false,
-                muted: false,
-                runtime_state: if make_active {
-                    TabRuntimeState::Active
-                } else {
-                    TabRuntimeState::Discarded
-                },
-            };
-            state.tabs.insert(tab_id, tab.clone());
-
-            let workspace = state
-                .workspaces
-                .get_mut(&workspace_id)
-                .ok_or(ReduceError::WorkspaceNotFound(workspace_id))?;
-            workspace.tab_order.push(tab_id);
-            if make_active {
-                workspace.active_tab_id = Some(tab_id);
-            }
-            ops.push(PatchOp::UpsertWorkspace(workspace.clone()));
-            if make_active {
-                ops.push(PatchOp::SetActiveTab {
-                    workspace_id,
-                    tab_id: Some(tab_id),
-                });
-            }
-
-            if make_active {
-                let profile_snapshot = {
-                    let profile = state
-                        .profiles
-                        .get_mut(&profile_id)
-                        .ok_or(ReduceError::ProfileNotFound(profile_id))?;
-                    profile.active_workspace_id = Some(workspace_id);
-                    profile.clone()
-                };
-                state.active_profile_id = Some(profile_id);
-                ops.push(PatchOp::UpsertProfile(profile_snapshot));
-                ops.push(PatchOp::SetActiveProfile { profile_id });
-                ops.push(PatchOp::SetActiveWorkspace {
-                    profile_id,
-                    workspace_id,
-                });
-            }
-
-            ops.push(PatchOp::UpsertTab(tab));
+                Some(url),
+                disposition != WindowDisposition::BackgroundTab,
+                Some(opener_tab_id),
+                &mut ops,
+            )?;
         }
         Intent::Navigate { tab_id, url } => {
+            let profile_id = state
+                .tabs
+                .get(&tab_id)
+                .ok_or(ReduceError::TabNotFound(tab_id))?
+                .profile_id;
+            let allowed = check_navigation(state, profile_id, &url)?;
             let tab = state
                 .tabs
                 .get_mut(&tab_id)
                 .ok_or(ReduceError::TabNotFound(tab_id))?;
-            if tab.url == url {
+            if tab.url == allowed.url && tab.blocked_navigation.is_none() {
+                // Entering the same address again retries a failed or
+                // crashed page.
+                if tab.status != TabStatus::Ok {
+                    reload_tab(tab, &mut ops);
+                }
                 return Ok(ops);
             }
-            tab.url = url;
+            tab.url = allowed.url;
+            tab.https_upgraded_from = allowed.upgraded_from;
+            tab.blocked_navigation = None;
+            tab.status = TabStatus::Ok;
+            tab.crash_count = 0;
             ops.push(PatchOp::UpsertTab(tab.clone()));
         }
         Intent::ObserveTabUrl { tab_id, url } => {
+            let profile_id = state
+                .tabs
+                .get(&tab_id)
+                .ok_or(ReduceError::TabNotFound(tab_id))?
+                .profile_id;
+            let decision = NavigationPolicy::for_profile(state, profile_id).evaluate(&url);
             let tab = state
                 .tabs
                 .get_mut(&tab_id)
                 .ok_or(ReduceError::TabNotFound(tab_id))?;
-            if tab.url == url {
-                return Ok(ops);
+            match decision {
+                // The blocked page itself is about:blank; seeing it load must
+                // not clear the blocked state.
+                Ok(allowed)
+                    if allowed.url == BLANK_PAGE_URL && tab.blocked_navigation.is_some() =>
+                {
+                    return Ok(ops);
+                }
+                Ok(allowed) => {
+                    if tab.url == allowed.url && tab.blocked_navigation.is_none() {
+                        return Ok(ops);
+                    }
+                    tab.url = allowed.url;
+                    if allowed.upgraded_from.is_some() {
+                        tab.https_upgraded_from = allowed.upgraded_from;
+                    }
+                    tab.blocked_navigation = None;
+                }
+                Err(blocked) => {
+                    if tab.blocked_navigation.as_ref() == Some(&blocked) {
+                        return Ok(ops);
+                    }
+                    tab.blocked_navigation = Some(blocked);
+                }
             }
-            tab.url = url;
             ops.push(PatchOp::UpsertTab(tab.clone()));
         }
         Intent::ObserveTabTitle { tab_id, title } => {
@@ -440,12 +605,176 @@ pub fn apply_intent(state: &mut BrowserState, intent: Intent) -> Result<Vec<Patc
                 .tabs
                 .get_mut(&tab_id)
                 .ok_or(ReduceError::TabNotFound(tab_id))?;
-            if tab.loading == is_loading {
+            // A new load replaces the error page of a failed one.
+            let clears_error = is_loading && matches!(tab.status, TabStatus::LoadFailed { .. });
+            if tab.loading == is_loading && !clears_error {
                 return Ok(ops);
             }
             tab.loading = is_loading;
+            if clears_error {
+                tab.status = TabStatus::Ok;
+            }
+            if !is_loading {
+                tab.https_upgraded_from = None;
+            }
             ops.push(PatchOp::UpsertTab(tab.clone()));
         }
+        Intent::ObserveTabLoadFailed {
+            tab_id,
+            url,
+            error_code,
+        } => {
+            let tab = state
+                .tabs
+                .get_mut(&tab_id)
+                .ok_or(ReduceError::TabNotFound(tab_id))?;
+            let failed_url = normalize_navigation_url(&url).unwrap_or(url);
+            if tab.url != failed_url || tab.status == TabStatus::Crashed {
+                return Ok(ops);
+            }
+            if let Some(insecure_url) = tab.https_upgraded_from.take() {
+                tab.blocked_navigation = Some(NavigationBlocked {
+                    url: insecure_url,
+                    reason: NavigationBlockReason::HttpsUnavailable,
+                });
+            } else {
+                let status = TabStatus::LoadFailed {
+                    url: failed_url,
+                    error_code,
+                };
+                if error_code == LOAD_ABORTED_ERROR_CODE || tab.status == status {
+                    return Ok(ops);
+                }
+                tab.status = status;
+            }
+            ops.push(PatchOp::UpsertTab(tab.clone()));
+        }
+        Intent::ObserveRendererCrashed { tab_id } => {
+            let tab = state
+                .tabs
+                .get_mut(&tab_id)
+                .ok_or(ReduceError::TabNotFound(tab_id))?;
+            if tab.status == TabStatus::Crashed {
+                return Ok(ops);
+            }
+            tab.status = TabStatus::Crashed;
+            tab.loading = false;
+            tab.audible = false;
+            tab.crash_count = tab.crash_count.saturating_add(1);
+            ops.push(PatchOp::UpsertTab(tab.clone()));
+        }
+        Intent::ReloadTab { tab_id } => {
+            let tab = state
+                .tabs
+                .get_mut(&tab_id)
+                .ok_or(ReduceError::TabNotFound(tab_id))?;
+            reload_tab(tab, &mut ops);
+        }
+        Intent::LifecycleTick { .. } => {
+            should_enforce_lifecycle = true;
+        }
+        Intent::ObserveTabMemory { tab_id, bytes } => {
+            if !state.tabs.contains_key(&tab_id) {
+                return Err(ReduceError::TabNotFound(tab_id));
+            }
+            should_enforce_lifecycle = state.tab_memory_bytes.insert(tab_id, bytes) != Some(bytes);
+        }
+        Intent::MemoryPressure { level } => {
+            should_enforce_lifecycle = state.memory_pressure != level;
+            state.memory_pressure = level;
+        }
+        Intent::ObserveTabAudible { tab_id, audible } => {
+            let tab = state
+                .tabs
+                .get_mut(&tab_id)
+                .ok_or(ReduceError::TabNotFound(tab_id))?;
+            if tab.audible == audible {
+                return Ok(ops);
+            }
+            tab.audible = audible;
+            ops.push(PatchOp::UpsertTab(tab.clone()));
+            // A tab that falls silent goes back under the warm budget.
+            should_enforce_lifecycle = !audible;
+        }
+        Intent::WarmTab { tab_id } => {
+            let tab = state
+                .tabs
+                .get(&tab_id)
+                .ok_or(ReduceError::TabNotFound(tab_id))?;
+            if tab.runtime_state == TabRuntimeState::Discarded {
+                let profile_id = tab.profile_id;
+                state.touch_warm_lru(profile_id, tab_id);
+                // Otherwise an old stamp would have the policy discard it
+                // again as idle.
+                state.tab_last_active_ms.insert(tab_id, now_ms);
+                should_enforce_lifecycle = true;
+            }
+        }
+        Intent::SetTabKeepAlive { tab_id, keep_alive } => {
+            let profile_id = state
+                .tabs
+                .get(&tab_id)
+                .ok_or(ReduceError::TabNotFound(tab_id))?
+                .profile_id;
+            let cap = keep_alive_cap(state);
+            let kept_alive = state
+                .tabs
+                .values()
+                .filter(|tab| tab.profile_id == profile_id && tab.keep_alive && tab.id != tab_id)
+                .count();
+            if keep_alive && kept_alive >= cap {
+                return Err(ReduceError::KeepAliveCapExceeded { profile_id, cap });
+            }
+            let tab = state
+                .tabs
+                .get_mut(&tab_id)
+                .ok_or(ReduceError::TabNotFound(tab_id))?;
+            if tab.keep_alive != keep_alive {
+                tab.keep_alive = keep_alive;
+                ops.push(PatchOp::UpsertTab(tab.clone()));
+                should_enforce_lifecycle = true;
+            }
+        }
+        Intent::SetTabMuted { tab_id, muted } => {
+            let tab = state
+                .tabs
+                .get_mut(&tab_id)
+                .ok_or(ReduceError::TabNotFound(tab_id))?;
+            set_tab_muted(tab, muted, &mut ops);
+            should_enforce_lifecycle = muted;
+        }
+        Intent::MuteOtherTabs { tab_id } => {
+            let profile_id = state
+                .tabs
+                .get(&tab_id)
+                .ok_or(ReduceError::TabNotFound(tab_id))?
+                .profile_id;
+            for tab in state
+                .tabs
+                .values_mut()
+                .filter(|tab| tab.profile_id == profile_id && tab.id != tab_id)
+            {
+                set_tab_muted(tab, true, &mut ops);
+            }
+            should_enforce_lifecycle = true;
+        }
+        Intent::SetWorkspaceMuted {
+            workspace_id,
+            muted,
+        } => {
+            let tab_order = state
+                .workspaces
+                .get(&workspace_id)
+                .ok_or(ReduceError::WorkspaceNotFound(workspace_id))?
+                .tab_order
+                .clone();
+            for tab_id in tab_order {
+                if let Some(tab) = state.tabs.get_mut(&tab_id) {
+                    set_tab_muted(tab, muted, &mut ops);
+                }
+            }
+            should_enforce_lifecycle = muted;
+        }
         Intent::PinTab { tab_id, pinned } => {
             let tab = state
                 .tabs
@@ -453,6 +782,7 @@ pub fn apply_intent(state: &mut BrowserState, intent: Intent) -> Result<Vec<Patc
                 .ok_or(ReduceError::TabNotFound(tab_id))?;
             tab.pinned = pinned;
             ops.push(PatchOp::UpsertTab(tab.clone()));
+            should_enforce_lifecycle = true;
         }
         Intent::ActivateTab { tab_id } => {
             should_enforce_lifecycle = true;
@@ -481,7 +811,7 @@ pub fn apply_intent(state: &mut BrowserState, intent: Intent) -> Result<Vec<Patc
                 && profile_active
                 && runtime_state == TabRuntimeState::Active
             {
-                enforce_lifecycle_policy(state, &mut ops);
+                enforce_lifecycle_policy(state, policy, now_ms, &mut ops);
                 return Ok(ops);
             }
 
@@ -621,7 +951,7 @@ pub fn apply_intent(state: &mut BrowserState, intent: Intent) -> Result<Vec<Patc
                 let insert_at = index.min(workspace.tab_order.len());
                 workspace.tab_order.insert(insert_at, tab_id);
                 ops.push(PatchOp::UpsertWorkspace(workspace.clone()));
-                enforce_lifecycle_policy(state, &mut ops);
+                enforce_lifecycle_policy(state, policy, now_ms, &mut ops);
                 return Ok(ops);
             }
 
@@ -702,6 +1032,7 @@ pub fn apply_intent(state: &mut BrowserState, intent: Intent) -> Result<Vec<Patc
                 .get_mut(&tab_id)
                 .ok_or(ReduceError::TabNotFound(tab_id))?;
             tab.runtime_state = TabRuntimeState::Discarded;
+            tab.audible = false;
             ops.push(PatchOp::UpsertTab(tab.clone()));
             state.remove_from_warm_lru(profile_id, tab_id);
             ops.push(PatchOp::SetActiveTab {
@@ -713,22 +1044,935 @@ pub fn apply_intent(state: &mut BrowserState, intent: Intent) -> Result<Vec<Patc
             });
         }
         Intent::SettingSet { key, value } => {
-            if key == WARM_POOL_BUDGET_KEY {
+            if is_lifecycle_setting(&key) {
                 should_enforce_lifecycle = true;
             }
             state.settings.insert(key.clone(), value.clone());
             ops.push(PatchOp::SettingChanged { key, value });
         }
+        Intent::ExportWorkspace { workspace_id, path } => {
+            let bundle = export_workspace(state, workspace_id)
+                .map_err(|_| ReduceError::WorkspaceNotFound(workspace_id))?;
+            ops.push(PatchOp::WorkspacesExported { path, bundle });
+        }
+        Intent::ExportProfile { profile_id, path } => {
+            let bundle = export_profile(state, profile_id)
+                .map_err(|_| ReduceError::ProfileNotFound(profile_id))?;
+            ops.push(PatchOp::WorkspacesExported { path, bundle });
+        }
+        Intent::ImportWorkspaces { profile_id, bundle } => {
+            if !state.profiles.contains_key(&profile_id) {
+                return Err(ReduceError::ProfileNotFound(profile_id));
+            }
+            if bundle.workspaces.is_empty() {
+                return Ok(ops);
+            }
+
+            insert_portable_workspaces(state, profile_id, bundle.workspaces, &mut ops);
+            if let Some(profile) = state.profiles.get(&profile_id) {
+                ops.push(PatchOp::UpsertProfile(profile.clone()));
+            }
+        }
+        Intent::AddBookmark {
+            profile_id,
+            folder_id,
+            url,
+            title,
+        } => {
+            if !state.profiles.contains_key(&profile_id) {
+                return Err(ReduceError::ProfileNotFound(profile_id));
+            }
+            ensure_bookmark_folder(state, profile_id, folder_id)?;
+            let url = normalize_navigation_url(&url).map_err(ReduceError::InvalidUrl)?;
+            let position = state.bookmarks_in_folder(profile_id, folder_id).len();
+            let bookmark_id = state.allocate_bookmark_id();
+            let bookmark = Bookmark {
+                id: bookmark_id,
+                profile_id,
+                folder_id,
+                url,
+                title,
+                position,
+            };
+            state.bookmarks.insert(bookmark_id, bookmark.clone());
+            ops.push(PatchOp::UpsertBookmark(bookmark));
+        }
+        Intent::RemoveBookmark { bookmark_id } => {
+            let bookmark = state
+                .bookmarks
+                .remove(&bookmark_id)
+                .ok_or(ReduceError::BookmarkNotFound(bookmark_id))?;
+            ops.push(PatchOp::RemoveBookmark {
+                bookmark_id,
+                profile_id: bookmark.profile_id,
+            });
+            reindex_bookmarks(
+                state,
+                bookmark.profile_id,
+                bookmark.folder_id,
+                None,
+                &mut ops,
+            );
+        }
+        Intent::MoveBookmark {
+            bookmark_id,
+            folder_id,
+            index,
+        } => {
+            let (profile_id, source_folder_id) = {
+                let bookmark = state
+                    .bookmarks
+                    .get(&bookmark_id)
+                    .ok_or(ReduceError::BookmarkNotFound(bookmark_id))?;
+                (bookmark.profile_id, bookmark.folder_id)
+            };
+            ensure_bookmark_folder(state, profile_id, folder_id)?;
+            if let Some(bookmark) = state.bookmarks.get_mut(&bookmark_id) {
+                bookmark.folder_id = folder_id;
+            }
+            reindex_bookmarks(
+                state,
+                profile_id,
+                folder_id,
+                Some((bookmark_id, index)),
+                &mut ops,
+            );
+            if source_folder_id != folder_id {
+                if let Some(bookmark) = state.bookmarks.get(&bookmark_id) {
+                    if !ops.iter().any(|op| {
+                        matches!(op, PatchOp::UpsertBookmark(candidate) if candidate.id == bookmark_id)
+                    }) {
+                        ops.push(PatchOp::UpsertBookmark(bookmark.clone()));
+                    }
+                }
+                reindex_bookmarks(state, profile_id, source_folder_id, None, &mut ops);
+            }
+        }
+        Intent::RenameBookmark { bookmark_id, title } => {
+            let bookmark = state
+                .bookmarks
+                .get_mut(&bookmark_id)
+                .ok_or(ReduceError::BookmarkNotFound(bookmark_id))?;
+            if bookmark.title == title {
+                return Ok(ops);
+            }
+            bookmark.title = title;
+            ops.push(PatchOp::UpsertBookmark(bookmark.clone()));
+        }
+        Intent::CreateBookmarkFolder {
+            profile_id,
+            parent_id,
+            name,
+        } => {
+            if !state.profiles.contains_key(&profile_id) {
+                return Err(ReduceError::ProfileNotFound(profile_id));
+            }
+            ensure_bookmark_folder(state, profile_id, parent_id)?;
+            let folder_id = state.allocate_bookmark_folder_id();
+            let folder = BookmarkFolder {
+                id: folder_id,
+                profile_id,
+                parent_id,
+                name,
+            };
+            state.bookmark_folders.insert(folder_id, folder.clone());
+            ops.push(PatchOp::UpsertBookmarkFolder(folder));
+        }
+        Intent::RenameBookmarkFolder { folder_id, name } => {
+            let folder = state
+                .bookmark_folders
+                .get_mut(&folder_id)
+                .ok_or(ReduceError::BookmarkFolderNotFound(folder_id))?;
+            if folder.name == name {
+                return Ok(ops);
+            }
+            folder.name = name;
+            ops.push(PatchOp::UpsertBookmarkFolder(folder.clone()));
+        }
+        Intent::RemoveBookmarkFolder { folder_id } => {
+            let profile_id = state
+                .bookmark_folders
+                .get(&folder_id)
+                .ok_or(ReduceError::BookmarkFolderNotFound(folder_id))?
+                .profile_id;
+            let removed_folders = bookmark_folder_subtree(state, folder_id);
+            state.bookmarks.retain(|bookmark_id, bookmark| {
+                let removed = bookmark
+                    .folder_id
+                    .map(|id| removed_folders.contains(&id))
+                    .unwrap_or(false);
+                if removed {
+                    ops.push(PatchOp::RemoveBookmark {
+                        bookmark_id: *bookmark_id,
+                        profile_id,
+                    });
+                }
+                !removed
+            });
+            for removed_folder_id in removed_folders {
+                state.bookmark_folders.remove(&removed_folder_id);
+                ops.push(PatchOp::RemoveBookmarkFolder {
+                    folder_id: removed_folder_id,
+                    profile_id,
+                });
+            }
+        }
+        Intent::ClearHistory { profile_id, scope } => {
+            if !state.profiles.contains_key(&profile_id) {
+                return Err(ReduceError::ProfileNotFound(profile_id));
+            }
+            let valid = match &scope {
+                HistoryClearScope::All => true,
+                HistoryClearScope::TimeRange { start_ms, end_ms } => start_ms < end_ms,
+                HistoryClearScope::Domain(domain) => !domain.trim().is_empty(),
+            };
+            if !valid {
+                return Err(ReduceError::InvalidHistoryScope(scope));
+            }
+            ops.push(PatchOp::HistoryCleared { profile_id, scope });
+        }
+        Intent::AddSearchEngine {
+            profile_id,
+            name,
+            keyword,
+            url_template,
+            make_default,
+        } => {
+            if !state.profiles.contains_key(&profile_id) {
+                return Err(ReduceError::ProfileNotFound(profile_id));
+            }
+            let keyword = normalize_keyword(&keyword);
+            validate_search_engine(&name, &keyword, &url_template)
+                .map_err(ReduceError::InvalidSearchEngine)?;
+            ensure_unique_search_keyword(state, profile_id, &keyword, None)?;
+            let engine_id = state.allocate_search_engine_id();
+            state.search_engines.insert(
+                engine_id,
+                SearchEngine {
+                    id: engine_id,
+                    profile_id,
+                    name: name.trim().to_owned(),
+                    keyword,
+                    url_template: url_template.trim().to_owned(),
+                    is_default: false,
+                    builtin: false,
+                },
+            );
+            if make_default {
+                set_default_search_engine(state, engine_id, &mut ops);
+            } else if let Some(engine) = state.search_engines.get(&engine_id) {
+                ops.push(PatchOp::UpsertSearchEngine(engine.clone()));
+            }
+        }
+        Intent::UpdateSearchEngine {
+            engine_id,
+            name,
+            keyword,
+            url_template,
+        } => {
+            let profile_id = state
+                .search_engines
+                .get(&engine_id)
+                .ok_or(ReduceError::SearchEngineNotFound(engine_id))?
+                .profile_id;
+            let keyword = normalize_keyword(&keyword);
+            validate_search_engine(&name, &keyword, &url_template)
+                .map_err(ReduceError::InvalidSearchEngine)?;
+            ensure_unique_search_keyword(state, profile_id, &keyword, Some(engine_id))?;
+            let engine = state
+                .search_engines
+                .get_mut(&engine_id)
+                .ok_or(ReduceError::SearchEngineNotFound(engine_id))?;
+            engine.name = name.trim().to_owned();
+            engine.keyword = keyword;
+            engine.url_template = url_template.trim().to_owned();
+            ops.push(PatchOp::UpsertSearchEngine(engine.clone()));
+        }
+        Intent::RemoveSearchEngine { engine_id } => {
+            let engine = state
+                .search_engines
+                .get(&engine_id)
+                .ok_or(ReduceError::SearchEngineNotFound(engine_id))?;
+            if engine.builtin {
+                return Err(ReduceError::CannotRemoveBuiltinSearchEngine(engine_id));
+            }
+            if engine.is_default {
+                return Err(ReduceError::CannotRemoveDefaultSearchEngine(engine_id));
+            }
+            let profile_id = engine.profile_id;
+            state.search_engines.remove(&engine_id);
+            ops.push(PatchOp::RemoveSearchEngine {
+                engine_id,
+                profile_id,
+            });
+        }
+        Intent::SetDefaultSearchEngine { engine_id } => {
+            if !state.search_engines.contains_key(&engine_id) {
+                return Err(ReduceError::SearchEngineNotFound(engine_id));
+            }
+            set_default_search_engine(state, engine_id, &mut ops);
+        }
+        Intent::ContinueInsecure { tab_id } => {
+            let tab = state
+                .tabs
+                .get(&tab_id)
+                .ok_or(ReduceError::TabNotFound(tab_id))?;
+            let insecure_url = match &tab.blocked_navigation {
+                Some(NavigationBlocked {
+                    url,
+                    reason: NavigationBlockReason::HttpsUnavailable,
+                }) => url.clone(),
+                _ => return Err(ReduceError::NoInsecureFallback(tab_id)),
+            };
+            let profile_id = tab.profile_id;
+            let host = normalize_exception_host(&web_host(&insecure_url).unwrap_or_default())
+                .map_err(ReduceError::InvalidUrl)?;
+            add_https_exception(state, profile_id, host, &mut ops);
+            let allowed = check_navigation(state, profile_id, &insecure_url)?;
+            let tab = state
+                .tabs
+                .get_mut(&tab_id)
+                .ok_or(ReduceError::TabNotFound(tab_id))?;
+            tab.url = allowed.url;
+            tab.https_upgraded_from = None;
+            tab.blocked_navigation = None;
+            ops.push(PatchOp::UpsertTab(tab.clone()));
+        }
+        Intent::AddHttpsException { profile_id, host } => {
+            if !state.profiles.contains_key(&profile_id) {
+                return Err(ReduceError::ProfileNotFound(profile_id));
+            }
+            let host = normalize_exception_host(&host).map_err(ReduceError::InvalidUrl)?;
+            add_https_exception(state, profile_id, host, &mut ops);
+        }
+        Intent::RemoveHttpsException { profile_id, host } => {
+            if !state.profiles.contains_key(&profile_id) {
+                return Err(ReduceError::ProfileNotFound(profile_id));
+            }
+            let host = normalize_exception_host(&host).map_err(ReduceError::InvalidUrl)?;
+            let hosts = state.https_exceptions.entry(profile_id).or_default();
+            if hosts.remove(&host) {
+                ops.push(PatchOp::RemoveHttpsException { profile_id, host });
+            }
+            if hosts.is_empty() {
+                state.https_exceptions.remove(&profile_id);
+            }
+        }
+        Intent::SetSitePermission {
+            profile_id,
+            origin,
+            kind,
+            setting,
+        } => {
+            if !state.profiles.contains_key(&profile_id) {
+                return Err(ReduceError::ProfileNotFound(profile_id));
+            }
+            let origin = normalize_origin(&origin).map_err(ReduceError::InvalidUrl)?;
+            store_site_permission(state, profile_id, &origin, kind, setting, &mut ops);
+        }
+        Intent::ResetSitePermission {
+            profile_id,
+            origin,
+            kind,
+        } => {
+            if !state.profiles.contains_key(&profile_id) {
+                return Err(ReduceError::ProfileNotFound(profile_id));
+            }
+            let origin = normalize_origin(&origin).map_err(ReduceError::InvalidUrl)?;
+            let entries = state.site_permissions.entry(profile_id).or_default();
+            let kinds: Vec<PermissionKind> = match kind {
+                Some(kind) => vec![kind],
+                None => PermissionKind::ALL.to_vec(),
+            };
+            for kind in kinds {
+                if entries.remove(&(origin.clone(), kind)).is_some() {
+                    ops.push(PatchOp::RemoveSitePermission {
+                        profile_id,
+                        origin: origin.clone(),
+                        kind,
+                    });
+                }
+            }
+            if entries.is_empty() {
+                state.site_permissions.remove(&profile_id);
+            }
+        }
+        Intent::ObservePermissionRequest {
+            tab_id,
+            request_id,
+            origin,
+            kind,
+        } => {
+            let tab = state
+                .tabs
+                .get(&tab_id)
+                .ok_or(ReduceError::TabNotFound(tab_id))?;
+            let setting = match normalize_origin(&origin) {
+                Ok(origin) if tab.runtime_state != TabRuntimeState::Discarded => {
+                    let setting = site_permission(state, tab.profile_id, &origin, kind);
+                    if setting == PermissionSetting::Ask {
+                        let tab = state
+                            .tabs
+                            .get_mut(&tab_id)
+                            .ok_or(ReduceError::TabNotFound(tab_id))?;
+                        if tab
+                            .pending_permissions
+                            .iter()
+                            .all(|pending| pending.request_id != request_id)
+                        {
+                            tab.pending_permissions.push(PendingPermission {
+                                request_id,
+                                origin,
+                                kind,
+                            });
+                            ops.push(PatchOp::UpsertTab(tab.clone()));
+                        }
+                        return Ok(ops);
+                    }
+                    setting
+                }
+                _ => PermissionSetting::Block,
+            };
+            ops.push(PatchOp::PermissionRequestSettled {
+                tab_id,
+                request_id,
+                granted: setting == PermissionSetting::Allow,
+            });
+        }
+        Intent::ResolvePermission {
+            request_id,
+            decision,
+            remember,
+        } => {
+            let (profile_id, pending) = state
+                .tabs
+                .values()
+                .find_map(|tab| {
+                    tab.pending_permissions
+                        .iter()
+                        .find(|pending| pending.request_id == request_id)
+                        .map(|pending| (tab.profile_id, pending.clone()))
+                })
+                .ok_or(ReduceError::PermissionRequestNotFound(request_id))?;
+            if remember {
+                store_site_permission(
+                    state,
+                    profile_id,
+                    &pending.origin,
+                    pending.kind,
+                    decision.setting(),
+                    &mut ops,
+                );
+            } else {
+                let granted = decision == PermissionDecision::Allow;
+                settle_pending_permissions(
+                    state,
+                    profile_id,
+                    |candidate| (candidate.request_id == request_id).then_some(granted),
+                    &mut ops,
+                );
+            }
+        }
+        Intent::ObserveDownloadStarted {
+            tab_id,
+            url,
+            target_path,
+            total_bytes,
+        } => {
+            let profile_id = state
+                .tabs
+                .get(&tab_id)
+                .map(|tab| tab.profile_id)
+                .ok_or(ReduceError::TabNotFound(tab_id))?;
+            let download_id = state.allocate_download_id();
+            let download = Download {
+                id: download_id,
+                profile_id,
+                tab_id: Some(tab_id),
+                url,
+                target_path,
+                received_bytes: 0,
+                total_bytes,
+                state: DownloadState::InProgress,
+            };
+            state.downloads.insert(download_id, download.clone());
+            ops.push(PatchOp::UpsertDownload(download));
+        }
+        Intent::ObserveDownloadProgress {
+            download_id,
+            received_bytes,
+            total_bytes,
+            state: download_state,
+        } => {
+            let download = state
+                .downloads
+                .get_mut(&download_id)
+                .ok_or(ReduceError::DownloadNotFound(download_id))?;
+            // Progress can race a cancel; a finished download stays as it is.
+            if download.state.is_finished() {
+                return Ok(ops);
+            }
+            let updated = Download {
+                received_bytes,
+                total_bytes: total_bytes.or(download.total_bytes),
+                state: download_state,
+                ..download.clone()
+            };
+            if updated != *download {
+                *download = updated;
+                ops.push(PatchOp::UpsertDownload(download.clone()));
+            }
+        }
+        Intent::PauseDownload { download_id } => {
+            transition_download(
+                state,
+                download_id,
+                &[DownloadState::InProgress],
+                DownloadState::Paused,
+                DownloadCommand::Pause,
+                &mut ops,
+            )?;
+        }
+        Intent::ResumeDownload { download_id } => {
+            transition_download(
+                state,
+                download_id,
+                &[DownloadState::Paused],
+                DownloadState::InProgress,
+                DownloadCommand::Resume,
+                &mut ops,
+            )?;
+        }
+        Intent::CancelDownload { download_id } => {
+            transition_download(
+                state,
+                download_id,
+                &[DownloadState::InProgress, DownloadState::Paused],
+                DownloadState::Cancelled,
+                DownloadCommand::Cancel,
+                &mut ops,
+            )?;
+        }
+        Intent::RemoveDownload { download_id } => {
+            let download = state
+                .downloads
+                .remove(&download_id)
+                .ok_or(ReduceError::DownloadNotFound(download_id))?;
+            if !download.state.is_finished() {
+                ops.push(PatchOp::DownloadCommandIssued {
+                    download_id,
+                    command: DownloadCommand::Cancel,
+                });
+            }
+            ops.push(PatchOp::RemoveDownload {
+                download_id,
+                profile_id: download.profile_id,
+            });
+        }
+        Intent::OpenDownloadFolder { download_id } => {
+            let download = state
+                .downloads
+                .get(&download_id)
+                .ok_or(ReduceError::DownloadNotFound(download_id))?;
+            ops.push(PatchOp::DownloadFolderOpened {
+                download_id,
+                target_path: download.target_path.clone(),
+            });
+        }
+        Intent::OpenBookmarkFolderAsWorkspace { folder_id } => {
+            let folder = state
+                .bookmark_folders
+                .get(&folder_id)
+                .cloned()
+                .ok_or(ReduceError::BookmarkFolderNotFound(folder_id))?;
+            let profile_id = folder.profile_id;
+            if !state.profiles.contains_key(&profile_id) {
+                return Err(ReduceError::ProfileNotFound(profile_id));
+            }
+            let tabs = collect_bookmark_folder_tabs(state, profile_id, folder_id);
+            insert_portable_workspaces(
+                state,
+                profile_id,
+                vec![PortableWorkspace {
+                    name: folder.name,
+                    tabs,
+                    active_tab_index: None,
+                }],
+                &mut ops,
+            );
+            if let Some(profile) = state.profiles.get(&profile_id) {
+                ops.push(PatchOp::UpsertProfile(profile.clone()));
+            }
+        }
     }
 
+    // A tab the intent moved out of Active was active until now; without the
+    // stamp the policy could take it for an idle one.
+    for tab_id in previously_active {
+        if state.tabs.contains_key(&tab_id) {
+            state.tab_last_active_ms.insert(tab_id, now_ms);
+        }
+    }
+    if let Some(from) = previous_focus {
+        learn_switch(state, from);
+    }
     if should_enforce_lifecycle {
-        enforce_lifecycle_policy(state, &mut ops);
+        enforce_lifecycle_policy(state, policy, now_ms, &mut ops);
     }
+    deny_abandoned_permission_requests(state, &open_permission_requests, &mut ops);
 
     Ok(ops)
 }
 
-fn enforce_lifecycle_policy(state: &mut BrowserState, ops: &mut Vec<PatchOp>) {
+// Opens a tab in `workspace_id`, appended to the end or, for a tab opened by
+// a page, placed after its opener and any tabs the opener already spawned.
+fn open_tab(
+    state: &mut BrowserState,
+    workspace_id: WorkspaceId,
+    url: Option<String>,
+    make_active: bool,
+    opener_tab_id: Option<TabId>,
+    ops: &mut Vec<PatchOp>,
+) -> Result<TabId, ReduceError> {
+    let profile_id = state
+        .workspaces
+        .get(&workspace_id)
+        .ok_or(ReduceError::WorkspaceNotFound(workspace_id))?
+        .profile_id;
+    let previous_active_tab = state
+        .workspaces
+        .get(&workspace_id)
+        .and_then(|workspace| workspace.active_tab_id);
+    let allowed = url
+        .map(|url| check_navigation(state, profile_id, &url))
+        .transpose()?;
+
+    if make_active {
+        if let Some(active_tab_id) = previous_active_tab {
+            if let Some(active_tab) = state.tabs.get_mut(&active_tab_id) {
+                active_tab.runtime_state = TabRuntimeState::Warm;
+                ops.push(PatchOp::UpsertTab(active_tab.clone()));
+            }
+        }
+    }
+
+    let (url, https_upgraded_from) = match allowed {
+        Some(allowed) => (Some(allowed.url), allowed.upgraded_from),
+        None => (None, None),
+    };
+    let tab_id = state.allocate_tab_id();
+    let resolved_url = url.unwrap_or_else(|| resolve_new_tab_url(state, workspace_id));
+    let tab = Tab {
+        id: tab_id,
+        profile_id,
+        workspace_id,
+        url: resolved_url,
+        title: String::new(),
+        loading: false,
+        thumbnail_data_url: None,
+        favicon_key: None,
+        pinned: false,
+        keep_alive: false,
+        muted: false,
+        audible: false,
+        runtime_state: if make_active {
+            TabRuntimeState::Active
+        } else {
+            TabRuntimeState::Discarded
+        },
+        blocked_navigation: None,
+        https_upgraded_from,
+        pending_permissions: Vec::new(),
+        opener_tab_id,
+        status: TabStatus::Ok,
+        crash_count: 0,
+    };
+    let workspace = state
+        .workspaces
+        .get(&workspace_id)
+        .ok_or(ReduceError::WorkspaceNotFound(workspace_id))?;
+    let index = match opener_tab_id
+        .and_then(|opener| workspace.tab_order.iter().position(|id| *id == opener))
+    {
+        Some(opener_index) => {
+            let spawned = workspace.tab_order[opener_index + 1..]
+                .iter()
+                .take_while(|id| {
+                    state.tabs.get(id).and_then(|tab| tab.opener_tab_id) == opener_tab_id
+                })
+                .count();
+            opener_index + 1 + spawned
+        }
+        None => workspace.tab_order.len(),
+    };
+    state.tabs.insert(tab_id, tab.clone());
+
+    let workspace = state
+        .workspaces
+        .get_mut(&workspace_id)
+        .ok_or(ReduceError::WorkspaceNotFound(workspace_id))?;
+    workspace.tab_order.insert(index, tab_id);
+    if make_active {
+        workspace.active_tab_id = Some(tab_id);
+    }
+    ops.push(PatchOp::UpsertWorkspace(workspace.clone()));
+    if make_active {
+        ops.push(PatchOp::SetActiveTab {
+            workspace_id,
+            tab_id: Some(tab_id),
+        });
+    }
+
+    if make_active {
+        let profile_snapshot = {
+            let profile = state
+                .profiles
+                .get_mut(&profile_id)
+                .ok_or(ReduceError::ProfileNotFound(profile_id))?;
+            profile.active_workspace_id = Some(workspace_id);
+            profile.clone()
+        };
+        state.active_profile_id = Some(profile_id);
+        ops.push(PatchOp::UpsertProfile(profile_snapshot));
+        ops.push(PatchOp::SetActiveProfile { profile_id });
+        ops.push(PatchOp::SetActiveWorkspace {
+            profile_id,
+            workspace_id,
+        });
+    }
+
+    ops.push(PatchOp::UpsertTab(tab));
+    Ok(tab_id)
+}
+
+// Moves a download from one of `from` to `to` and asks the host to follow.
+// Already being in `to` is a no-op so repeated clicks are harmless.
+fn transition_download(
+    state: &mut BrowserState,
+    download_id: DownloadId,
+    from: &[DownloadState],
+    to: DownloadState,
+    command: DownloadCommand,
+    ops: &mut Vec<PatchOp>,
+) -> Result<(), ReduceError> {
+    let download = state
+        .downloads
+        .get_mut(&download_id)
+        .ok_or(ReduceError::DownloadNotFound(download_id))?;
+    if download.state == to {
+        return Ok(());
+    }
+    if !from.contains(&download.state) {
+        return Err(ReduceError::InvalidDownloadTransition {
+            download_id,
+            state: download.state,
+        });
+    }
+    download.state = to;
+    ops.push(PatchOp::UpsertDownload(download.clone()));
+    ops.push(PatchOp::DownloadCommandIssued {
+        download_id,
+        command,
+    });
+    Ok(())
+}
+
+// Imported tabs start Discarded and imported workspaces are appended without
+// becoming active, so no content views are created until the user opens them.
+fn insert_portable_workspaces(
+    state: &mut BrowserState,
+    profile_id: ProfileId,
+    workspaces: Vec<PortableWorkspace>,
+    ops: &mut Vec<PatchOp>,
+) {
+    for imported in workspaces {
+        let workspace_id = state.allocate_workspace_id();
+        let mut tab_order = Vec::with_capacity(imported.tabs.len());
+        for imported_tab in imported.tabs {
+            let tab_id = state.allocate_tab_id();
+            let tab = Tab {
+                id: tab_id,
+                profile_id,
+                workspace_id,
+                url: imported_tab.url,
+                title: imported_tab.title,
+                loading: false,
+                thumbnail_data_url: None,
+                favicon_key: None,
+                pinned: imported_tab.pinned,
+                keep_alive: false,
+                muted: false,
+                audible: false,
+                runtime_state: TabRuntimeState::Discarded,
+                blocked_navigation: None,
+                https_upgraded_from: None,
+                pending_permissions: Vec::new(),
+                opener_tab_id: None,
+                status: TabStatus::Ok,
+                crash_count: 0,
+            };
+            state.tabs.insert(tab_id, tab.clone());
+            tab_order.push(tab_id);
+            ops.push(PatchOp::UpsertTab(tab));
+        }
+        let active_tab_id = imported
+            .active_tab_index
+            .and_then(|index| tab_order.get(index))
+            .or_else(|| tab_order.first())
+            .copied();
+        let workspace = Workspace {
+            id: workspace_id,
+            profile_id,
+            name: imported.name,
+            tab_order,
+            active_tab_id,
+        };
+        state.workspaces.insert(workspace_id, workspace.clone());
+        ops.push(PatchOp::UpsertWorkspace(workspace));
+
+        if let Some(profile) = state.profiles.get_mut(&profile_id) {
+            profile.workspace_order.push(workspace_id);
+        }
+    }
+}
+
+fn ensure_bookmark_folder(
+    state: &BrowserState,
+    profile_id: ProfileId,
+    folder_id: Option<BookmarkFolderId>,
+) -> Result<(), ReduceError> {
+    let Some(folder_id) = folder_id else {
+        return Ok(());
+    };
+    let folder = state
+        .bookmark_folders
+        .get(&folder_id)
+        .ok_or(ReduceError::BookmarkFolderNotFound(folder_id))?;
+    if folder.profile_id != profile_id {
+        return Err(ReduceError::BookmarkFolderProfileMismatch {
+            folder_id,
+            profile_id,
+        });
+    }
+    Ok(())
+}
+
+// Rewrites sibling positions to 0..n, optionally placing `insert` at an index,
+// and emits an upsert for every bookmark whose position changed.
+fn reindex_bookmarks(
+    state: &mut BrowserState,
+    profile_id: ProfileId,
+    folder_id: Option<BookmarkFolderId>,
+    insert: Option<(BookmarkId, usize)>,
+    ops: &mut Vec<PatchOp>,
+) {
+    let mut order: Vec<BookmarkId> = state
+        .bookmarks_in_folder(profile_id, folder_id)
+        .into_iter()
+        .map(|bookmark| bookmark.id)
+        .filter(|bookmark_id| Some(*bookmark_id) != insert.map(|(id, _)| id))
+        .collect();
+    if let Some((bookmark_id, index)) = insert {
+        let insert_at = index.min(order.len());
+        order.insert(insert_at, bookmark_id);
+    }
+    for (position, bookmark_id) in order.into_iter().enumerate() {
+        if let Some(bookmark) = state.bookmarks.get_mut(&bookmark_id) {
+            if bookmark.position != position {
+                bookmark.position = position;
+                ops.push(PatchOp::UpsertBookmark(bookmark.clone()));
+            }
+        }
+    }
+}
+
+fn bookmark_folder_subtree(
+    state: &BrowserState,
+    root_id: BookmarkFolderId,
+) -> Vec<BookmarkFolderId> {
+    let mut subtree = vec![root_id];
+    let mut cursor = 0;
+    while cursor < subtree.len() {
+        let parent_id = subtree[cursor];
+        let children: Vec<BookmarkFolderId> = state
+            .bookmark_folders
+            .values()
+            .filter(|folder| folder.parent_id == Some(parent_id))
+            .map(|folder| folder.id)
+            .filter(|folder_id| !subtree.contains(folder_id))
+            .collect();
+        subtree.extend(children);
+        cursor += 1;
+    }
+    subtree
+}
+
+fn collect_bookmark_folder_tabs(
+    state: &BrowserState,
+    profile_id: ProfileId,
+    folder_id: BookmarkFolderId,
+) -> Vec<PortableTab> {
+    bookmark_folder_subtree(state, folder_id)
+        .into_iter()
+        .flat_map(|folder_id| state.bookmarks_in_folder(profile_id, Some(folder_id)))
+        .map(|bookmark| PortableTab {
+            url: bookmark.url.clone(),
+            title: bookmark.title.clone(),
+            pinned: false,
+        })
+        .collect()
+}
+
+fn ensure_unique_search_keyword(
+    state: &BrowserState,
+    profile_id: ProfileId,
+    keyword: &str,
+    editing: Option<SearchEngineId>,
+) -> Result<(), ReduceError> {
+    let taken = state.search_engines.values().any(|engine| {
+        engine.profile_id == profile_id && Some(engine.id) != editing && engine.keyword == keyword
+    });
+    if taken {
+        return Err(ReduceError::DuplicateSearchKeyword {
+            profile_id,
+            keyword: keyword.to_owned(),
+        });
+    }
+    Ok(())
+}
+
+// Moves the default flag within the engine's profile, emitting upserts only
+// for rows whose flag actually changed.
+fn set_default_search_engine(
+    state: &mut BrowserState,
+    engine_id: SearchEngineId,
+    ops: &mut Vec<PatchOp>,
+) {
+    let Some(profile_id) = state
+        .search_engines
+        .get(&engine_id)
+        .map(|engine| engine.profile_id)
+    else {
+        return;
+    };
+    for engine in state.search_engines.values_mut() {
+        if engine.profile_id != profile_id {
+            continue;
+        }
+        let is_default = engine.id == engine_id;
+        if engine.is_default != is_default {
+            engine.is_default = is_default;
+            ops.push(PatchOp::UpsertSearchEngine(engine.clone()));
+        }
+    }
+}
+
+fn enforce_lifecycle_policy(
+    state: &mut BrowserState,
+    policy: &dyn LifecyclePolicy,
+    now_ms: i64,
+    ops: &mut Vec<PatchOp>,
+) {
     state.prune_warm_lru();
 
     let active_profile_id = state.active_profile_id;
@@ -740,57 +1984,220 @@ fn enforce_lifecycle_policy(state: &mut BrowserState, ops: &mut Vec<PatchOp>) {
         state.prune_warm_lru();
     }
 
-    let warm_budget = warm_pool_budget(state);
-    let warm_set: BTreeSet<TabId> = active_profile_id
-        .and_then(|profile_id| state.warm_lru.get(&profile_id))
-        .map(|lru| {
-            lru.iter()
-                .rev()
-                .copied()
-                .filter(|tab_id| Some(*tab_id) != active_tab_id)
-                .filter(|tab_id| {
-                    state
-                        .tabs
-                        .get(tab_id)
-                        .map(|tab| Some(tab.profile_id) == active_profile_id)
-                        .unwrap_or(false)
-                })
-                .take(warm_budget)
-                .collect()
-        })
-        .unwrap_or_default();
+    if let Some(tab_id) = active_tab_id {
+        state.tab_last_active_ms.insert(tab_id, now_ms);
+    }
+
+    let context = LifecycleContext {
+        active_profile_id,
+        active_tab_id,
+        now_ms,
+    };
+    let desired = policy.desired_states(state, &context);
 
     for tab in state.tabs.values_mut() {
-        let desired_state = if Some(tab.id) == active_tab_id {
-            TabRuntimeState::Active
-        } else if Some(tab.profile_id) == active_profile_id && warm_set.contains(&tab.id) {
-            TabRuntimeState::Warm
-        } else {
-            TabRuntimeState::Discarded
+        let desired_state = match desired.get(&tab.id) {
+            _ if Some(tab.id) == active_tab_id => TabRuntimeState::Active,
+            Some(TabRuntimeState::Active) => TabRuntimeState::Warm,
+            Some(desired_state) => *desired_state,
+            None => TabRuntimeState::Discarded,
         };
 
         if tab.runtime_state != desired_state {
             tab.runtime_state = desired_state;
+            if desired_state == TabRuntimeState::Discarded {
+                tab.audible = false;
+            }
             ops.push(PatchOp::UpsertTab(tab.clone()));
         }
     }
 }
 
-fn active_tab_for_profile(state: &BrowserState, profile_id: ProfileId) -> Option<TabId> {
-    let workspace_id = state.profiles.get(&profile_id)?.active_workspace_id?;
-    state.workspaces.get(&workspace_id)?.active_tab_id
+fn check_navigation(
+    state: &BrowserState,
+    profile_id: ProfileId,
+    url: &str,
+) -> Result<NavigationAllowed, ReduceError> {
+    NavigationPolicy::for_profile(state, profile_id)
+        .evaluate(url)
+        .map_err(ReduceError::NavigationBlocked)
 }
 
-fn warm_pool_budget(state: &BrowserState) -> usize {
-    match state.settings.get(WARM_POOL_BUDGET_KEY) {
-        Some(SettingValue::Int(value)) => {
-            let clamped = (*value).clamp(0, MAX_WARM_POOL_BUDGET as i64);
-            clamped as usize
+fn add_https_exception(
+    state: &mut BrowserState,
+    profile_id: ProfileId,
+    host: String,
+    ops: &mut Vec<PatchOp>,
+) {
+    if state
+        .https_exceptions
+        .entry(profile_id)
+        .or_default()
+        .insert(host.clone())
+    {
+        ops.push(PatchOp::UpsertHttpsException { profile_id, host });
+    }
+}
+
+fn store_site_permission(
+    state: &mut BrowserState,
+    profile_id: ProfileId,
+    origin: &str,
+    kind: PermissionKind,
+    setting: PermissionSetting,
+    ops: &mut Vec<PatchOp>,
+) {
+    let previous = state
+        .site_permissions
+        .entry(profile_id)
+        .or_default()
+        .insert((origin.to_owned(), kind), setting);
+    if previous != Some(setting) {
+        ops.push(PatchOp::UpsertSitePermission {
+            profile_id,
+            origin: origin.to_owned(),
+            kind,
+            setting,
+        });
+    }
+    if setting != PermissionSetting::Ask {
+        let granted = setting == PermissionSetting::Allow;
+        settle_pending_permissions(
+            state,
+            profile_id,
+            |pending| (pending.origin == origin && pending.kind == kind).then_some(granted),
+            ops,
+        );
+    }
+}
+
+// Removes the profile's pending requests that `decide` answers, with
+// `Some(granted)`, and tells the runtime to complete them.
+fn settle_pending_permissions(
+    state: &mut BrowserState,
+    profile_id: ProfileId,
+    mut decide: impl FnMut(&PendingPermission) -> Option<bool>,
+    ops: &mut Vec<PatchOp>,
+) {
+    for tab in state
+        .tabs
+        .values_mut()
+        .filter(|tab| tab.profile_id == profile_id)
+    {
+        let before = tab.pending_permissions.len();
+        let tab_id = tab.id;
+        tab.pending_permissions
+            .retain(|pending| match decide(pending) {
+                Some(granted) => {
+                    ops.push(PatchOp::PermissionRequestSettled {
+                        tab_id,
+                        request_id: pending.request_id,
+                        granted,
+                    });
+                    false
+                }
+                None => true,
+            });
+        if tab.pending_permissions.len() != before {
+            ops.push(PatchOp::UpsertTab(tab.clone()));
         }
-        _ => DEFAULT_WARM_POOL_BUDGET,
     }
 }
 
+// Requests whose tab was closed or discarded by this intent can no longer be
+// shown, so they are denied.
+fn deny_abandoned_permission_requests(
+    state: &mut BrowserState,
+    open_requests: &[(TabId, PermissionRequestId)],
+    ops: &mut Vec<PatchOp>,
+) {
+    let mut changed_tabs = BTreeSet::new();
+    for &(tab_id, request_id) in open_requests {
+        match state.tabs.get_mut(&tab_id) {
+            None => {}
+            // Discarded and crashed tabs have no renderer left to answer.
+            Some(tab)
+                if tab.runtime_state == TabRuntimeState::Discarded
+                    || tab.status == TabStatus::Crashed =>
+            {
+                let before = tab.pending_permissions.len();
+                tab.pending_permissions
+                    .retain(|pending| pending.request_id != request_id);
+                if tab.pending_permissions.len() == before {
+                    continue;
+                }
+                changed_tabs.insert(tab_id);
+            }
+            Some(_) => continue,
+        }
+        ops.push(PatchOp::PermissionRequestSettled {
+            tab_id,
+            request_id,
+            granted: false,
+        });
+    }
+    for tab_id in changed_tabs {
+        if let Some(tab) = state.tabs.get(&tab_id) {
+            ops.push(PatchOp::UpsertTab(tab.clone()));
+        }
+    }
+}
+
+fn set_tab_muted(tab: &mut Tab, muted: bool, ops: &mut Vec<PatchOp>) {
+    if tab.muted != muted {
+        tab.muted = muted;
+        ops.push(PatchOp::UpsertTab(tab.clone()));
+    }
+}
+
+fn reload_tab(tab: &mut Tab, ops: &mut Vec<PatchOp>) {
+    tab.status = TabStatus::Ok;
+    ops.push(PatchOp::UpsertTab(tab.clone()));
+    ops.push(PatchOp::TabReloaded { tab_id: tab.id });
+}
+
+fn active_focus(state: &BrowserState) -> Option<(WorkspaceId, Option<TabId>)> {
+    let workspace_id = state
+        .profiles
+        .get(&state.active_profile_id?)?
+        .active_workspace_id?;
+    Some((
+        workspace_id,
+        state.workspaces.get(&workspace_id)?.active_tab_id,
+    ))
+}
+
+// Counts the switch the user just made, for policies that predict the next
+// one.
+fn learn_switch(state: &mut BrowserState, from: (WorkspaceId, Option<TabId>)) {
+    let Some(to) = active_focus(state) else {
+        return;
+    };
+    if let ((_, Some(from_tab)), (_, Some(to_tab))) = (from, to) {
+        if from_tab != to_tab {
+            *state
+                .tab_transitions
+                .entry(from_tab)
+                .or_default()
+                .entry(to_tab)
+                .or_default() += 1;
+        }
+    }
+    if from.0 != to.0 {
+        *state
+            .workspace_transitions
+            .entry(from.0)
+            .or_default()
+            .entry(to.0)
+            .or_default() += 1;
+    }
+}
+
+fn active_tab_for_profile(state: &BrowserState, profile_id: ProfileId) -> Option<TabId> {
+    let workspace_id = state.profiles.get(&profile_id)?.active_workspace_id?;
+    state.workspaces.get(&workspace_id)?.active_tab_id
+}
+
 fn resolve_new_tab_url(state: &BrowserState, workspace_id: WorkspaceId) -> String {
     let behavior = setting_text(state, NEW_TAB_BEHAVIOR_KEY)
         .unwrap_or("homepage")
@@ -825,20 +2232,3 @@ fn setting_text<'a>(state: &'a BrowserState, key: &str) -> Option<&'a str> {
         _ => None,
     }
 }
-
-fn normalize_configured_url(value: &str) -> Option<String> {
-    let trimmed = value.trim();
-    if trimmed.is_empty() {
-        return None;
-    }
-    if trimmed.eq_ignore_ascii_case("about:blank") {
-        return Some("about:blank".to_owned());
-    }
-    if trimmed.starts_with("https://") || trimmed.starts_with("http://") {
-        return Some(trimmed.to_owned());
-    }
-    if trimmed.contains("://") {
-        return None;
-    }
-    Some(format!("https://{trimmed}"))
-}
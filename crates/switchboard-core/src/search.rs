@@ -0,0 +1,327 @@
+use crate::ids::{ProfileId, SearchEngineId};
+use crate::state::{BrowserState, SearchEngine, SettingValue};
+use crate::url::{is_local_host, parse_web_url};
+
+pub const SEARCH_TERMS_PLACEHOLDER: &str = "{searchTerms}";
+// Global setting from before the per-profile registry; only used to pick
+// which built-in becomes the default when a profile is first seeded.
+pub const LEGACY_SEARCH_ENGINE_SETTING_KEY: &str = "search_engine";
+
+pub struct BuiltinSearchEngine {
+    pub legacy_key: &'static str,
+    pub name: &'static str,
+    pub keyword: &'static str,
+    pub url_template: &'static str,
+}
+
+pub const BUILTIN_SEARCH_ENGINES: &[BuiltinSearchEngine] = &[
+    BuiltinSearchEngine {
+        legacy_key: "google",
+        name: "Google",
+        keyword: "google",
+        url_template: "https://www.google.com/search?q={searchTerms}",
+    },
+    BuiltinSearchEngine {
+        legacy_key: "duckduckgo",
+        name: "DuckDuckGo",
+        keyword: "ddg",
+        url_template: "https://duckduckgo.com/?q={searchTerms}",
+    },
+    BuiltinSearchEngine {
+        legacy_key: "bing",
+        name: "Bing",
+        keyword: "bing",
+        url_template: "https://www.bing.com/search?q={searchTerms}",
+    },
+    BuiltinSearchEngine {
+        legacy_key: "brave",
+        name: "Brave Search",
+        keyword: "br",
+        url_template: "https://search.brave.com/search?q={searchTerms}",
+    },
+    BuiltinSearchEngine {
+        legacy_key: "kagi",
+        name: "Kagi",
+        keyword: "kagi",
+        url_template: "https://kagi.com/search?q={searchTerms}",
+    },
+    BuiltinSearchEngine {
+        legacy_key: "startpage",
+        name: "Startpage",
+        keyword: "sp",
+        url_template: "https://www.startpage.com/do/dsearch?query={searchTerms}",
+    },
+    BuiltinSearchEngine {
+        legacy_key: "wikipedia",
+        name: "Wikipedia",
+        keyword: "w",
+        url_template: "https://en.wikipedia.org/w/index.php?search={searchTerms}",
+    },
+    BuiltinSearchEngine {
+        legacy_key: "github",
+        name: "GitHub",
+        keyword: "gh",
+        url_template: "https://github.com/search?q={searchTerms}",
+    },
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedSearch {
+    pub engine_id: Option<SearchEngineId>,
+    pub engine_name: String,
+    pub terms: String,
+    pub url: String,
+}
+
+// Adds the built-in engines to a profile that has none yet and returns the
+// inserted rows so callers can emit patch ops for them.
+pub(crate) fn seed_search_engines(
+    state: &mut BrowserState,
+    profile_id: ProfileId,
+) -> Vec<SearchEngine> {
+    if state
+        .search_engines
+        .values()
+        .any(|engine| engine.profile_id == profile_id)
+    {
+        return Vec::new();
+    }
+    let default_key = match state.settings.get(LEGACY_SEARCH_ENGINE_SETTING_KEY) {
+        Some(SettingValue::Text(key))
+            if BUILTIN_SEARCH_ENGINES
+                .iter()
+                .any(|builtin| builtin.legacy_key == key.as_str()) =>
+        {
+            key.clone()
+        }
+        _ => BUILTIN_SEARCH_ENGINES[0].legacy_key.to_owned(),
+    };
+    let mut seeded = Vec::with_capacity(BUILTIN_SEARCH_ENGINES.len());
+    for builtin in BUILTIN_SEARCH_ENGINES {
+        let engine = SearchEngine {
+            id: state.allocate_search_engine_id(),
+            profile_id,
+            name: builtin.name.to_owned(),
+            keyword: builtin.keyword.to_owned(),
+            url_template: builtin.url_template.to_owned(),
+            is_default: builtin.legacy_key == default_key,
+            builtin: true,
+        };
+        state.search_engines.insert(engine.id, engine.clone());
+        seeded.push(engine);
+    }
+    seeded
+}
+
+// Seeds every profile that predates the registry; used when loading state.
+pub fn ensure_search_engines(state: &mut BrowserState) {
+    let profile_ids: Vec<_> = state.profiles.keys().copied().collect();
+    for profile_id in profile_ids {
+        seed_search_engines(state, profile_id);
+    }
+}
+
+pub fn search_engines_for_profile(
+    state: &BrowserState,
+    profile_id: ProfileId,
+) -> Vec<&SearchEngine> {
+    state
+        .search_engines
+        .values()
+        .filter(|engine| engine.profile_id == profile_id)
+        .collect()
+}
+
+pub fn default_search_engine(state: &BrowserState, profile_id: ProfileId) -> Option<&SearchEngine> {
+    let engines = search_engines_for_profile(state, profile_id);
+    engines
+        .iter()
+        .find(|engine| engine.is_default)
+        .or_else(|| engines.first())
+        .copied()
+}
+
+pub fn find_by_keyword<'a>(
+    state: &'a BrowserState,
+    profile_id: ProfileId,
+    keyword: &str,
+) -> Option<&'a SearchEngine> {
+    state.search_engines.values().find(|engine| {
+        engine.profile_id == profile_id && engine.keyword.eq_ignore_ascii_case(keyword)
+    })
+}
+
+// `gh tokio` searches the engine with keyword `gh`; anything else goes to the
+// profile default. Profiles without a registry fall back to the first built-in.
+pub fn resolve_search(state: &BrowserState, profile_id: ProfileId, input: &str) -> ResolvedSearch {
+    let input = input.trim();
+    if let Some((first, rest)) = input.split_once(char::is_whitespace) {
+        let terms = rest.trim();
+        if !terms.is_empty() {
+            if let Some(engine) = find_by_keyword(state, profile_id, first) {
+                return ResolvedSearch {
+                    engine_id: Some(engine.id),
+                    engine_name: engine.name.clone(),
+                    terms: terms.to_owned(),
+                    url: expand_template(&engine.url_template, terms),
+                };
+            }
+        }
+    }
+    match default_search_engine(state, profile_id) {
+        Some(engine) => ResolvedSearch {
+            engine_id: Some(engine.id),
+            engine_name: engine.name.clone(),
+            terms: input.to_owned(),
+            url: expand_template(&engine.url_template, input),
+        },
+        None => {
+            let builtin = &BUILTIN_SEARCH_ENGINES[0];
+            ResolvedSearch {
+                engine_id: None,
+                engine_name: builtin.name.to_owned(),
+                terms: input.to_owned(),
+                url: expand_template(builtin.url_template, input),
+            }
+        }
+    }
+}
+
+pub fn expand_template(template: &str, terms: &str) -> String {
+    template.replace(SEARCH_TERMS_PLACEHOLDER, &encode_query_component(terms))
+}
+
+pub fn normalize_keyword(keyword: &str) -> String {
+    keyword.trim().to_lowercase()
+}
+
+pub fn validate_search_engine(name: &str, keyword: &str, url_template: &str) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err("search engine name cannot be empty".to_owned());
+    }
+    if keyword.is_empty() || keyword.contains(char::is_whitespace) {
+        return Err(format!("invalid search keyword: {keyword:?}"));
+    }
+    if !url_template.contains(SEARCH_TERMS_PLACEHOLDER) {
+        return Err(format!(
+            "search template must contain {SEARCH_TERMS_PLACEHOLDER}: {url_template}"
+        ));
+    }
+    // Checked with stand-in terms, so a placeholder in the host is rejected
+    // along with anything else `parse_web_url` would refuse to load.
+    let url = parse_web_url(&url_template.replace(SEARCH_TERMS_PLACEHOLDER, "%s"))
+        .map_err(|error| format!("invalid search template {url_template}: {error}"))?;
+    // Queries would leak in the clear; plain http is only fine on this machine.
+    if !url.secure && !is_local_host(&url.host) {
+        return Err(format!(
+            "search template must use https unless it is local: {url_template}"
+        ));
+    }
+    Ok(())
+}
+
+// Same unreserved set as JavaScript's encodeURIComponent.
+fn encode_query_component(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric()
+            || matches!(
+                byte,
+                b'-' | b'_' | b'.' | b'!' | b'~' | b'*' | b'\'' | b'(' | b')'
+            )
+        {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::state::{BrowserState, SettingValue};
+
+    use super::{
+        default_search_engine, resolve_search, seed_search_engines, validate_search_engine,
+        BUILTIN_SEARCH_ENGINES,
+    };
+
+    #[test]
+    fn seeding_honors_legacy_setting_and_is_idempotent() {
+        let mut state = BrowserState::default();
+        state.settings.insert(
+            "search_engine".to_owned(),
+            SettingValue::Text("duckduckgo".to_owned()),
+        );
+        let profile_id = state.add_profile("Default");
+
+        let seeded = seed_search_engines(&mut state, profile_id);
+        assert_eq!(seeded.len(), BUILTIN_SEARCH_ENGINES.len());
+        assert!(seed_search_engines(&mut state, profile_id).is_empty());
+        let default = default_search_engine(&state, profile_id).expect("default should exist");
+        assert_eq!(default.name, "DuckDuckGo");
+        assert_eq!(seeded.iter().filter(|engine| engine.is_default).count(), 1);
+    }
+
+    #[test]
+    fn keywords_select_engine_and_fall_back_to_default() {
+        let mut state = BrowserState::default();
+        let profile_id = state.add_profile("Default");
+        seed_search_engines(&mut state, profile_id);
+
+        let github = resolve_search(&state, profile_id, "gh tokio runtime");
+        assert_eq!(github.engine_name, "GitHub");
+        assert_eq!(github.terms, "tokio runtime");
+        assert_eq!(github.url, "https://github.com/search?q=tokio%20runtime");
+
+        let wiki = resolve_search(&state, profile_id, "W Rust (language)");
+        assert_eq!(
+            wiki.url,
+            "https://en.wikipedia.org/w/index.php?search=Rust%20(language)"
+        );
+
+        // Single letters start ordinary queries too often to be keywords.
+        let query = resolve_search(&state, profile_id, "k means clustering");
+        assert_eq!(query.engine_name, "Google");
+        assert_eq!(query.terms, "k means clustering");
+        assert_eq!(
+            query.url,
+            "https://www.google.com/search?q=k%20means%20clustering"
+        );
+        let kagi = resolve_search(&state, profile_id, "kagi k means");
+        assert_eq!(kagi.engine_name, "Kagi");
+        assert_eq!(kagi.terms, "k means");
+
+        let keyword_only = resolve_search(&state, profile_id, "gh");
+        assert_eq!(keyword_only.engine_name, "Google");
+        assert_eq!(keyword_only.url, "https://www.google.com/search?q=gh");
+
+        let unseeded = resolve_search(&BrowserState::default(), profile_id, "a&b");
+        assert_eq!(unseeded.engine_id, None);
+        assert_eq!(unseeded.url, "https://www.google.com/search?q=a%26b");
+    }
+
+    #[test]
+    fn validation_rejects_bad_templates_and_keywords() {
+        assert!(validate_search_engine(
+            "Docs",
+            "d",
+            "https://docs.rs/releases/search?query={searchTerms}"
+        )
+        .is_ok());
+        assert!(validate_search_engine("Docs", "d", "https://docs.rs/?q=%s").is_err());
+        assert!(validate_search_engine("Docs", "d", "javascript:{searchTerms}").is_err());
+        assert!(
+            validate_search_engine("Docs", "two words", "https://x.test/{searchTerms}").is_err()
+        );
+        assert!(validate_search_engine(" ", "d", "https://x.test/{searchTerms}").is_err());
+        assert!(validate_search_engine("Docs", "d", "https://{searchTerms}.test/").is_err());
+        assert!(validate_search_engine("Docs", "d", "https:///?q={searchTerms}").is_err());
+        assert!(validate_search_engine("Docs", "d", "http://x.test/?q={searchTerms}").is_err());
+        assert!(
+            validate_search_engine("Local", "l", "http://localhost:8080/?q={searchTerms}").is_ok()
+        );
+        assert!(validate_search_engine("Local", "l", "http://127.0.0.1/?q={searchTerms}").is_ok());
+    }
+}
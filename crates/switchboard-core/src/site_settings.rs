@@ -0,0 +1,254 @@
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use crate::ids::{PermissionRequestId, ProfileId};
+use crate::state::BrowserState;
+use crate::url::{parse_web_url, UrlError, WebUrl};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PermissionKind {
+    Camera,
+    Microphone,
+    Geolocation,
+    Notifications,
+    Clipboard,
+    Popups,
+    Autoplay,
+    JavaScript,
+}
+
+impl PermissionKind {
+    pub const ALL: [Self; 8] = [
+        Self::Camera,
+        Self::Microphone,
+        Self::Geolocation,
+        Self::Notifications,
+        Self::Clipboard,
+        Self::Popups,
+        Self::Autoplay,
+        Self::JavaScript,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Camera => "camera",
+            Self::Microphone => "microphone",
+            Self::Geolocation => "geolocation",
+            Self::Notifications => "notifications",
+            Self::Clipboard => "clipboard",
+            Self::Popups => "popups",
+            Self::Autoplay => "autoplay",
+            Self::JavaScript => "javascript",
+        }
+    }
+
+    pub fn parse(input: &str) -> Option<Self> {
+        let input = input.trim();
+        Self::ALL
+            .into_iter()
+            .find(|kind| kind.as_str().eq_ignore_ascii_case(input))
+    }
+
+    // What a site gets before the user has said anything about it.
+    pub fn default_setting(self) -> PermissionSetting {
+        match self {
+            Self::Autoplay | Self::JavaScript => PermissionSetting::Allow,
+            Self::Popups => PermissionSetting::Block,
+            Self::Camera
+            | Self::Microphone
+            | Self::Geolocation
+            | Self::Notifications
+            | Self::Clipboard => PermissionSetting::Ask,
+        }
+    }
+}
+
+impl Display for PermissionKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PermissionSetting {
+    Allow,
+    Block,
+    Ask,
+}
+
+impl PermissionSetting {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Allow => "allow",
+            Self::Block => "block",
+            Self::Ask => "ask",
+        }
+    }
+
+    pub fn parse(input: &str) -> Option<Self> {
+        match input.trim().to_ascii_lowercase().as_str() {
+            "allow" => Some(Self::Allow),
+            "block" => Some(Self::Block),
+            "ask" => Some(Self::Ask),
+            _ => None,
+        }
+    }
+}
+
+impl Display for PermissionSetting {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.write_str(self.as_str())
+    }
+}
+
+// The user's answer to one pending request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PermissionDecision {
+    Allow,
+    Deny,
+}
+
+impl PermissionDecision {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Allow => "allow",
+            Self::Deny => "deny",
+        }
+    }
+
+    pub fn parse(input: &str) -> Option<Self> {
+        match input.trim().to_ascii_lowercase().as_str() {
+            "allow" => Some(Self::Allow),
+            "deny" => Some(Self::Deny),
+            _ => None,
+        }
+    }
+
+    // The site setting stored when the decision is remembered.
+    pub fn setting(self) -> PermissionSetting {
+        match self {
+            Self::Allow => PermissionSetting::Allow,
+            Self::Deny => PermissionSetting::Block,
+        }
+    }
+}
+
+// A prompt-style permission the page asked for while its site was set to
+// `Ask`. The host holds the request open until it is settled, which happens
+// when the user decides, a matching setting is stored, or the tab is closed
+// or discarded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingPermission {
+    pub request_id: PermissionRequestId,
+    pub origin: String,
+    pub kind: PermissionKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SitePermission {
+    pub origin: String,
+    pub kind: PermissionKind,
+    pub setting: PermissionSetting,
+}
+
+// Site settings are keyed by web origin: scheme, host and non-default port.
+// Accepts a full URL or a bare `https://host` origin.
+pub fn normalize_origin(input: &str) -> Result<String, UrlError> {
+    let url = parse_web_url(input)?;
+    Ok(WebUrl {
+        userinfo: None,
+        tail: String::new(),
+        ..url
+    }
+    .to_string())
+}
+
+// Stored setting for the origin, or the kind's default.
+pub fn site_permission(
+    state: &BrowserState,
+    profile_id: ProfileId,
+    origin: &str,
+    kind: PermissionKind,
+) -> PermissionSetting {
+    state
+        .site_permissions
+        .get(&profile_id)
+        .and_then(|entries| entries.get(&(origin.to_owned(), kind)))
+        .copied()
+        .unwrap_or_else(|| kind.default_setting())
+}
+
+// Explicit entries for a profile, ordered by origin then kind. `origin`
+// narrows the list to a single site.
+pub fn site_permissions(
+    state: &BrowserState,
+    profile_id: ProfileId,
+    origin: Option<&str>,
+) -> Vec<SitePermission> {
+    let Some(entries) = state.site_permissions.get(&profile_id) else {
+        return Vec::new();
+    };
+    entries
+        .iter()
+        .filter(|((entry_origin, _), _)| origin.is_none_or(|origin| entry_origin == origin))
+        .map(|((origin, kind), setting)| SitePermission {
+            origin: origin.clone(),
+            kind: *kind,
+            setting: *setting,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::url::UrlError;
+
+    use super::{normalize_origin, PermissionDecision, PermissionKind, PermissionSetting};
+
+    #[test]
+    fn origins_drop_path_userinfo_and_default_ports() {
+        let cases = [
+            ("https://Example.com/path?q=1", Ok("https://example.com")),
+            ("http://user:pw@example.com:80/", Ok("http://example.com")),
+            ("https://example.com:8443", Ok("https://example.com:8443")),
+            ("https://[::1]:3000/x", Ok("https://[::1]:3000")),
+            (
+                "example.com",
+                Err(UrlError::MissingScheme("example.com".to_owned())),
+            ),
+            (
+                "ftp://example.com",
+                Err(UrlError::UnsupportedScheme("ftp".to_owned())),
+            ),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(
+                normalize_origin(input),
+                expected.map(str::to_owned),
+                "origin {input:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn kinds_and_settings_roundtrip_through_their_names() {
+        for kind in PermissionKind::ALL {
+            assert_eq!(PermissionKind::parse(kind.as_str()), Some(kind));
+        }
+        for setting in [
+            PermissionSetting::Allow,
+            PermissionSetting::Block,
+            PermissionSetting::Ask,
+        ] {
+            assert_eq!(PermissionSetting::parse(setting.as_str()), Some(setting));
+        }
+        assert_eq!(
+            PermissionKind::parse("JavaScript"),
+            Some(PermissionKind::JavaScript)
+        );
+        assert_eq!(PermissionKind::parse("midi"), None);
+        for decision in [PermissionDecision::Allow, PermissionDecision::Deny] {
+            assert_eq!(PermissionDecision::parse(decision.as_str()), Some(decision));
+        }
+        assert_eq!(PermissionSetting::parse("maybe"), None);
+    }
+}
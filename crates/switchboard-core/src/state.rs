@@ -1,6 +1,12 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
-use crate::ids::{ProfileId, TabId, WorkspaceId};
+use crate::downloads::Download;
+use crate::ids::{
+    BookmarkFolderId, BookmarkId, DownloadId, ProfileId, SearchEngineId, TabId, WorkspaceId,
+};
+use crate::lifecycle::MemoryPressureLevel;
+use crate::navigation::NavigationBlocked;
+use crate::site_settings::{PendingPermission, PermissionKind, PermissionSetting};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TabRuntimeState {
@@ -16,6 +22,42 @@ impl Default for TabRuntimeState {
     }
 }
 
+// Outcome of the tab's last load as reported by the host. Not persisted.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum TabStatus {
+    #[default]
+    Ok,
+    LoadFailed {
+        url: String,
+        error_code: i32,
+    },
+    // The renderer died; the tab has no live view until it is reloaded.
+    Crashed,
+}
+
+impl TabStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Ok => "ok",
+            Self::LoadFailed { .. } => "load_failed",
+            Self::Crashed => "crashed",
+        }
+    }
+}
+
+const CRASH_RELOAD_BASE_DELAY_MS: u64 = 2_000;
+const CRASH_RELOAD_MAX_ATTEMPTS: u32 = 4;
+
+// Backoff before a crashed background tab is reloaded automatically: 2s, 4s,
+// 8s and 16s for the first four crashes, after which the tab waits for the
+// user.
+pub fn crash_reload_delay_ms(crash_count: u32) -> Option<u64> {
+    if crash_count == 0 || crash_count > CRASH_RELOAD_MAX_ATTEMPTS {
+        return None;
+    }
+    Some(CRASH_RELOAD_BASE_DELAY_MS << (crash_count - 1))
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Profile {
     pub id: ProfileId,
@@ -42,9 +84,65 @@ pub struct Tab {
     pub title: String,
     pub loading: bool,
     pub thumbnail_data_url: Option<String>,
+    // Key into the app's favicon cache; the icon bytes live on disk, not in
+    // state.
+    pub favicon_key: Option<String>,
     pub pinned: bool,
+    // Kept warm whatever the LRU says, up to `lifecycle.keep_alive_cap` tabs
+    // per profile.
+    pub keep_alive: bool,
     pub muted: bool,
+    // The page is playing sound, muted or not. Reported by the host for live
+    // tabs only. Not persisted.
+    pub audible: bool,
     pub runtime_state: TabRuntimeState,
+    // Set when a content-initiated navigation was refused; the view shows a
+    // blocked page while `url` keeps the last allowed address. Not persisted.
+    pub blocked_navigation: Option<NavigationBlocked>,
+    // Original http address while an https-only upgrade is loading, so a
+    // failed load can offer the insecure fallback. Not persisted.
+    pub https_upgraded_from: Option<String>,
+    // Permission prompts waiting on the user, oldest first. Not persisted.
+    pub pending_permissions: Vec<PendingPermission>,
+    // Tab whose page opened this one through a link target or `window.open`.
+    // It may since have been closed. Not persisted.
+    pub opener_tab_id: Option<TabId>,
+    pub status: TabStatus,
+    // Renderer crashes since the user last navigated the tab; drives the
+    // auto-reload backoff. Not persisted.
+    pub crash_count: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BookmarkFolder {
+    pub id: BookmarkFolderId,
+    pub profile_id: ProfileId,
+    pub parent_id: Option<BookmarkFolderId>,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bookmark {
+    pub id: BookmarkId,
+    pub profile_id: ProfileId,
+    pub folder_id: Option<BookmarkFolderId>,
+    pub url: String,
+    pub title: String,
+    // Dense ordering among bookmarks sharing the same profile and folder.
+    pub position: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchEngine {
+    pub id: SearchEngineId,
+    pub profile_id: ProfileId,
+    pub name: String,
+    // Lowercase shortcut typed before the query, e.g. `gh tokio`.
+    pub keyword: String,
+    // Contains `{searchTerms}`, replaced with the encoded query.
+    pub url_template: String,
+    pub is_default: bool,
+    pub builtin: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -65,12 +163,38 @@ pub struct BrowserState {
     pub workspaces: BTreeMap<WorkspaceId, Workspace>,
     pub tabs: BTreeMap<TabId, Tab>,
     pub settings: BTreeMap<String, SettingValue>,
+    pub bookmarks: BTreeMap<BookmarkId, Bookmark>,
+    pub bookmark_folders: BTreeMap<BookmarkFolderId, BookmarkFolder>,
+    pub search_engines: BTreeMap<SearchEngineId, SearchEngine>,
+    // Hosts each profile allows over plain http despite https-only mode.
+    pub https_exceptions: BTreeMap<ProfileId, BTreeSet<String>>,
+    // Explicit per-origin permission settings; anything absent uses the
+    // kind's default.
+    pub site_permissions:
+        BTreeMap<ProfileId, BTreeMap<(String, PermissionKind), PermissionSetting>>,
+    // Only finished downloads are persisted; active ones end with the session.
+    pub downloads: BTreeMap<DownloadId, Download>,
     // Runtime-only warm pool LRU per profile (oldest -> newest).
     pub warm_lru: BTreeMap<ProfileId, Vec<TabId>>,
+    // Runtime-only: when each tab was last the active tab, in milliseconds
+    // since the Unix epoch.
+    pub tab_last_active_ms: BTreeMap<TabId, i64>,
+    // Runtime-only: the host's latest memory estimate per tab. Discarded tabs
+    // keep theirs as a guess at what warming them again would cost.
+    pub tab_memory_bytes: BTreeMap<TabId, u64>,
+    pub memory_pressure: MemoryPressureLevel,
+    // Runtime-only: how often the user went from one tab, or workspace, to
+    // another, learned from ActivateTab and SwitchWorkspace.
+    pub tab_transitions: BTreeMap<TabId, BTreeMap<TabId, u32>>,
+    pub workspace_transitions: BTreeMap<WorkspaceId, BTreeMap<WorkspaceId, u32>>,
     pub active_profile_id: Option<ProfileId>,
     next_profile_id: u64,
     next_workspace_id: u64,
     next_tab_id: u64,
+    next_bookmark_id: u64,
+    next_bookmark_folder_id: u64,
+    next_search_engine_id: u64,
+    next_download_id: u64,
 }
 
 impl Default for BrowserState {
@@ -80,11 +204,26 @@ impl Default for BrowserState {
             workspaces: BTreeMap::new(),
             tabs: BTreeMap::new(),
             settings: BTreeMap::new(),
+            bookmarks: BTreeMap::new(),
+            bookmark_folders: BTreeMap::new(),
+            search_engines: BTreeMap::new(),
+            https_exceptions: BTreeMap::new(),
+            site_permissions: BTreeMap::new(),
+            downloads: BTreeMap::new(),
             warm_lru: BTreeMap::new(),
+            tab_last_active_ms: BTreeMap::new(),
+            tab_memory_bytes: BTreeMap::new(),
+            memory_pressure: MemoryPressureLevel::Normal,
+            tab_transitions: BTreeMap::new(),
+            workspace_transitions: BTreeMap::new(),
             active_profile_id: None,
             next_profile_id: 1,
             next_workspace_id: 1,
             next_tab_id: 1,
+            next_bookmark_id: 1,
+            next_bookmark_folder_id: 1,
+            next_search_engine_id: 1,
+            next_download_id: 1,
         }
     }
 }
@@ -156,6 +295,44 @@ impl BrowserState {
         id
     }
 
+    pub(crate) fn allocate_bookmark_id(&mut self) -> BookmarkId {
+        let id = BookmarkId(self.next_bookmark_id);
+        self.next_bookmark_id += 1;
+        id
+    }
+
+    pub(crate) fn allocate_bookmark_folder_id(&mut self) -> BookmarkFolderId {
+        let id = BookmarkFolderId(self.next_bookmark_folder_id);
+        self.next_bookmark_folder_id += 1;
+        id
+    }
+
+    pub(crate) fn allocate_search_engine_id(&mut self) -> SearchEngineId {
+        let id = SearchEngineId(self.next_search_engine_id);
+        self.next_search_engine_id += 1;
+        id
+    }
+
+    pub(crate) fn allocate_download_id(&mut self) -> DownloadId {
+        let id = DownloadId(self.next_download_id);
+        self.next_download_id += 1;
+        id
+    }
+
+    pub fn bookmarks_in_folder(
+        &self,
+        profile_id: ProfileId,
+        folder_id: Option<BookmarkFolderId>,
+    ) -> Vec<&Bookmark> {
+        let mut bookmarks: Vec<&Bookmark> = self
+            .bookmarks
+            .values()
+            .filter(|bookmark| bookmark.profile_id == profile_id && bookmark.folder_id == folder_id)
+            .collect();
+        bookmarks.sort_by_key(|bookmark| (bookmark.position, bookmark.id));
+        bookmarks
+    }
+
     fn allocate_profile_id(&mut self) -> ProfileId {
         let id = ProfileId(self.next_profile_id);
         self.next_profile_id += 1;
@@ -191,6 +368,18 @@ impl BrowserState {
             });
             !tab_ids.is_empty() && self.profiles.contains_key(profile_id)
         });
+        self.tab_last_active_ms
+            .retain(|tab_id, _| self.tabs.contains_key(tab_id));
+        self.tab_memory_bytes
+            .retain(|tab_id, _| self.tabs.contains_key(tab_id));
+        self.tab_transitions.retain(|tab_id, next| {
+            next.retain(|next_id, _| self.tabs.contains_key(next_id));
+            !next.is_empty() && self.tabs.contains_key(tab_id)
+        });
+        self.workspace_transitions.retain(|workspace_id, next| {
+            next.retain(|next_id, _| self.workspaces.contains_key(next_id));
+            !next.is_empty() && self.workspaces.contains_key(workspace_id)
+        });
     }
 
     pub fn recompute_next_ids(&mut self) {
@@ -212,5 +401,29 @@ impl BrowserState {
             .next_back()
             .map(|id| id.0.saturating_add(1))
             .unwrap_or(1);
+        self.next_bookmark_id = self
+            .bookmarks
+            .keys()
+            .next_back()
+            .map(|id| id.0.saturating_add(1))
+            .unwrap_or(1);
+        self.next_bookmark_folder_id = self
+            .bookmark_folders
+            .keys()
+            .next_back()
+            .map(|id| id.0.saturating_add(1))
+            .unwrap_or(1);
+        self.next_search_engine_id = self
+            .search_engines
+            .keys()
+            .next_back()
+            .map(|id| id.0.saturating_add(1))
+            .unwrap_or(1);
+        self.next_download_id = self
+            .downloads
+            .keys()
+            .next_back()
+            .map(|id| id.0.saturating_add(1))
+            .unwrap_or(1);
     }
 }
@@ -0,0 +1,556 @@
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+pub const BLANK_PAGE_URL: &str = "about:blank";
+pub const UI_URL_SCHEME: &str = "app";
+pub const UI_URL_HOST: &str = "ui";
+
+const MAX_HOST_LEN: usize = 253;
+const MAX_LABEL_LEN: usize = 63;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UrlError {
+    Empty,
+    MissingScheme(String),
+    UnsupportedScheme(String),
+    InvalidHost(String),
+    InvalidPort(String),
+}
+
+impl Display for UrlError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::Empty => write!(f, "url is empty"),
+            Self::MissingScheme(url) => write!(f, "url has no scheme: {url}"),
+            Self::UnsupportedScheme(scheme) => write!(f, "unsupported url scheme: {scheme}"),
+            Self::InvalidHost(host) => write!(f, "invalid url host: {host}"),
+            Self::InvalidPort(port) => write!(f, "invalid url port: {port}"),
+        }
+    }
+}
+
+// How typed navigation input should be treated. `Url` and `Hostname` carry the
+// normalized URL to load; `Search` carries the trimmed query text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputKind {
+    Url(String),
+    Hostname(String),
+    Search(String),
+    Internal(String),
+}
+
+impl InputKind {
+    // The URL a navigation should load, if the input names one that content
+    // views may open. Internal pages other than about:blank are not loadable.
+    pub fn navigable_url(&self) -> Option<&str> {
+        match self {
+            Self::Url(url) | Self::Hostname(url) => Some(url),
+            Self::Internal(url) if url == BLANK_PAGE_URL => Some(url),
+            Self::Internal(_) | Self::Search(_) => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WebUrl {
+    pub secure: bool,
+    pub userinfo: Option<String>,
+    // Lowercase ASCII; IDN labels are punycode and IPv6 is unbracketed.
+    pub host: String,
+    // None when absent or equal to the scheme default.
+    pub port: Option<u16>,
+    // Path, query and fragment, verbatim.
+    pub tail: String,
+}
+
+impl WebUrl {
+    pub fn scheme(&self) -> &'static str {
+        if self.secure {
+            "https"
+        } else {
+            "http"
+        }
+    }
+}
+
+impl Display for WebUrl {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{}://", self.scheme())?;
+        if let Some(userinfo) = &self.userinfo {
+            write!(f, "{userinfo}@")?;
+        }
+        if self.host.contains(':') {
+            write!(f, "[{}]", self.host)?;
+        } else {
+            f.write_str(&self.host)?;
+        }
+        if let Some(port) = self.port {
+            write!(f, ":{port}")?;
+        }
+        f.write_str(&self.tail)
+    }
+}
+
+pub fn parse_web_url(input: &str) -> Result<WebUrl, UrlError> {
+    let trimmed = input.trim();
+    if trimmed.is_empty() {
+        return Err(UrlError::Empty);
+    }
+    let (scheme, rest) = trimmed
+        .split_once("://")
+        .filter(|(scheme, _)| is_scheme(scheme))
+        .ok_or_else(|| match explicit_scheme(trimmed) {
+            Some(scheme) => UrlError::UnsupportedScheme(scheme),
+            None => UrlError::MissingScheme(trimmed.to_owned()),
+        })?;
+    let secure = match scheme.to_ascii_lowercase().as_str() {
+        "https" => true,
+        "http" => false,
+        other => return Err(UrlError::UnsupportedScheme(other.to_owned())),
+    };
+
+    let authority_end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+    let (authority, tail) = rest.split_at(authority_end);
+    let (userinfo, host_port) = match authority.rsplit_once('@') {
+        Some((userinfo, host_port)) => (Some(userinfo.to_owned()), host_port),
+        None => (None, authority),
+    };
+    let (raw_host, raw_port) = split_host_port(host_port)?;
+    let host = normalize_host(raw_host)?;
+    let port = match raw_port {
+        None | Some("") => None,
+        Some(raw_port) => Some(
+            raw_port
+                .parse::<u16>()
+                .ok()
+                .filter(|port| *port != 0)
+                .ok_or_else(|| UrlError::InvalidPort(raw_port.to_owned()))?,
+        ),
+    };
+    let default_port = if secure { 443 } else { 80 };
+
+    Ok(WebUrl {
+        secure,
+        userinfo,
+        host,
+        port: port.filter(|port| *port != default_port),
+        tail: tail.to_owned(),
+    })
+}
+
+// Canonical form of a URL a content view may load: http(s) or about:blank.
+pub fn normalize_navigation_url(input: &str) -> Result<String, UrlError> {
+    let trimmed = input.trim();
+    if trimmed.eq_ignore_ascii_case(BLANK_PAGE_URL) {
+        return Ok(BLANK_PAGE_URL.to_owned());
+    }
+    parse_web_url(trimmed).map(|url| url.to_string())
+}
+
+// Homepage and custom new-tab settings may omit the scheme; https is assumed.
+pub fn normalize_configured_url(input: &str) -> Option<String> {
+    let trimmed = input.trim();
+    if trimmed.is_empty() {
+        return None;
+    }
+    if trimmed.contains("://") || trimmed.eq_ignore_ascii_case(BLANK_PAGE_URL) {
+        return normalize_navigation_url(trimmed).ok();
+    }
+    normalize_navigation_url(&format!("https://{trimmed}")).ok()
+}
+
+pub fn is_web_url(input: &str) -> bool {
+    parse_web_url(input).is_ok()
+}
+
+pub fn web_host(input: &str) -> Option<String> {
+    parse_web_url(input).ok().map(|url| url.host)
+}
+
+// Lowercased scheme of an absolute URL such as `app://ui` or `mailto:x`.
+pub fn url_scheme(input: &str) -> Option<String> {
+    let trimmed = input.trim();
+    match trimmed.split_once("://") {
+        Some((scheme, _)) if is_scheme(scheme) => Some(scheme.to_ascii_lowercase()),
+        _ => explicit_scheme(trimmed),
+    }
+}
+
+pub fn is_ui_url(input: &str) -> bool {
+    let trimmed = input.trim();
+    url_scheme(trimmed).as_deref() == Some(UI_URL_SCHEME)
+        && trimmed
+            .split_once("://")
+            .map(|(_, rest)| rest.split(['/', '?', '#']).next().unwrap_or_default())
+            .is_some_and(|host| host.eq_ignore_ascii_case(UI_URL_HOST))
+}
+
+pub fn classify_input(text: &str) -> Option<InputKind> {
+    let raw = text.trim();
+    if raw.is_empty() {
+        return None;
+    }
+    if let Some(scheme) = url_scheme(raw) {
+        return Some(match scheme.as_str() {
+            "http" | "https" => match parse_web_url(raw) {
+                Ok(url) => InputKind::Url(url.to_string()),
+                Err(_) => InputKind::Search(raw.to_owned()),
+            },
+            "about" => InputKind::Internal(raw.to_ascii_lowercase()),
+            UI_URL_SCHEME => InputKind::Internal(raw.to_owned()),
+            _ => InputKind::Search(raw.to_owned()),
+        });
+    }
+    if raw.contains(char::is_whitespace) {
+        return Some(InputKind::Search(raw.to_owned()));
+    }
+    let Ok(url) = parse_web_url(&format!("https://{raw}")) else {
+        return Some(InputKind::Search(raw.to_owned()));
+    };
+    // `me@example.com` reads as an address to search for, not a login.
+    if url.userinfo.is_some() || !looks_like_hostname(&url.host) {
+        return Some(InputKind::Search(raw.to_owned()));
+    }
+    if is_local_host(&url.host) {
+        // Reparsed so default-port elision applies to http rather than https.
+        return parse_web_url(&format!("http://{raw}"))
+            .ok()
+            .map(|url| InputKind::Hostname(url.to_string()));
+    }
+    Some(InputKind::Hostname(url.to_string()))
+}
+
+fn looks_like_hostname(host: &str) -> bool {
+    if is_local_host(host) {
+        return true;
+    }
+    let Some((_, tld)) = host.rsplit_once('.') else {
+        return false;
+    };
+    tld.starts_with("xn--")
+        || (tld.len() >= 2 && tld.bytes().all(|byte| byte.is_ascii_alphabetic()))
+}
+
+pub(crate) fn is_local_host(host: &str) -> bool {
+    host == "localhost"
+        || host.ends_with(".localhost")
+        || host.parse::<Ipv4Addr>().is_ok()
+        || host.parse::<Ipv6Addr>().is_ok()
+}
+
+fn is_scheme(value: &str) -> bool {
+    let mut chars = value.chars();
+    chars.next().is_some_and(|ch| ch.is_ascii_alphabetic())
+        && chars.all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '+' | '.' | '-'))
+}
+
+// `mailto:x` has a scheme; `localhost:3000` and `example.com:8080/a` are a
+// host followed by a port.
+fn explicit_scheme(value: &str) -> Option<String> {
+    let (scheme, rest) = value.split_once(':')?;
+    if !is_scheme(scheme) {
+        return None;
+    }
+    let port_len = rest.bytes().take_while(u8::is_ascii_digit).count();
+    let after_port = &rest[port_len..];
+    if port_len > 0 && (after_port.is_empty() || after_port.starts_with(['/', '?', '#'])) {
+        return None;
+    }
+    Some(scheme.to_ascii_lowercase())
+}
+
+fn split_host_port(host_port: &str) -> Result<(&str, Option<&str>), UrlError> {
+    if let Some(rest) = host_port.strip_prefix('[') {
+        let (host, after) = rest
+            .split_once(']')
+            .ok_or_else(|| UrlError::InvalidHost(host_port.to_owned()))?;
+        let port = match after {
+            "" => None,
+            _ => Some(
+                after
+                    .strip_prefix(':')
+                    .ok_or_else(|| UrlError::InvalidHost(host_port.to_owned()))?,
+            ),
+        };
+        return Ok((host, port));
+    }
+    Ok(match host_port.rsplit_once(':') {
+        Some((host, port)) => (host, Some(port)),
+        None => (host_port, None),
+    })
+}
+
+fn normalize_host(raw: &str) -> Result<String, UrlError> {
+    let invalid = || UrlError::InvalidHost(raw.to_owned());
+    if raw.contains(':') {
+        return raw
+            .parse::<Ipv6Addr>()
+            .map(|addr| addr.to_string())
+            .map_err(|_| invalid());
+    }
+    let trimmed = raw.strip_suffix('.').unwrap_or(raw);
+    if trimmed.is_empty() {
+        return Err(invalid());
+    }
+    let mut labels = Vec::new();
+    for label in trimmed.split('.') {
+        let label = if label.is_ascii() {
+            label.to_ascii_lowercase()
+        } else {
+            format!(
+                "xn--{}",
+                punycode_encode(&label.to_lowercase()).ok_or_else(invalid)?
+            )
+        };
+        let valid = !label.is_empty()
+            && label.len() <= MAX_LABEL_LEN
+            && !label.starts_with('-')
+            && !label.ends_with('-')
+            && label
+                .bytes()
+                .all(|byte| byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_'));
+        if !valid {
+            return Err(invalid());
+        }
+        labels.push(label);
+    }
+    let host = labels.join(".");
+    if host.len() > MAX_HOST_LEN {
+        return Err(invalid());
+    }
+    Ok(host)
+}
+
+const PUNYCODE_BASE: u32 = 36;
+const PUNYCODE_TMIN: u32 = 1;
+const PUNYCODE_TMAX: u32 = 26;
+const PUNYCODE_SKEW: u32 = 38;
+const PUNYCODE_DAMP: u32 = 700;
+const PUNYCODE_INITIAL_BIAS: u32 = 72;
+const PUNYCODE_INITIAL_N: u32 = 128;
+
+// RFC 3492 encoder for a single label, without the `xn--` prefix. Returns
+// None on arithmetic overflow, which only absurdly long labels can reach.
+fn punycode_encode(label: &str) -> Option<String> {
+    let code_points: Vec<u32> = label.chars().map(u32::from).collect();
+    let mut output: String = label.chars().filter(char::is_ascii).collect();
+    let basic_count = output.len() as u32;
+    if basic_count > 0 {
+        output.push('-');
+    }
+
+    let mut n = PUNYCODE_INITIAL_N;
+    let mut delta: u32 = 0;
+    let mut bias = PUNYCODE_INITIAL_BIAS;
+    let mut handled = basic_count;
+    while (handled as usize) < code_points.len() {
+        let next = code_points.iter().copied().filter(|&cp| cp >= n).min()?;
+        delta = delta.checked_add((next - n).checked_mul(handled + 1)?)?;
+        n = next;
+        for &cp in &code_points {
+            if cp < n {
+                delta = delta.checked_add(1)?;
+            }
+            if cp != n {
+                continue;
+            }
+            let mut q = delta;
+            let mut k = PUNYCODE_BASE;
+            loop {
+                let t = if k <= bias {
+                    PUNYCODE_TMIN
+                } else if k >= bias + PUNYCODE_TMAX {
+                    PUNYCODE_TMAX
+                } else {
+                    k - bias
+                };
+                if q < t {
+                    break;
+                }
+                output.push(punycode_digit(t + (q - t) % (PUNYCODE_BASE - t)));
+                q = (q - t) / (PUNYCODE_BASE - t);
+                k += PUNYCODE_BASE;
+            }
+            output.push(punycode_digit(q));
+            bias = punycode_adapt(delta, handled + 1, handled == basic_count);
+            delta = 0;
+            handled += 1;
+        }
+        delta = delta.checked_add(1)?;
+        n += 1;
+    }
+    Some(output)
+}
+
+fn punycode_adapt(delta: u32, num_points: u32, first_time: bool) -> u32 {
+    let mut delta = if first_time {
+        delta / PUNYCODE_DAMP
+    } else {
+        delta / 2
+    };
+    delta += delta / num_points;
+    let mut k = 0;
+    while delta > ((PUNYCODE_BASE - PUNYCODE_TMIN) * PUNYCODE_TMAX) / 2 {
+        delta /= PUNYCODE_BASE - PUNYCODE_TMIN;
+        k += PUNYCODE_BASE;
+    }
+    k + (PUNYCODE_BASE - PUNYCODE_TMIN + 1) * delta / (delta + PUNYCODE_SKEW)
+}
+
+fn punycode_digit(value: u32) -> char {
+    let byte = if value < 26 {
+        b'a' + value as u8
+    } else {
+        b'0' + (value - 26) as u8
+    };
+    byte as char
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        classify_input, is_ui_url, normalize_configured_url, normalize_navigation_url,
+        punycode_encode, web_host, InputKind, UrlError,
+    };
+
+    #[test]
+    fn classify_input_table() {
+        let url = |value: &str| Some(InputKind::Url(value.to_owned()));
+        let hostname = |value: &str| Some(InputKind::Hostname(value.to_owned()));
+        let search = |value: &str| Some(InputKind::Search(value.to_owned()));
+        let internal = |value: &str| Some(InputKind::Internal(value.to_owned()));
+        let cases = [
+            ("", None),
+            ("   ", None),
+            (
+                "https://Example.COM/Path?q=1",
+                url("https://example.com/Path?q=1"),
+            ),
+            ("HTTP://example.com:80/", url("http://example.com/")),
+            ("https://example.com:8443", url("https://example.com:8443")),
+            (
+                "https://münchen.de/karte",
+                url("https://xn--mnchen-3ya.de/karte"),
+            ),
+            ("https://[::1]:8080/a", url("https://[::1]:8080/a")),
+            ("https://exa mple.com", search("https://exa mple.com")),
+            (
+                "https://example.com:99999",
+                search("https://example.com:99999"),
+            ),
+            ("example.com", hostname("https://example.com")),
+            (
+                "example.com:8080/docs",
+                hostname("https://example.com:8080/docs"),
+            ),
+            ("Bücher.example", hostname("https://xn--bcher-kva.example")),
+            ("日本語.jp", hostname("https://xn--wgv71a119e.jp")),
+            ("localhost", hostname("http://localhost")),
+            ("localhost:3000/api", hostname("http://localhost:3000/api")),
+            ("app.localhost:5173", hostname("http://app.localhost:5173")),
+            ("127.0.0.1:8000", hostname("http://127.0.0.1:8000")),
+            ("[::1]:8080", hostname("http://[::1]:8080")),
+            ("[2001:DB8::1]", hostname("http://[2001:db8::1]")),
+            ("about:blank", internal("about:blank")),
+            ("About:Settings", internal("about:settings")),
+            ("app://ui", internal("app://ui")),
+            ("tokio", search("tokio")),
+            ("rust lang", search("rust lang")),
+            ("3.14", search("3.14")),
+            ("node.js", hostname("https://node.js")),
+            ("me@example.com", search("me@example.com")),
+            ("mailto:me@example.com", search("mailto:me@example.com")),
+            ("javascript:alert(1)", search("javascript:alert(1)")),
+            ("file:///etc/passwd", search("file:///etc/passwd")),
+            ("-bad-.com", search("-bad-.com")),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(classify_input(input), expected, "input {input:?}");
+        }
+    }
+
+    #[test]
+    fn navigation_urls_only_allow_web_and_blank_pages() {
+        let cases: [(&str, Result<&str, UrlError>); 9] = [
+            ("https://example.com", Ok("https://example.com")),
+            (
+                "  http://EXAMPLE.com:8080/a  ",
+                Ok("http://example.com:8080/a"),
+            ),
+            (
+                "https://user:pw@example.com",
+                Ok("https://user:pw@example.com"),
+            ),
+            ("ABOUT:BLANK", Ok("about:blank")),
+            ("", Err(UrlError::Empty)),
+            (
+                "javascript:alert(1)",
+                Err(UrlError::UnsupportedScheme("javascript".to_owned())),
+            ),
+            (
+                "file:///etc/passwd",
+                Err(UrlError::UnsupportedScheme("file".to_owned())),
+            ),
+            (
+                "app://ui",
+                Err(UrlError::UnsupportedScheme("app".to_owned())),
+            ),
+            (
+                "example.com",
+                Err(UrlError::MissingScheme("example.com".to_owned())),
+            ),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(
+                normalize_navigation_url(input),
+                expected.map(str::to_owned),
+                "input {input:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn helpers_table() {
+        let configured = [
+            ("youtube.com", Some("https://youtube.com")),
+            ("http://intranet:8080", Some("http://intranet:8080")),
+            ("about:blank", Some("about:blank")),
+            ("file:///tmp/x", None),
+            ("  ", None),
+        ];
+        for (input, expected) in configured {
+            assert_eq!(
+                normalize_configured_url(input).as_deref(),
+                expected,
+                "input {input:?}"
+            );
+        }
+
+        let hosts = [
+            ("https://User@Example.COM:8443/path", Some("example.com")),
+            ("http://[::1]:8080/", Some("::1")),
+            ("https://example.com./?q", Some("example.com")),
+            ("about:blank", None),
+        ];
+        for (input, expected) in hosts {
+            assert_eq!(web_host(input).as_deref(), expected, "input {input:?}");
+        }
+
+        for (input, expected) in [
+            ("app://ui", true),
+            ("APP://ui/index.html", true),
+            ("app://uinot", false),
+            ("https://ui", false),
+        ] {
+            assert_eq!(is_ui_url(input), expected, "input {input:?}");
+        }
+
+        for (input, expected) in [
+            ("münchen", "mnchen-3ya"),
+            ("bücher", "bcher-kva"),
+            ("日本語", "wgv71a119e"),
+        ] {
+            assert_eq!(punycode_encode(input).as_deref(), Some(expected));
+        }
+    }
+}